@@ -139,6 +139,12 @@ fn bench_filter_in_memory(c: &mut Criterion) {
                 defer_until: None,
                 project: None,
                 area: None,
+                parent: None,
+                blocked_by: Vec::new(),
+                priority: None,
+                recurrence: None,
+                series: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
                 projects_count: None,
@@ -171,6 +177,38 @@ fn bench_list_tasks_1000(c: &mut Criterion) {
     });
 }
 
+/// Benchmark: Large vault scan (1000 files) with a warm parse cache.
+fn bench_list_tasks_1000_cached(c: &mut Criterion) {
+    let (temp, sdk) = setup_vault(1000);
+    let config = sdk
+        .config()
+        .clone()
+        .with_cache_path(temp.path().join(".taskdn/cache.bin"));
+    let sdk = Taskdn::new(config).expect("Failed to create SDK");
+    sdk.list_tasks(&TaskFilter::new()).expect("List failed");
+
+    c.bench_function("list_tasks_1000_cached", |b| {
+        b.iter(|| {
+            let _tasks = sdk
+                .list_tasks(black_box(&TaskFilter::new()))
+                .expect("List failed");
+        });
+    });
+}
+
+/// Benchmark: Query a 10k-task vault from the in-memory index.
+fn bench_index_query_10000(c: &mut Criterion) {
+    let (_temp, sdk) = setup_vault(10_000);
+    let index = sdk.build_index().expect("Index failed");
+    let filter = TaskFilter::new().with_status(TaskStatus::Ready);
+
+    c.bench_function("index_query_10000", |b| {
+        b.iter(|| {
+            let _tasks = index.list_tasks(black_box(&filter));
+        });
+    });
+}
+
 criterion_group!(
     benches,
     bench_parse_single_file,
@@ -179,5 +217,7 @@ criterion_group!(
     bench_list_tasks_with_filter_100,
     bench_filter_in_memory,
     bench_list_tasks_1000,
+    bench_list_tasks_1000_cached,
+    bench_index_query_10000,
 );
 criterion_main!(benches);
@@ -0,0 +1,387 @@
+//! Nested areas.
+//!
+//! An area becomes a sub-area of another through its `parent` field, a
+//! reference to the parent area file ("Client: Acme" under "Work"). The
+//! *subtree* of an area is the area itself and its sub-areas at any depth.
+//! [`Taskdn::get_projects_for_area_subtree`], [`Taskdn::get_tasks_for_area_subtree`]
+//! and the `within_area` filters on [`AreaFilter`] and [`TaskFilter`] match
+//! against the whole subtree.
+//!
+//! A parent cycle is reported by [`Taskdn::get_area_warnings`]. Subtree
+//! queries stop when they reach an area again, so a cycle never loops.
+
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::filter::{AreaFilter, ProjectFilter, TaskFilter};
+use crate::hierarchy::AreaTree;
+use crate::types::{Area, FileReference, Project, Task};
+use crate::validation::ValidationWarning;
+use crate::Taskdn;
+
+/// The areas in a subtree and the projects assigned to any of them.
+pub(crate) struct AreaScope<'a> {
+    areas: Vec<&'a Area>,
+    projects: Vec<&'a Project>,
+}
+
+impl<'a> AreaScope<'a> {
+    /// The subtree rooted at the area `root` points to.
+    ///
+    /// The scope is empty if no area matches `root`.
+    pub(crate) fn resolve(
+        areas: impl IntoIterator<Item = &'a Area>,
+        projects: impl IntoIterator<Item = &'a Project>,
+        root: &FileReference,
+    ) -> Self {
+        let tree = AreaTree::new(areas);
+        let areas = tree.find(root).map(|index| subtree(&tree, index));
+        Self::new(areas.unwrap_or_default(), projects)
+    }
+
+    /// The subtree rooted at `area`, or just `area` if it isn't among `areas`.
+    pub(crate) fn of(
+        area: &'a Area,
+        areas: impl IntoIterator<Item = &'a Area>,
+        projects: impl IntoIterator<Item = &'a Project>,
+    ) -> Self {
+        let tree = AreaTree::new(areas);
+        let areas = match tree.position(&area.path) {
+            Some(index) => subtree(&tree, index),
+            None => vec![area],
+        };
+        Self::new(areas, projects)
+    }
+
+    fn new(areas: Vec<&'a Area>, projects: impl IntoIterator<Item = &'a Project>) -> Self {
+        let projects = projects
+            .into_iter()
+            .filter(|project| {
+                project
+                    .area
+                    .as_ref()
+                    .is_some_and(|reference| points_to_any(reference, &areas))
+            })
+            .collect();
+        Self { areas, projects }
+    }
+
+    /// The projects assigned to an area in the subtree.
+    pub(crate) fn projects(&self) -> &[&'a Project] {
+        &self.projects
+    }
+
+    /// Returns true if the area is in the subtree.
+    pub(crate) fn contains_area(&self, area: &Area) -> bool {
+        self.areas.iter().any(|a| a.path == area.path)
+    }
+
+    /// Returns true if the task is assigned to an area in the subtree,
+    /// directly or through its project.
+    pub(crate) fn contains_task(&self, task: &Task) -> bool {
+        let direct = task
+            .area
+            .as_ref()
+            .is_some_and(|reference| points_to_any(reference, &self.areas));
+        direct
+            || task.project.as_ref().is_some_and(|reference| {
+                self.projects
+                    .iter()
+                    .any(|project| reference.points_to(&project.path, &project.title))
+            })
+    }
+}
+
+/// The area at `root` followed by its sub-areas at any depth, in path order.
+fn subtree<'a>(tree: &AreaTree<'a>, root: usize) -> Vec<&'a Area> {
+    let mut descendants: Vec<usize> = tree.descendants(root).into_iter().collect();
+    descendants.sort_unstable();
+    std::iter::once(root)
+        .chain(descendants)
+        .map(|index| tree.node(index))
+        .collect()
+}
+
+fn points_to_any(reference: &FileReference, areas: &[&Area]) -> bool {
+    areas
+        .iter()
+        .any(|area| reference.points_to(&area.path, &area.title))
+}
+
+impl Taskdn {
+    /// Get the direct sub-areas of an area.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the parent area file
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the area doesn't exist.
+    /// Returns an error if the areas directory cannot be read.
+    pub fn get_subareas(&self, path: impl AsRef<Path>) -> Result<Vec<Area>> {
+        let area = self.get_area(path)?;
+        let areas = self.list_areas(&AreaFilter::new())?;
+        let tree = AreaTree::new(&areas);
+
+        Ok(match tree.position(&area.path) {
+            Some(index) => tree.children(index).map(|i| tree.node(i).clone()).collect(),
+            None => Vec::new(),
+        })
+    }
+
+    /// Get the parent of an area.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the sub-area file
+    ///
+    /// # Returns
+    /// `None` if the area has no `parent`.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the area doesn't exist.
+    /// Returns `Error::UnresolvedReference` if no area matches its `parent`.
+    pub fn get_parent_area(&self, path: impl AsRef<Path>) -> Result<Option<Area>> {
+        let area = self.get_area(path)?;
+        let Some(reference) = &area.parent else {
+            return Ok(None);
+        };
+
+        let areas = self.list_areas(&AreaFilter::new())?;
+        let tree = AreaTree::new(&areas);
+        tree.position(&area.path)
+            .and_then(|index| tree.parent(index))
+            .map(|parent| Some(tree.node(parent).clone()))
+            .ok_or_else(|| Error::UnresolvedReference {
+                reference: reference.to_string(),
+            })
+    }
+
+    /// Get the projects assigned to an area or any of its sub-areas.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the area file
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the area doesn't exist.
+    /// Returns an error if the areas or projects directory cannot be read.
+    pub fn get_projects_for_area_subtree(&self, path: impl AsRef<Path>) -> Result<Vec<Project>> {
+        let area = self.get_area(path)?;
+        let areas = self.list_areas(&AreaFilter::new())?;
+        let projects = self.list_projects(&ProjectFilter::new())?;
+        let scope = AreaScope::of(&area, &areas, &projects);
+
+        Ok(scope
+            .projects()
+            .iter()
+            .map(|&project| project.clone())
+            .collect())
+    }
+
+    /// Get the tasks assigned to an area or any of its sub-areas, directly or
+    /// via a project, including archived tasks.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the area file
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the area doesn't exist.
+    /// Returns an error if the tasks, projects or areas directory cannot be read.
+    pub fn get_tasks_for_area_subtree(&self, path: impl AsRef<Path>) -> Result<Vec<Task>> {
+        let area = self.get_area(path)?;
+        let areas = self.list_areas(&AreaFilter::new())?;
+        let projects = self.list_projects(&ProjectFilter::new())?;
+        let scope = AreaScope::of(&area, &areas, &projects);
+
+        let mut tasks = self.list_tasks(&TaskFilter::new().include_archive_dir())?;
+        tasks.retain(|task| scope.contains_task(task));
+        Ok(tasks)
+    }
+
+    /// Get validation warnings for an area, including cycles through its
+    /// `parent`.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the area doesn't exist.
+    /// Returns an error if the areas directory cannot be read.
+    pub fn get_area_warnings(&self, path: impl AsRef<Path>) -> Result<Vec<ValidationWarning>> {
+        let area = self.get_area(path)?;
+        let areas = self.list_areas(&AreaFilter::new())?;
+        let tree = AreaTree::new(&areas);
+
+        let in_cycle = tree
+            .position(&area.path)
+            .is_some_and(|index| tree.in_cycle(index));
+        let mut warnings = area.validate();
+        if in_cycle {
+            warnings.push(ValidationWarning::ParentCycle);
+        }
+        Ok(warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{setup_test_env, write_file};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn area(temp: &TempDir, name: &str, parent: Option<&str>) {
+        let parent = parent.map_or(String::new(), |p| format!("parent: \"[[{p}]]\"\n"));
+        let content = format!("---\ntitle: {name}\n{parent}---\n");
+        write_file(temp, &format!("areas/{name}.md"), &content);
+    }
+
+    fn project(temp: &TempDir, name: &str, area: &str) {
+        let content = format!("---\ntitle: {name}\nstatus: ready\narea: \"[[{area}]]\"\n---\n");
+        write_file(temp, &format!("projects/{name}.md"), &content);
+    }
+
+    fn task(temp: &TempDir, path: &str, field: &str, target: &str) {
+        let content = format!(
+            "---\ntitle: {path}\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n{field}: \"[[{target}]]\"\n---\n"
+        );
+        write_file(temp, &format!("tasks/{path}"), &content);
+    }
+
+    /// work > acme > acme-support, plus an unrelated home area.
+    fn vault(temp: &TempDir) {
+        area(temp, "work", None);
+        area(temp, "acme", Some("work"));
+        area(temp, "acme-support", Some("acme"));
+        area(temp, "home", None);
+        project(temp, "website", "acme");
+        project(temp, "hiring", "work");
+        project(temp, "garden", "home");
+        task(temp, "invoice.md", "area", "acme-support");
+        task(temp, "archive/deploy.md", "project", "website");
+        task(temp, "interview.md", "project", "hiring");
+        task(temp, "weed.md", "project", "garden");
+        task(temp, "laundry.md", "area", "home");
+    }
+
+    fn titles<T>(items: &[T], title: impl Fn(&T) -> &str) -> Vec<String> {
+        let mut titles: Vec<String> = items.iter().map(|i| title(i).to_string()).collect();
+        titles.sort_unstable();
+        titles
+    }
+
+    #[test]
+    fn subareas_and_parent() {
+        let (temp, taskdn) = setup_test_env();
+        vault(&temp);
+
+        let subareas = taskdn.get_subareas("work.md").unwrap();
+        assert_eq!(titles(&subareas, |a| &a.title), ["acme"]);
+        assert!(taskdn.get_subareas("home.md").unwrap().is_empty());
+
+        let parent = taskdn.get_parent_area("acme-support.md").unwrap().unwrap();
+        assert_eq!(parent.title, "acme");
+        assert!(taskdn.get_parent_area("work.md").unwrap().is_none());
+    }
+
+    #[test]
+    fn unresolved_parent_is_an_error() {
+        let (temp, taskdn) = setup_test_env();
+        area(&temp, "orphan", Some("missing"));
+
+        assert!(matches!(
+            taskdn.get_parent_area("orphan.md"),
+            Err(Error::UnresolvedReference { .. })
+        ));
+    }
+
+    #[test]
+    fn malformed_parent_is_kept_with_a_warning() {
+        let (temp, taskdn) = setup_test_env();
+        area(&temp, "work", None);
+        fs::write(
+            temp.path().join("areas/acme.md"),
+            "---\ntitle: acme\nparent:\n  - \"[[work]]\"\n---\n",
+        )
+        .unwrap();
+
+        let acme = taskdn.get_area("acme.md").unwrap();
+        assert_eq!(acme.parent, None);
+        assert!(acme.extra.contains_key("parent"));
+        assert!(taskdn.get_parent_area("acme.md").unwrap().is_none());
+        assert_eq!(
+            taskdn.get_area_warnings("acme.md").unwrap(),
+            [ValidationWarning::InvalidFieldValue { field: "parent" }]
+        );
+    }
+
+    #[test]
+    fn subtree_queries() {
+        let (temp, taskdn) = setup_test_env();
+        vault(&temp);
+
+        let projects = taskdn.get_projects_for_area_subtree("work.md").unwrap();
+        assert_eq!(titles(&projects, |p| &p.title), ["hiring", "website"]);
+
+        let tasks = taskdn.get_tasks_for_area_subtree("work.md").unwrap();
+        assert_eq!(
+            titles(&tasks, |t| &t.title),
+            ["archive/deploy.md", "interview.md", "invoice.md"]
+        );
+
+        let tasks = taskdn.get_tasks_for_area_subtree("acme.md").unwrap();
+        assert_eq!(
+            titles(&tasks, |t| &t.title),
+            ["archive/deploy.md", "invoice.md"]
+        );
+
+        // Direct queries are unchanged
+        assert_eq!(taskdn.get_tasks_for_area("work.md").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn within_area_filters() {
+        let (temp, taskdn) = setup_test_env();
+        vault(&temp);
+
+        let areas = taskdn
+            .list_areas(&AreaFilter::new().within_area("[[acme]]"))
+            .unwrap();
+        assert_eq!(titles(&areas, |a| &a.title), ["acme", "acme-support"]);
+
+        let filter = TaskFilter::new().within_area("[[work]]");
+        let tasks = taskdn.list_tasks(&filter).unwrap();
+        assert_eq!(titles(&tasks, |t| &t.title), ["interview.md", "invoice.md"]);
+
+        let index = taskdn.build_index().unwrap();
+        let indexed: Vec<Task> = index.list_tasks(&filter).into_iter().cloned().collect();
+        assert_eq!(
+            titles(&indexed, |t| &t.title),
+            ["interview.md", "invoice.md"]
+        );
+        let indexed: Vec<Area> = index
+            .list_areas(&AreaFilter::new().within_area("[[acme]]"))
+            .into_iter()
+            .cloned()
+            .collect();
+        assert_eq!(titles(&indexed, |a| &a.title), ["acme", "acme-support"]);
+
+        let missing = TaskFilter::new().within_area("[[missing]]");
+        assert!(taskdn.list_tasks(&missing).unwrap().is_empty());
+    }
+
+    #[test]
+    fn parent_cycles_are_reported() {
+        let (temp, taskdn) = setup_test_env();
+        area(&temp, "a", Some("b"));
+        area(&temp, "b", Some("a"));
+        area(&temp, "self", Some("self"));
+        area(&temp, "c", Some("a"));
+
+        for path in ["a.md", "b.md", "self.md"] {
+            let warnings = taskdn.get_area_warnings(path).unwrap();
+            assert_eq!(warnings, [ValidationWarning::ParentCycle], "{path}");
+        }
+        assert!(taskdn.get_area_warnings("c.md").unwrap().is_empty());
+
+        let areas = taskdn
+            .list_areas(&AreaFilter::new().within_area("[[a]]"))
+            .unwrap();
+        assert_eq!(titles(&areas, |a| &a.title), ["a", "b", "c"]);
+    }
+}
@@ -0,0 +1,1248 @@
+//! Persistent parse cache.
+//!
+//! When [`TaskdnConfig::cache_path`](crate::TaskdnConfig::cache_path) is set,
+//! list operations store every parsed entity in a single binary file and reuse
+//! it on the next scan, so only files that changed are parsed again.
+//!
+//! Each record is keyed by path and remembers the file's modification time,
+//! size and content hash:
+//!
+//! - If the modification time and size are unchanged, the record is used
+//!   without reading the file.
+//! - Otherwise the file is read and hashed. If the content is unchanged
+//!   (for example after a `touch`), the record is still used.
+//! - Otherwise the file is parsed again.
+//!
+//! Modification times are only trusted once they are comfortably older than
+//! the scan that wrote the cache. A file edited during (or just before) a scan
+//! could otherwise keep a stale record on file systems with coarse timestamps.
+//!
+//! The cache is best effort. A missing, corrupt, or outdated cache file is
+//! ignored and rebuilt by a full scan, and failures to save it never fail the
+//! list operation.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{Datelike, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use rayon::prelude::*;
+
+use crate::error::{Error, Result};
+use crate::types::{
+    Area, AreaStatus, DateTimeValue, FileReference, ParsedArea, ParsedProject, ParsedTask,
+    Priority, Project, ProjectStatus, Tag, Task, TaskStatus,
+};
+use crate::utils::content_hash;
+use crate::writer::atomic_write;
+use crate::Taskdn;
+
+/// Identifies a taskdn cache file.
+const MAGIC: &[u8; 8] = b"TASKDNC\0";
+
+/// Bump whenever the encoding of any record changes.
+const FORMAT_VERSION: u32 = 8;
+
+/// How much older than the last scan a modification time must be before it is
+/// trusted without reading the file.
+const SETTLE_SECS: u64 = 2;
+
+impl Taskdn {
+    /// Read and parse each file in `paths`, using the parse cache if configured.
+    ///
+    /// Returns one result per path, in the same order. `dirs` lists the
+    /// directories that were scanned to produce `paths`; cached records for
+    /// files that have disappeared from them are dropped.
+    pub(crate) fn parse_files<T: Cacheable>(
+        &self,
+        paths: &[PathBuf],
+        dirs: &[PathBuf],
+    ) -> Vec<Result<T>> {
+        let Some(cache_path) = &self.config.cache_path else {
+            return paths.par_iter().map(|path| read_and_parse(path)).collect();
+        };
+
+        let scan_started = Timestamp::now();
+        let mut cache = CacheFile::load(cache_path);
+
+        let (results, outcomes): (Vec<Result<T>>, Vec<Outcome>) = paths
+            .par_iter()
+            .map(|path| lookup(path, cache.entries.get(path), cache.saved_at))
+            .unzip();
+
+        let mut changed = false;
+        for (path, outcome) in paths.iter().zip(outcomes) {
+            match outcome {
+                Outcome::Unchanged => {}
+                Outcome::Updated(entry) => {
+                    cache.entries.insert(path.clone(), entry);
+                    changed = true;
+                }
+                Outcome::Uncached => changed |= cache.entries.remove(path).is_some(),
+            }
+        }
+
+        // Forget files of this kind that no longer exist in the scanned directories
+        let seen: HashSet<&PathBuf> = paths.iter().collect();
+        let before = cache.entries.len();
+        cache.entries.retain(|path, entry| {
+            entry.kind != T::KIND
+                || seen.contains(path)
+                || !path
+                    .parent()
+                    .is_some_and(|parent| dirs.iter().any(|dir| dir == parent))
+        });
+        changed |= cache.entries.len() != before;
+
+        if changed {
+            cache.saved_at = scan_started;
+            // Best effort: a cache that can't be saved just means a slower next scan
+            let _ = cache.save(cache_path);
+        }
+
+        results
+    }
+}
+
+/// An entity that can be stored in the parse cache.
+pub(crate) trait Cacheable: Sized + Send {
+    /// Distinguishes records of different entity types in the cache file.
+    const KIND: u8;
+
+    /// Parse file content read from `path`.
+    fn parse(path: &Path, content: &str) -> Result<Self>;
+
+    /// Append the entity (without its path) to `out`.
+    fn encode(&self, out: &mut Encoder);
+
+    /// Decode an entity written by [`Cacheable::encode`].
+    fn decode(input: &mut Decoder<'_>, path: &Path) -> Option<Self>;
+}
+
+/// Read and parse a single file without consulting the cache.
+fn read_and_parse<T: Cacheable>(path: &Path) -> Result<T> {
+    let content = fs::read_to_string(path).map_err(|_| not_found(path))?;
+    T::parse(path, &content)
+}
+
+fn not_found(path: &Path) -> Error {
+    Error::NotFound {
+        path: path.to_path_buf(),
+    }
+}
+
+/// What to do with a file's cache record after looking it up.
+enum Outcome {
+    /// The record is still valid.
+    Unchanged,
+    /// The record should be replaced.
+    Updated(Entry),
+    /// The file can't be cached (unreadable, invalid, or no modification time).
+    Uncached,
+}
+
+/// Resolve a single file, from its cache record if still valid.
+fn lookup<T: Cacheable>(
+    path: &Path,
+    cached: Option<&Entry>,
+    saved_at: Timestamp,
+) -> (Result<T>, Outcome) {
+    let cached = cached.filter(|entry| entry.kind == T::KIND);
+    let Ok(metadata) = fs::metadata(path) else {
+        return (Err(not_found(path)), Outcome::Uncached);
+    };
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(Timestamp::from_system_time);
+
+    // Fast path: unchanged metadata, no need to read the file
+    if let (Some(entry), Some(modified)) = (cached, modified) {
+        if entry.modified == modified && entry.size == metadata.len() && entry.is_settled(saved_at)
+        {
+            if let Some(entity) = entry.decode(path) {
+                return (Ok(entity), Outcome::Unchanged);
+            }
+        }
+    }
+
+    let Ok(content) = fs::read_to_string(path) else {
+        return (Err(not_found(path)), Outcome::Uncached);
+    };
+    let size = content.len() as u64;
+    let hash = content_hash(content.as_bytes());
+
+    // Metadata changed but the content didn't
+    if let (Some(entry), Some(modified)) = (cached, modified) {
+        if entry.size == size && entry.hash == hash {
+            if let Some(entity) = entry.decode(path) {
+                let entry = Entry {
+                    modified,
+                    ..entry.clone()
+                };
+                return (Ok(entity), Outcome::Updated(entry));
+            }
+        }
+    }
+
+    match T::parse(path, &content) {
+        Ok(entity) => {
+            let outcome = modified.map_or(Outcome::Uncached, |modified| {
+                Outcome::Updated(Entry::new(&entity, modified, size, hash))
+            });
+            (Ok(entity), outcome)
+        }
+        Err(e) => (Err(e), Outcome::Uncached),
+    }
+}
+
+// =============================================================================
+// Cache File
+// =============================================================================
+
+/// A point in time with nanosecond precision, as stored in the cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Timestamp {
+    secs: u64,
+    nanos: u32,
+}
+
+impl Timestamp {
+    fn now() -> Self {
+        Self::from_system_time(SystemTime::now()).unwrap_or_default()
+    }
+
+    fn from_system_time(time: SystemTime) -> Option<Self> {
+        let duration = time.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            secs: duration.as_secs(),
+            nanos: duration.subsec_nanos(),
+        })
+    }
+}
+
+/// A cached entity together with the file metadata it was parsed from.
+#[derive(Debug, Clone)]
+struct Entry {
+    kind: u8,
+    modified: Timestamp,
+    size: u64,
+    hash: u64,
+    payload: Vec<u8>,
+}
+
+impl Entry {
+    fn new<T: Cacheable>(entity: &T, modified: Timestamp, size: u64, hash: u64) -> Self {
+        let mut out = Encoder::default();
+        entity.encode(&mut out);
+        Self {
+            kind: T::KIND,
+            modified,
+            size,
+            hash,
+            payload: out.buf,
+        }
+    }
+
+    /// Whether the file's modification time is old enough to be trusted.
+    fn is_settled(&self, saved_at: Timestamp) -> bool {
+        self.modified.secs.saturating_add(SETTLE_SECS) < saved_at.secs
+    }
+
+    fn decode<T: Cacheable>(&self, path: &Path) -> Option<T> {
+        let mut input = Decoder::new(&self.payload);
+        let entity = T::decode(&mut input, path)?;
+        input.is_empty().then_some(entity)
+    }
+}
+
+/// The contents of a cache file.
+///
+/// Layout: magic, format version, the start time of the scan that wrote it,
+/// the records, and a trailing checksum over everything before it.
+#[derive(Debug, Default)]
+struct CacheFile {
+    saved_at: Timestamp,
+    entries: HashMap<PathBuf, Entry>,
+}
+
+impl CacheFile {
+    /// Load a cache file, returning an empty cache if it is missing or unusable.
+    fn load(path: &Path) -> Self {
+        fs::read(path)
+            .ok()
+            .and_then(|bytes| Self::decode(&bytes))
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        atomic_write(path, self.encode())
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut out = Encoder::default();
+        out.buf.extend_from_slice(MAGIC);
+        out.u32(FORMAT_VERSION);
+        out.timestamp(self.saved_at);
+
+        // Paths that aren't valid UTF-8 are simply not cached
+        let entries: Vec<(&str, &Entry)> = self
+            .entries
+            .iter()
+            .filter_map(|(path, entry)| Some((path.to_str()?, entry)))
+            .collect();
+        out.len(entries.len());
+        for (path, entry) in entries {
+            out.str(path);
+            out.u8(entry.kind);
+            out.timestamp(entry.modified);
+            out.u64(entry.size);
+            out.u64(entry.hash);
+            out.bytes(&entry.payload);
+        }
+
+        let checksum = content_hash(&out.buf);
+        out.u64(checksum);
+        out.buf
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let (body, checksum) = bytes.split_at(bytes.len().checked_sub(8)?);
+        if content_hash(body) != Decoder::new(checksum).u64()? {
+            return None;
+        }
+
+        let mut input = Decoder::new(body);
+        if input.take(MAGIC.len())? != MAGIC || input.u32()? != FORMAT_VERSION {
+            return None;
+        }
+        let saved_at = input.timestamp()?;
+
+        let count = input.len()?;
+        let mut entries = HashMap::new();
+        for _ in 0..count {
+            let path = PathBuf::from(input.str()?);
+            let entry = Entry {
+                kind: input.u8()?,
+                modified: input.timestamp()?,
+                size: input.u64()?,
+                hash: input.u64()?,
+                payload: input.bytes()?.to_vec(),
+            };
+            entries.insert(path, entry);
+        }
+
+        input.is_empty().then_some(Self { saved_at, entries })
+    }
+}
+
+// =============================================================================
+// Encoding
+// =============================================================================
+
+/// Writes primitive values in a compact little-endian format.
+#[derive(Debug, Default)]
+pub(crate) struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    fn u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u64(&mut self, value: u64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn i32(&mut self, value: i32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn i64(&mut self, value: i64) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn len(&mut self, len: usize) {
+        self.u64(len as u64);
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.len(bytes.len());
+        self.buf.extend_from_slice(bytes);
+    }
+
+    fn str(&mut self, value: &str) {
+        self.bytes(value.as_bytes());
+    }
+
+    fn bool(&mut self, value: bool) {
+        self.u8(u8::from(value));
+    }
+
+    fn option<T>(&mut self, value: Option<&T>, mut write: impl FnMut(&mut Self, &T)) {
+        self.bool(value.is_some());
+        if let Some(value) = value {
+            write(self, value);
+        }
+    }
+
+    fn timestamp(&mut self, value: Timestamp) {
+        self.u64(value.secs);
+        self.u32(value.nanos);
+    }
+
+    fn date(&mut self, value: NaiveDate) {
+        self.i32(value.num_days_from_ce());
+    }
+
+    fn datetime_value(&mut self, value: &DateTimeValue) {
+        match value {
+            DateTimeValue::Date(date) => {
+                self.u8(0);
+                self.date(*date);
+            }
+            DateTimeValue::DateTime(datetime) => {
+                self.u8(1);
+                self.naive_datetime(datetime);
+            }
+            DateTimeValue::DateTimeWithOffset(datetime) => {
+                self.u8(2);
+                self.naive_datetime(&datetime.naive_local());
+                self.i32(datetime.offset().local_minus_utc());
+            }
+        }
+    }
+
+    fn naive_datetime(&mut self, value: &NaiveDateTime) {
+        self.date(value.date());
+        self.u32(value.num_seconds_from_midnight());
+        self.u32(value.nanosecond());
+    }
+
+    fn tags(&mut self, tags: &[Tag]) {
+        self.len(tags.len());
+        for tag in tags {
+            self.str(tag.as_str());
+        }
+    }
+
+    fn reference(&mut self, value: &FileReference) {
+        match value {
+            FileReference::WikiLink { target, display } => {
+                self.u8(0);
+                self.str(target);
+                self.option(display.as_ref(), |out, d| out.str(d));
+            }
+            FileReference::RelativePath(path) => {
+                self.u8(1);
+                self.str(path);
+            }
+            FileReference::Filename(name) => {
+                self.u8(2);
+                self.str(name);
+            }
+        }
+    }
+
+    fn references(&mut self, references: &[FileReference]) {
+        self.len(references.len());
+        for reference in references {
+            self.reference(reference);
+        }
+    }
+
+    fn extra(&mut self, extra: &HashMap<String, serde_yaml::Value>) {
+        self.len(extra.len());
+        for (key, value) in extra {
+            self.str(key);
+            self.yaml(value);
+        }
+    }
+
+    fn yaml(&mut self, value: &serde_yaml::Value) {
+        use serde_yaml::Value;
+
+        match value {
+            Value::Null => self.u8(0),
+            Value::Bool(b) => {
+                self.u8(1);
+                self.bool(*b);
+            }
+            Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    self.u8(2);
+                    self.i64(i);
+                } else if let Some(u) = n.as_u64() {
+                    self.u8(3);
+                    self.u64(u);
+                } else {
+                    self.u8(4);
+                    self.u64(n.as_f64().unwrap_or_default().to_bits());
+                }
+            }
+            Value::String(s) => {
+                self.u8(5);
+                self.str(s);
+            }
+            Value::Sequence(items) => {
+                self.u8(6);
+                self.len(items.len());
+                for item in items {
+                    self.yaml(item);
+                }
+            }
+            Value::Mapping(mapping) => {
+                self.u8(7);
+                self.len(mapping.len());
+                for (key, value) in mapping {
+                    self.yaml(key);
+                    self.yaml(value);
+                }
+            }
+            // Rare enough to store as YAML text
+            Value::Tagged(_) => {
+                self.u8(8);
+                self.str(&serde_yaml::to_string(value).unwrap_or_default());
+            }
+        }
+    }
+}
+
+/// Reads values written by [`Encoder`]. Every method returns `None` on
+/// malformed or truncated input.
+#[derive(Debug)]
+pub(crate) struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        if len > self.data.len() {
+            return None;
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Some(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.array().map(u32::from_le_bytes)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        self.array().map(u64::from_le_bytes)
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.array().map(i32::from_le_bytes)
+    }
+
+    fn i64(&mut self) -> Option<i64> {
+        self.array().map(i64::from_le_bytes)
+    }
+
+    fn len(&mut self) -> Option<usize> {
+        usize::try_from(self.u64()?).ok()
+    }
+
+    fn bytes(&mut self) -> Option<&'a [u8]> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn str(&mut self) -> Option<String> {
+        String::from_utf8(self.bytes()?.to_vec()).ok()
+    }
+
+    fn bool(&mut self) -> Option<bool> {
+        match self.u8()? {
+            0 => Some(false),
+            1 => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Read an optional value. The outer `None` means malformed input.
+    #[allow(clippy::option_option)]
+    fn option<T>(&mut self, read: impl FnOnce(&mut Self) -> Option<T>) -> Option<Option<T>> {
+        if self.bool()? {
+            read(self).map(Some)
+        } else {
+            Some(None)
+        }
+    }
+
+    fn timestamp(&mut self) -> Option<Timestamp> {
+        Some(Timestamp {
+            secs: self.u64()?,
+            nanos: self.u32()?,
+        })
+    }
+
+    fn date(&mut self) -> Option<NaiveDate> {
+        NaiveDate::from_num_days_from_ce_opt(self.i32()?)
+    }
+
+    fn datetime_value(&mut self) -> Option<DateTimeValue> {
+        match self.u8()? {
+            0 => self.date().map(DateTimeValue::Date),
+            1 => self.naive_datetime().map(DateTimeValue::DateTime),
+            2 => {
+                let datetime = self.naive_datetime()?;
+                let offset = FixedOffset::east_opt(self.i32()?)?;
+                datetime
+                    .and_local_timezone(offset)
+                    .single()
+                    .map(DateTimeValue::DateTimeWithOffset)
+            }
+            _ => None,
+        }
+    }
+
+    fn naive_datetime(&mut self) -> Option<NaiveDateTime> {
+        let date = self.date()?;
+        let time = NaiveTime::from_num_seconds_from_midnight_opt(self.u32()?, self.u32()?)?;
+        Some(date.and_time(time))
+    }
+
+    fn tags(&mut self) -> Option<Vec<Tag>> {
+        let count = self.len()?;
+        (0..count).map(|_| Tag::new(&self.str()?)).collect()
+    }
+
+    fn reference(&mut self) -> Option<FileReference> {
+        match self.u8()? {
+            0 => Some(FileReference::WikiLink {
+                target: self.str()?,
+                display: self.option(Self::str)?,
+            }),
+            1 => self.str().map(FileReference::RelativePath),
+            2 => self.str().map(FileReference::Filename),
+            _ => None,
+        }
+    }
+
+    fn references(&mut self) -> Option<Vec<FileReference>> {
+        let count = self.len()?;
+        (0..count).map(|_| self.reference()).collect()
+    }
+
+    fn extra(&mut self) -> Option<HashMap<String, serde_yaml::Value>> {
+        let count = self.len()?;
+        (0..count)
+            .map(|_| Some((self.str()?, self.yaml()?)))
+            .collect()
+    }
+
+    fn yaml(&mut self) -> Option<serde_yaml::Value> {
+        use serde_yaml::Value;
+
+        Some(match self.u8()? {
+            0 => Value::Null,
+            1 => Value::Bool(self.bool()?),
+            2 => Value::Number(self.i64()?.into()),
+            3 => Value::Number(self.u64()?.into()),
+            4 => Value::Number(f64::from_bits(self.u64()?).into()),
+            5 => Value::String(self.str()?),
+            6 => {
+                let count = self.len()?;
+                Value::Sequence((0..count).map(|_| self.yaml()).collect::<Option<_>>()?)
+            }
+            7 => {
+                let count = self.len()?;
+                Value::Mapping(
+                    (0..count)
+                        .map(|_| Some((self.yaml()?, self.yaml()?)))
+                        .collect::<Option<_>>()?,
+                )
+            }
+            8 => serde_yaml::from_str(&self.str()?).ok()?,
+            _ => return None,
+        })
+    }
+}
+
+// =============================================================================
+// Entity Records
+// =============================================================================
+
+impl Cacheable for Task {
+    const KIND: u8 = 0;
+
+    fn parse(path: &Path, content: &str) -> Result<Self> {
+        Ok(ParsedTask::parse(content)
+            .map_err(|e| e.with_path(path))?
+            .with_path(path))
+    }
+
+    fn encode(&self, out: &mut Encoder) {
+        out.str(&self.title);
+        out.str(self.status.as_str());
+        out.datetime_value(&self.created_at);
+        out.datetime_value(&self.updated_at);
+        out.option(self.completed_at.as_ref(), Encoder::datetime_value);
+        out.option(self.due.as_ref(), Encoder::datetime_value);
+        out.option(self.scheduled.as_ref(), |out, date| out.date(*date));
+        out.option(self.defer_until.as_ref(), |out, date| out.date(*date));
+        out.option(self.project.as_ref(), Encoder::reference);
+        out.option(self.area.as_ref(), Encoder::reference);
+        out.option(self.parent.as_ref(), Encoder::reference);
+        out.references(&self.blocked_by);
+        out.option(self.priority.as_ref(), |out, priority| {
+            out.yaml(&priority.to_yaml());
+        });
+        out.option(self.recurrence.as_ref(), |out, rule| {
+            out.str(&rule.to_string());
+        });
+        out.option(self.series.as_ref(), |out, series| out.str(series));
+        out.tags(&self.tags);
+        out.str(&self.body);
+        out.extra(&self.extra);
+        out.option(self.projects_count.as_ref(), |out, count| out.len(*count));
+    }
+
+    fn decode(input: &mut Decoder<'_>, path: &Path) -> Option<Self> {
+        Some(Self {
+            path: path.to_path_buf(),
+            title: input.str()?,
+            status: input.str()?.parse::<TaskStatus>().ok()?,
+            created_at: input.datetime_value()?,
+            updated_at: input.datetime_value()?,
+            completed_at: input.option(Decoder::datetime_value)?,
+            due: input.option(Decoder::datetime_value)?,
+            scheduled: input.option(Decoder::date)?,
+            defer_until: input.option(Decoder::date)?,
+            project: input.option(Decoder::reference)?,
+            area: input.option(Decoder::reference)?,
+            parent: input.option(Decoder::reference)?,
+            blocked_by: input.references()?,
+            priority: input.option(|input| Priority::from_yaml(&input.yaml()?))?,
+            recurrence: input.option(|input| input.str()?.parse().ok())?,
+            series: input.option(Decoder::str)?,
+            tags: input.tags()?,
+            body: input.str()?,
+            extra: input.extra()?,
+            projects_count: input.option(Decoder::len)?,
+        })
+    }
+}
+
+impl Cacheable for Project {
+    const KIND: u8 = 1;
+
+    fn parse(path: &Path, content: &str) -> Result<Self> {
+        Ok(ParsedProject::parse(content)
+            .map_err(|e| e.with_path(path))?
+            .with_path(path))
+    }
+
+    fn encode(&self, out: &mut Encoder) {
+        out.str(&self.title);
+        out.option(self.unique_id.as_ref(), |out, id| out.str(id));
+        out.option(self.status.as_ref(), |out, status| out.str(status.as_str()));
+        out.option(self.description.as_ref(), |out, d| out.str(d));
+        out.option(self.area.as_ref(), Encoder::reference);
+        out.option(self.start_date.as_ref(), |out, date| out.date(*date));
+        out.option(self.end_date.as_ref(), |out, date| out.date(*date));
+        out.references(&self.blocked_by);
+        out.tags(&self.tags);
+        out.str(&self.body);
+        out.extra(&self.extra);
+    }
+
+    fn decode(input: &mut Decoder<'_>, path: &Path) -> Option<Self> {
+        Some(Self {
+            path: path.to_path_buf(),
+            title: input.str()?,
+            unique_id: input.option(Decoder::str)?,
+            status: input.option(|input| input.str()?.parse::<ProjectStatus>().ok())?,
+            description: input.option(Decoder::str)?,
+            area: input.option(Decoder::reference)?,
+            start_date: input.option(Decoder::date)?,
+            end_date: input.option(Decoder::date)?,
+            blocked_by: input.references()?,
+            tags: input.tags()?,
+            body: input.str()?,
+            extra: input.extra()?,
+        })
+    }
+}
+
+impl Cacheable for Area {
+    const KIND: u8 = 2;
+
+    fn parse(path: &Path, content: &str) -> Result<Self> {
+        Ok(ParsedArea::parse(content)
+            .map_err(|e| e.with_path(path))?
+            .with_path(path))
+    }
+
+    fn encode(&self, out: &mut Encoder) {
+        out.str(&self.title);
+        out.option(self.status.as_ref(), |out, status| out.str(status.as_str()));
+        out.option(self.area_type.as_ref(), |out, t| out.str(t));
+        out.option(self.description.as_ref(), |out, d| out.str(d));
+        out.option(self.parent.as_ref(), Encoder::reference);
+        out.tags(&self.tags);
+        out.str(&self.body);
+        out.extra(&self.extra);
+    }
+
+    fn decode(input: &mut Decoder<'_>, path: &Path) -> Option<Self> {
+        Some(Self {
+            path: path.to_path_buf(),
+            title: input.str()?,
+            status: input.option(|input| input.str()?.parse::<AreaStatus>().ok())?,
+            area_type: input.option(Decoder::str)?,
+            description: input.option(Decoder::str)?,
+            parent: input.option(Decoder::reference)?,
+            tags: input.tags()?,
+            body: input.str()?,
+            extra: input.extra()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TaskdnConfig;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn setup_test_env() -> (TempDir, Taskdn) {
+        let temp = TempDir::new().unwrap();
+        let tasks_dir = temp.path().join("tasks");
+        let projects_dir = temp.path().join("projects");
+        let areas_dir = temp.path().join("areas");
+
+        fs::create_dir_all(&tasks_dir).unwrap();
+        fs::create_dir_all(&projects_dir).unwrap();
+        fs::create_dir_all(&areas_dir).unwrap();
+
+        let config = TaskdnConfig::new(tasks_dir, projects_dir, areas_dir)
+            .with_cache_path(temp.path().join(".taskdn/cache.bin"));
+        let sdk = Taskdn::new(config).unwrap();
+        (temp, sdk)
+    }
+
+    fn cache_path(sdk: &Taskdn) -> PathBuf {
+        sdk.config().cache_path.clone().unwrap()
+    }
+
+    fn write_task(sdk: &Taskdn, filename: &str, title: &str) -> PathBuf {
+        let path = sdk.config().tasks_dir.join(filename);
+        let content = format!(
+            "---\ntitle: {title}\nstatus: ready\ncreated-at: 2025-01-01T09:30:00\nupdated-at: 2025-01-02\n---\n\nBody of {title}.\n"
+        );
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    /// Move a file's modification time into the past so the cache trusts it.
+    fn backdate(path: &Path) {
+        let past = SystemTime::now() - Duration::from_secs(60);
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(past)
+            .unwrap();
+    }
+
+    /// Rewrite every cached task record with a different title, so tests can
+    /// tell whether a result came from the cache or from the file.
+    fn poison_cached_titles(sdk: &Taskdn) {
+        let path = cache_path(sdk);
+        let mut cache = CacheFile::load(&path);
+        for (file, entry) in &mut cache.entries {
+            let mut task: Task = entry.decode(file).unwrap();
+            task.title = format!("cached {}", task.title);
+            *entry = Entry {
+                payload: Entry::new(&task, entry.modified, entry.size, entry.hash).payload,
+                ..entry.clone()
+            };
+        }
+        cache.save(&path).unwrap();
+    }
+
+    fn titles(sdk: &Taskdn) -> Vec<String> {
+        let mut titles: Vec<String> = sdk
+            .list_tasks(&crate::TaskFilter::new())
+            .unwrap()
+            .into_iter()
+            .map(|t| t.title)
+            .collect();
+        titles.sort();
+        titles
+    }
+
+    mod records {
+        use super::*;
+
+        #[test]
+        fn task_round_trips() {
+            let content = "---\ntitle: Full\nstatus: in-progress\ncreated-at: 2025-01-01T09:30:00\nupdated-at: 2025-01-02\ncompleted-at: 2025-01-03T18:00:00+05:30\ndue: 2025-02-01T17:00:00\nscheduled: 2025-01-15\ndefer-until: 2025-01-10\nproject: \"[[Q1 Planning|Q1]]\"\narea: ./areas/work.md\nparent: \"[[Epic]]\"\nblocked-by:\n  - \"[[Design]]\"\n  - spec.md\nrecurrence: FREQ=WEEKLY;BYDAY=MO,FR\nseries: full\npriority: high\ntags:\n  - a\n  - b\nestimate: 2.5\noffset: -3\nmeta:\n  nested: true\n  none: null\ntagged: !custom value\n---\n\nBody\n";
+            let task = Task::parse(Path::new("/tasks/full.md"), content).unwrap();
+
+            let entry = Entry::new(&task, Timestamp::default(), 0, 0);
+            let decoded: Task = entry.decode(Path::new("/tasks/full.md")).unwrap();
+            assert_eq!(decoded, task);
+        }
+
+        #[test]
+        fn project_round_trips() {
+            let content = "---\ntitle: Launch\nunique-id: L1\nstatus: in-progress\ndescription: Ship it\narea: \"[[Work]]\"\nstart-date: 2025-01-01\nend-date: 2025-03-01\nblocked-by:\n  - \"[[Other]]\"\n  - other.md\ncustom: 3\n---\nBody\n";
+            let project = Project::parse(Path::new("/projects/launch.md"), content).unwrap();
+
+            let entry = Entry::new(&project, Timestamp::default(), 0, 0);
+            let decoded: Project = entry.decode(Path::new("/projects/launch.md")).unwrap();
+            assert_eq!(decoded, project);
+        }
+
+        #[test]
+        fn area_round_trips() {
+            let content = "---\ntitle: Work\nstatus: active\ntype: professional\ndescription: Job\nparent: \"[[Life]]\"\n---\nBody\n";
+            let area = Area::parse(Path::new("/areas/work.md"), content).unwrap();
+
+            let entry = Entry::new(&area, Timestamp::default(), 0, 0);
+            let decoded: Area = entry.decode(Path::new("/areas/work.md")).unwrap();
+            assert_eq!(decoded, area);
+        }
+
+        fn round_trip<T: Cacheable>(entity: &T, path: &Path) -> T {
+            let entry = Entry::new(entity, Timestamp::default(), 0, 0);
+            entry.decode(path).unwrap()
+        }
+
+        fn tags(tags: &[Tag]) -> Vec<&str> {
+            tags.iter().map(Tag::as_str).collect()
+        }
+
+        fn extra() -> HashMap<String, serde_yaml::Value> {
+            serde_yaml::from_str(
+                "estimate: 2.5
+meta: {nested: [1, null]}
+tagged: !custom value",
+            )
+            .unwrap()
+        }
+
+        // The literals below list every field without `..`, so adding a field
+        // to an entity fails to compile until the codec and these tests cover it.
+
+        #[test]
+        fn task_with_every_field_round_trips() {
+            let path = Path::new("/tasks/full.md");
+            let task = Task {
+                path: path.to_path_buf(),
+                title: "Full".to_string(),
+                status: TaskStatus::InProgress,
+                created_at: "2025-01-01".parse().unwrap(),
+                updated_at: "2025-01-02T09:30:00".parse().unwrap(),
+                completed_at: Some("2025-01-03T18:00:00+05:30".parse().unwrap()),
+                due: Some("2025-02-01T17:00:00Z".parse().unwrap()),
+                scheduled: Some(NaiveDate::from_ymd_opt(2025, 1, 15).unwrap()),
+                defer_until: Some(NaiveDate::from_ymd_opt(2025, 1, 10).unwrap()),
+                project: Some(FileReference::WikiLink {
+                    target: "Q1 Planning".to_string(),
+                    display: Some("Q1".to_string()),
+                }),
+                area: Some(FileReference::RelativePath("./areas/work.md".to_string())),
+                parent: Some(FileReference::WikiLink {
+                    target: "Epic".to_string(),
+                    display: None,
+                }),
+                blocked_by: vec![
+                    FileReference::Filename("spec.md".to_string()),
+                    FileReference::RelativePath("./tasks/design.md".to_string()),
+                ],
+                tags: vec![Tag::new("Work/Client").unwrap(), Tag::new("home").unwrap()],
+                priority: Some(Priority::Other(serde_yaml::Value::Bool(false))),
+                recurrence: Some("FREQ=WEEKLY;BYDAY=MO,FR".parse().unwrap()),
+                series: Some("full".to_string()),
+                body: "Body\n\n- [ ] Item".to_string(),
+                extra: extra(),
+                projects_count: Some(2),
+            };
+
+            let decoded = round_trip(&task, path);
+            assert_eq!(decoded, task);
+            assert_eq!(tags(&decoded.tags), tags(&task.tags));
+        }
+
+        #[test]
+        fn project_with_every_field_round_trips() {
+            let path = Path::new("/projects/launch.md");
+            let project = Project {
+                path: path.to_path_buf(),
+                title: "Launch".to_string(),
+                unique_id: Some("L1".to_string()),
+                status: Some(ProjectStatus::Blocked),
+                description: Some("Ship it".to_string()),
+                area: Some(FileReference::Filename("work.md".to_string())),
+                start_date: Some(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()),
+                end_date: Some(NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()),
+                blocked_by: vec![FileReference::WikiLink {
+                    target: "Design".to_string(),
+                    display: Some("the design".to_string()),
+                }],
+                tags: vec![Tag::new("Launch").unwrap()],
+                body: "Body".to_string(),
+                extra: extra(),
+            };
+
+            let decoded = round_trip(&project, path);
+            assert_eq!(decoded, project);
+            assert_eq!(tags(&decoded.tags), tags(&project.tags));
+        }
+
+        #[test]
+        fn area_with_every_field_round_trips() {
+            let path = Path::new("/areas/work.md");
+            let area = Area {
+                path: path.to_path_buf(),
+                title: "Work".to_string(),
+                status: Some(AreaStatus::Archived),
+                area_type: Some("professional".to_string()),
+                description: Some("Job".to_string()),
+                parent: Some(FileReference::RelativePath("./areas/life.md".to_string())),
+                tags: vec![Tag::new("Job").unwrap()],
+                body: "Body".to_string(),
+                extra: extra(),
+            };
+
+            let decoded = round_trip(&area, path);
+            assert_eq!(decoded, area);
+            assert_eq!(tags(&decoded.tags), tags(&area.tags));
+        }
+
+        #[test]
+        fn truncated_payload_is_rejected() {
+            let task = Task::parse(
+                Path::new("/tasks/a.md"),
+                "---\ntitle: A\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\n",
+            )
+            .unwrap();
+            let mut entry = Entry::new(&task, Timestamp::default(), 0, 0);
+            entry.payload.truncate(entry.payload.len() - 1);
+
+            assert!(entry.decode::<Task>(Path::new("/tasks/a.md")).is_none());
+        }
+    }
+
+    mod cache_file {
+        use super::*;
+
+        #[test]
+        fn round_trips() {
+            let mut cache = CacheFile {
+                saved_at: Timestamp { secs: 10, nanos: 5 },
+                entries: HashMap::new(),
+            };
+            cache.entries.insert(
+                PathBuf::from("/tasks/a.md"),
+                Entry {
+                    kind: 1,
+                    modified: Timestamp { secs: 3, nanos: 4 },
+                    size: 42,
+                    hash: 7,
+                    payload: vec![1, 2, 3],
+                },
+            );
+
+            let decoded = CacheFile::decode(&cache.encode()).unwrap();
+            assert_eq!(decoded.saved_at, cache.saved_at);
+            let entry = &decoded.entries[Path::new("/tasks/a.md")];
+            assert_eq!(entry.size, 42);
+            assert_eq!(entry.payload, vec![1, 2, 3]);
+        }
+
+        #[test]
+        fn corrupt_data_is_rejected() {
+            let bytes = CacheFile::default().encode();
+
+            let mut flipped = bytes.clone();
+            flipped[10] ^= 0xff;
+            assert!(CacheFile::decode(&flipped).is_none());
+            assert!(CacheFile::decode(&bytes[..bytes.len() - 1]).is_none());
+            assert!(CacheFile::decode(b"").is_none());
+        }
+
+        #[test]
+        fn other_versions_are_rejected() {
+            let mut bytes = CacheFile::default().encode();
+            bytes[MAGIC.len()] = 99;
+            let body_len = bytes.len() - 8;
+            let checksum = content_hash(&bytes[..body_len]);
+            bytes[body_len..].copy_from_slice(&checksum.to_le_bytes());
+
+            assert!(CacheFile::decode(&bytes).is_none());
+        }
+    }
+
+    mod scanning {
+        use super::*;
+
+        #[test]
+        fn creates_cache_file() {
+            let (_temp, sdk) = setup_test_env();
+            write_task(&sdk, "a.md", "A");
+
+            assert_eq!(titles(&sdk), vec!["A"]);
+            let cache = CacheFile::load(&cache_path(&sdk));
+            assert_eq!(cache.entries.len(), 1);
+        }
+
+        #[test]
+        fn reuses_records_for_unchanged_files() {
+            let (_temp, sdk) = setup_test_env();
+            backdate(&write_task(&sdk, "a.md", "A"));
+            titles(&sdk);
+
+            poison_cached_titles(&sdk);
+            assert_eq!(titles(&sdk), vec!["cached A"]);
+        }
+
+        #[test]
+        fn reparses_changed_files() {
+            let (_temp, sdk) = setup_test_env();
+            backdate(&write_task(&sdk, "a.md", "A"));
+            backdate(&write_task(&sdk, "b.md", "B"));
+            titles(&sdk);
+            poison_cached_titles(&sdk);
+
+            write_task(&sdk, "b.md", "B2");
+            assert_eq!(titles(&sdk), vec!["B2", "cached A"]);
+        }
+
+        #[test]
+        fn touched_files_are_verified_by_hash() {
+            let (_temp, sdk) = setup_test_env();
+            let path = write_task(&sdk, "a.md", "A");
+            backdate(&path);
+            titles(&sdk);
+            poison_cached_titles(&sdk);
+
+            // New modification time, same content
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(SystemTime::now())
+                .unwrap();
+            assert_eq!(titles(&sdk), vec!["cached A"]);
+        }
+
+        #[test]
+        fn recently_modified_files_are_not_trusted() {
+            let (_temp, sdk) = setup_test_env();
+            // Not backdated: the modification time is too close to the scan
+            write_task(&sdk, "a.md", "A");
+            titles(&sdk);
+            poison_cached_titles(&sdk);
+
+            // Same size and same modification time, different content
+            let path = sdk.config().tasks_dir.join("a.md");
+            let modified = fs::metadata(&path).unwrap().modified().unwrap();
+            write_task(&sdk, "a.md", "Z");
+            fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+
+            assert_eq!(titles(&sdk), vec!["Z"]);
+        }
+
+        #[test]
+        fn deleted_files_are_dropped() {
+            let (_temp, sdk) = setup_test_env();
+            write_task(&sdk, "a.md", "A");
+            let b = write_task(&sdk, "b.md", "B");
+            titles(&sdk);
+
+            fs::remove_file(&b).unwrap();
+            assert_eq!(titles(&sdk), vec!["A"]);
+            let cache = CacheFile::load(&cache_path(&sdk));
+            assert_eq!(cache.entries.len(), 1);
+        }
+
+        #[test]
+        fn corrupt_cache_falls_back_to_full_scan() {
+            let (_temp, sdk) = setup_test_env();
+            write_task(&sdk, "a.md", "A");
+            titles(&sdk);
+
+            fs::write(cache_path(&sdk), b"garbage").unwrap();
+            assert_eq!(titles(&sdk), vec!["A"]);
+
+            // And the cache is rebuilt
+            assert_eq!(CacheFile::load(&cache_path(&sdk)).entries.len(), 1);
+        }
+
+        #[test]
+        fn invalid_files_are_not_cached() {
+            let (_temp, sdk) = setup_test_env();
+            write_task(&sdk, "a.md", "A");
+            fs::write(sdk.config().tasks_dir.join("bad.md"), "no frontmatter").unwrap();
+
+            assert_eq!(titles(&sdk), vec!["A"]);
+            assert_eq!(CacheFile::load(&cache_path(&sdk)).entries.len(), 1);
+        }
+
+        #[test]
+        fn scans_keep_records_of_other_kinds() {
+            let (_temp, sdk) = setup_test_env();
+            write_task(&sdk, "a.md", "A");
+            fs::write(
+                sdk.config().projects_dir.join("p.md"),
+                "---\ntitle: P\n---\n",
+            )
+            .unwrap();
+
+            sdk.list_projects(&crate::ProjectFilter::new()).unwrap();
+            titles(&sdk);
+            assert_eq!(CacheFile::load(&cache_path(&sdk)).entries.len(), 2);
+        }
+
+        #[test]
+        fn without_cache_path_nothing_is_written() {
+            let temp = TempDir::new().unwrap();
+            let tasks_dir = temp.path().join("tasks");
+            fs::create_dir_all(&tasks_dir).unwrap();
+            let config = TaskdnConfig::new(tasks_dir.clone(), tasks_dir.clone(), tasks_dir);
+            let sdk = Taskdn::new(config).unwrap();
+            write_task(&sdk, "a.md", "A");
+
+            assert_eq!(titles(&sdk), vec!["A"]);
+            assert!(!temp.path().join(".taskdn").exists());
+        }
+    }
+}
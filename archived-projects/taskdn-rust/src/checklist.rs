@@ -0,0 +1,133 @@
+//! Editing checklists in task and project bodies.
+
+use std::path::Path;
+
+use crate::error::{Error, Result};
+use crate::types::{ChecklistEdit, ProjectUpdates, TaskUpdates};
+use crate::Taskdn;
+
+impl Taskdn {
+    /// Apply a checklist edit to a task's body.
+    ///
+    /// Only the lines of the affected checklist items change; the rest of
+    /// the body is kept as it is. `updated-at` is set like any other update.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the task file (absolute or relative to `tasks_dir`)
+    /// * `edit` - The change to make, with items indexed as in [`Task::checklist`](crate::Task::checklist)
+    ///
+    /// # Errors
+    /// Returns `Error::ChecklistItemNotFound` if the edit refers to an item
+    /// the task doesn't have.
+    /// Returns an error if the file cannot be read, parsed or written.
+    pub fn edit_task_checklist(&self, path: impl AsRef<Path>, edit: &ChecklistEdit) -> Result<()> {
+        let task = self.get_task(path)?;
+        let body = edit
+            .apply(&task.body)
+            .ok_or_else(|| item_not_found(&task.path, edit))?;
+        self.update_task(&task.path, TaskUpdates::new().body(body))?;
+        Ok(())
+    }
+
+    /// Apply a checklist edit to a project's body.
+    ///
+    /// See [`Taskdn::edit_task_checklist`].
+    ///
+    /// # Errors
+    /// Returns `Error::ChecklistItemNotFound` if the edit refers to an item
+    /// the project doesn't have.
+    /// Returns an error if the file cannot be read, parsed or written.
+    pub fn edit_project_checklist(
+        &self,
+        path: impl AsRef<Path>,
+        edit: &ChecklistEdit,
+    ) -> Result<()> {
+        let project = self.get_project(path)?;
+        let body = edit
+            .apply(&project.body)
+            .ok_or_else(|| item_not_found(&project.path, edit))?;
+        self.update_project(&project.path, ProjectUpdates::new().body(body))?;
+        Ok(())
+    }
+}
+
+fn item_not_found(path: &Path, edit: &ChecklistEdit) -> Error {
+    Error::ChecklistItemNotFound {
+        path: path.to_path_buf(),
+        index: edit.index().unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{setup_test_env_with, write_file};
+    use crate::TaskdnConfig;
+    use std::fs;
+    use tempfile::TempDir;
+
+    const TASK: &str = "---\ntitle: Plan offsite\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\n\n- [ ] Book venue\n- [ ] Send agenda\n\nNotes stay put.\n";
+
+    fn setup(config: impl FnOnce(TaskdnConfig) -> TaskdnConfig) -> (TempDir, Taskdn) {
+        let (temp, taskdn) = setup_test_env_with(config);
+        write_file(&temp, "tasks/offsite.md", TASK);
+        (temp, taskdn)
+    }
+
+    #[test]
+    fn edits_task_checklist() {
+        let (_temp, taskdn) = setup(|config| config);
+        taskdn
+            .edit_task_checklist("offsite.md", &ChecklistEdit::Check(0))
+            .unwrap();
+        taskdn
+            .edit_task_checklist("offsite.md", &ChecklistEdit::add("Order food"))
+            .unwrap();
+
+        let task = taskdn.get_task("offsite.md").unwrap();
+        assert_eq!(
+            task.body,
+            "- [x] Book venue\n- [ ] Send agenda\n- [ ] Order food\n\nNotes stay put."
+        );
+        assert_ne!(task.updated_at, "2025-01-01".parse().unwrap());
+        assert_eq!(task.checklist().completion(), Some(1.0 / 3.0));
+    }
+
+    #[test]
+    fn preserves_frontmatter_formatting() {
+        let (temp, taskdn) = setup(|config| config.with_preserve_formatting(true));
+        taskdn
+            .edit_task_checklist("offsite.md", &ChecklistEdit::Remove(1))
+            .unwrap();
+
+        let content = fs::read_to_string(temp.path().join("tasks/offsite.md")).unwrap();
+        assert!(content.starts_with("---\ntitle: Plan offsite\nstatus: ready\n"));
+        assert!(content.ends_with("---\n\n- [ ] Book venue\n\nNotes stay put.\n"));
+    }
+
+    #[test]
+    fn missing_item_is_an_error() {
+        let (temp, taskdn) = setup(|config| config);
+        let result = taskdn.edit_task_checklist("offsite.md", &ChecklistEdit::Toggle(5));
+        assert!(matches!(
+            result,
+            Err(Error::ChecklistItemNotFound { index: 5, .. })
+        ));
+        let content = fs::read_to_string(temp.path().join("tasks/offsite.md")).unwrap();
+        assert_eq!(content, TASK);
+    }
+
+    #[test]
+    fn edits_project_checklist() {
+        let (temp, taskdn) = setup(|config| config);
+        let path = temp.path().join("projects/launch.md");
+        fs::write(&path, "---\ntitle: Launch\n---\n- [ ] Write post\n").unwrap();
+
+        taskdn
+            .edit_project_checklist(&path, &ChecklistEdit::add_under(0, "Add screenshots"))
+            .unwrap();
+
+        let project = taskdn.get_project(&path).unwrap();
+        assert_eq!(project.body, "- [ ] Write post\n  - [ ] Add screenshots");
+    }
+}
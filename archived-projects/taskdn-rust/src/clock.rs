@@ -0,0 +1,177 @@
+//! Clocks that supply the current time.
+//!
+//! Every timestamp the SDK writes (`created-at`, `updated-at`, `completed-at`,
+//! journal and trash metadata, date-prefixed filenames) comes from the
+//! [`Clock`] held by [`Taskdn`]. It defaults to [`SystemClock`]; swap in a
+//! [`FixedClock`] for deterministic tests or an [`OffsetClock`] to preview
+//! the vault at another time.
+//!
+//! ```ignore
+//! use taskdn::{FixedClock, TaskFilter, Taskdn};
+//!
+//! let sdk = Taskdn::new(config)?.with_clock(FixedClock::new("2025-01-15T09:00:00Z".parse()?));
+//! let overdue = sdk.list_tasks(&TaskFilter::overdue(sdk.today()))?;
+//! ```
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, Timelike, Utc};
+
+use crate::types::DateTimeValue;
+use crate::Taskdn;
+
+/// A source of the current time.
+pub trait Clock: Debug + Send + Sync {
+    /// The current instant.
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Today's date in the local timezone.
+    fn today(&self) -> NaiveDate {
+        self.now().with_timezone(&Local).date_naive()
+    }
+}
+
+/// The system clock. This is the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that always reports the same instant.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(DateTime<Utc>);
+
+impl FixedClock {
+    /// Create a clock stopped at `instant`.
+    #[must_use]
+    pub fn new(instant: DateTime<Utc>) -> Self {
+        Self(instant)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// A clock running a fixed duration ahead of (or, if negative, behind)
+/// another clock.
+#[derive(Debug, Clone)]
+pub struct OffsetClock {
+    base: Arc<dyn Clock>,
+    offset: Duration,
+}
+
+impl OffsetClock {
+    /// Create a clock `offset` ahead of the system clock.
+    #[must_use]
+    pub fn new(offset: Duration) -> Self {
+        Self::wrapping(SystemClock, offset)
+    }
+
+    /// Create a clock `offset` ahead of `base`.
+    #[must_use]
+    pub fn wrapping(base: impl Clock + 'static, offset: Duration) -> Self {
+        Self {
+            base: Arc::new(base),
+            offset,
+        }
+    }
+}
+
+impl Clock for OffsetClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.base.now() + self.offset
+    }
+}
+
+impl Taskdn {
+    /// Use `clock` for timestamps and [`Taskdn::today`] instead of the
+    /// system clock.
+    #[must_use]
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Returns the clock in use.
+    #[must_use]
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// Today's local date according to the clock.
+    ///
+    /// Pass this to the relative [`TaskFilter`](crate::TaskFilter) presets,
+    /// such as [`TaskFilter::overdue`](crate::TaskFilter::overdue).
+    #[must_use]
+    pub fn today(&self) -> NaiveDate {
+        self.clock.today()
+    }
+
+    /// The current instant, truncated to whole seconds.
+    fn now(&self) -> DateTime<Utc> {
+        let now = self.clock.now();
+        now.with_nanosecond(0).unwrap_or(now)
+    }
+
+    /// The current time as an entity timestamp, following
+    /// [`TaskdnConfig::timestamp_policy`](crate::TaskdnConfig::timestamp_policy).
+    pub(crate) fn timestamp(&self) -> DateTimeValue {
+        self.config.timestamp_policy.at(self.now())
+    }
+
+    /// The current local time, for filenames.
+    pub(crate) fn now_local(&self) -> NaiveDateTime {
+        self.now().with_timezone(&Local).naive_local()
+    }
+
+    /// The current UTC time.
+    ///
+    /// Used for SDK bookkeeping (journal entries, trash metadata) rather than
+    /// entity timestamps.
+    pub(crate) fn now_utc(&self) -> NaiveDateTime {
+        self.now().naive_utc()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instant(s: &str) -> DateTime<Utc> {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn fixed_clock_is_stopped() {
+        let clock = FixedClock::new(instant("2025-01-15T09:00:00Z"));
+        assert_eq!(clock.now(), instant("2025-01-15T09:00:00Z"));
+        assert_eq!(clock.now(), clock.now());
+    }
+
+    #[test]
+    fn offset_clock_shifts_its_base() {
+        let base = FixedClock::new(instant("2025-01-15T09:00:00Z"));
+        let tomorrow = OffsetClock::wrapping(base, Duration::days(1));
+        assert_eq!(tomorrow.now(), instant("2025-01-16T09:00:00Z"));
+
+        let yesterday = OffsetClock::wrapping(base, Duration::days(-1));
+        assert_eq!(yesterday.now(), instant("2025-01-14T09:00:00Z"));
+    }
+
+    #[test]
+    fn today_is_the_local_date() {
+        let clock = FixedClock::new(instant("2025-01-15T09:00:00Z"));
+        let expected = instant("2025-01-15T09:00:00Z")
+            .with_timezone(&Local)
+            .date_naive();
+        assert_eq!(clock.today(), expected);
+    }
+}
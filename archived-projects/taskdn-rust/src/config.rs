@@ -2,10 +2,14 @@
 
 use std::path::PathBuf;
 
+use crate::filename::FilenameStrategy;
+use crate::types::TimestampPolicy;
+
 /// Configuration for initializing the Taskdn SDK.
 ///
 /// Specifies the paths to the directories containing tasks, projects, and areas.
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct TaskdnConfig {
     /// Path to the directory containing task files.
     pub tasks_dir: PathBuf,
@@ -13,6 +17,67 @@ pub struct TaskdnConfig {
     pub projects_dir: PathBuf,
     /// Path to the directory containing area files.
     pub areas_dir: PathBuf,
+    /// Patch existing files in place when updating them, instead of
+    /// regenerating the frontmatter.
+    ///
+    /// When enabled, only keys whose values changed are rewritten. Comments,
+    /// key order, quoting and list style of everything else are preserved.
+    /// Defaults to `false`.
+    pub preserve_formatting: bool,
+    /// File in which to cache parsed entities between list operations.
+    ///
+    /// When set, list operations only parse files that changed since the
+    /// cache was written. A vault-local path such as `.taskdn/cache.bin` works
+    /// well. Defaults to `None` (no caching).
+    pub cache_path: Option<PathBuf>,
+    /// File in which to record mutations so they can be undone.
+    ///
+    /// When set, every create, update, archive and delete appends an entry
+    /// holding the affected files' contents before and after the change, which
+    /// [`Taskdn::undo`](crate::Taskdn::undo) and [`Taskdn::redo`](crate::Taskdn::redo)
+    /// replay. A vault-local path such as `.taskdn/journal.yaml` works well.
+    /// If the entry can't be recorded, the operation returns the error even
+    /// though the change itself was made. Defaults to `None` (no journal).
+    pub journal_path: Option<PathBuf>,
+    /// Move deleted files to the trash instead of removing them.
+    ///
+    /// Each entity directory gets its own `.trash` folder holding deleted
+    /// files alongside metadata recording where they came from. See
+    /// [`Taskdn::list_trash`](crate::Taskdn::list_trash). Defaults to `false`.
+    pub use_trash: bool,
+    /// Rename files to match their new title when an update changes it.
+    ///
+    /// The new filename is generated from the title with the
+    /// [`filename_strategy`](TaskdnConfig::filename_strategy), with a `-2`,
+    /// `-3`, ... suffix if taken, and every reference to the file is rewritten
+    /// as by [`Taskdn::rename_task`](crate::Taskdn::rename_task). Applies to
+    /// `update_*` and [`Transaction`](crate::Transaction) updates; the
+    /// `update_*_checked` and `update_*_merging` variants never rename.
+    /// Defaults to `false`.
+    pub rename_on_retitle: bool,
+    /// How filenames are generated for new tasks, projects and areas.
+    ///
+    /// Only used when no filename is given explicitly. Defaults to
+    /// [`FilenameStrategy::Slug`].
+    pub filename_strategy: FilenameStrategy,
+    /// How `created-at`, `updated-at` and `completed-at` are written.
+    ///
+    /// Defaults to [`TimestampPolicy::Local`].
+    pub timestamp_policy: TimestampPolicy,
+    /// Check tasks against their subtasks in [`Taskdn::get_task_warnings`](crate::Taskdn::get_task_warnings).
+    ///
+    /// When enabled, a completed task with open subtasks, an open task whose
+    /// subtasks are all completed, and a task that is its own ancestor get a
+    /// [`ValidationWarning`](crate::ValidationWarning). Defaults to `false`.
+    pub subtask_rollup: bool,
+    /// Move tasks from `blocked` to `ready` when their last blocker completes.
+    ///
+    /// When enabled, marking a task done or dropped through
+    /// [`Taskdn::update_task`](crate::Taskdn::update_task) (and the methods built
+    /// on it, such as `complete_task`) or a [`Transaction`](crate::Transaction)
+    /// also updates every `blocked` task that lists it in `blocked-by` and has
+    /// no other open blockers. Defaults to `false`.
+    pub unblock_dependents: bool,
 }
 
 impl TaskdnConfig {
@@ -23,6 +88,113 @@ impl TaskdnConfig {
             tasks_dir,
             projects_dir,
             areas_dir,
+            preserve_formatting: false,
+            cache_path: None,
+            journal_path: None,
+            use_trash: false,
+            rename_on_retitle: false,
+            filename_strategy: FilenameStrategy::default(),
+            timestamp_policy: TimestampPolicy::default(),
+            subtask_rollup: false,
+            unblock_dependents: false,
         }
     }
+
+    /// Enable or disable format-preserving updates.
+    ///
+    /// See [`TaskdnConfig::preserve_formatting`].
+    #[must_use]
+    pub fn with_preserve_formatting(mut self, enabled: bool) -> Self {
+        self.preserve_formatting = enabled;
+        self
+    }
+
+    /// Cache parsed entities in the given file.
+    ///
+    /// See [`TaskdnConfig::cache_path`].
+    #[must_use]
+    pub fn with_cache_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_path = Some(path.into());
+        self
+    }
+
+    /// Record an undo journal in the given file.
+    ///
+    /// See [`TaskdnConfig::journal_path`].
+    #[must_use]
+    pub fn with_journal_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.journal_path = Some(path.into());
+        self
+    }
+
+    /// Enable or disable moving deleted files to the trash.
+    ///
+    /// See [`TaskdnConfig::use_trash`].
+    #[must_use]
+    pub fn with_trash(mut self, enabled: bool) -> Self {
+        self.use_trash = enabled;
+        self
+    }
+
+    /// Enable or disable renaming files when their title changes.
+    ///
+    /// See [`TaskdnConfig::rename_on_retitle`].
+    #[must_use]
+    pub fn with_rename_on_retitle(mut self, enabled: bool) -> Self {
+        self.rename_on_retitle = enabled;
+        self
+    }
+
+    /// Set how filenames are generated for new entities.
+    ///
+    /// See [`TaskdnConfig::filename_strategy`].
+    #[must_use]
+    pub fn with_filename_strategy(mut self, strategy: FilenameStrategy) -> Self {
+        self.filename_strategy = strategy;
+        self
+    }
+
+    /// Set how the SDK writes timestamps.
+    ///
+    /// See [`TaskdnConfig::timestamp_policy`].
+    #[must_use]
+    pub fn with_timestamp_policy(mut self, policy: TimestampPolicy) -> Self {
+        self.timestamp_policy = policy;
+        self
+    }
+
+    /// Enable or disable subtask rollup warnings.
+    ///
+    /// See [`TaskdnConfig::subtask_rollup`].
+    #[must_use]
+    pub fn with_subtask_rollup(mut self, enabled: bool) -> Self {
+        self.subtask_rollup = enabled;
+        self
+    }
+
+    /// Enable or disable unblocking dependents when a task completes.
+    ///
+    /// See [`TaskdnConfig::unblock_dependents`].
+    #[must_use]
+    pub fn with_unblock_dependents(mut self, enabled: bool) -> Self {
+        self.unblock_dependents = enabled;
+        self
+    }
+}
+
+/// How deleting a project or area treats the entities that reference it.
+///
+/// Used by [`Taskdn::delete_project_with_policy`](crate::Taskdn::delete_project_with_policy)
+/// and [`Taskdn::delete_area_with_policy`](crate::Taskdn::delete_area_with_policy).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum DeletePolicy {
+    /// Refuse to delete while anything references the entity.
+    #[default]
+    Block,
+    /// Delete, clearing the reference on every dependent.
+    Unlink,
+    /// Delete, pointing every dependent at another project or area instead.
+    ///
+    /// The path is absolute or relative to the projects/areas directory.
+    Reassign(PathBuf),
 }
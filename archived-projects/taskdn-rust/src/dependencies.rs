@@ -0,0 +1,316 @@
+//! Dependencies between tasks.
+//!
+//! A task's `blocked-by` field lists the tasks it waits on, each resolved
+//! with [`Taskdn::resolve_task_reference`]. A task is *actionable* once every
+//! blocker is done or dropped. A blocker that doesn't resolve counts as
+//! unfinished, so a typo never unblocks a task.
+//!
+//! With [`TaskdnConfig::unblock_dependents`](crate::TaskdnConfig::unblock_dependents)
+//! enabled, completing a task's last open blocker moves it from `blocked` to
+//! `ready`.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::filter::TaskFilter;
+use crate::types::{FileReference, ParsedTask, Task, TaskStatus, TaskUpdates};
+use crate::{Taskdn, Transaction};
+
+impl Task {
+    /// Returns true if every blocker is done or dropped, looking up each
+    /// blocker's status with `status_of`.
+    pub(crate) fn blockers_completed(
+        &self,
+        mut status_of: impl FnMut(&FileReference) -> Option<TaskStatus>,
+    ) -> bool {
+        self.blocked_by
+            .iter()
+            .all(|blocker| status_of(blocker).is_some_and(|status| status.is_completed()))
+    }
+}
+
+impl Taskdn {
+    /// Get the tasks a task is blocked by.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the task file
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the task doesn't exist.
+    /// Returns `Error::UnresolvedReference` if a `blocked-by` entry matches no task.
+    pub fn get_blockers(&self, path: impl AsRef<Path>) -> Result<Vec<Task>> {
+        let task = self.get_task(path)?;
+        task.blocked_by
+            .iter()
+            .map(|blocker| self.get_task(self.resolve_task_reference(blocker)?))
+            .collect()
+    }
+
+    /// Get the tasks that list a task in their `blocked-by`, including archived ones.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the blocking task file
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the task doesn't exist.
+    /// Returns an error if the tasks directory cannot be read.
+    pub fn get_dependents(&self, path: impl AsRef<Path>) -> Result<Vec<Task>> {
+        let task = self.get_task(path)?;
+        let mut dependents = self.list_tasks(&TaskFilter::new().include_archive_dir())?;
+        dependents.retain(|dependent| self.is_blocked_by(dependent, &task.path));
+        Ok(dependents)
+    }
+
+    /// Returns true if every task in the task's `blocked-by` is done or dropped.
+    ///
+    /// A task without blockers is always actionable. Blockers that match no
+    /// task count as unfinished.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the task doesn't exist.
+    /// Returns `Error::Parse` if the file cannot be parsed.
+    pub fn is_task_actionable(&self, path: impl AsRef<Path>) -> Result<bool> {
+        let task = self.get_task(path)?;
+        Ok(task.blockers_completed(|blocker| self.blocker_status(blocker, &HashMap::new())))
+    }
+
+    /// The status of the task a blocker reference resolves to.
+    ///
+    /// Tasks in `known` are used without reading their files again.
+    pub(crate) fn blocker_status(
+        &self,
+        blocker: &FileReference,
+        known: &HashMap<PathBuf, TaskStatus>,
+    ) -> Option<TaskStatus> {
+        let path = self.resolve_task_reference(blocker).ok()?;
+        if let Some(status) = known.get(&path) {
+            return Some(*status);
+        }
+        let content = fs::read_to_string(&path).ok()?;
+        ParsedTask::parse(&content).ok().map(|task| task.status)
+    }
+
+    /// Returns true if one of `task`'s blockers resolves to the file at `blocker`.
+    fn is_blocked_by(&self, task: &Task, blocker: &Path) -> bool {
+        task.blocked_by.iter().any(|reference| {
+            self.resolve_task_reference(reference)
+                .is_ok_and(|path| same_file(&path, blocker))
+        })
+    }
+}
+
+impl Transaction<'_> {
+    /// Stage moving the `blocked` tasks whose last open blocker is the task at
+    /// `completed` to `ready`.
+    ///
+    /// Blockers are read through the transaction, so the completion being
+    /// staged alongside counts.
+    pub(crate) fn stage_unblock_dependents(&mut self, completed: &Path) -> Result<()> {
+        let taskdn = self.taskdn();
+        let blocked = taskdn.list_tasks(&TaskFilter::new().with_status(TaskStatus::Blocked))?;
+
+        for dependent in blocked {
+            if !taskdn.is_blocked_by(&dependent, completed) {
+                continue;
+            }
+            if dependent.blockers_completed(|blocker| self.staged_status(blocker)) {
+                self.update_task(
+                    &dependent.path,
+                    TaskUpdates::new().status(TaskStatus::Ready),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The status of the task a reference resolves to, including staged changes.
+    fn staged_status(&mut self, reference: &FileReference) -> Option<TaskStatus> {
+        let path = self.taskdn().resolve_task_reference(reference).ok()?;
+        let content = self.read(&path).ok()?;
+        ParsedTask::parse(&content).ok().map(|task| task.status)
+    }
+}
+
+/// Returns true if both paths name the same file.
+fn same_file(a: &Path, b: &Path) -> bool {
+    a == b
+        || matches!(
+            (fs::canonicalize(a), fs::canonicalize(b)),
+            (Ok(a), Ok(b)) if a == b
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+    use crate::test_support::{setup_test_env_with, write_file};
+    use tempfile::TempDir;
+
+    fn write(temp: &TempDir, path: &str, status: &str, blockers: &[&str]) {
+        let blocked_by = if blockers.is_empty() {
+            String::new()
+        } else {
+            format!("blocked-by: [\"[[{}]]\"]\n", blockers.join("]]\", \"[["))
+        };
+        let content = format!(
+            "---\ntitle: {path}\nstatus: {status}\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n{blocked_by}---\n"
+        );
+        write_file(temp, &format!("tasks/{path}"), &content);
+    }
+
+    fn titles(tasks: &[Task]) -> Vec<&str> {
+        let mut titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+        titles.sort_unstable();
+        titles
+    }
+
+    #[test]
+    fn blockers_and_dependents() {
+        let (temp, taskdn) = setup_test_env_with(|c| c.with_unblock_dependents(false));
+        write(&temp, "design.md", "done", &[]);
+        write(&temp, "archive/spec.md", "done", &[]);
+        write(&temp, "build.md", "blocked", &["design", "spec"]);
+        write(&temp, "ship.md", "blocked", &["build"]);
+
+        let blockers = taskdn.get_blockers("build.md").unwrap();
+        assert_eq!(titles(&blockers), ["archive/spec.md", "design.md"]);
+
+        let dependents = taskdn.get_dependents("design.md").unwrap();
+        assert_eq!(titles(&dependents), ["build.md"]);
+        assert!(taskdn.get_dependents("ship.md").unwrap().is_empty());
+    }
+
+    #[test]
+    fn unresolved_blocker() {
+        let (temp, taskdn) = setup_test_env_with(|c| c.with_unblock_dependents(false));
+        write(&temp, "build.md", "blocked", &["missing"]);
+
+        assert!(matches!(
+            taskdn.get_blockers("build.md"),
+            Err(Error::UnresolvedReference { .. })
+        ));
+        assert!(!taskdn.is_task_actionable("build.md").unwrap());
+    }
+
+    #[test]
+    fn actionable_when_all_blockers_completed() {
+        let (temp, taskdn) = setup_test_env_with(|c| c.with_unblock_dependents(false));
+        write(&temp, "design.md", "done", &[]);
+        write(&temp, "review.md", "dropped", &[]);
+        write(&temp, "build.md", "blocked", &["design", "review"]);
+        write(&temp, "ship.md", "blocked", &["build", "design"]);
+
+        assert!(taskdn.is_task_actionable("build.md").unwrap());
+        assert!(!taskdn.is_task_actionable("ship.md").unwrap());
+        assert!(taskdn.is_task_actionable("design.md").unwrap());
+    }
+
+    #[test]
+    fn actionable_filter() {
+        let (temp, taskdn) = setup_test_env_with(|c| c.with_unblock_dependents(false));
+        write(&temp, "design.md", "in-progress", &[]);
+        write(&temp, "archive/spec.md", "done", &[]);
+        write(&temp, "build.md", "blocked", &["design"]);
+        write(&temp, "docs.md", "ready", &["spec"]);
+
+        let actionable = TaskFilter::new().actionable();
+        let tasks = taskdn.list_tasks(&actionable).unwrap();
+        assert_eq!(titles(&tasks), ["design.md", "docs.md"]);
+
+        let waiting = TaskFilter::new().not_actionable();
+        let tasks = taskdn.list_tasks(&waiting).unwrap();
+        assert_eq!(titles(&tasks), ["build.md"]);
+
+        let index = taskdn.build_index().unwrap();
+        let indexed: Vec<Task> = index.list_tasks(&actionable).into_iter().cloned().collect();
+        assert_eq!(titles(&indexed), ["design.md", "docs.md"]);
+        let indexed: Vec<Task> = index.list_tasks(&waiting).into_iter().cloned().collect();
+        assert_eq!(titles(&indexed), ["build.md"]);
+    }
+
+    #[test]
+    fn completing_last_blocker_unblocks_dependents() {
+        let (temp, taskdn) = setup_test_env_with(|c| c.with_unblock_dependents(true));
+        write(&temp, "design.md", "ready", &[]);
+        write(&temp, "review.md", "ready", &[]);
+        write(&temp, "build.md", "blocked", &["design", "review"]);
+        write(&temp, "docs.md", "blocked", &["design"]);
+
+        taskdn.complete_task("design.md").unwrap();
+        assert_eq!(
+            taskdn.get_task("docs.md").unwrap().status,
+            TaskStatus::Ready
+        );
+        assert_eq!(
+            taskdn.get_task("build.md").unwrap().status,
+            TaskStatus::Blocked
+        );
+
+        taskdn.drop_task("review.md").unwrap();
+        assert_eq!(
+            taskdn.get_task("build.md").unwrap().status,
+            TaskStatus::Ready
+        );
+    }
+
+    #[test]
+    fn unblocking_in_a_transaction() {
+        let (temp, taskdn) = setup_test_env_with(|c| c.with_unblock_dependents(true));
+        write(&temp, "design.md", "ready", &[]);
+        write(&temp, "review.md", "ready", &[]);
+        write(&temp, "build.md", "blocked", &["design", "review"]);
+
+        let mut tx = taskdn.transaction();
+        tx.complete_task("design.md").unwrap();
+        tx.update_task("review.md", TaskUpdates::new().status(TaskStatus::Done))
+            .unwrap();
+        assert!(tx.commit().is_complete_success());
+
+        assert_eq!(
+            taskdn.get_task("build.md").unwrap().status,
+            TaskStatus::Ready
+        );
+    }
+
+    #[test]
+    fn checked_and_merging_updates_unblock() {
+        let (temp, taskdn) = setup_test_env_with(|c| c.with_unblock_dependents(true));
+        write(&temp, "design.md", "ready", &[]);
+        write(&temp, "review.md", "ready", &[]);
+        write(&temp, "build.md", "blocked", &["design"]);
+        write(&temp, "docs.md", "blocked", &["review"]);
+        let done = TaskUpdates::new().status(TaskStatus::Done);
+
+        let (_, revision) = taskdn.get_task_with_revision("design.md").unwrap();
+        taskdn
+            .update_task_checked("design.md", done.clone(), revision)
+            .unwrap();
+        let base = taskdn.get_task("review.md").unwrap();
+        taskdn
+            .update_task_merging("review.md", &base, done)
+            .unwrap();
+
+        for dependent in ["build.md", "docs.md"] {
+            assert_eq!(
+                taskdn.get_task(dependent).unwrap().status,
+                TaskStatus::Ready
+            );
+        }
+    }
+
+    #[test]
+    fn unblocking_is_opt_in() {
+        let (temp, taskdn) = setup_test_env_with(|c| c.with_unblock_dependents(false));
+        write(&temp, "design.md", "ready", &[]);
+        write(&temp, "build.md", "blocked", &["design"]);
+
+        taskdn.complete_task("design.md").unwrap();
+        assert_eq!(
+            taskdn.get_task("build.md").unwrap().status,
+            TaskStatus::Blocked
+        );
+    }
+}
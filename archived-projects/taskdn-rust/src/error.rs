@@ -1,6 +1,6 @@
 //! Error types for the taskdn library.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// All errors that can occur in the taskdn library.
 #[derive(Debug, thiserror::Error)]
@@ -51,7 +51,45 @@ pub enum Error {
 
     /// Cannot delete file due to constraints.
     #[error("cannot delete {}: {reason}", path.display())]
-    DeleteBlocked { path: PathBuf, reason: String },
+    DeleteBlocked {
+        path: PathBuf,
+        reason: String,
+        /// Files that still reference the one being deleted.
+        referenced_by: Vec<PathBuf>,
+    },
+
+    /// The file was modified by someone else since it was last read.
+    ///
+    /// Carries both versions so the caller can show a diff or retry.
+    /// `fields` lists the frontmatter keys both sides changed, when known.
+    #[error("{} was modified externally", path.display())]
+    Conflict {
+        path: PathBuf,
+        /// The content this update would have written.
+        ours: String,
+        /// The content currently on disk.
+        theirs: String,
+        /// Frontmatter keys changed on both sides (empty if not computed).
+        fields: Vec<String>,
+    },
+
+    /// A date typed by a person could not be understood.
+    ///
+    /// Returned for ambiguous numeric dates such as `12/1`, as well as
+    /// unrecognised input.
+    #[error("invalid date '{input}': {message}")]
+    InvalidDate { input: String, message: String },
+
+    /// Projects block each other in a cycle, so they have no dependency order.
+    #[error("dependency cycle between {} projects", paths.len())]
+    DependencyCycle {
+        /// The projects on a cycle.
+        paths: Vec<PathBuf>,
+    },
+
+    /// A checklist edit referred to an item the body doesn't have.
+    #[error("no checklist item {index} in {}", path.display())]
+    ChecklistItemNotFound { path: PathBuf, index: usize },
 
     /// Directory not found.
     #[error("directory not found: {}", path.display())]
@@ -62,6 +100,44 @@ pub enum Error {
     Io(#[from] std::io::Error),
 }
 
+impl Error {
+    /// Attach a file path to an error produced while parsing content.
+    ///
+    /// Converts the `Content*` variants into their path-carrying equivalents.
+    /// Other variants are returned unchanged.
+    pub(crate) fn with_path(self, path: &Path) -> Self {
+        match self {
+            Error::ContentParse { message } => Error::Parse {
+                path: path.to_path_buf(),
+                message,
+            },
+            Error::ContentMissingField { field } => Error::MissingField {
+                path: path.to_path_buf(),
+                field,
+            },
+            Error::ContentInvalidField { field, message } => Error::InvalidField {
+                path: path.to_path_buf(),
+                field,
+                message,
+            },
+            other => other,
+        }
+    }
+
+    /// A `DeleteBlocked` error for a file that is still referenced.
+    pub(crate) fn delete_blocked(path: &Path, referenced_by: Vec<PathBuf>) -> Self {
+        let count = referenced_by.len();
+        Error::DeleteBlocked {
+            path: path.to_path_buf(),
+            reason: format!(
+                "referenced by {count} file{}",
+                if count == 1 { "" } else { "s" }
+            ),
+            referenced_by,
+        }
+    }
+}
+
 /// Result type alias for taskdn operations.
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -122,6 +198,67 @@ impl<T> Default for BatchResult<T> {
     }
 }
 
+/// Result type for list operations that skip files they cannot parse.
+///
+/// The plain `list_*` methods silently drop invalid files. The
+/// `list_*_with_skipped` variants return them here instead, so callers can
+/// report which files were ignored and why.
+#[derive(Debug)]
+pub struct ListResult<T> {
+    /// Entities that were parsed successfully and matched the filter.
+    pub items: Vec<T>,
+    /// Files that could not be read or parsed, with their errors.
+    pub skipped: Vec<(PathBuf, Error)>,
+}
+
+impl<T> ListResult<T> {
+    /// Creates a new empty list result.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Returns true if any files were skipped.
+    #[must_use]
+    pub fn has_skipped(&self) -> bool {
+        !self.skipped.is_empty()
+    }
+
+    /// Returns the number of skipped files.
+    #[must_use]
+    pub fn skipped_count(&self) -> usize {
+        self.skipped.len()
+    }
+
+    /// Split per-file parse results into items and skipped files.
+    pub(crate) fn from_results(paths: &[PathBuf], results: Vec<Result<T>>) -> Self {
+        let mut list = Self::new();
+        for (path, result) in paths.iter().zip(results) {
+            match result {
+                Ok(item) => list.items.push(item),
+                Err(e) => list.skipped.push((path.clone(), e)),
+            }
+        }
+        list
+    }
+
+    /// Keep only the items matching `predicate`. Skipped files are unaffected.
+    #[must_use]
+    pub(crate) fn filter(mut self, predicate: impl FnMut(&T) -> bool) -> Self {
+        self.items.retain(predicate);
+        self
+    }
+}
+
+impl<T> Default for ListResult<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -184,4 +321,36 @@ mod tests {
         let converted = result.into_result();
         assert!(converted.is_err());
     }
+
+    #[test]
+    fn list_result_splits_results() {
+        let paths = vec![PathBuf::from("/test/a.md"), PathBuf::from("/test/b.md")];
+        let results = vec![
+            Ok(1),
+            Err(Error::NotFound {
+                path: PathBuf::from("/test/b.md"),
+            }),
+        ];
+
+        let list = ListResult::from_results(&paths, results);
+        assert_eq!(list.items, vec![1]);
+        assert!(list.has_skipped());
+        assert_eq!(list.skipped_count(), 1);
+        assert_eq!(list.skipped[0].0, PathBuf::from("/test/b.md"));
+    }
+
+    #[test]
+    fn list_result_filter_keeps_skipped() {
+        let paths = vec![PathBuf::from("/test/a.md"), PathBuf::from("/test/b.md")];
+        let results = vec![
+            Ok(1),
+            Err(Error::NotFound {
+                path: PathBuf::from("/test/b.md"),
+            }),
+        ];
+
+        let list = ListResult::from_results(&paths, results).filter(|n| *n > 1);
+        assert!(list.items.is_empty());
+        assert_eq!(list.skipped_count(), 1);
+    }
 }
@@ -185,6 +185,11 @@ impl Taskdn {
         // For deleted files, we need to work with the raw path.
         let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
 
+        // Files in the trash are no longer part of the vault
+        if self.is_in_trash(&path) {
+            return None;
+        }
+
         // Check tasks_dir (including archive subdirectory)
         if let Ok(tasks_dir) = self.config.tasks_dir.canonicalize() {
             if path.starts_with(&tasks_dir) {
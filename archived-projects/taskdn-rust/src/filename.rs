@@ -0,0 +1,228 @@
+//! Filename generation for new entity files.
+//!
+//! [`FilenameStrategy`] decides what a new task, project or area file is
+//! called when [`NewTask::with_filename`](crate::NewTask::with_filename) and
+//! friends weren't used, and what happens when that name is already taken.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::error::{Error, Result};
+use crate::utils::generate_filename;
+use crate::Taskdn;
+
+/// How filenames are generated for new entities.
+///
+/// Set with [`TaskdnConfig::with_filename_strategy`](crate::TaskdnConfig::with_filename_strategy).
+/// Every strategy except [`FilenameStrategy::Slug`] adds a `-2`, `-3`, ...
+/// suffix when the generated name is taken, so entities may share a title.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FilenameStrategy {
+    /// A slug of the title (`call-dad.md`). Creating fails with
+    /// `Error::Validation` if the file already exists.
+    #[default]
+    Slug,
+    /// A slug of the title, with a numeric suffix if taken (`call-dad-2.md`).
+    SlugWithSuffix,
+    /// Today's date followed by a slug of the title (`2025-01-15-call-dad.md`).
+    DatePrefixed,
+    /// A timestamp ID that doesn't depend on the title (`20250115143000.md`).
+    UniqueId,
+    /// A slug of the title with non-ASCII characters transliterated instead
+    /// of dropped (`Café Review` becomes `cafe-review.md`, `日本旅行` becomes
+    /// `ribenluxing.md`).
+    Transliterated,
+}
+
+impl FilenameStrategy {
+    /// The filename this strategy generates for `title` at `now`.
+    pub(crate) fn filename(self, title: &str, now: NaiveDateTime) -> String {
+        match self {
+            Self::Slug | Self::SlugWithSuffix => generate_filename(title),
+            Self::DatePrefixed => date_prefixed(now.date(), &generate_filename(title)),
+            Self::UniqueId => format!("{}.md", now.format("%Y%m%d%H%M%S")),
+            Self::Transliterated => generate_filename(&any_ascii::any_ascii(title)),
+        }
+    }
+
+    /// The filename a file at `path` should get when its title changes to `title`.
+    ///
+    /// Returns `None` if the filename doesn't depend on the title. Date
+    /// prefixes already in the filename are kept.
+    pub(crate) fn retitled(self, path: &Path, title: &str, now: NaiveDateTime) -> Option<String> {
+        match self {
+            Self::UniqueId => None,
+            Self::DatePrefixed => {
+                let date = path
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .and_then(|stem| stem.get(..10))
+                    .and_then(|prefix| NaiveDate::parse_from_str(prefix, "%Y-%m-%d").ok())
+                    .unwrap_or_else(|| now.date());
+                Some(date_prefixed(date, &generate_filename(title)))
+            }
+            _ => Some(self.filename(title, now)),
+        }
+    }
+}
+
+fn date_prefixed(date: NaiveDate, filename: &str) -> String {
+    format!("{}-{filename}", date.format("%Y-%m-%d"))
+}
+
+/// `dir` joined with `filename`, or with a `-2`, `-3`, ... suffix before the
+/// `.md` extension while `exists` reports the path as taken.
+pub(crate) fn available_path(
+    dir: &Path,
+    filename: &str,
+    exists: impl Fn(&Path) -> bool,
+) -> PathBuf {
+    let stem = filename.strip_suffix(".md").unwrap_or(filename);
+
+    let mut candidate = dir.join(filename);
+    let mut n = 2;
+    while exists(&candidate) {
+        candidate = dir.join(format!("{stem}-{n}.md"));
+        n += 1;
+    }
+    candidate
+}
+
+impl Taskdn {
+    /// Choose the path for a new entity file in `dir`.
+    ///
+    /// Uses `filename` if given, otherwise generates one from `title` with
+    /// [`TaskdnConfig::filename_strategy`](crate::TaskdnConfig::filename_strategy).
+    /// `exists` reports whether a path is already taken.
+    ///
+    /// # Errors
+    /// Returns `Error::Validation` if the file already exists and either
+    /// `filename` was given or the strategy is [`FilenameStrategy::Slug`].
+    pub(crate) fn new_file_path(
+        &self,
+        dir: &Path,
+        filename: Option<&str>,
+        title: &str,
+        exists: impl Fn(&Path) -> bool,
+    ) -> Result<PathBuf> {
+        let strategy = self.config.filename_strategy;
+        let (filename, suffix) = match filename {
+            Some(filename) => (filename.to_string(), false),
+            None => (
+                strategy.filename(title, self.now_local()),
+                strategy != FilenameStrategy::Slug,
+            ),
+        };
+
+        if suffix {
+            return Ok(available_path(dir, &filename, exists));
+        }
+
+        let path = dir.join(&filename);
+        if exists(&path) {
+            return Err(Error::Validation {
+                path,
+                message: format!("file already exists: {filename}"),
+            });
+        }
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S").unwrap()
+    }
+
+    mod strategies {
+        use super::*;
+
+        #[test]
+        fn slug_strategies_use_generate_filename() {
+            let now = at("2025-01-15T14:30:00");
+            assert_eq!(
+                FilenameStrategy::Slug.filename("Call dad", now),
+                "call-dad.md"
+            );
+            assert_eq!(
+                FilenameStrategy::SlugWithSuffix.filename("Call dad", now),
+                "call-dad.md"
+            );
+        }
+
+        #[test]
+        fn date_prefixed() {
+            let now = at("2025-01-15T14:30:00");
+            assert_eq!(
+                FilenameStrategy::DatePrefixed.filename("Call dad", now),
+                "2025-01-15-call-dad.md"
+            );
+        }
+
+        #[test]
+        fn unique_id() {
+            let now = at("2025-01-15T14:30:05");
+            assert_eq!(
+                FilenameStrategy::UniqueId.filename("Call dad", now),
+                "20250115143005.md"
+            );
+        }
+
+        #[test]
+        fn transliterated() {
+            let now = at("2025-01-15T14:30:00");
+            let strategy = FilenameStrategy::Transliterated;
+            assert_eq!(strategy.filename("Café Review", now), "cafe-review.md");
+            assert_eq!(strategy.filename("日本旅行", now), "ribenluxing.md");
+            assert_eq!(strategy.filename("Straße", now), "strasse.md");
+        }
+    }
+
+    mod retitled {
+        use super::*;
+
+        #[test]
+        fn date_prefix_is_kept() {
+            let now = at("2025-03-01T09:00:00");
+            let path = Path::new("/tasks/2025-01-15-call-dad.md");
+            assert_eq!(
+                FilenameStrategy::DatePrefixed.retitled(path, "Call mum", now),
+                Some("2025-01-15-call-mum.md".to_string())
+            );
+            assert_eq!(
+                FilenameStrategy::DatePrefixed.retitled(Path::new("/tasks/old.md"), "New", now),
+                Some("2025-03-01-new.md".to_string())
+            );
+        }
+
+        #[test]
+        fn unique_id_never_renames() {
+            let now = at("2025-03-01T09:00:00");
+            let path = Path::new("/tasks/20250115143005.md");
+            assert_eq!(FilenameStrategy::UniqueId.retitled(path, "New", now), None);
+        }
+    }
+
+    mod available_path {
+        use super::*;
+
+        #[test]
+        fn adds_numeric_suffix_while_taken() {
+            let dir = Path::new("/tasks");
+            let taken = [dir.join("call-dad.md"), dir.join("call-dad-2.md")];
+
+            assert_eq!(
+                available_path(dir, "call-dad.md", |p| taken.iter().any(|t| t == p)),
+                dir.join("call-dad-3.md")
+            );
+            assert_eq!(
+                available_path(dir, "other.md", |p| taken.iter().any(|t| t == p)),
+                dir.join("other.md")
+            );
+        }
+    }
+}
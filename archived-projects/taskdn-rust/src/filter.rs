@@ -1,7 +1,10 @@
 //! Filter types for querying tasks, projects, and areas.
 
-use crate::types::{AreaStatus, FileReference, ProjectStatus, TaskStatus};
+use crate::types::{
+    tags_from, AreaStatus, Checklist, FileReference, Priority, ProjectStatus, Tag, Task, TaskStatus,
+};
 use chrono::{NaiveDate, NaiveDateTime};
+use std::cmp::Ordering;
 
 /// Filter criteria for querying tasks.
 ///
@@ -25,10 +28,19 @@ pub struct TaskFilter {
     pub area: Option<FileReference>,
     /// Tasks whose project is in this area.
     pub area_via_project: Option<FileReference>,
+    /// Tasks in this area or one of its sub-areas, directly or via their project.
+    pub within_area: Option<FileReference>,
     /// Tasks that have a project assigned.
     pub has_project: Option<bool>,
     /// Tasks that have an area assigned.
     pub has_area: Option<bool>,
+    /// Tasks with (`true`) or without (`false`) a parent task.
+    pub has_parent: Option<bool>,
+    /// Subtasks of this task, at any depth.
+    pub descendants_of: Option<FileReference>,
+    /// Tasks whose blockers are all done or dropped (`true`), or that still
+    /// wait on one (`false`).
+    pub actionable: Option<bool>,
 
     // Date filtering (compares by date portion for datetime fields)
     /// Tasks due before this date.
@@ -50,6 +62,32 @@ pub struct TaskFilter {
     /// Tasks visible as of this date (`defer_until` <= date OR `defer_until` is None).
     pub visible_as_of: Option<NaiveDate>,
 
+    // Priority filtering (by rank, see `Priority`)
+    /// Tasks with at least this priority.
+    pub min_priority: Option<Priority>,
+    /// Tasks with at most this priority.
+    pub max_priority: Option<Priority>,
+
+    // Tag filtering (hierarchical: `work` matches `work/client`)
+    /// Tasks with at least one of these tags.
+    pub tags_any: Option<Vec<Tag>>,
+    /// Tasks with all of these tags.
+    pub tags_all: Option<Vec<Tag>>,
+    /// Exclude tasks with any of these tags.
+    pub tags_none: Option<Vec<Tag>>,
+
+    // Checklist filtering (`- [ ]` items in the body)
+    /// Tasks with (`true`) or without (`false`) unchecked checklist items.
+    pub has_open_checklist_items: Option<bool>,
+    /// Tasks whose checklist is at least this complete (0.0 to 1.0).
+    pub min_checklist_completion: Option<f64>,
+    /// Tasks whose checklist is at most this complete (0.0 to 1.0).
+    pub max_checklist_completion: Option<f64>,
+
+    // Ordering
+    /// Order of the returned tasks (default: unspecified).
+    pub sort: Option<TaskSort>,
+
     // Archive handling
     /// Include tasks from the archive subdirectory (default: false).
     pub include_archive_dir: bool,
@@ -117,6 +155,14 @@ impl TaskFilter {
         self
     }
 
+    /// Filter to tasks in this area or any of its sub-areas, directly or via
+    /// their project.
+    #[must_use]
+    pub fn within_area(mut self, area: impl Into<FileReference>) -> Self {
+        self.within_area = Some(area.into());
+        self
+    }
+
     /// Filter to tasks that have a project assigned.
     #[must_use]
     pub fn with_project(mut self) -> Self {
@@ -145,6 +191,42 @@ impl TaskFilter {
         self
     }
 
+    /// Filter to top-level tasks (tasks without a `parent`).
+    #[must_use]
+    pub fn top_level_only(mut self) -> Self {
+        self.has_parent = Some(false);
+        self
+    }
+
+    /// Filter to subtasks (tasks with a `parent`).
+    #[must_use]
+    pub fn subtasks_only(mut self) -> Self {
+        self.has_parent = Some(true);
+        self
+    }
+
+    /// Filter to subtasks of this task, at any depth.
+    #[must_use]
+    pub fn descendants_of(mut self, task: impl Into<FileReference>) -> Self {
+        self.descendants_of = Some(task.into());
+        self
+    }
+
+    /// Filter to tasks whose `blocked-by` tasks are all done or dropped,
+    /// including tasks without blockers.
+    #[must_use]
+    pub fn actionable(mut self) -> Self {
+        self.actionable = Some(true);
+        self
+    }
+
+    /// Filter to tasks still waiting on an open or missing `blocked-by` task.
+    #[must_use]
+    pub fn not_actionable(mut self) -> Self {
+        self.actionable = Some(false);
+        self
+    }
+
     // === Dates ===
 
     /// Filter to tasks due before this date.
@@ -212,6 +294,98 @@ impl TaskFilter {
         self
     }
 
+    // === Priority ===
+
+    /// Filter to tasks with at least this priority.
+    ///
+    /// Tasks without a priority, or with an unrecognised one, never match.
+    #[must_use]
+    pub fn priority_at_least(mut self, priority: impl Into<Priority>) -> Self {
+        self.min_priority = Some(priority.into());
+        self
+    }
+
+    /// Filter to tasks with at most this priority.
+    ///
+    /// Tasks without a priority, or with an unrecognised one, never match.
+    #[must_use]
+    pub fn priority_at_most(mut self, priority: impl Into<Priority>) -> Self {
+        self.max_priority = Some(priority.into());
+        self
+    }
+
+    // === Tags ===
+
+    /// Filter to tasks with at least one of these tags.
+    ///
+    /// Tags match hierarchically: `work` also matches `work/client`. Both
+    /// the `tags` field and inline `#tags` in the body are checked.
+    #[must_use]
+    pub fn tagged_any<S: AsRef<str>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags_any = Some(tags_from(tags));
+        self
+    }
+
+    /// Filter to tasks with all of these tags (matched hierarchically).
+    #[must_use]
+    pub fn tagged_all<S: AsRef<str>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags_all = Some(tags_from(tags));
+        self
+    }
+
+    /// Exclude tasks with any of these tags (matched hierarchically).
+    #[must_use]
+    pub fn not_tagged<S: AsRef<str>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags_none = Some(tags_from(tags));
+        self
+    }
+
+    // === Checklist ===
+
+    /// Filter to tasks with unchecked checklist items.
+    #[must_use]
+    pub fn with_open_checklist_items(mut self) -> Self {
+        self.has_open_checklist_items = Some(true);
+        self
+    }
+
+    /// Filter to tasks without unchecked checklist items, including tasks
+    /// without a checklist.
+    #[must_use]
+    pub fn without_open_checklist_items(mut self) -> Self {
+        self.has_open_checklist_items = Some(false);
+        self
+    }
+
+    /// Filter to tasks whose checklist is at least this complete, as a
+    /// fraction of checked items from 0.0 to 1.0.
+    ///
+    /// Tasks without a checklist never match.
+    #[must_use]
+    pub fn checklist_completion_at_least(mut self, completion: f64) -> Self {
+        self.min_checklist_completion = Some(completion);
+        self
+    }
+
+    /// Filter to tasks whose checklist is at most this complete, as a
+    /// fraction of checked items from 0.0 to 1.0.
+    ///
+    /// Tasks without a checklist never match.
+    #[must_use]
+    pub fn checklist_completion_at_most(mut self, completion: f64) -> Self {
+        self.max_checklist_completion = Some(completion);
+        self
+    }
+
+    // === Ordering ===
+
+    /// Return tasks in this order.
+    #[must_use]
+    pub fn sorted_by(mut self, sort: TaskSort) -> Self {
+        self.sort = Some(sort);
+        self
+    }
+
     // === Archive ===
 
     /// Include tasks from the archive subdirectory.
@@ -270,6 +444,83 @@ impl TaskFilter {
     }
 }
 
+/// Order for listed tasks.
+///
+/// Tasks missing the sort field come last, and ties are broken by path so
+/// the order is stable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TaskSort {
+    /// Most important first (see [`Priority::rank`]).
+    Priority,
+    /// Earliest due first.
+    Due,
+    /// Earliest scheduled first.
+    Scheduled,
+    /// Oldest first.
+    CreatedAt,
+    /// Alphabetically by title, ignoring case.
+    Title,
+}
+
+impl TaskSort {
+    /// Compare two tasks in this order.
+    #[must_use]
+    pub fn compare(&self, a: &Task, b: &Task) -> Ordering {
+        let by_field = match self {
+            Self::Priority => Priority::cmp_importance(a.priority.as_ref(), b.priority.as_ref()),
+            Self::Due => missing_last(a.due.as_ref(), b.due.as_ref()),
+            Self::Scheduled => missing_last(a.scheduled.as_ref(), b.scheduled.as_ref()),
+            Self::CreatedAt => a.created_at.cmp(&b.created_at),
+            Self::Title => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+        };
+        by_field.then_with(|| a.path.cmp(&b.path))
+    }
+}
+
+/// Ascending order with `None` last.
+fn missing_last<T: Ord>(a: Option<&T>, b: Option<&T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Check an entity's tags against any-of, all-of and none-of tag lists.
+///
+/// An entity has a filter tag if one of its tags is within it.
+fn matches_tags(
+    tags: &[Tag],
+    any: Option<&[Tag]>,
+    all: Option<&[Tag]>,
+    none: Option<&[Tag]>,
+) -> bool {
+    let has = |wanted: &Tag| tags.iter().any(|tag| tag.is_within(wanted));
+    any.map_or(true, |any| any.iter().any(has))
+        && all.map_or(true, |all| all.iter().all(has))
+        && none.map_or(true, |none| !none.iter().any(has))
+}
+
+/// Check a body's checklist against the checklist criteria.
+fn matches_checklist(
+    checklist: &Checklist,
+    has_open: Option<bool>,
+    min: Option<f64>,
+    max: Option<f64>,
+) -> bool {
+    if has_open.is_some_and(|has_open| has_open != (checklist.open_count() > 0)) {
+        return false;
+    }
+    if min.is_none() && max.is_none() {
+        return true;
+    }
+    checklist.completion().is_some_and(|completion| {
+        min.map_or(true, |min| completion >= min) && max.map_or(true, |max| completion <= max)
+    })
+}
+
 /// Filter criteria for querying projects.
 #[derive(Debug, Clone, Default)]
 pub struct ProjectFilter {
@@ -279,6 +530,21 @@ pub struct ProjectFilter {
     pub area: Option<FileReference>,
     /// Projects that have an area assigned.
     pub has_area: Option<bool>,
+    /// Projects with at least one of these tags.
+    pub tags_any: Option<Vec<Tag>>,
+    /// Projects with all of these tags.
+    pub tags_all: Option<Vec<Tag>>,
+    /// Exclude projects with any of these tags.
+    pub tags_none: Option<Vec<Tag>>,
+    /// Projects with (`true`) or without (`false`) unchecked checklist items.
+    pub has_open_checklist_items: Option<bool>,
+    /// Projects whose checklist is at least this complete (0.0 to 1.0).
+    pub min_checklist_completion: Option<f64>,
+    /// Projects whose checklist is at most this complete (0.0 to 1.0).
+    pub max_checklist_completion: Option<f64>,
+    /// Projects with (`true`) or without (`false`) a `blocked-by` project
+    /// that is not done.
+    pub blocked_by_unfinished: Option<bool>,
 }
 
 impl ProjectFilter {
@@ -323,6 +589,82 @@ impl ProjectFilter {
         self
     }
 
+    /// Filter to projects blocked by a project that is not done.
+    ///
+    /// `blocked-by` entries that match no project count as unfinished.
+    #[must_use]
+    pub fn blocked_by_unfinished(mut self) -> Self {
+        self.blocked_by_unfinished = Some(true);
+        self
+    }
+
+    /// Filter to projects whose `blocked-by` projects are all done,
+    /// including projects without blockers.
+    #[must_use]
+    pub fn without_unfinished_blockers(mut self) -> Self {
+        self.blocked_by_unfinished = Some(false);
+        self
+    }
+
+    /// Filter to projects with at least one of these tags.
+    ///
+    /// Tags match hierarchically: `work` also matches `work/client`. Both
+    /// the `tags` field and inline `#tags` in the body are checked.
+    #[must_use]
+    pub fn tagged_any<S: AsRef<str>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags_any = Some(tags_from(tags));
+        self
+    }
+
+    /// Filter to projects with all of these tags (matched hierarchically).
+    #[must_use]
+    pub fn tagged_all<S: AsRef<str>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags_all = Some(tags_from(tags));
+        self
+    }
+
+    /// Exclude projects with any of these tags (matched hierarchically).
+    #[must_use]
+    pub fn not_tagged<S: AsRef<str>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags_none = Some(tags_from(tags));
+        self
+    }
+
+    /// Filter to projects with unchecked checklist items.
+    #[must_use]
+    pub fn with_open_checklist_items(mut self) -> Self {
+        self.has_open_checklist_items = Some(true);
+        self
+    }
+
+    /// Filter to projects without unchecked checklist items, including projects
+    /// without a checklist.
+    #[must_use]
+    pub fn without_open_checklist_items(mut self) -> Self {
+        self.has_open_checklist_items = Some(false);
+        self
+    }
+
+    /// Filter to projects whose checklist is at least this complete, as a
+    /// fraction of checked items from 0.0 to 1.0.
+    ///
+    /// Projects without a checklist never match.
+    #[must_use]
+    pub fn checklist_completion_at_least(mut self, completion: f64) -> Self {
+        self.min_checklist_completion = Some(completion);
+        self
+    }
+
+    /// Filter to projects whose checklist is at most this complete, as a
+    /// fraction of checked items from 0.0 to 1.0.
+    ///
+    /// Projects without a checklist never match.
+    #[must_use]
+    pub fn checklist_completion_at_most(mut self, completion: f64) -> Self {
+        self.max_checklist_completion = Some(completion);
+        self
+    }
+
     // === Preset Filters ===
 
     /// Active projects (not done or paused).
@@ -340,8 +682,10 @@ impl ProjectFilter {
 impl TaskFilter {
     /// Check if a task matches this filter.
     ///
-    /// Note: `area_via_project` is NOT checked here because it requires
-    /// looking up the project file. This should be handled at the SDK level.
+    /// Note: `area_via_project`, `within_area`, `descendants_of` and
+    /// `actionable` are NOT checked here because they require looking up other
+    /// files. These are
+    /// handled at the SDK level.
     ///
     /// # Arguments
     /// * `task` - The task to check
@@ -351,10 +695,65 @@ impl TaskFilter {
     #[must_use]
     #[allow(clippy::too_many_lines)]
     pub fn matches(&self, task: &crate::Task) -> bool {
-        self.matches_archive(task)
-            && self.matches_status(task)
+        self.matches_archive(task) && self.matches_fields(task)
+    }
+
+    /// Check all criteria except archive handling.
+    ///
+    /// Used by callers that already know whether the task is archived.
+    pub(crate) fn matches_fields(&self, task: &crate::Task) -> bool {
+        self.matches_status(task)
             && self.matches_assignment(task)
             && self.matches_dates(task)
+            && self.matches_priority(task)
+            && self.matches_tags(task)
+            && self.matches_checklist(task)
+    }
+
+    /// Sort `tasks` by [`TaskFilter::sort`], if set.
+    pub(crate) fn sort<T: std::borrow::Borrow<Task>>(&self, tasks: &mut [T]) {
+        if let Some(sort) = self.sort {
+            tasks.sort_by(|a, b| sort.compare(a.borrow(), b.borrow()));
+        }
+    }
+
+    fn matches_priority(&self, task: &crate::Task) -> bool {
+        if self.min_priority.is_none() && self.max_priority.is_none() {
+            return true;
+        }
+        let Some(rank) = task.priority.as_ref().and_then(Priority::rank) else {
+            return false;
+        };
+        let min = self.min_priority.as_ref().and_then(Priority::rank);
+        let max = self.max_priority.as_ref().and_then(Priority::rank);
+        min.map_or(true, |min| rank >= min) && max.map_or(true, |max| rank <= max)
+    }
+
+    fn matches_tags(&self, task: &crate::Task) -> bool {
+        if self.tags_any.is_none() && self.tags_all.is_none() && self.tags_none.is_none() {
+            return true;
+        }
+        matches_tags(
+            &task.all_tags(),
+            self.tags_any.as_deref(),
+            self.tags_all.as_deref(),
+            self.tags_none.as_deref(),
+        )
+    }
+
+    fn matches_checklist(&self, task: &crate::Task) -> bool {
+        if self.has_open_checklist_items.is_none()
+            && self.min_checklist_completion.is_none()
+            && self.max_checklist_completion.is_none()
+        {
+            return true;
+        }
+        matches_checklist(
+            &task.checklist(),
+            self.has_open_checklist_items,
+            self.min_checklist_completion,
+            self.max_checklist_completion,
+        )
     }
 
     fn matches_archive(&self, task: &crate::Task) -> bool {
@@ -414,29 +813,35 @@ impl TaskFilter {
             }
         }
 
+        if let Some(has_parent) = self.has_parent {
+            if has_parent != task.parent.is_some() {
+                return false;
+            }
+        }
+
         true
     }
 
     #[allow(clippy::too_many_lines)]
     fn matches_dates(&self, task: &crate::Task) -> bool {
-        // Date filtering - compare by date portion
+        // Date filtering - compare by local date
         if let Some(before) = self.due_before {
             match &task.due {
-                Some(due) if due.date() < before => {}
+                Some(due) if due.local_date() < before => {}
                 _ => return false,
             }
         }
 
         if let Some(after) = self.due_after {
             match &task.due {
-                Some(due) if due.date() > after => {}
+                Some(due) if due.local_date() > after => {}
                 _ => return false,
             }
         }
 
         if let Some(on) = self.due_on {
             match &task.due {
-                Some(due) if due.date() == on => {}
+                Some(due) if due.local_date() == on => {}
                 _ => return false,
             }
         }
@@ -463,13 +868,8 @@ impl TaskFilter {
         }
 
         if let Some(before) = self.created_before {
-            // and_hms_opt with valid hours/mins/secs always returns Some
-            let task_created = task.created_at.datetime().unwrap_or_else(|| {
-                task.created_at
-                    .date()
-                    .and_hms_opt(0, 0, 0)
-                    .unwrap_or_default()
-            });
+            // Date-only values are midnight
+            let task_created = task.created_at.local_datetime();
             if task_created >= before {
                 return false;
             }
@@ -477,12 +877,14 @@ impl TaskFilter {
 
         if let Some(after) = self.created_after {
             // and_hms_opt with valid hours/mins/secs always returns Some
-            let task_created = task.created_at.datetime().unwrap_or_else(|| {
+            let task_created = if task.created_at.is_date_only() {
                 task.created_at
                     .date()
                     .and_hms_opt(23, 59, 59)
                     .unwrap_or_default()
-            });
+            } else {
+                task.created_at.local_datetime()
+            };
             if task_created <= after {
                 return false;
             }
@@ -508,6 +910,8 @@ pub struct AreaFilter {
     /// If None, returns all areas.
     /// Use `Some(vec![AreaStatus::Active])` to exclude archived.
     pub status: Option<Vec<AreaStatus>>,
+    /// This area and its sub-areas, at any depth.
+    pub within_area: Option<FileReference>,
 }
 
 impl AreaFilter {
@@ -531,6 +935,13 @@ impl AreaFilter {
         self
     }
 
+    /// Filter to this area and its sub-areas, at any depth.
+    #[must_use]
+    pub fn within_area(mut self, area: impl Into<FileReference>) -> Self {
+        self.within_area = Some(area.into());
+        self
+    }
+
     // === Preset Filters ===
 
     /// Only active areas (not archived).
@@ -542,6 +953,9 @@ impl AreaFilter {
 
 impl ProjectFilter {
     /// Check if a project matches this filter.
+    ///
+    /// Note: `blocked_by_unfinished` is NOT checked here because it requires
+    /// looking up other projects. This is handled at the SDK level.
     #[must_use]
     pub fn matches(&self, project: &crate::Project) -> bool {
         // Status filtering
@@ -570,12 +984,40 @@ impl ProjectFilter {
             }
         }
 
+        // Tag filtering
+        if (self.tags_any.is_some() || self.tags_all.is_some() || self.tags_none.is_some())
+            && !matches_tags(
+                &project.all_tags(),
+                self.tags_any.as_deref(),
+                self.tags_all.as_deref(),
+                self.tags_none.as_deref(),
+            )
+        {
+            return false;
+        }
+
+        // Checklist filtering
+        if self.has_open_checklist_items.is_some()
+            || self.min_checklist_completion.is_some()
+            || self.max_checklist_completion.is_some()
+        {
+            return matches_checklist(
+                &project.checklist(),
+                self.has_open_checklist_items,
+                self.min_checklist_completion,
+                self.max_checklist_completion,
+            );
+        }
+
         true
     }
 }
 
 impl AreaFilter {
     /// Check if an area matches this filter.
+    ///
+    /// Note: `within_area` is NOT checked here because it requires looking up
+    /// other areas. This is handled at the SDK level.
     #[must_use]
     pub fn matches(&self, area: &crate::Area) -> bool {
         // Status filtering
@@ -718,6 +1160,12 @@ mod tests {
             let filter = AreaFilter::active();
             assert_eq!(filter.status, Some(vec![AreaStatus::Active]));
         }
+
+        #[test]
+        fn within_area() {
+            let filter = AreaFilter::new().within_area("[[Work]]");
+            assert_eq!(filter.within_area, Some(FileReference::wiki_link("Work")));
+        }
     }
 
     mod task_filter_matches {
@@ -739,6 +1187,12 @@ mod tests {
                 defer_until: None,
                 project: None,
                 area: None,
+                parent: None,
+                blocked_by: Vec::new(),
+                priority: None,
+                recurrence: None,
+                series: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
                 projects_count: None,
@@ -840,6 +1294,17 @@ mod tests {
             assert!(!filter.matches(&task_with_proj));
         }
 
+        #[test]
+        fn parent_filters() {
+            let mut task = sample_task();
+            assert!(TaskFilter::new().top_level_only().matches(&task));
+            assert!(!TaskFilter::new().subtasks_only().matches(&task));
+
+            task.parent = Some(FileReference::wiki_link("Epic"));
+            assert!(!TaskFilter::new().top_level_only().matches(&task));
+            assert!(TaskFilter::new().subtasks_only().matches(&task));
+        }
+
         #[test]
         fn due_before_matches() {
             let filter =
@@ -877,6 +1342,36 @@ mod tests {
             assert!(!filter.matches(&task));
         }
 
+        #[test]
+        fn offset_datetimes_compare_in_local_time() {
+            // Noon local time on 2025-06-15, written at +09:00
+            let local = NaiveDate::from_ymd_opt(2025, 6, 15)
+                .unwrap()
+                .and_hms_opt(12, 0, 0)
+                .unwrap();
+            let offset = chrono::FixedOffset::east_opt(9 * 3600).unwrap();
+            let written = local
+                .and_local_timezone(chrono::Local)
+                .unwrap()
+                .with_timezone(&offset);
+
+            let mut task = sample_task();
+            task.due = Some(written.into());
+            task.created_at = written.into();
+
+            let date = NaiveDate::from_ymd_opt(2025, 6, 15).unwrap();
+            assert!(TaskFilter::new().due_on(date).matches(&task));
+
+            let second = chrono::Duration::seconds(1);
+            assert!(TaskFilter::new()
+                .created_before(local + second)
+                .matches(&task));
+            assert!(!TaskFilter::new().created_before(local).matches(&task));
+            assert!(TaskFilter::new()
+                .created_after(local - second)
+                .matches(&task));
+        }
+
         #[test]
         fn scheduled_on_matches() {
             let filter =
@@ -947,6 +1442,162 @@ mod tests {
             deferred.defer_until = Some(NaiveDate::from_ymd_opt(2025, 6, 20).unwrap());
             assert!(!filter.matches(&deferred));
         }
+
+        #[test]
+        fn priority_range_uses_rank() {
+            let filter = TaskFilter::new()
+                .priority_at_least(Priority::Medium)
+                .priority_at_most(5);
+            let mut task = sample_task();
+
+            task.priority = Some(Priority::Low);
+            assert!(!filter.matches(&task));
+            task.priority = Some(Priority::High);
+            assert!(filter.matches(&task));
+            task.priority = Some(Priority::Numeric(5));
+            assert!(filter.matches(&task));
+            task.priority = Some(Priority::Numeric(6));
+            assert!(!filter.matches(&task));
+        }
+
+        #[test]
+        fn priority_filter_excludes_unranked_tasks() {
+            let filter = TaskFilter::new().priority_at_most(Priority::High);
+            let mut task = sample_task();
+            assert!(!filter.matches(&task));
+            task.priority = Some(Priority::Other("someday".into()));
+            assert!(!filter.matches(&task));
+        }
+
+        #[test]
+        fn tag_filters_match_hierarchically() {
+            let mut task = sample_task();
+            task.tags = vec![Tag::new("Work/Client").unwrap()];
+            task.body = "Ask about #invoices".to_string();
+
+            assert!(TaskFilter::new().tagged_any(["work"]).matches(&task));
+            assert!(TaskFilter::new()
+                .tagged_any(["home", "#invoices"])
+                .matches(&task));
+            assert!(!TaskFilter::new().tagged_any(["work/other"]).matches(&task));
+            assert!(!TaskFilter::new().tagged_any(["client"]).matches(&task));
+
+            assert!(TaskFilter::new()
+                .tagged_all(["work", "invoices"])
+                .matches(&task));
+            assert!(!TaskFilter::new()
+                .tagged_all(["work", "home"])
+                .matches(&task));
+
+            assert!(!TaskFilter::new().not_tagged(["work"]).matches(&task));
+            assert!(TaskFilter::new().not_tagged(["home"]).matches(&task));
+        }
+
+        #[test]
+        fn checklist_filters() {
+            let mut task = sample_task();
+            task.body = "- [x] One\n- [ ] Two\n- [ ] Three\n- [ ] Four".to_string();
+
+            assert!(TaskFilter::new().with_open_checklist_items().matches(&task));
+            assert!(!TaskFilter::new()
+                .without_open_checklist_items()
+                .matches(&task));
+            assert!(TaskFilter::new()
+                .checklist_completion_at_least(0.25)
+                .checklist_completion_at_most(0.5)
+                .matches(&task));
+            assert!(!TaskFilter::new()
+                .checklist_completion_at_least(0.5)
+                .matches(&task));
+        }
+
+        #[test]
+        fn checklist_filters_without_checklist() {
+            let task = sample_task();
+            assert!(!TaskFilter::new().with_open_checklist_items().matches(&task));
+            assert!(TaskFilter::new()
+                .without_open_checklist_items()
+                .matches(&task));
+            assert!(!TaskFilter::new()
+                .checklist_completion_at_most(1.0)
+                .matches(&task));
+        }
+
+        #[test]
+        fn tag_filter_excludes_untagged_tasks() {
+            let task = sample_task();
+            assert!(!TaskFilter::new().tagged_any(["work"]).matches(&task));
+            assert!(TaskFilter::new().not_tagged(["work"]).matches(&task));
+        }
+    }
+
+    mod task_sort {
+        use super::*;
+        use std::collections::HashMap;
+        use std::path::PathBuf;
+
+        fn task(name: &str, priority: Option<Priority>, due: Option<&str>) -> Task {
+            Task {
+                path: PathBuf::from(format!("/tasks/{name}.md")),
+                title: name.to_string(),
+                status: TaskStatus::Ready,
+                created_at: "2025-01-01".parse().unwrap(),
+                updated_at: "2025-01-01".parse().unwrap(),
+                completed_at: None,
+                due: due.map(|d| d.parse().unwrap()),
+                scheduled: None,
+                defer_until: None,
+                project: None,
+                area: None,
+                parent: None,
+                blocked_by: Vec::new(),
+                priority,
+                recurrence: None,
+                series: None,
+                tags: Vec::new(),
+                body: String::new(),
+                extra: HashMap::new(),
+                projects_count: None,
+            }
+        }
+
+        fn titles(filter: &TaskFilter, mut tasks: Vec<Task>) -> Vec<String> {
+            filter.sort(&mut tasks);
+            tasks.into_iter().map(|t| t.title).collect()
+        }
+
+        #[test]
+        fn by_priority_most_important_first() {
+            let tasks = vec![
+                task("none", None, None),
+                task("low", Some(Priority::Low), None),
+                task("b-high", Some(Priority::High), None),
+                task("a-high", Some(Priority::High), None),
+                task("other", Some(Priority::Other("?".into())), None),
+            ];
+            let filter = TaskFilter::new().sorted_by(TaskSort::Priority);
+            assert_eq!(
+                titles(&filter, tasks),
+                ["a-high", "b-high", "low", "none", "other"]
+            );
+        }
+
+        #[test]
+        fn by_due_missing_last() {
+            let tasks = vec![
+                task("none", None, None),
+                task("later", None, Some("2025-02-01")),
+                task("sooner", None, Some("2025-01-15T09:00:00")),
+            ];
+            let filter = TaskFilter::new().sorted_by(TaskSort::Due);
+            assert_eq!(titles(&filter, tasks), ["sooner", "later", "none"]);
+        }
+
+        #[test]
+        fn unsorted_filter_keeps_order() {
+            let tasks = vec![task("b", None, None), task("a", None, None)];
+            assert_eq!(titles(&TaskFilter::new(), tasks), ["b", "a"]);
+        }
     }
 
     mod project_filter_matches {
@@ -966,6 +1617,7 @@ mod tests {
                 start_date: None,
                 end_date: None,
                 blocked_by: Vec::new(),
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
             }
@@ -1006,6 +1658,31 @@ mod tests {
             let project = sample_project();
             assert!(!filter.matches(&project));
         }
+
+        #[test]
+        fn tag_filters_match() {
+            let mut project = sample_project();
+            project.tags = vec![Tag::new("work/client").unwrap()];
+
+            assert!(ProjectFilter::new().tagged_any(["work"]).matches(&project));
+            assert!(ProjectFilter::new()
+                .tagged_all(["work", "work/client"])
+                .matches(&project));
+            assert!(!ProjectFilter::new().not_tagged(["work"]).matches(&project));
+        }
+
+        #[test]
+        fn checklist_filter_matches() {
+            let mut project = sample_project();
+            project.body = "- [x] Spec\n- [x] Build".to_string();
+
+            assert!(!ProjectFilter::new()
+                .with_open_checklist_items()
+                .matches(&project));
+            assert!(ProjectFilter::new()
+                .checklist_completion_at_least(1.0)
+                .matches(&project));
+        }
     }
 
     mod area_filter_matches {
@@ -1021,6 +1698,8 @@ mod tests {
                 status: Some(AreaStatus::Active),
                 area_type: None,
                 description: None,
+                parent: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
             }
@@ -0,0 +1,717 @@
+//! Format-preserving frontmatter editing.
+//!
+//! Regenerating a file from its parsed entity (see the `Display` impls in
+//! `writer.rs`) normalises key order, quoting and layout, and drops comments.
+//! This module instead patches the original text and touches only the keys
+//! whose values changed (S3 §3.2). Unchanged keys, comments, blank lines, key
+//! order and the body are kept byte-for-byte. Changed keys keep their original
+//! quoting and flow/block sequence style where possible.
+
+use crate::writer::{render_field, yaml_string, yaml_value_to_string, FieldValue, Fields};
+use std::collections::HashMap;
+
+/// How a scalar was quoted in the original file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quote {
+    Plain,
+    Single,
+    Double,
+}
+
+/// How a value was laid out in the original file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Style {
+    Scalar(Quote),
+    FlowSequence(Quote),
+    BlockSequence { indent: String, quote: Quote },
+    Other,
+}
+
+/// A new value, reduced to the shapes we know how to restyle.
+enum Shape {
+    /// A formatted scalar, and whether it is a string (and may be re-quoted).
+    Scalar(String, bool),
+    /// Formatted sequence items, each with the same string flag.
+    List(Vec<(String, bool)>),
+    /// Anything else (nested mappings etc.), rendered canonically.
+    Complex,
+}
+
+/// A run of frontmatter lines.
+#[derive(Debug)]
+enum Segment {
+    /// A top-level `key: value` entry and its continuation lines.
+    Entry { key: String, lines: Vec<String> },
+    /// Comments, blank lines, or anything else that is passed through as-is.
+    Trivia(String),
+}
+
+/// A markdown file split into frontmatter segments and everything else.
+struct Document<'a> {
+    newline: &'static str,
+    opening: &'a str,
+    segments: Vec<Segment>,
+    closing: &'a str,
+    closing_has_newline: bool,
+    body: &'a str,
+}
+
+/// Split `content` into its frontmatter (with delimiters) and its body, as
+/// [`patch`] sees them.
+///
+/// Returns `None` if `content` has no frontmatter.
+pub(crate) fn split_body(content: &str) -> Option<(&str, &str)> {
+    let doc = Document::parse(content)?;
+    Some(content.split_at(content.len() - doc.body.len()))
+}
+
+/// Patch `original` so that its frontmatter holds `after` instead of `before`.
+///
+/// `before` and `before_body` must describe the entity as parsed from
+/// `original`. Keys whose values are equal in both are left untouched.
+/// `aliases` lists alternative spellings a file may use for a canonical key
+/// (for example a task's singular `project` instead of `projects`).
+///
+/// Returns `None` if the frontmatter block cannot be located; callers should
+/// then fall back to regenerating the whole file.
+pub(crate) fn patch(
+    original: &str,
+    before: &Fields,
+    before_body: &str,
+    after: &Fields,
+    after_body: &str,
+    aliases: &[(&str, &str)],
+) -> Option<String> {
+    let mut doc = Document::parse(original)?;
+
+    let before_map: HashMap<&str, &FieldValue> =
+        before.iter().map(|(k, v)| (k.as_str(), v)).collect();
+    let after_map: HashMap<&str, &FieldValue> =
+        after.iter().map(|(k, v)| (k.as_str(), v)).collect();
+
+    // Removed keys
+    for (key, _) in before {
+        if !after_map.contains_key(key.as_str()) {
+            if let Some((index, _)) = doc.find(key, aliases) {
+                doc.segments.remove(index);
+            }
+        }
+    }
+
+    // Changed and added keys, in canonical order
+    for (position, (key, value)) in after.iter().enumerate() {
+        if before_map.get(key.as_str()) == Some(&value) {
+            continue;
+        }
+
+        if let Some((index, is_alias)) = doc.find(key, aliases) {
+            let value = if is_alias {
+                alias_value(value)
+            } else {
+                value.clone()
+            };
+            if let Segment::Entry { key, lines } = &mut doc.segments[index] {
+                *lines = restyle(key, lines, &value);
+            }
+        } else {
+            // Insert after the nearest preceding key that the file already has
+            let anchor = after[..position]
+                .iter()
+                .rev()
+                .find_map(|(k, _)| doc.find(k, aliases).map(|(i, _)| i));
+            let segment = Segment::Entry {
+                key: key.clone(),
+                lines: render_field(key, value).lines().map(String::from).collect(),
+            };
+            match anchor {
+                Some(index) => doc.segments.insert(index + 1, segment),
+                None => doc.segments.push(segment),
+            }
+        }
+    }
+
+    if before_body == after_body {
+        return Some(doc.render(doc.body));
+    }
+
+    // Parsed bodies are trimmed, so keep the blank lines around the original
+    let new_body = after_body.trim();
+    let body = if new_body.is_empty() {
+        String::new()
+    } else {
+        let leading = &doc.body[..doc.body.len() - doc.body.trim_start().len()];
+        let trailing = &doc.body[doc.body.trim_end().len()..];
+        format!("{leading}{new_body}{trailing}")
+    };
+    Some(doc.render(&body))
+}
+
+impl<'a> Document<'a> {
+    fn parse(content: &'a str) -> Option<Self> {
+        let newline = if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let mut lines = content.split_inclusive('\n');
+
+        let first = lines.next()?;
+        let opening = strip_eol(first);
+        if opening.trim_end() != "---" {
+            return None;
+        }
+
+        let mut offset = first.len();
+
+        let mut frontmatter = Vec::new();
+        for line in lines {
+            offset += line.len();
+            let text = strip_eol(line);
+            if text.trim_end() == "---" {
+                return Some(Self {
+                    newline,
+                    opening,
+                    segments: segment(&frontmatter),
+                    closing: text,
+                    closing_has_newline: line.ends_with('\n'),
+                    body: &content[offset..],
+                });
+            }
+            frontmatter.push(text);
+        }
+
+        None
+    }
+
+    /// Find the segment index for `key`, trying its aliases if the file
+    /// doesn't use the canonical spelling. Also reports whether an alias matched.
+    fn find(&self, key: &str, aliases: &[(&str, &str)]) -> Option<(usize, bool)> {
+        let position = |wanted: &str| {
+            self.segments
+                .iter()
+                .position(|s| matches!(s, Segment::Entry { key, .. } if key == wanted))
+        };
+
+        if let Some(index) = position(key) {
+            return Some((index, false));
+        }
+
+        aliases
+            .iter()
+            .filter(|(canonical, _)| *canonical == key)
+            .find_map(|(_, alias)| position(alias).map(|i| (i, true)))
+    }
+
+    fn render(&self, body: &str) -> String {
+        let mut out = String::with_capacity(self.body.len() + 256);
+        out.push_str(self.opening);
+        out.push_str(self.newline);
+
+        for segment in &self.segments {
+            match segment {
+                Segment::Entry { lines, .. } => {
+                    for line in lines {
+                        out.push_str(line);
+                        out.push_str(self.newline);
+                    }
+                }
+                Segment::Trivia(line) => {
+                    out.push_str(line);
+                    out.push_str(self.newline);
+                }
+            }
+        }
+
+        out.push_str(self.closing);
+        if self.closing_has_newline || !body.is_empty() {
+            out.push_str(self.newline);
+        }
+        out.push_str(body);
+        out
+    }
+}
+
+fn strip_eol(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+/// Split frontmatter lines into top-level entries and trivia.
+fn segment(lines: &[&str]) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let Some((key, _)) = entry_key(lines[i]) else {
+            segments.push(Segment::Trivia(lines[i].to_string()));
+            i += 1;
+            continue;
+        };
+
+        // Continuation lines belong to the entry; blank lines only do if
+        // more continuation lines follow them.
+        let mut end = i + 1;
+        let mut j = i + 1;
+        while j < lines.len() {
+            if is_continuation(lines[j]) {
+                j += 1;
+                end = j;
+            } else if lines[j].trim().is_empty() {
+                j += 1;
+            } else {
+                break;
+            }
+        }
+
+        segments.push(Segment::Entry {
+            key,
+            lines: lines[i..end].iter().map(|l| (*l).to_string()).collect(),
+        });
+        i = end;
+    }
+
+    segments
+}
+
+fn is_continuation(line: &str) -> bool {
+    !line.trim().is_empty()
+        && (line.starts_with(' ')
+            || line.starts_with('\t')
+            || line == "-"
+            || line.starts_with("- "))
+}
+
+/// Parse a top-level `key:` line, returning the key and the byte length of
+/// the `key:` prefix (everything up to and including the colon).
+fn entry_key(line: &str) -> Option<(String, usize)> {
+    let first = line.chars().next()?;
+    if first.is_whitespace() || matches!(first, '#' | '-' | '[' | '{' | '%') {
+        return None;
+    }
+
+    let (key, search_from) = if first == '"' || first == '\'' {
+        let close = line[1..].find(first)? + 1;
+        (line[1..close].to_string(), close + 1)
+    } else {
+        let colon = find_mapping_colon(line, 0)?;
+        (line[..colon].trim_end().to_string(), 0)
+    };
+
+    let colon = find_mapping_colon(line, search_from)?;
+    if search_from > 0 && !line[search_from..colon].trim().is_empty() {
+        return None;
+    }
+    Some((key, colon + 1))
+}
+
+/// Find a `:` that is followed by whitespace or the end of the line.
+fn find_mapping_colon(line: &str, from: usize) -> Option<usize> {
+    let bytes = line.as_bytes();
+    (from..bytes.len())
+        .find(|&i| bytes[i] == b':' && bytes.get(i + 1).map_or(true, |b| *b == b' ' || *b == b'\t'))
+}
+
+/// Split the text after `key:` into the value and a trailing ` # comment`.
+fn split_comment(rest: &str) -> (&str, &str) {
+    let mut in_single = false;
+    let mut in_double = false;
+    let mut escaped = false;
+    let mut previous = ' ';
+
+    for (i, c) in rest.char_indices() {
+        if in_double {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_double = false;
+            }
+        } else if in_single {
+            if c == '\'' {
+                in_single = false;
+            }
+        } else if c == '"' {
+            in_double = true;
+        } else if c == '\'' {
+            in_single = true;
+        } else if c == '#' && previous.is_whitespace() {
+            let start = rest[..i].trim_end().len();
+            return (&rest[..start], &rest[start..]);
+        }
+        previous = c;
+    }
+
+    (rest, "")
+}
+
+fn quote_of(value: &str) -> Quote {
+    if value.starts_with('"') {
+        Quote::Double
+    } else if value.starts_with('\'') {
+        Quote::Single
+    } else {
+        Quote::Plain
+    }
+}
+
+/// Work out how an existing entry was written.
+fn detect_style(lines: &[String], prefix_len: usize) -> Style {
+    let (value, _) = split_comment(&lines[0][prefix_len..]);
+    let value = value.trim();
+
+    if value.is_empty() {
+        let first_item = lines[1..]
+            .iter()
+            .find(|l| !l.trim().is_empty() && !l.trim_start().starts_with('#'));
+        if let Some(item) = first_item {
+            let trimmed = item.trim_start();
+            if trimmed == "-" || trimmed.starts_with("- ") {
+                return Style::BlockSequence {
+                    indent: item[..item.len() - trimmed.len()].to_string(),
+                    quote: quote_of(trimmed[1..].trim_start()),
+                };
+            }
+        }
+        return Style::Other;
+    }
+
+    if let Some(inner) = value.strip_prefix('[') {
+        return Style::FlowSequence(quote_of(inner.trim_start()));
+    }
+    if value.starts_with(['{', '|', '>', '&', '*', '!']) {
+        return Style::Other;
+    }
+
+    Style::Scalar(quote_of(value))
+}
+
+fn shape(value: &FieldValue) -> Shape {
+    use serde_yaml::Value;
+
+    let yaml_scalar = |v: &Value| match v {
+        Value::Sequence(_) | Value::Mapping(_) | Value::Tagged(_) => None,
+        Value::String(_) => Some((yaml_value_to_string(v, 0), true)),
+        other => Some((yaml_value_to_string(other, 0), false)),
+    };
+
+    match value {
+        FieldValue::Scalar(s) => Shape::Scalar(s.clone(), true),
+        FieldValue::List(items) => Shape::List(items.iter().map(|i| (i.clone(), true)).collect()),
+        FieldValue::Yaml(Value::Sequence(seq)) => seq
+            .iter()
+            .map(yaml_scalar)
+            .collect::<Option<Vec<_>>>()
+            .map_or(Shape::Complex, Shape::List),
+        FieldValue::Yaml(v) => {
+            yaml_scalar(v).map_or(Shape::Complex, |(s, is_string)| Shape::Scalar(s, is_string))
+        }
+    }
+}
+
+/// Re-render an existing entry with a new value, keeping its layout.
+fn restyle(key: &str, lines: &[String], value: &FieldValue) -> Vec<String> {
+    let prefix_len = entry_key(&lines[0]).map_or(0, |(_, len)| len);
+    let prefix = &lines[0][..prefix_len];
+    let (_, comment) = split_comment(&lines[0][prefix_len..]);
+    let style = detect_style(lines, prefix_len);
+
+    match (shape(value), style) {
+        (Shape::Scalar(text, is_string), Style::Scalar(quote)) => {
+            vec![format!(
+                "{prefix} {}{comment}",
+                requote(&text, is_string, quote)
+            )]
+        }
+        (Shape::Scalar(text, _), _) => vec![format!("{prefix} {text}{comment}")],
+        (Shape::List(items), Style::FlowSequence(quote)) => {
+            let items: Vec<String> = items
+                .iter()
+                .map(|(text, is_string)| requote(text, *is_string, quote))
+                .collect();
+            vec![format!("{prefix} [{}]{comment}", items.join(", "))]
+        }
+        (Shape::List(items), _) if items.is_empty() => vec![format!("{prefix} []{comment}")],
+        (Shape::List(items), Style::BlockSequence { indent, quote }) => {
+            let mut result = vec![format!("{prefix}{comment}")];
+            result.extend(items.iter().map(|(text, is_string)| {
+                format!("{indent}- {}", requote(text, *is_string, quote))
+            }));
+            result
+        }
+        (Shape::List(items), _) => {
+            let mut result = vec![format!("{prefix}{comment}")];
+            result.extend(items.iter().map(|(text, _)| format!("  - {text}")));
+            result
+        }
+        (Shape::Complex, _) => render_field(key, value).lines().map(String::from).collect(),
+    }
+}
+
+/// Convert a value for a key written under an alias.
+///
+/// Aliases are singular spellings of list keys, so a one-item list becomes a
+/// plain scalar.
+fn alias_value(value: &FieldValue) -> FieldValue {
+    match value {
+        FieldValue::List(items) if items.len() == 1 => FieldValue::Scalar(items[0].clone()),
+        other => other.clone(),
+    }
+}
+
+/// Quote a formatted string scalar the way the original value was quoted.
+fn requote(formatted: &str, is_string: bool, quote: Quote) -> String {
+    if !is_string {
+        return formatted.to_string();
+    }
+
+    let raw = unquote(formatted);
+    match quote {
+        Quote::Plain if is_plain_safe(&raw) => raw,
+        Quote::Plain => yaml_string(&raw),
+        // Single-quoted scalars can't hold line breaks
+        Quote::Single if !raw.contains('\n') => format!("'{}'", raw.replace('\'', "''")),
+        Quote::Single | Quote::Double => double_quoted(&raw),
+    }
+}
+
+/// Whether `raw` reads back as the same string when written unquoted, in both
+/// block and flow context. Dates and datetimes stay plain this way.
+fn is_plain_safe(raw: &str) -> bool {
+    // YAML 1.1 booleans still trip up other readers
+    !matches!(raw, "yes" | "no" | "on" | "off")
+        && !raw.contains([',', '[', ']', '{', '}'])
+        && matches!(
+            serde_yaml::from_str::<serde_yaml::Value>(raw),
+            Ok(serde_yaml::Value::String(parsed)) if parsed == raw
+        )
+}
+
+fn double_quoted(raw: &str) -> String {
+    format!("\"{}\"", raw.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Undo the double-quoting applied by `yaml_string`.
+fn unquote(formatted: &str) -> String {
+    let Some(inner) = formatted
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+    else {
+        return formatted.to_string();
+    };
+
+    let mut result = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                result.push(next);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{FileReference, ParsedProject, ParsedTask, TaskStatus};
+
+    const TASK_ALIASES: &[(&str, &str)] = &[("projects", "project")];
+
+    /// Parse `original`, apply `edit` to the task and patch the file.
+    fn patch_task(original: &str, edit: impl FnOnce(&mut ParsedTask)) -> String {
+        let before = ParsedTask::parse(original).unwrap();
+        let mut after = before.clone();
+        edit(&mut after);
+        patch(
+            original,
+            &before.frontmatter_fields(),
+            &before.body,
+            &after.frontmatter_fields(),
+            &after.body,
+            TASK_ALIASES,
+        )
+        .unwrap()
+    }
+
+    mod unchanged_content {
+        use super::*;
+
+        #[test]
+        fn no_changes_is_byte_identical() {
+            let original = "---\n# A comment\ntitle: 'Quoted'\nstatus: ready\ncustom: [b, a]\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\n\nBody text\n";
+            assert_eq!(patch_task(original, |_| {}), original);
+        }
+
+        #[test]
+        fn keeps_comments_order_and_extra_keys() {
+            let original = "---\ntitle: Task\n# status comment\nstatus: ready # inline\nzeta: 1\nalpha: 2\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\n";
+            let patched = patch_task(original, |t| t.status = TaskStatus::Done);
+
+            assert_eq!(
+                patched,
+                "---\ntitle: Task\n# status comment\nstatus: done # inline\nzeta: 1\nalpha: 2\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\n"
+            );
+        }
+
+        #[test]
+        fn keeps_body_byte_for_byte() {
+            let original = "---\ntitle: Task\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\n\n\n  indented body   \n";
+            let patched = patch_task(original, |t| t.status = TaskStatus::Done);
+            assert!(patched.ends_with("---\n\n\n  indented body   \n"));
+        }
+
+        #[test]
+        fn keeps_crlf_line_endings() {
+            let original = "---\r\ntitle: Task\r\nstatus: ready\r\ncreated-at: 2025-01-01\r\nupdated-at: 2025-01-01\r\n---\r\nBody\r\n";
+            let patched = patch_task(original, |t| t.status = TaskStatus::Done);
+            assert_eq!(
+                patched,
+                "---\r\ntitle: Task\r\nstatus: done\r\ncreated-at: 2025-01-01\r\nupdated-at: 2025-01-01\r\n---\r\nBody\r\n"
+            );
+        }
+    }
+
+    mod quoting {
+        use super::*;
+
+        #[test]
+        fn keeps_single_quotes() {
+            let original = "---\ntitle: 'Old: title'\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\n";
+            let patched = patch_task(original, |t| t.title = "It's new".to_string());
+            assert!(patched.contains("title: 'It''s new'\n"));
+            assert_eq!(ParsedTask::parse(&patched).unwrap().title, "It's new");
+        }
+
+        #[test]
+        fn keeps_double_quotes() {
+            let original = "---\ntitle: \"Old\"\nstatus: \"ready\"\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\n";
+            let patched = patch_task(original, |t| t.status = TaskStatus::InProgress);
+            assert!(patched.contains("status: \"in-progress\"\n"));
+            assert!(patched.contains("title: \"Old\"\n"));
+        }
+
+        #[test]
+        fn plain_values_are_quoted_only_when_needed() {
+            let original = "---\ntitle: Old\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\n";
+            let patched = patch_task(original, |t| t.title = "New: with colon".to_string());
+            assert!(patched.contains("title: \"New: with colon\"\n"));
+        }
+
+        #[test]
+        fn hash_inside_quotes_is_not_a_comment() {
+            let original = "---\ntitle: \"Issue #42\" # tracked\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\n";
+            let patched = patch_task(original, |t| t.title = "Issue #43".to_string());
+            assert!(patched.contains("title: \"Issue #43\" # tracked\n"));
+        }
+    }
+
+    mod sequences {
+        use super::*;
+
+        #[test]
+        fn keeps_block_sequence_indentation_and_quotes() {
+            let original = "---\ntitle: Task\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\nprojects:\n    - '[[Old]]'\n---\n";
+            let patched = patch_task(original, |t| {
+                t.project = Some(FileReference::wiki_link("New"));
+            });
+            assert!(patched.contains("projects:\n    - '[[New]]'\n---"));
+        }
+
+        #[test]
+        fn keeps_flow_sequence() {
+            let original = "---\ntitle: Task\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\nprojects: [\"[[Old]]\"]\n---\n";
+            let patched = patch_task(original, |t| {
+                t.project = Some(FileReference::wiki_link("New"));
+            });
+            assert!(patched.contains("projects: [\"[[New]]\"]\n"));
+        }
+
+        #[test]
+        fn writes_singular_project_key_when_file_uses_it() {
+            let original = "---\ntitle: Task\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\nproject: \"[[Old]]\"\n---\n";
+            let patched = patch_task(original, |t| {
+                t.project = Some(FileReference::wiki_link("New"));
+            });
+            assert!(patched.contains("project: \"[[New]]\"\n"));
+            assert!(!patched.contains("projects"));
+        }
+
+        #[test]
+        fn project_blocked_by_list() {
+            let original = "---\ntitle: Project\nblocked-by: ['[[A]]'] # deps\n---\n";
+            let before = ParsedProject::parse(original).unwrap();
+            let mut after = before.clone();
+            after.blocked_by.push(FileReference::wiki_link("B"));
+
+            let patched = patch(
+                original,
+                &before.frontmatter_fields(),
+                &before.body,
+                &after.frontmatter_fields(),
+                &after.body,
+                &[],
+            )
+            .unwrap();
+
+            assert_eq!(
+                patched,
+                "---\ntitle: Project\nblocked-by: ['[[A]]', '[[B]]'] # deps\n---\n"
+            );
+        }
+    }
+
+    mod adding_and_removing {
+        use super::*;
+
+        #[test]
+        fn new_key_is_inserted_after_its_predecessor() {
+            let original = "---\ntitle: Task\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n# trailing comment\nnote: keep\n---\n";
+            let patched = patch_task(original, |t| {
+                t.completed_at = Some("2025-01-05".parse().unwrap());
+            });
+            assert_eq!(
+                patched,
+                "---\ntitle: Task\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\ncompleted-at: 2025-01-05\n# trailing comment\nnote: keep\n---\n"
+            );
+        }
+
+        #[test]
+        fn removed_key_takes_its_continuation_lines() {
+            let original = "---\ntitle: Task\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\nprojects:\n  - \"[[A]]\"\narea: \"[[Work]]\"\n---\n";
+            let patched = patch_task(original, |t| t.project = None);
+            assert_eq!(
+                patched,
+                "---\ntitle: Task\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\narea: \"[[Work]]\"\n---\n"
+            );
+        }
+
+        #[test]
+        fn changed_body_is_written() {
+            let original = "---\ntitle: Task\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\nOld body\n";
+            let patched = patch_task(original, |t| t.body = "New body\n".to_string());
+            assert!(patched.ends_with("---\nNew body\n"));
+        }
+
+        #[test]
+        fn changed_body_keeps_surrounding_blank_lines() {
+            let original = "---\ntitle: Task\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\n\nOld body\n\n";
+            let patched = patch_task(original, |t| t.body = "New body".to_string());
+            assert!(patched.ends_with("---\n\nNew body\n\n"));
+        }
+    }
+
+    mod fallback {
+        use super::*;
+
+        #[test]
+        fn missing_frontmatter_returns_none() {
+            let fields = vec![("title".to_string(), FieldValue::Scalar("A".to_string()))];
+            assert!(patch("no frontmatter", &fields, "", &fields, "", &[]).is_none());
+            assert!(patch("---\ntitle: A\n", &fields, "", &fields, "", &[]).is_none());
+        }
+    }
+}
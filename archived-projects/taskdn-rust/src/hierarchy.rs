@@ -0,0 +1,371 @@
+//! Parent/child task hierarchy.
+//!
+//! A task becomes a subtask of another through its `parent` field, a
+//! reference to the parent task file. Subtasks are full task files with
+//! their own status and dates, and can have subtasks of their own. Areas
+//! nest the same way (see `area_hierarchy`), sharing the [`Tree`] here.
+//!
+//! With [`TaskdnConfig::subtask_rollup`](crate::TaskdnConfig::subtask_rollup)
+//! enabled, [`Taskdn::get_task_warnings`] also checks a task against its
+//! subtasks and parents (see [`ValidationWarning::OpenSubtasks`]).
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::filter::TaskFilter;
+use crate::types::{Area, FileReference, Task};
+use crate::validation::ValidationWarning;
+use crate::Taskdn;
+
+/// An entity that can name another of its kind as its parent.
+pub(crate) trait Node {
+    fn path(&self) -> &Path;
+    fn title(&self) -> &str;
+    fn parent_ref(&self) -> Option<&FileReference>;
+}
+
+impl Node for Task {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn parent_ref(&self) -> Option<&FileReference> {
+        self.parent.as_ref()
+    }
+}
+
+impl Node for Area {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn parent_ref(&self) -> Option<&FileReference> {
+        self.parent.as_ref()
+    }
+}
+
+/// The parent links between a set of tasks or areas.
+pub(crate) struct Tree<'a, T> {
+    nodes: Vec<&'a T>,
+    /// Index of each node's parent, if it resolves to another node in the set.
+    parents: Vec<Option<usize>>,
+}
+
+/// The parent links between a set of tasks.
+pub(crate) type TaskTree<'a> = Tree<'a, Task>;
+
+/// The parent links between a set of areas.
+pub(crate) type AreaTree<'a> = Tree<'a, Area>;
+
+impl<'a, T: Node> Tree<'a, T> {
+    pub(crate) fn new(nodes: impl IntoIterator<Item = &'a T>) -> Self {
+        let mut nodes: Vec<&T> = nodes.into_iter().collect();
+        nodes.sort_by(|a, b| a.path().cmp(b.path()));
+
+        let parents = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| {
+                let parent = find(&nodes, node.parent_ref()?)?;
+                (parent != index).then_some(parent)
+            })
+            .collect();
+
+        Self { nodes, parents }
+    }
+
+    /// The node at `index`.
+    pub(crate) fn node(&self, index: usize) -> &'a T {
+        self.nodes[index]
+    }
+
+    /// Find the node a reference points to.
+    pub(crate) fn find(&self, reference: &FileReference) -> Option<usize> {
+        find(&self.nodes, reference)
+    }
+
+    /// Find the node at `path`.
+    pub(crate) fn position(&self, path: &Path) -> Option<usize> {
+        self.nodes
+            .binary_search_by(|node| node.path().cmp(path))
+            .ok()
+    }
+
+    /// The parent of the node at `index`.
+    pub(crate) fn parent(&self, index: usize) -> Option<usize> {
+        self.parents[index]
+    }
+
+    /// The direct children of the node at `index`.
+    pub(crate) fn children(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        self.parents
+            .iter()
+            .enumerate()
+            .filter(move |(_, parent)| **parent == Some(index))
+            .map(|(child, _)| child)
+    }
+
+    /// All descendants of the node at `index`, at any depth.
+    pub(crate) fn descendants(&self, index: usize) -> HashSet<usize> {
+        let mut found = HashSet::new();
+        let mut pending = vec![index];
+        while let Some(next) = pending.pop() {
+            for child in self.children(next) {
+                if child != index && found.insert(child) {
+                    pending.push(child);
+                }
+            }
+        }
+        found
+    }
+
+    /// Returns true if following parents from `index` leads back to it,
+    /// including a node that names itself as its parent.
+    pub(crate) fn in_cycle(&self, index: usize) -> bool {
+        let node = self.node(index);
+        if node.parent_ref().and_then(|p| self.find(p)) == Some(index) {
+            return true;
+        }
+
+        let mut seen = HashSet::new();
+        let mut current = self.parent(index);
+        while let Some(next) = current {
+            if next == index {
+                return true;
+            }
+            if !seen.insert(next) {
+                return false;
+            }
+            current = self.parent(next);
+        }
+        false
+    }
+}
+
+impl TaskTree<'_> {
+    /// Rollup warnings for the task at `index`.
+    pub(crate) fn warnings(&self, index: usize) -> Vec<ValidationWarning> {
+        let mut warnings = Vec::new();
+        if self.in_cycle(index) {
+            warnings.push(ValidationWarning::ParentCycle);
+        }
+
+        let task = self.node(index);
+        let children: Vec<&Task> = self.children(index).map(|i| self.node(i)).collect();
+        let open = children
+            .iter()
+            .filter(|child| !child.status.is_completed())
+            .count();
+
+        if task.status.is_completed() && open > 0 {
+            warnings.push(ValidationWarning::OpenSubtasks { count: open });
+        }
+        if !task.status.is_completed() && !children.is_empty() && open == 0 {
+            warnings.push(ValidationWarning::AllSubtasksCompleted {
+                count: children.len(),
+            });
+        }
+        warnings
+    }
+}
+
+/// Paths of the tasks in `tasks` that descend from the one `root` points to.
+pub(crate) fn descendant_paths<'a>(
+    tasks: impl IntoIterator<Item = &'a Task>,
+    root: &FileReference,
+) -> HashSet<PathBuf> {
+    let tree = TaskTree::new(tasks);
+    tree.find(root)
+        .map(|index| {
+            tree.descendants(index)
+                .into_iter()
+                .map(|i| tree.node(i).path.clone())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Find the first of `nodes` that `reference` points to.
+fn find<T: Node>(nodes: &[&T], reference: &FileReference) -> Option<usize> {
+    nodes
+        .iter()
+        .position(|node| reference.points_to(node.path(), node.title()))
+}
+
+impl Taskdn {
+    /// Get the direct subtasks of a task, including archived ones.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the parent task file
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the task doesn't exist.
+    /// Returns an error if the tasks directory cannot be read.
+    pub fn get_subtasks(&self, path: impl AsRef<Path>) -> Result<Vec<Task>> {
+        let task = self.get_task(path)?;
+        let tasks = self.list_tasks(&TaskFilter::new().include_archive_dir())?;
+        let tree = TaskTree::new(&tasks);
+
+        Ok(match tree.position(&task.path) {
+            Some(index) => tree.children(index).map(|i| tree.node(i).clone()).collect(),
+            None => Vec::new(),
+        })
+    }
+
+    /// Get the parent of a task.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the subtask file
+    ///
+    /// # Returns
+    /// `None` if the task has no `parent`.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the task doesn't exist.
+    /// Returns `Error::UnresolvedReference` if no task matches its `parent`.
+    pub fn get_parent(&self, path: impl AsRef<Path>) -> Result<Option<Task>> {
+        let task = self.get_task(path)?;
+        let Some(reference) = &task.parent else {
+            return Ok(None);
+        };
+
+        let tasks = self.list_tasks(&TaskFilter::new().include_archive_dir())?;
+        let tree = TaskTree::new(&tasks);
+        tree.position(&task.path)
+            .and_then(|index| tree.parent(index))
+            .map(|parent| Some(tree.node(parent).clone()))
+            .ok_or_else(|| Error::UnresolvedReference {
+                reference: reference.to_string(),
+            })
+    }
+
+    /// Subtask rollup warnings for a task, as reported by
+    /// [`Taskdn::get_task_warnings`] with `subtask_rollup` enabled.
+    pub(crate) fn subtask_warnings(&self, task: &Task) -> Result<Vec<ValidationWarning>> {
+        let tasks = self.list_tasks(&TaskFilter::new().include_archive_dir())?;
+        let tree = TaskTree::new(&tasks);
+        Ok(tree
+            .position(&task.path)
+            .map(|index| tree.warnings(index))
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{setup_test_env_with, write_file};
+    use tempfile::TempDir;
+
+    fn write(temp: &TempDir, name: &str, status: &str, parent: Option<&str>) {
+        let parent = parent.map_or(String::new(), |p| format!("parent: \"[[{p}]]\"\n"));
+        let content = format!(
+            "---\ntitle: {name}\nstatus: {status}\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n{parent}---\n"
+        );
+        write_file(temp, &format!("tasks/{name}.md"), &content);
+    }
+
+    fn titles(tasks: &[Task]) -> Vec<&str> {
+        let mut titles: Vec<&str> = tasks.iter().map(|t| t.title.as_str()).collect();
+        titles.sort_unstable();
+        titles
+    }
+
+    #[test]
+    fn subtasks_and_parent() {
+        let (temp, taskdn) = setup_test_env_with(|c| c.with_subtask_rollup(true));
+        write(&temp, "epic", "in-progress", None);
+        write(&temp, "design", "done", Some("epic"));
+        write(&temp, "build", "ready", Some("epic"));
+        write(&temp, "wire", "ready", Some("build"));
+
+        let subtasks = taskdn.get_subtasks("epic.md").unwrap();
+        assert_eq!(titles(&subtasks), ["build", "design"]);
+        assert!(taskdn.get_subtasks("wire.md").unwrap().is_empty());
+
+        let parent = taskdn.get_parent("wire.md").unwrap().unwrap();
+        assert_eq!(parent.title, "build");
+        assert!(taskdn.get_parent("epic.md").unwrap().is_none());
+    }
+
+    #[test]
+    fn unresolved_parent_is_an_error() {
+        let (temp, taskdn) = setup_test_env_with(|c| c.with_subtask_rollup(true));
+        write(&temp, "orphan", "ready", Some("missing"));
+
+        assert!(matches!(
+            taskdn.get_parent("orphan.md"),
+            Err(Error::UnresolvedReference { .. })
+        ));
+    }
+
+    #[test]
+    fn filter_descendants_of() {
+        let (temp, taskdn) = setup_test_env_with(|c| c.with_subtask_rollup(true));
+        write(&temp, "epic", "ready", None);
+        write(&temp, "build", "ready", Some("epic"));
+        write(&temp, "wire", "ready", Some("build"));
+        write(&temp, "other", "ready", None);
+
+        let filter = TaskFilter::new().descendants_of("[[epic]]");
+        let tasks = taskdn.list_tasks(&filter).unwrap();
+        assert_eq!(titles(&tasks), ["build", "wire"]);
+
+        let index = taskdn.build_index().unwrap();
+        let indexed: Vec<Task> = index.list_tasks(&filter).into_iter().cloned().collect();
+        assert_eq!(titles(&indexed), ["build", "wire"]);
+
+        let top_level = taskdn
+            .list_tasks(&TaskFilter::new().top_level_only())
+            .unwrap();
+        assert_eq!(titles(&top_level), ["epic", "other"]);
+    }
+
+    #[test]
+    fn rollup_warnings() {
+        let (temp, taskdn) = setup_test_env_with(|c| c.with_subtask_rollup(true));
+        write(&temp, "shipped", "done", None);
+        write(&temp, "leftover", "ready", Some("shipped"));
+        write(&temp, "waiting", "ready", None);
+        write(&temp, "finished", "dropped", Some("waiting"));
+
+        let warnings = taskdn.get_task_warnings("shipped.md").unwrap();
+        assert!(warnings.contains(&ValidationWarning::OpenSubtasks { count: 1 }));
+
+        let warnings = taskdn.get_task_warnings("waiting.md").unwrap();
+        assert!(warnings.contains(&ValidationWarning::AllSubtasksCompleted { count: 1 }));
+
+        let disabled = Taskdn::new(taskdn.config().clone().with_subtask_rollup(false)).unwrap();
+        assert!(!disabled
+            .get_task_warnings("shipped.md")
+            .unwrap()
+            .contains(&ValidationWarning::OpenSubtasks { count: 1 }));
+    }
+
+    #[test]
+    fn parent_cycles_are_reported() {
+        let (temp, taskdn) = setup_test_env_with(|c| c.with_subtask_rollup(true));
+        write(&temp, "a", "ready", Some("b"));
+        write(&temp, "b", "ready", Some("a"));
+        write(&temp, "self", "ready", Some("self"));
+
+        for path in ["a.md", "self.md"] {
+            let warnings = taskdn.get_task_warnings(path).unwrap();
+            assert!(warnings.contains(&ValidationWarning::ParentCycle), "{path}");
+        }
+
+        let descendants = taskdn
+            .list_tasks(&TaskFilter::new().descendants_of("[[a]]"))
+            .unwrap();
+        assert_eq!(titles(&descendants), ["b"]);
+    }
+}
@@ -0,0 +1,869 @@
+//! In-memory index of a vault.
+//!
+//! Every query on [`Taskdn`] reads and parses files from disk. That is fine for
+//! scripts and CLIs, but long-running applications that query the vault
+//! repeatedly should load it once into a [`VaultIndex`] and keep it current by
+//! applying the [`VaultEvent`]s produced by [`Taskdn::process_file_change`].
+//!
+//! # Example
+//!
+//! ```ignore
+//! use taskdn::{FileChangeKind, TaskFilter, TaskStatus};
+//!
+//! let mut index = taskdn.build_index()?;
+//! let ready = index.list_tasks(&TaskFilter::new().with_status(TaskStatus::Ready));
+//!
+//! // From your file watcher callback:
+//! if let Some(event) = taskdn.process_file_change(&path, FileChangeKind::Modified)? {
+//!     index.apply_event(event);
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::area_hierarchy::AreaScope;
+use crate::error::Result;
+use crate::events::VaultEvent;
+use crate::filter::{AreaFilter, ProjectFilter, TaskFilter};
+use crate::hierarchy::descendant_paths;
+use crate::project_graph::{has_unfinished_blocker, ProjectGraph};
+use crate::resolve::reference_path;
+use crate::types::{Area, FileReference, Project, Task, TaskStatus};
+use crate::Taskdn;
+
+/// An in-memory snapshot of all tasks, projects, and areas in a vault.
+///
+/// All queries are answered from memory without touching the file system.
+/// Results are returned in path order.
+///
+/// Paths passed to lookup methods may be absolute or relative to the
+/// corresponding configured directory, as with [`Taskdn::get_task`] and friends.
+#[derive(Debug, Clone)]
+pub struct VaultIndex {
+    tasks_dir: VaultDir,
+    projects_dir: VaultDir,
+    areas_dir: VaultDir,
+    tasks: BTreeMap<PathBuf, IndexedTask>,
+    projects: BTreeMap<PathBuf, Project>,
+    areas: BTreeMap<PathBuf, Area>,
+}
+
+/// A task together with facts that are expensive to recompute on every query.
+#[derive(Debug, Clone)]
+struct IndexedTask {
+    task: Task,
+    archived: bool,
+}
+
+impl IndexedTask {
+    fn new(task: Task) -> Self {
+        let archived = task.is_archived();
+        Self { task, archived }
+    }
+}
+
+/// A configured vault directory, canonicalised once so event paths can be
+/// matched against it however they spell the directory.
+#[derive(Debug, Clone)]
+struct VaultDir {
+    configured: PathBuf,
+    canonical: PathBuf,
+    /// Whether files in the `archive` subdirectory belong to it too.
+    has_archive: bool,
+}
+
+impl VaultDir {
+    fn new(dir: &Path, has_archive: bool) -> Self {
+        Self {
+            configured: dir.to_path_buf(),
+            canonical: dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf()),
+            has_archive,
+        }
+    }
+
+    /// Map `path` onto the configured directory, or `None` if the scanner
+    /// wouldn't list it.
+    ///
+    /// Only the parent directory is canonicalised, so this works for deleted
+    /// files and keeps a symlinked file at its own path.
+    fn locate(&self, path: &Path) -> Option<PathBuf> {
+        let name = path.file_name()?;
+        let parent = path.parent()?;
+        let parent = parent
+            .canonicalize()
+            .unwrap_or_else(|_| parent.to_path_buf());
+        let relative = parent
+            .strip_prefix(&self.canonical)
+            .or_else(|_| parent.strip_prefix(&self.configured))
+            .ok()?;
+
+        let listed = relative.as_os_str().is_empty()
+            || (self.has_archive && relative == Path::new("archive"));
+        listed.then(|| self.configured.join(relative).join(name))
+    }
+
+    /// Resolve a lookup path - if relative, resolve against the directory.
+    fn resolve(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.configured.join(path)
+        }
+    }
+}
+
+impl Taskdn {
+    /// Load the whole vault into an in-memory index.
+    ///
+    /// Includes archived tasks. Invalid files are silently skipped, as with
+    /// the `list_*` methods.
+    ///
+    /// # Errors
+    /// Returns an error if the tasks, projects, or areas directory cannot be read.
+    pub fn build_index(&self) -> Result<VaultIndex> {
+        let tasks = self.list_tasks(&TaskFilter::new().include_archive_dir())?;
+        let projects = self.load_projects()?.items;
+        let areas = self.load_areas()?.items;
+
+        Ok(VaultIndex {
+            tasks_dir: VaultDir::new(&self.config.tasks_dir, true),
+            projects_dir: VaultDir::new(&self.config.projects_dir, false),
+            areas_dir: VaultDir::new(&self.config.areas_dir, false),
+            tasks: tasks
+                .into_iter()
+                .map(|t| (t.path.clone(), IndexedTask::new(t)))
+                .collect(),
+            projects: projects.into_iter().map(|p| (p.path.clone(), p)).collect(),
+            areas: areas.into_iter().map(|a| (a.path.clone(), a)).collect(),
+        })
+    }
+}
+
+impl VaultIndex {
+    // ==========================================================================
+    // Entity Lookups
+    // ==========================================================================
+
+    /// Get a task by path.
+    #[must_use]
+    pub fn get_task(&self, path: impl AsRef<Path>) -> Option<&Task> {
+        self.tasks
+            .get(&self.tasks_dir.resolve(path.as_ref()))
+            .map(|entry| &entry.task)
+    }
+
+    /// Get a project by path.
+    #[must_use]
+    pub fn get_project(&self, path: impl AsRef<Path>) -> Option<&Project> {
+        self.projects.get(&self.projects_dir.resolve(path.as_ref()))
+    }
+
+    /// Get an area by path.
+    #[must_use]
+    pub fn get_area(&self, path: impl AsRef<Path>) -> Option<&Area> {
+        self.areas.get(&self.areas_dir.resolve(path.as_ref()))
+    }
+
+    /// Returns the number of indexed tasks, including archived ones.
+    #[must_use]
+    pub fn task_count(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns the number of indexed project files.
+    #[must_use]
+    pub fn project_count(&self) -> usize {
+        self.projects.len()
+    }
+
+    /// Returns the number of indexed area files.
+    #[must_use]
+    pub fn area_count(&self) -> usize {
+        self.areas.len()
+    }
+
+    // ==========================================================================
+    // Queries
+    // ==========================================================================
+
+    /// List tasks matching a filter.
+    ///
+    /// Unlike [`Taskdn::list_tasks`], this also honours
+    /// [`TaskFilter::area_via_project`]. [`TaskFilter::descendants_of`] and
+    /// [`TaskFilter::actionable`] are resolved against all indexed tasks,
+    /// including archived ones, and [`TaskFilter::within_area`] against all
+    /// indexed areas and projects.
+    #[must_use]
+    pub fn list_tasks(&self, filter: &TaskFilter) -> Vec<&Task> {
+        let via_projects = filter
+            .area_via_project
+            .as_ref()
+            .map(|area_ref| -> Vec<&Project> {
+                self.visible_projects()
+                    .filter(|p| p.area.as_ref() == Some(area_ref))
+                    .collect()
+            });
+        let descendants = filter
+            .descendants_of
+            .as_ref()
+            .map(|root| descendant_paths(self.all_tasks(), root));
+        let scope = filter
+            .within_area
+            .as_ref()
+            .map(|root| AreaScope::resolve(self.visible_areas(), self.visible_projects(), root));
+
+        let mut tasks: Vec<&Task> = self
+            .tasks
+            .values()
+            .filter(|entry| filter.include_archive_dir || !entry.archived)
+            .map(|entry| &entry.task)
+            .filter(|task| filter.matches_fields(task))
+            .filter(|task| {
+                via_projects
+                    .as_ref()
+                    .map_or(true, |projects| Self::task_in_any_project(task, projects))
+            })
+            .filter(|task| {
+                descendants
+                    .as_ref()
+                    .map_or(true, |paths| paths.contains(&task.path))
+            })
+            .filter(|task| {
+                scope
+                    .as_ref()
+                    .map_or(true, |scope| scope.contains_task(task))
+            })
+            .filter(|task| {
+                filter.actionable.map_or(true, |actionable| {
+                    task.blockers_completed(|blocker| self.task_status(blocker)) == actionable
+                })
+            })
+            .collect();
+        filter.sort(&mut tasks);
+        tasks
+    }
+
+    /// Count tasks matching a filter.
+    #[must_use]
+    pub fn count_tasks(&self, filter: &TaskFilter) -> usize {
+        self.list_tasks(filter).len()
+    }
+
+    /// List projects matching a filter.
+    ///
+    /// Applies the same `taskdn-type` opt-in rule as [`Taskdn::list_projects`],
+    /// and also honours [`ProjectFilter::blocked_by_unfinished`].
+    #[must_use]
+    pub fn list_projects(&self, filter: &ProjectFilter) -> Vec<&Project> {
+        let visible: Vec<&Project> = self.visible_projects().collect();
+        visible
+            .iter()
+            .copied()
+            .filter(|p| filter.matches(p))
+            .filter(|p| {
+                filter.blocked_by_unfinished.map_or(true, |wanted| {
+                    has_unfinished_blocker(p, &visible, &self.projects_dir.configured) == wanted
+                })
+            })
+            .collect()
+    }
+
+    /// Build the dependency graph of the indexed projects.
+    ///
+    /// Applies the same `taskdn-type` opt-in rule as [`Taskdn::project_graph`].
+    #[must_use]
+    pub fn project_graph(&self) -> ProjectGraph {
+        let projects = self.visible_projects().cloned().collect();
+        ProjectGraph::new(projects, &self.projects_dir.configured)
+    }
+
+    /// List areas matching a filter.
+    ///
+    /// Applies the same `taskdn-type` opt-in rule as [`Taskdn::list_areas`],
+    /// and also honours [`AreaFilter::within_area`].
+    #[must_use]
+    pub fn list_areas(&self, filter: &AreaFilter) -> Vec<&Area> {
+        let scope = filter
+            .within_area
+            .as_ref()
+            .map(|root| AreaScope::resolve(self.visible_areas(), [], root));
+
+        self.visible_areas()
+            .filter(|a| filter.matches(a))
+            .filter(|a| scope.as_ref().map_or(true, |scope| scope.contains_area(a)))
+            .collect()
+    }
+
+    // ==========================================================================
+    // Related Entity Queries
+    // ==========================================================================
+
+    /// Get all tasks assigned to this project, including archived tasks.
+    ///
+    /// Returns an empty list if the project is not in the index.
+    #[must_use]
+    pub fn tasks_for_project(&self, project: impl AsRef<Path>) -> Vec<&Task> {
+        let Some(project) = self.get_project(project) else {
+            return Vec::new();
+        };
+
+        self.all_tasks()
+            .filter(|task| Self::task_in_any_project(task, &[project]))
+            .collect()
+    }
+
+    /// Get all projects assigned to this area.
+    ///
+    /// Returns an empty list if the area is not in the index.
+    #[must_use]
+    pub fn projects_for_area(&self, area: impl AsRef<Path>) -> Vec<&Project> {
+        let Some(area) = self.get_area(area) else {
+            return Vec::new();
+        };
+
+        self.visible_projects()
+            .filter(|project| {
+                project
+                    .area
+                    .as_ref()
+                    .is_some_and(|area_ref| area_ref.points_to(&area.path, &area.title))
+            })
+            .collect()
+    }
+
+    /// Get all tasks assigned to this area, directly or via one of its projects.
+    ///
+    /// Returns an empty list if the area is not in the index.
+    #[must_use]
+    pub fn tasks_for_area(&self, area: impl AsRef<Path>) -> Vec<&Task> {
+        let Some(area) = self.get_area(&area) else {
+            return Vec::new();
+        };
+        let area_projects = self.projects_for_area(&area.path);
+
+        self.all_tasks()
+            .filter(|task| {
+                let direct_match = task
+                    .area
+                    .as_ref()
+                    .is_some_and(|area_ref| area_ref.points_to(&area.path, &area.title));
+
+                direct_match || Self::task_in_any_project(task, &area_projects)
+            })
+            .collect()
+    }
+
+    /// Get all projects assigned to this area or any of its sub-areas.
+    ///
+    /// Returns an empty list if the area is not in the index.
+    #[must_use]
+    pub fn projects_for_area_subtree(&self, area: impl AsRef<Path>) -> Vec<&Project> {
+        let Some(area) = self.get_area(area) else {
+            return Vec::new();
+        };
+
+        AreaScope::of(area, self.visible_areas(), self.visible_projects())
+            .projects()
+            .to_vec()
+    }
+
+    /// Get all tasks assigned to this area or any of its sub-areas, directly
+    /// or via a project.
+    ///
+    /// Returns an empty list if the area is not in the index.
+    #[must_use]
+    pub fn tasks_for_area_subtree(&self, area: impl AsRef<Path>) -> Vec<&Task> {
+        let Some(area) = self.get_area(area) else {
+            return Vec::new();
+        };
+        let scope = AreaScope::of(area, self.visible_areas(), self.visible_projects());
+
+        self.all_tasks()
+            .filter(|task| scope.contains_task(task))
+            .collect()
+    }
+
+    // ==========================================================================
+    // Incremental Updates
+    // ==========================================================================
+
+    /// Apply a vault event to the index.
+    ///
+    /// Created and updated entities replace any entry at the same path;
+    /// deleted entities are removed.
+    ///
+    /// Event paths are matched after resolving symlinks in their directory,
+    /// and stored entities get the path the index was built with. Events for
+    /// files the `list_*` methods wouldn't find, such as files in nested
+    /// subdirectories other than the tasks `archive`, are ignored.
+    pub fn apply_event(&mut self, event: VaultEvent) {
+        match event {
+            VaultEvent::TaskCreated(mut task) | VaultEvent::TaskUpdated(mut task) => {
+                if let Some(path) = self.tasks_dir.locate(&task.path) {
+                    task.path = path;
+                    self.tasks.insert(task.path.clone(), IndexedTask::new(task));
+                }
+            }
+            VaultEvent::TaskDeleted { path } => {
+                if let Some(path) = self.tasks_dir.locate(&path) {
+                    self.tasks.remove(&path);
+                }
+            }
+            VaultEvent::ProjectCreated(mut project) | VaultEvent::ProjectUpdated(mut project) => {
+                if let Some(path) = self.projects_dir.locate(&project.path) {
+                    project.path = path;
+                    self.projects.insert(project.path.clone(), project);
+                }
+            }
+            VaultEvent::ProjectDeleted { path } => {
+                if let Some(path) = self.projects_dir.locate(&path) {
+                    self.projects.remove(&path);
+                }
+            }
+            VaultEvent::AreaCreated(mut area) | VaultEvent::AreaUpdated(mut area) => {
+                if let Some(path) = self.areas_dir.locate(&area.path) {
+                    area.path = path;
+                    self.areas.insert(area.path.clone(), area);
+                }
+            }
+            VaultEvent::AreaDeleted { path } => {
+                if let Some(path) = self.areas_dir.locate(&path) {
+                    self.areas.remove(&path);
+                }
+            }
+        }
+    }
+
+    // ==========================================================================
+    // Internal Helpers
+    // ==========================================================================
+
+    /// All tasks, including archived ones.
+    fn all_tasks(&self) -> impl Iterator<Item = &Task> {
+        self.tasks.values().map(|entry| &entry.task)
+    }
+
+    /// The status of the task a reference resolves to, following the rules of
+    /// [`Taskdn::resolve_task_reference`].
+    fn task_status(&self, reference: &FileReference) -> Option<TaskStatus> {
+        let tasks_dir = &self.tasks_dir.configured;
+        [tasks_dir.clone(), tasks_dir.join("archive")]
+            .iter()
+            .find_map(|dir| self.tasks.get(&reference_path(reference, dir)))
+            .map(|entry| entry.task.status)
+    }
+
+    /// Projects after applying the `taskdn-type` opt-in rule.
+    fn visible_projects(&self) -> impl Iterator<Item = &Project> {
+        let has_opt_in = self
+            .projects
+            .values()
+            .any(|p| Taskdn::has_taskdn_type(&p.extra, "project"));
+
+        self.projects
+            .values()
+            .filter(move |p| !has_opt_in || Taskdn::has_taskdn_type(&p.extra, "project"))
+    }
+
+    /// Areas after applying the `taskdn-type` opt-in rule.
+    fn visible_areas(&self) -> impl Iterator<Item = &Area> {
+        let has_opt_in = self
+            .areas
+            .values()
+            .any(|a| Taskdn::has_taskdn_type(&a.extra, "area"));
+
+        self.areas
+            .values()
+            .filter(move |a| !has_opt_in || Taskdn::has_taskdn_type(&a.extra, "area"))
+    }
+
+    /// Check whether a task's project reference points to any of `projects`.
+    fn task_in_any_project(task: &Task, projects: &[&Project]) -> bool {
+        task.project.as_ref().is_some_and(|proj_ref| {
+            projects
+                .iter()
+                .any(|project| proj_ref.points_to(&project.path, &project.title))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileChangeKind, TaskStatus, TaskdnConfig};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup_test_env() -> (TempDir, Taskdn) {
+        let temp = TempDir::new().unwrap();
+        let tasks_dir = temp.path().join("tasks");
+        let projects_dir = temp.path().join("projects");
+        let areas_dir = temp.path().join("areas");
+
+        fs::create_dir_all(tasks_dir.join("archive")).unwrap();
+        fs::create_dir_all(&projects_dir).unwrap();
+        fs::create_dir_all(&areas_dir).unwrap();
+
+        let config = TaskdnConfig::new(tasks_dir, projects_dir, areas_dir);
+        let sdk = Taskdn::new(config).unwrap();
+        (temp, sdk)
+    }
+
+    fn write_file(dir: &Path, filename: &str, content: &str) -> PathBuf {
+        let path = dir.join(filename);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn task_content(title: &str, status: &str, extra: &str) -> String {
+        format!(
+            "---\ntitle: {title}\nstatus: {status}\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n{extra}---\n"
+        )
+    }
+
+    /// A vault with one area, two projects (one in the area) and four tasks.
+    fn populated_vault() -> (TempDir, Taskdn) {
+        let (temp, sdk) = setup_test_env();
+        let config = sdk.config().clone();
+
+        write_file(
+            &config.areas_dir,
+            "work.md",
+            "---\ntitle: Work\nstatus: active\n---\n",
+        );
+        write_file(
+            &config.projects_dir,
+            "launch.md",
+            "---\ntitle: Launch\narea: \"[[work]]\"\n---\n",
+        );
+        write_file(
+            &config.projects_dir,
+            "garden.md",
+            "---\ntitle: Garden\n---\n",
+        );
+
+        write_file(
+            &config.tasks_dir,
+            "a.md",
+            &task_content("A", "ready", "project: \"[[launch]]\"\n"),
+        );
+        write_file(
+            &config.tasks_dir,
+            "b.md",
+            &task_content("B", "in-progress", "area: \"[[Work]]\"\n"),
+        );
+        write_file(
+            &config.tasks_dir,
+            "c.md",
+            &task_content("C", "ready", "project: garden.md\n"),
+        );
+        write_file(
+            &config.tasks_dir.join("archive"),
+            "d.md",
+            &task_content("D", "done", "project: \"[[Launch]]\"\n"),
+        );
+
+        (temp, sdk)
+    }
+
+    /// Task titles in sorted order (path order puts `archive/` mid-list).
+    fn task_titles(tasks: &[&Task]) -> Vec<String> {
+        let mut titles: Vec<String> = tasks.iter().map(|t| t.title.clone()).collect();
+        titles.sort();
+        titles
+    }
+
+    fn project_titles(projects: &[&Project]) -> Vec<String> {
+        projects.iter().map(|p| p.title.clone()).collect()
+    }
+
+    mod build {
+        use super::*;
+
+        #[test]
+        fn loads_all_entities() {
+            let (_temp, sdk) = populated_vault();
+            let index = sdk.build_index().unwrap();
+
+            assert_eq!(index.task_count(), 4);
+            assert_eq!(index.project_count(), 2);
+            assert_eq!(index.area_count(), 1);
+        }
+
+        #[test]
+        fn skips_invalid_files() {
+            let (_temp, sdk) = populated_vault();
+            write_file(&sdk.config().tasks_dir, "broken.md", "no frontmatter");
+
+            let index = sdk.build_index().unwrap();
+            assert_eq!(index.task_count(), 4);
+        }
+
+        #[test]
+        fn lookups_accept_relative_paths() {
+            let (_temp, sdk) = populated_vault();
+            let index = sdk.build_index().unwrap();
+
+            assert_eq!(index.get_task("a.md").unwrap().title, "A");
+            assert_eq!(index.get_task("archive/d.md").unwrap().title, "D");
+            assert_eq!(index.get_project("launch.md").unwrap().title, "Launch");
+            assert_eq!(index.get_area("work.md").unwrap().title, "Work");
+            assert!(index.get_task("missing.md").is_none());
+        }
+    }
+
+    mod queries {
+        use super::*;
+
+        #[test]
+        fn task_filter_matches_disk_queries() {
+            let (_temp, sdk) = populated_vault();
+            let index = sdk.build_index().unwrap();
+
+            for filter in [
+                TaskFilter::new(),
+                TaskFilter::new().include_archive_dir(),
+                TaskFilter::new().with_status(TaskStatus::Ready),
+                TaskFilter::new().with_project(),
+                TaskFilter::new().in_area("[[Work]]"),
+            ] {
+                let mut expected: Vec<String> = sdk
+                    .list_tasks(&filter)
+                    .unwrap()
+                    .into_iter()
+                    .map(|t| t.title)
+                    .collect();
+                expected.sort();
+                assert_eq!(task_titles(&index.list_tasks(&filter)), expected);
+                assert_eq!(index.count_tasks(&filter), expected.len());
+            }
+        }
+
+        #[test]
+        fn area_via_project() {
+            let (_temp, sdk) = populated_vault();
+            let index = sdk.build_index().unwrap();
+
+            let filter = TaskFilter::new()
+                .in_area_via_project("[[work]]")
+                .include_archive_dir();
+            assert_eq!(task_titles(&index.list_tasks(&filter)), vec!["A", "D"]);
+        }
+
+        #[test]
+        fn project_and_area_filters() {
+            let (_temp, sdk) = populated_vault();
+            let index = sdk.build_index().unwrap();
+
+            let projects = index.list_projects(&ProjectFilter::new().with_area());
+            assert_eq!(project_titles(&projects), vec!["Launch"]);
+
+            let areas = index.list_areas(&AreaFilter::active());
+            assert_eq!(areas.len(), 1);
+            assert_eq!(areas[0].title, "Work");
+        }
+
+        #[test]
+        fn honours_taskdn_type_opt_in() {
+            let (_temp, sdk) = populated_vault();
+            write_file(
+                &sdk.config().projects_dir,
+                "typed.md",
+                "---\ntitle: Typed\ntaskdn-type: project\n---\n",
+            );
+            let index = sdk.build_index().unwrap();
+
+            let projects = index.list_projects(&ProjectFilter::new());
+            assert_eq!(project_titles(&projects), vec!["Typed"]);
+        }
+    }
+
+    mod relationships {
+        use super::*;
+
+        #[test]
+        fn tasks_for_project_includes_archive() {
+            let (_temp, sdk) = populated_vault();
+            let index = sdk.build_index().unwrap();
+
+            assert_eq!(
+                task_titles(&index.tasks_for_project("launch.md")),
+                vec!["A", "D"]
+            );
+            assert_eq!(
+                task_titles(&index.tasks_for_project("garden.md")),
+                vec!["C"]
+            );
+        }
+
+        #[test]
+        fn projects_for_area() {
+            let (_temp, sdk) = populated_vault();
+            let index = sdk.build_index().unwrap();
+
+            let projects = index.projects_for_area("work.md");
+            assert_eq!(project_titles(&projects), vec!["Launch"]);
+        }
+
+        #[test]
+        fn tasks_for_area_matches_disk_query() {
+            let (_temp, sdk) = populated_vault();
+            let index = sdk.build_index().unwrap();
+
+            let mut expected: Vec<String> = sdk
+                .get_tasks_for_area("work.md")
+                .unwrap()
+                .into_iter()
+                .map(|t| t.title)
+                .collect();
+            expected.sort();
+
+            assert_eq!(task_titles(&index.tasks_for_area("work.md")), expected);
+            assert_eq!(expected, vec!["A", "B", "D"]);
+        }
+
+        #[test]
+        fn unknown_entities_have_no_relations() {
+            let (_temp, sdk) = populated_vault();
+            let index = sdk.build_index().unwrap();
+
+            assert!(index.tasks_for_project("missing.md").is_empty());
+            assert!(index.projects_for_area("missing.md").is_empty());
+            assert!(index.tasks_for_area("missing.md").is_empty());
+        }
+    }
+
+    mod events {
+        use super::*;
+
+        #[test]
+        fn applies_created_updated_and_deleted_events() {
+            let (_temp, sdk) = populated_vault();
+            let mut index = sdk.build_index().unwrap();
+            let tasks_dir = sdk.config().tasks_dir.clone();
+
+            let path = write_file(&tasks_dir, "e.md", &task_content("E", "inbox", ""));
+            let event = sdk
+                .process_file_change(&path, FileChangeKind::Created)
+                .unwrap()
+                .unwrap();
+            index.apply_event(event);
+            assert_eq!(index.get_task(&path).unwrap().status, TaskStatus::Inbox);
+
+            write_file(&tasks_dir, "e.md", &task_content("E", "ready", ""));
+            let event = sdk
+                .process_file_change(&path, FileChangeKind::Modified)
+                .unwrap()
+                .unwrap();
+            index.apply_event(event);
+            assert_eq!(index.get_task(&path).unwrap().status, TaskStatus::Ready);
+
+            fs::remove_file(&path).unwrap();
+            let event = sdk
+                .process_file_change(&path, FileChangeKind::Deleted)
+                .unwrap()
+                .unwrap();
+            index.apply_event(event);
+            assert!(index.get_task(&path).is_none());
+            assert_eq!(index.task_count(), 4);
+        }
+
+        #[test]
+        fn project_changes_update_relationships() {
+            let (_temp, sdk) = populated_vault();
+            let mut index = sdk.build_index().unwrap();
+
+            let path = write_file(
+                &sdk.config().projects_dir,
+                "garden.md",
+                "---\ntitle: Garden\narea: \"[[work]]\"\n---\n",
+            );
+            let event = sdk
+                .process_file_change(&path, FileChangeKind::Modified)
+                .unwrap()
+                .unwrap();
+            index.apply_event(event);
+
+            assert_eq!(
+                task_titles(&index.tasks_for_area("work.md")),
+                vec!["A", "B", "C", "D"]
+            );
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn matches_paths_through_a_symlinked_vault_root() {
+            let temp = TempDir::new().unwrap();
+            let real = temp.path().join("real");
+            for dir in ["tasks/nested", "projects", "areas"] {
+                fs::create_dir_all(real.join(dir)).unwrap();
+            }
+            let link = temp.path().join("link");
+            std::os::unix::fs::symlink(&real, &link).unwrap();
+            let sdk = Taskdn::new(TaskdnConfig::new(
+                link.join("tasks"),
+                link.join("projects"),
+                link.join("areas"),
+            ))
+            .unwrap();
+            write_file(&link.join("tasks"), "a.md", &task_content("A", "inbox", ""));
+            let mut index = sdk.build_index().unwrap();
+
+            // A watcher reporting the resolved path updates the same entry
+            let real_path =
+                write_file(&real.join("tasks"), "a.md", &task_content("A", "ready", ""));
+            let event = sdk
+                .process_file_change(&real_path, FileChangeKind::Modified)
+                .unwrap()
+                .unwrap();
+            index.apply_event(event);
+            assert_eq!(index.task_count(), 1);
+            let task = index.get_task("a.md").unwrap();
+            assert_eq!(task.status, TaskStatus::Ready);
+            assert_eq!(task.path, link.join("tasks/a.md"));
+
+            // Files the scanner doesn't list are ignored
+            let nested = write_file(
+                &real.join("tasks/nested"),
+                "b.md",
+                &task_content("B", "inbox", ""),
+            );
+            let event = sdk
+                .process_file_change(&nested, FileChangeKind::Created)
+                .unwrap()
+                .unwrap();
+            index.apply_event(event);
+            assert_eq!(index.task_count(), 1);
+
+            fs::remove_file(&real_path).unwrap();
+            let event = sdk
+                .process_file_change(&real_path, FileChangeKind::Deleted)
+                .unwrap()
+                .unwrap();
+            index.apply_event(event);
+            assert_eq!(index.task_count(), 0);
+        }
+
+        #[test]
+        fn matches_rebuilt_index_after_events() {
+            let (_temp, sdk) = populated_vault();
+            let mut index = sdk.build_index().unwrap();
+            let areas_dir = sdk.config().areas_dir.clone();
+
+            let path = write_file(&areas_dir, "home.md", "---\ntitle: Home\n---\n");
+            let event = sdk
+                .process_file_change(&path, FileChangeKind::Created)
+                .unwrap()
+                .unwrap();
+            index.apply_event(event);
+
+            let rebuilt = sdk.build_index().unwrap();
+            assert_eq!(index.area_count(), rebuilt.area_count());
+            assert_eq!(
+                index.list_areas(&AreaFilter::new()),
+                rebuilt.list_areas(&AreaFilter::new())
+            );
+        }
+    }
+}
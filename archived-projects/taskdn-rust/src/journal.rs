@@ -0,0 +1,593 @@
+//! Undo journal.
+//!
+//! When [`TaskdnConfig::journal_path`](crate::TaskdnConfig::journal_path) is set,
+//! every mutating operation records the contents of the files it touched before
+//! and after the change. [`Taskdn::undo`] and [`Taskdn::redo`] replay those
+//! entries through a [`Transaction`](crate::Transaction), and refuse with
+//! `Error::Conflict` if any of the files has been modified since.
+//!
+//! The journal is a YAML file:
+//!
+//! ```yaml
+//! position: 1
+//! entries:
+//! - operation: archive_task
+//!   recorded-at: 2025-01-15T14:30:00
+//!   changes:
+//!   - path: /vault/tasks/write-report.md
+//!     before: |
+//!       ---
+//!       title: Write report
+//!       ...
+//!     after: null
+//!   - path: /vault/tasks/archive/write-report.md
+//!     before: null
+//!     after: |
+//!       ...
+//! ```
+//!
+//! Entries before `position` can be undone; entries from `position` onwards
+//! have been undone and can be redone until a new change is recorded. Only the
+//! most recent [`MAX_ENTRIES`] entries are kept.
+//!
+//! A journal that can't be parsed is never overwritten: recording moves it
+//! aside to `journal.yaml.corrupt` and returns the parse error, and the next
+//! change starts a new journal.
+//!
+//! The whole file is rewritten for every entry, so processes sharing a journal
+//! race: when two record at once, the last write wins and the other entry is
+//! lost. Give each process its own journal or serialise the writers.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::writer::atomic_write;
+use crate::Taskdn;
+
+/// Maximum number of entries kept in the journal.
+pub const MAX_ENTRIES: usize = 100;
+
+/// A recorded change that can be undone or redone.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// The SDK method that made the change, e.g. `"archive_task"`.
+    pub operation: String,
+    /// When the change was made (UTC).
+    #[serde(rename = "recorded-at", with = "crate::utils::serde_timestamp")]
+    pub recorded_at: NaiveDateTime,
+    /// Every file the change touched.
+    pub changes: Vec<JournalChange>,
+}
+
+/// The contents of one file before and after a recorded change.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalChange {
+    /// Path to the file.
+    pub path: PathBuf,
+    /// Content before the change, or `None` if the file didn't exist.
+    pub before: Option<String>,
+    /// Content after the change, or `None` if the file was removed.
+    pub after: Option<String>,
+}
+
+impl JournalChange {
+    pub(crate) fn created(path: &Path, after: &str) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            before: None,
+            after: Some(after.to_string()),
+        }
+    }
+
+    pub(crate) fn modified(path: &Path, before: &str, after: &str) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            before: Some(before.to_string()),
+            after: Some(after.to_string()),
+        }
+    }
+
+    pub(crate) fn removed(path: &Path, before: &str) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            before: Some(before.to_string()),
+            after: None,
+        }
+    }
+}
+
+/// The journal file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Journal {
+    position: usize,
+    entries: Vec<JournalEntry>,
+}
+
+impl Journal {
+    /// Load the journal, or an empty one if the file doesn't exist yet.
+    fn load(path: &Path) -> Result<Self> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut journal: Self = serde_yaml::from_str(&content).map_err(|e| Error::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        journal.position = journal.position.min(journal.entries.len());
+        Ok(journal)
+    }
+
+    /// Load the journal to append to it. A journal that can't be parsed is
+    /// moved aside to `<name>.corrupt` so it isn't overwritten, and the parse
+    /// error is returned.
+    fn load_for_append(path: &Path) -> Result<Self> {
+        match Self::load(path) {
+            Err(Error::Parse { path, message }) => {
+                let mut aside = path.clone().into_os_string();
+                aside.push(".corrupt");
+                let aside = PathBuf::from(aside);
+                fs::rename(&path, &aside)?;
+                Err(Error::Parse {
+                    message: format!("{message} (moved to {})", aside.display()),
+                    path,
+                })
+            }
+            other => other,
+        }
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        let content = serde_yaml::to_string(self).map_err(|e| Error::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        atomic_write(path, content)
+    }
+
+    /// Append an entry, discarding anything that could still be redone.
+    fn push(&mut self, entry: JournalEntry) {
+        self.entries.truncate(self.position);
+        self.entries.push(entry);
+
+        let excess = self.entries.len().saturating_sub(MAX_ENTRIES);
+        self.entries.drain(..excess);
+        self.position = self.entries.len();
+    }
+}
+
+impl Taskdn {
+    // ==========================================================================
+    // Undo / Redo
+    // ==========================================================================
+
+    /// Revert the most recent change recorded in the journal.
+    ///
+    /// All files touched by the change are restored together; a move is undone
+    /// by moving the file back.
+    ///
+    /// # Returns
+    /// The entry that was undone, or `None` if there is nothing to undo or no
+    /// journal is configured.
+    ///
+    /// # Errors
+    /// Returns `Error::Conflict` if any of the files was modified after the
+    /// change was recorded; nothing is changed in that case.
+    /// Returns an error if the journal cannot be read or the files cannot be written.
+    pub fn undo(&self) -> Result<Option<JournalEntry>> {
+        self.replay(Direction::Undo)
+    }
+
+    /// Re-apply the most recently undone change.
+    ///
+    /// # Returns
+    /// The entry that was redone, or `None` if there is nothing to redo or no
+    /// journal is configured.
+    ///
+    /// # Errors
+    /// Returns `Error::Conflict` if any of the files was modified after the
+    /// change was undone; nothing is changed in that case.
+    /// Returns an error if the journal cannot be read or the files cannot be written.
+    pub fn redo(&self) -> Result<Option<JournalEntry>> {
+        self.replay(Direction::Redo)
+    }
+
+    fn replay(&self, direction: Direction) -> Result<Option<JournalEntry>> {
+        let Some(journal_path) = &self.config.journal_path else {
+            return Ok(None);
+        };
+        let mut journal = Journal::load(journal_path)?;
+
+        let index = match direction {
+            Direction::Undo => journal.position.checked_sub(1),
+            Direction::Redo => Some(journal.position).filter(|&i| i < journal.entries.len()),
+        };
+        let Some(entry) = index.and_then(|i| journal.entries.get(i)).cloned() else {
+            return Ok(None);
+        };
+
+        let mut tx = self.transaction();
+        match direction {
+            Direction::Undo => {
+                for change in entry.changes.iter().rev() {
+                    tx.stage_restore(&change.path, change.after.as_deref(), change.before.clone())?;
+                }
+            }
+            Direction::Redo => {
+                for change in &entry.changes {
+                    tx.stage_restore(&change.path, change.before.as_deref(), change.after.clone())?;
+                }
+            }
+        }
+
+        let result = tx.apply();
+        if let Some((_, error)) = result.failed.into_iter().next() {
+            return Err(error);
+        }
+
+        journal.position = match direction {
+            Direction::Undo => journal.position - 1,
+            Direction::Redo => journal.position + 1,
+        };
+        journal.save(journal_path)?;
+        Ok(Some(entry))
+    }
+
+    // ==========================================================================
+    // Recording
+    // ==========================================================================
+
+    /// Returns true if mutations are being recorded.
+    pub(crate) fn journaling(&self) -> bool {
+        self.config.journal_path.is_some()
+    }
+
+    /// Append an entry to the journal, if one is configured.
+    ///
+    /// `changes` is only called when journaling. The change has already been
+    /// made, so an error means only the recording failed.
+    ///
+    /// # Errors
+    /// Returns `Error::Parse` if the journal can't be parsed; it is moved aside
+    /// first (see [`Journal::load_for_append`]).
+    /// Returns an error if the journal cannot be read or written.
+    pub(crate) fn record(
+        &self,
+        operation: &str,
+        changes: impl FnOnce() -> Vec<JournalChange>,
+    ) -> Result<()> {
+        let Some(journal_path) = &self.config.journal_path else {
+            return Ok(());
+        };
+
+        let mut journal = Journal::load_for_append(journal_path)?;
+        journal.push(JournalEntry {
+            operation: operation.to_string(),
+            recorded_at: self.now_utc(),
+            changes: changes(),
+        });
+        journal.save(journal_path)
+    }
+
+    /// Remove a file and record the removal.
+    pub(crate) fn remove_file_recorded(&self, operation: &str, path: &Path) -> Result<()> {
+        let before = self.read_for_journal(path);
+        fs::remove_file(path).map_err(|_| Error::NotFound {
+            path: path.to_path_buf(),
+        })?;
+
+        if let Some(before) = before {
+            self.record(operation, || vec![JournalChange::removed(path, &before)])?;
+        }
+        Ok(())
+    }
+
+    /// Move a file and record the move.
+    pub(crate) fn rename_file_recorded(
+        &self,
+        operation: &str,
+        from: &Path,
+        to: &Path,
+    ) -> Result<()> {
+        let content = self.read_for_journal(from);
+        fs::rename(from, to)?;
+
+        if let Some(content) = content {
+            self.record(operation, || {
+                vec![
+                    JournalChange::removed(from, &content),
+                    JournalChange::created(to, &content),
+                ]
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Read a file's content if it's going to be recorded.
+    pub(crate) fn read_for_journal(&self, path: &Path) -> Option<String> {
+        if self.journaling() {
+            fs::read_to_string(path).ok()
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Direction {
+    Undo,
+    Redo,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NewProject, NewTask, TaskStatus, TaskUpdates, TaskdnConfig};
+    use tempfile::TempDir;
+
+    fn setup_test_env() -> (TempDir, Taskdn) {
+        let temp = TempDir::new().unwrap();
+        let tasks_dir = temp.path().join("tasks");
+        let projects_dir = temp.path().join("projects");
+        let areas_dir = temp.path().join("areas");
+
+        fs::create_dir_all(&tasks_dir).unwrap();
+        fs::create_dir_all(&projects_dir).unwrap();
+        fs::create_dir_all(&areas_dir).unwrap();
+
+        let config = TaskdnConfig::new(tasks_dir, projects_dir, areas_dir)
+            .with_journal_path(temp.path().join(".taskdn/journal.yaml"));
+        let sdk = Taskdn::new(config).unwrap();
+        (temp, sdk)
+    }
+
+    fn load_journal(sdk: &Taskdn) -> Journal {
+        Journal::load(sdk.config().journal_path.as_ref().unwrap()).unwrap()
+    }
+
+    mod recording {
+        use super::*;
+
+        #[test]
+        fn records_each_operation() {
+            let (_temp, sdk) = setup_test_env();
+
+            let path = sdk.create_task(NewTask::new("Write report")).unwrap();
+            sdk.complete_task(&path).unwrap();
+            let archived = sdk.archive_task(&path).unwrap();
+            sdk.delete_task(&archived).unwrap();
+
+            let journal = load_journal(&sdk);
+            let operations: Vec<_> = journal
+                .entries
+                .iter()
+                .map(|e| e.operation.as_str())
+                .collect();
+            assert_eq!(
+                operations,
+                ["create_task", "update_task", "archive_task", "delete_task"]
+            );
+            assert_eq!(journal.position, 4);
+
+            let archive = &journal.entries[2];
+            assert_eq!(archive.changes.len(), 2);
+            assert_eq!(archive.changes[0].path, path);
+            assert!(archive.changes[0].after.is_none());
+            assert_eq!(archive.changes[1].path, archived);
+            assert!(archive.changes[1].before.is_none());
+        }
+
+        #[test]
+        fn records_transactions_as_one_entry() {
+            let (_temp, sdk) = setup_test_env();
+
+            let mut tx = sdk.transaction();
+            tx.create_task(NewTask::new("A")).unwrap();
+            tx.create_project(NewProject::new("P")).unwrap();
+            assert!(tx.commit().is_complete_success());
+
+            let journal = load_journal(&sdk);
+            assert_eq!(journal.entries.len(), 1);
+            assert_eq!(journal.entries[0].operation, "transaction");
+            assert_eq!(journal.entries[0].changes.len(), 2);
+        }
+
+        #[test]
+        fn nothing_recorded_without_journal_path() {
+            let (temp, _) = setup_test_env();
+            let config = TaskdnConfig::new(
+                temp.path().join("tasks"),
+                temp.path().join("projects"),
+                temp.path().join("areas"),
+            );
+            let sdk = Taskdn::new(config).unwrap();
+
+            sdk.create_task(NewTask::new("A")).unwrap();
+            assert!(!temp.path().join(".taskdn").exists());
+            assert!(sdk.undo().unwrap().is_none());
+        }
+
+        #[test]
+        fn corrupt_journal_is_moved_aside() {
+            let (temp, sdk) = setup_test_env();
+            let journal_path = temp.path().join(".taskdn/journal.yaml");
+            fs::create_dir_all(journal_path.parent().unwrap()).unwrap();
+            fs::write(&journal_path, "entries: [unclosed").unwrap();
+
+            let result = sdk.create_task(NewTask::new("A"));
+            assert!(matches!(result, Err(Error::Parse { .. })));
+            assert!(sdk.config().tasks_dir.join("a.md").exists());
+            assert!(!journal_path.exists());
+            assert_eq!(
+                fs::read_to_string(temp.path().join(".taskdn/journal.yaml.corrupt")).unwrap(),
+                "entries: [unclosed"
+            );
+
+            sdk.create_task(NewTask::new("B")).unwrap();
+            assert_eq!(load_journal(&sdk).entries.len(), 1);
+        }
+
+        #[test]
+        fn write_errors_are_returned() {
+            let (temp, sdk) = setup_test_env();
+            // The journal's directory can't be created over a file
+            fs::write(temp.path().join(".taskdn"), "").unwrap();
+
+            assert!(sdk.create_task(NewTask::new("A")).is_err());
+
+            let mut tx = sdk.transaction();
+            tx.create_task(NewTask::new("B")).unwrap();
+            let result = tx.commit();
+            assert_eq!(result.succeeded.len(), 1);
+            assert_eq!(result.failed.len(), 1);
+            assert_eq!(result.failed[0].0, temp.path().join(".taskdn/journal.yaml"));
+        }
+
+        #[test]
+        fn keeps_only_recent_entries() {
+            let mut journal = Journal::default();
+            for i in 0..MAX_ENTRIES + 5 {
+                journal.push(JournalEntry {
+                    operation: format!("op-{i}"),
+                    recorded_at: NaiveDateTime::default(),
+                    changes: Vec::new(),
+                });
+            }
+
+            assert_eq!(journal.entries.len(), MAX_ENTRIES);
+            assert_eq!(journal.position, MAX_ENTRIES);
+            assert_eq!(journal.entries[0].operation, "op-5");
+        }
+
+        #[test]
+        fn round_trips_through_yaml() {
+            let (_temp, sdk) = setup_test_env();
+            sdk.create_task(NewTask::new("Write report")).unwrap();
+
+            let journal = load_journal(&sdk);
+            let yaml = serde_yaml::to_string(&journal).unwrap();
+            let reloaded: Journal = serde_yaml::from_str(&yaml).unwrap();
+            assert_eq!(reloaded.entries, journal.entries);
+        }
+    }
+
+    mod undo_redo {
+        use super::*;
+
+        #[test]
+        fn undoes_and_redoes_an_update() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_task(NewTask::new("Write report")).unwrap();
+            let created = fs::read_to_string(&path).unwrap();
+
+            sdk.complete_task(&path).unwrap();
+            let completed = fs::read_to_string(&path).unwrap();
+
+            let entry = sdk.undo().unwrap().unwrap();
+            assert_eq!(entry.operation, "update_task");
+            assert_eq!(fs::read_to_string(&path).unwrap(), created);
+
+            let entry = sdk.redo().unwrap().unwrap();
+            assert_eq!(entry.operation, "update_task");
+            assert_eq!(fs::read_to_string(&path).unwrap(), completed);
+            assert_eq!(sdk.get_task(&path).unwrap().status, TaskStatus::Done);
+        }
+
+        #[test]
+        fn undoes_an_archive() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_task(NewTask::new("Write report")).unwrap();
+            let archived = sdk.archive_task(&path).unwrap();
+
+            sdk.undo().unwrap();
+            assert!(path.exists());
+            assert!(!archived.exists());
+
+            sdk.redo().unwrap();
+            assert!(!path.exists());
+            assert!(archived.exists());
+        }
+
+        #[test]
+        fn undoes_create_and_delete() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_task(NewTask::new("Write report")).unwrap();
+            let content = fs::read_to_string(&path).unwrap();
+            sdk.delete_task(&path).unwrap();
+
+            sdk.undo().unwrap();
+            assert_eq!(fs::read_to_string(&path).unwrap(), content);
+
+            sdk.undo().unwrap();
+            assert!(!path.exists());
+
+            assert!(sdk.undo().unwrap().is_none());
+        }
+
+        #[test]
+        fn refuses_when_file_changed() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_task(NewTask::new("Write report")).unwrap();
+            sdk.complete_task(&path).unwrap();
+
+            let edited = fs::read_to_string(&path)
+                .unwrap()
+                .replace("Write report", "Edited elsewhere");
+            fs::write(&path, &edited).unwrap();
+
+            let result = sdk.undo();
+            assert!(matches!(result, Err(Error::Conflict { theirs, .. }) if theirs == edited));
+            assert_eq!(fs::read_to_string(&path).unwrap(), edited);
+            assert_eq!(load_journal(&sdk).position, 2);
+        }
+
+        #[test]
+        fn refuses_redo_when_file_changed() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_task(NewTask::new("Write report")).unwrap();
+            sdk.undo().unwrap();
+
+            fs::write(&path, "recreated").unwrap();
+            assert!(matches!(sdk.redo(), Err(Error::Conflict { .. })));
+            assert_eq!(fs::read_to_string(&path).unwrap(), "recreated");
+        }
+
+        #[test]
+        fn new_change_discards_redo_history() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_task(NewTask::new("Write report")).unwrap();
+            sdk.complete_task(&path).unwrap();
+            sdk.undo().unwrap();
+
+            sdk.update_task(&path, TaskUpdates::new().title("Renamed"))
+                .unwrap();
+            assert!(sdk.redo().unwrap().is_none());
+
+            let journal = load_journal(&sdk);
+            assert_eq!(journal.entries.len(), 2);
+            assert_eq!(journal.position, 2);
+        }
+
+        #[test]
+        fn replay_is_not_recorded() {
+            let (_temp, sdk) = setup_test_env();
+            sdk.create_task(NewTask::new("Write report")).unwrap();
+            sdk.undo().unwrap();
+
+            let journal = load_journal(&sdk);
+            assert_eq!(journal.entries.len(), 1);
+            assert_eq!(journal.position, 0);
+        }
+    }
+}
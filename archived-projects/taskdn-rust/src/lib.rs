@@ -12,6 +12,7 @@
 //! - **Validate** files against the Taskdn specification
 //! - **Preserve** unknown frontmatter fields during round-trip serialization
 //! - **Process** file system events into typed vault events
+//! - **Index** a vault in memory and keep it current from vault events
 //! - **Watch** directories for changes (with the `watch` feature)
 //!
 //! # Quick Start
@@ -46,6 +47,7 @@
 //! - [`NewTask`], [`NewProject`], [`NewArea`] - Builder types for creating entities
 //! - [`TaskFilter`], [`ProjectFilter`], [`AreaFilter`] - Query filters
 //! - [`TaskStatus`], [`ProjectStatus`], [`AreaStatus`] - Status enums
+//! - [`VaultIndex`] - In-memory index for repeated queries
 //!
 //! # File Watching
 //!
@@ -77,13 +79,34 @@
 //! - [`types`] - All entity types, status enums, and builder types
 //! - [`validation`] - Spec compliance warnings
 
+mod area_hierarchy;
+mod cache;
+mod checklist;
+mod clock;
 mod config;
+mod dependencies;
 mod error;
 mod events;
+mod filename;
 mod filter;
+mod frontmatter;
+mod hierarchy;
+mod index;
+mod journal;
+mod merge;
 mod operations;
 mod parser;
+mod project_graph;
+mod recurring;
+mod relative_date;
+mod rename;
 mod resolve;
+mod summary;
+mod tags;
+#[cfg(test)]
+mod test_support;
+mod transaction;
+mod trash;
 pub mod types;
 mod utils;
 pub mod validation;
@@ -91,17 +114,38 @@ pub mod validation;
 mod watcher;
 mod writer;
 
+// Re-export clocks
+pub use clock::{Clock, FixedClock, OffsetClock, SystemClock};
+
 // Re-export configuration
-pub use config::TaskdnConfig;
+pub use config::{DeletePolicy, TaskdnConfig};
 
 // Re-export error types
-pub use error::{BatchResult, Error, Result};
+pub use error::{BatchResult, Error, ListResult, Result};
 
 // Re-export event types (always available)
 pub use events::{FileChangeKind, VaultEvent};
 
+// Re-export filename strategies
+pub use filename::FilenameStrategy;
+
 // Re-export filter types
-pub use filter::{AreaFilter, ProjectFilter, TaskFilter};
+pub use filter::{AreaFilter, ProjectFilter, TaskFilter, TaskSort};
+
+// Re-export the in-memory index
+pub use index::VaultIndex;
+
+// Re-export the project dependency graph
+pub use project_graph::ProjectGraph;
+
+// Re-export undo journal types
+pub use journal::{JournalChange, JournalEntry};
+
+// Re-export transactions
+pub use transaction::Transaction;
+
+// Re-export trash types
+pub use trash::{TrashedFile, TRASH_DIR};
 
 // Re-export watcher types (only with "watch" feature)
 #[cfg(feature = "watch")]
@@ -109,9 +153,11 @@ pub use watcher::{FileWatcher, WatchConfig};
 
 // Re-export all entity types
 pub use types::{
-    Area, AreaStatus, AreaUpdates, DateTimeValue, FileReference, NewArea, NewProject, NewTask,
-    ParsedArea, ParsedProject, ParsedTask, Project, ProjectStatus, ProjectUpdates, Task,
-    TaskStatus, TaskUpdates,
+    Area, AreaStatus, AreaUpdates, Checklist, ChecklistEdit, ChecklistItem, DateTimeValue,
+    ExpectedRevision, FileReference, Frequency, NewArea, NewProject, NewTask, ParsedArea,
+    ParsedProject, ParsedTask, Priority, ProgressSummary, Project, ProjectStatus, ProjectUpdates,
+    Recurrence, RecurrenceBasis, Revision, Tag, TagCount, Task, TaskStatus, TaskUpdates,
+    TimestampPolicy,
 };
 
 // Re-export validation types
@@ -124,6 +170,7 @@ pub use validation::ValidationWarning;
 #[derive(Debug)]
 pub struct Taskdn {
     config: TaskdnConfig,
+    clock: std::sync::Arc<dyn Clock>,
 }
 
 impl Taskdn {
@@ -150,7 +197,10 @@ impl Taskdn {
             });
         }
 
-        Ok(Self { config })
+        Ok(Self {
+            config,
+            clock: std::sync::Arc::new(SystemClock),
+        })
     }
 
     /// Returns a reference to the configuration.
@@ -0,0 +1,114 @@
+//! Field-level three-way merging of frontmatter.
+//!
+//! Used to apply an update on top of a file that was edited externally since
+//! it was read (see S3 §4.2). Two versions can be merged when they changed
+//! different frontmatter keys.
+
+use crate::writer::{FieldValue, Fields};
+use std::collections::{BTreeSet, HashMap};
+
+/// Find the keys that both `ours` and `theirs` changed relative to `base`.
+///
+/// A key only conflicts when both sides changed it *and* ended up with
+/// different values. Keys listed in `ignore` never conflict (for example
+/// timestamps that the SDK maintains itself).
+///
+/// Returns the conflicting keys in sorted order; an empty list means the two
+/// versions can be merged.
+pub(crate) fn conflicting_fields(
+    base: &Fields,
+    ours: &Fields,
+    theirs: &Fields,
+    ignore: &[&str],
+) -> Vec<String> {
+    let base = as_map(base);
+    let ours = as_map(ours);
+    let theirs = as_map(theirs);
+
+    let keys: BTreeSet<&str> = base
+        .keys()
+        .chain(ours.keys())
+        .chain(theirs.keys())
+        .copied()
+        .filter(|key| !ignore.contains(key))
+        .collect();
+
+    keys.into_iter()
+        .filter(|key| {
+            let (b, o, t) = (base.get(key), ours.get(key), theirs.get(key));
+            o != b && t != b && o != t
+        })
+        .map(ToString::to_string)
+        .collect()
+}
+
+fn as_map(fields: &Fields) -> HashMap<&str, &FieldValue> {
+    fields
+        .iter()
+        .map(|(key, value)| (key.as_str(), value))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(pairs: &[(&str, &str)]) -> Fields {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), FieldValue::Scalar((*v).to_string())))
+            .collect()
+    }
+
+    #[test]
+    fn no_changes_no_conflicts() {
+        let base = fields(&[("title", "A"), ("status", "ready")]);
+        assert!(conflicting_fields(&base, &base, &base, &[]).is_empty());
+    }
+
+    #[test]
+    fn different_keys_do_not_conflict() {
+        let base = fields(&[("title", "A"), ("status", "ready")]);
+        let ours = fields(&[("title", "A"), ("status", "done")]);
+        let theirs = fields(&[("title", "B"), ("status", "ready")]);
+        assert!(conflicting_fields(&base, &ours, &theirs, &[]).is_empty());
+    }
+
+    #[test]
+    fn same_key_different_values_conflicts() {
+        let base = fields(&[("status", "ready")]);
+        let ours = fields(&[("status", "done")]);
+        let theirs = fields(&[("status", "blocked")]);
+        assert_eq!(
+            conflicting_fields(&base, &ours, &theirs, &[]),
+            vec!["status".to_string()]
+        );
+    }
+
+    #[test]
+    fn same_key_same_value_does_not_conflict() {
+        let base = fields(&[("status", "ready")]);
+        let ours = fields(&[("status", "done")]);
+        assert!(conflicting_fields(&base, &ours, &ours, &[]).is_empty());
+    }
+
+    #[test]
+    fn added_and_removed_keys() {
+        let base = fields(&[("title", "A"), ("due", "2025-01-01")]);
+        // We removed `due`, they changed it
+        let ours = fields(&[("title", "A")]);
+        let theirs = fields(&[("title", "A"), ("due", "2025-02-01")]);
+        assert_eq!(
+            conflicting_fields(&base, &ours, &theirs, &[]),
+            vec!["due".to_string()]
+        );
+    }
+
+    #[test]
+    fn ignored_keys_never_conflict() {
+        let base = fields(&[("updated-at", "1")]);
+        let ours = fields(&[("updated-at", "2")]);
+        let theirs = fields(&[("updated-at", "3")]);
+        assert!(conflicting_fields(&base, &ours, &theirs, &["updated-at"]).is_empty());
+    }
+}
@@ -1,12 +1,18 @@
 //! Area operations for the Taskdn SDK.
 
-use crate::error::{Error, Result};
+use crate::area_hierarchy::AreaScope;
+use crate::config::DeletePolicy;
+use crate::error::{Error, ListResult, Result};
 use crate::filter::{AreaFilter, ProjectFilter, TaskFilter};
-use crate::types::{Area, AreaUpdates, NewArea, ParsedArea, Project, Task};
-use crate::utils::generate_filename;
-use crate::writer::write_area;
+use crate::journal::JournalChange;
+use crate::merge::conflicting_fields;
+use crate::types::{
+    Area, AreaUpdates, FileReference, NewArea, ParsedArea, Project, ProjectUpdates, Revision, Task,
+    TaskUpdates,
+};
+use crate::writer::{render_area, write_area, write_content, Original};
 use crate::Taskdn;
-use rayon::prelude::*;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -25,27 +31,26 @@ impl Taskdn {
     /// Returns `Error::Parse` if the file cannot be parsed.
     pub fn get_area(&self, path: impl AsRef<Path>) -> Result<Area> {
         let path = self.resolve_area_path(path.as_ref())?;
-        let content =
-            fs::read_to_string(&path).map_err(|_| Error::NotFound { path: path.clone() })?;
-
-        ParsedArea::parse(&content)
-            .map(|parsed| parsed.with_path(&path))
-            .map_err(|e| match e {
-                Error::ContentParse { message } => Error::Parse {
-                    path: path.clone(),
-                    message,
-                },
-                Error::ContentMissingField { field } => Error::MissingField {
-                    path: path.clone(),
-                    field,
-                },
-                Error::ContentInvalidField { field, message } => Error::InvalidField {
-                    path: path.clone(),
-                    field,
-                    message,
-                },
-                other => other,
-            })
+        let (area, _) = Self::read_area_file(&path)?;
+        Ok(area)
+    }
+
+    /// Get a single area together with the revision of its file.
+    ///
+    /// Pass the revision to [`Taskdn::update_area_checked`] to make sure the file
+    /// hasn't been changed by someone else before writing it back.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the area file (absolute or relative to `areas_dir`)
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the file doesn't exist.
+    /// Returns `Error::Parse` if the file cannot be parsed.
+    pub fn get_area_with_revision(&self, path: impl AsRef<Path>) -> Result<(Area, Revision)> {
+        let path = self.resolve_area_path(path.as_ref())?;
+        let (area, content) = Self::read_area_file(&path)?;
+        let revision = Revision::for_file(&path, &content)?;
+        Ok((area, revision))
     }
 
     /// List areas matching a filter.
@@ -59,6 +64,52 @@ impl Taskdn {
     /// # Errors
     /// Returns an error if the areas directory cannot be read.
     pub fn list_areas(&self, filter: &AreaFilter) -> Result<Vec<Area>> {
+        Ok(self.list_areas_with_skipped(filter)?.items)
+    }
+
+    /// List areas matching a filter, reporting files that could not be parsed.
+    ///
+    /// Like `list_areas()`, but invalid files are returned in
+    /// [`ListResult::skipped`] with their errors instead of being dropped.
+    ///
+    /// # Arguments
+    /// * `filter` - Filter criteria for matching areas
+    ///
+    /// # Errors
+    /// Returns an error if the areas directory cannot be read.
+    pub fn list_areas_with_skipped(&self, filter: &AreaFilter) -> Result<ListResult<Area>> {
+        let all_areas = self.load_areas()?;
+
+        // Check for opt-in behavior: if any has taskdn-type: area, filter to those only
+        let has_opt_in = all_areas
+            .items
+            .iter()
+            .any(|x| Self::has_taskdn_type_area(&x.extra, "area"));
+
+        let visible =
+            all_areas.filter(|x| !has_opt_in || Self::has_taskdn_type_area(&x.extra, "area"));
+
+        // `within_area` looks at every visible area, not just matching ones
+        let within: Option<HashSet<PathBuf>> = filter.within_area.as_ref().map(|root| {
+            let scope = AreaScope::resolve(&visible.items, [], root);
+            visible
+                .items
+                .iter()
+                .filter(|x| scope.contains_area(x))
+                .map(|x| x.path.clone())
+                .collect()
+        });
+
+        Ok(visible.filter(|x| {
+            filter.matches(x)
+                && within
+                    .as_ref()
+                    .map_or(true, |paths| paths.contains(&x.path))
+        }))
+    }
+
+    /// Parse every area file, before the `taskdn-type` opt-in is applied.
+    pub(crate) fn load_areas(&self) -> Result<ListResult<Area>> {
         let entries = fs::read_dir(&self.config.areas_dir)?;
 
         // Collect paths first for parallel processing
@@ -74,31 +125,9 @@ impl Taskdn {
             })
             .collect();
 
-        // Parse all areas in parallel
-        let all_areas: Vec<Area> = paths
-            .par_iter()
-            .filter_map(|path| self.get_area(path).ok())
-            .collect();
-
-        // Check for opt-in behavior: if any has taskdn-type: area, filter to those only
-        let has_opt_in = all_areas
-            .iter()
-            .any(|a| Self::has_taskdn_type_area(&a.extra, "area"));
-
-        let areas: Vec<Area> = if has_opt_in {
-            all_areas
-                .into_iter()
-                .filter(|a| Self::has_taskdn_type_area(&a.extra, "area"))
-                .filter(|a| filter.matches(a))
-                .collect()
-        } else {
-            all_areas
-                .into_iter()
-                .filter(|a| filter.matches(a))
-                .collect()
-        };
-
-        Ok(areas)
+        // Parse all areas (in parallel, through the cache)
+        let results = self.parse_files(&paths, std::slice::from_ref(&self.config.areas_dir));
+        Ok(ListResult::from_results(&paths, results))
     }
 
     /// Check if extra fields contain `taskdn-type` with the specified value.
@@ -123,33 +152,42 @@ impl Taskdn {
     /// # Errors
     /// Returns an error if the file cannot be created.
     pub fn create_area(&self, area: NewArea) -> Result<PathBuf> {
-        let filename = area
-            .filename
-            .clone()
-            .unwrap_or_else(|| generate_filename(&area.title));
-
-        let path = self.config.areas_dir.join(&filename);
-
-        // Check if file already exists
-        if path.exists() {
-            return Err(Error::Validation {
-                path: path.clone(),
-                message: format!("file already exists: {filename}"),
-            });
-        }
+        let area = self.prepare_new_area(area, Path::exists)?;
+        write_area(&area.path, &area, None)?;
+        self.record("create_area", || {
+            vec![JournalChange::created(&area.path, &area.to_string())]
+        })?;
+        Ok(area.path)
+    }
+
+    /// Build the area that `create_area` would write, checking the target is free.
+    ///
+    /// `exists` reports whether a path is already taken.
+    pub(crate) fn prepare_new_area(
+        &self,
+        area: NewArea,
+        exists: impl Fn(&Path) -> bool,
+    ) -> Result<Area> {
+        let path = self.new_file_path(
+            &self.config.areas_dir,
+            area.filename.as_deref(),
+            &area.title,
+            exists,
+        )?;
 
         let full_area = Area {
-            path: path.clone(),
+            path,
             title: area.title,
             status: area.status,
             area_type: area.area_type,
             description: area.description,
+            parent: area.parent,
+            tags: area.tags,
             body: area.body,
             extra: area.extra,
         };
 
-        write_area(&path, &full_area)?;
-        Ok(path)
+        Ok(full_area)
     }
 
     // ==========================================================================
@@ -162,34 +200,151 @@ impl Taskdn {
     /// * `path` - Path to the area file
     /// * `updates` - Partial updates to apply
     ///
+    /// # Returns
+    /// The area's path. If the title changes and
+    /// [`TaskdnConfig::rename_on_retitle`](crate::TaskdnConfig::rename_on_retitle)
+    /// is enabled, the file is renamed to match and every reference to it rewritten.
+    ///
     /// # Errors
     /// Returns an error if the file cannot be read or written.
-    pub fn update_area(&self, path: impl AsRef<Path>, updates: AreaUpdates) -> Result<()> {
+    pub fn update_area(&self, path: impl AsRef<Path>, updates: AreaUpdates) -> Result<PathBuf> {
         let path = self.resolve_area_path(path.as_ref())?;
-        let mut area = self.get_area(&path)?;
+        let (area, content) = Self::read_area_file(&path)?;
+        let (path, _) = self.write_area_updates(&path, area, &content, updates)?;
+        Ok(path)
+    }
 
-        // Apply updates
-        if let Some(title) = updates.title {
-            area.title = title;
-        }
-        if let Some(status) = updates.status {
-            area.status = status;
-        }
-        if let Some(area_type) = updates.area_type {
-            area.area_type = area_type;
+    /// Update an area only if its file hasn't changed since it was read.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the area file
+    /// * `updates` - Partial updates to apply
+    /// * `expected` - The revision returned by [`Taskdn::get_area_with_revision`]
+    ///
+    /// # Returns
+    /// The revision of the file after the update. Title changes rename the file
+    /// as [`Taskdn::update_area`] does when
+    /// [`TaskdnConfig::rename_on_retitle`](crate::TaskdnConfig::rename_on_retitle)
+    /// is enabled; the revision is then that of the renamed file.
+    ///
+    /// # Errors
+    /// Returns `Error::Conflict` if the file no longer matches `expected`; the file
+    /// is left untouched in that case.
+    /// Returns an error if the file cannot be read or written.
+    pub fn update_area_checked(
+        &self,
+        path: impl AsRef<Path>,
+        updates: AreaUpdates,
+        expected: &Revision,
+    ) -> Result<Revision> {
+        let path = self.resolve_area_path(path.as_ref())?;
+        let (area, content) = Self::read_area_file(&path)?;
+
+        if *expected != Revision::for_file(&path, &content)? {
+            let mut ours = area;
+            updates.apply_to(&mut ours);
+            return Err(Error::Conflict {
+                path,
+                ours: ours.to_string(),
+                theirs: content,
+                fields: Vec::new(),
+            });
         }
-        if let Some(description) = updates.description {
-            area.description = description;
+
+        self.write_area_updates(&path, area, &content, updates)
+            .map(|(_, revision)| revision)
+    }
+
+    /// Update an area, merging with any external edits made since `base` was read.
+    ///
+    /// See [`Taskdn::update_task_merging`] for how the merge works.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the area file
+    /// * `base` - The area as it was when the caller read it
+    /// * `updates` - Partial updates to apply
+    ///
+    /// # Returns
+    /// The revision of the file after the update. Title changes rename the file
+    /// as [`Taskdn::update_area`] does when
+    /// [`TaskdnConfig::rename_on_retitle`](crate::TaskdnConfig::rename_on_retitle)
+    /// is enabled; the revision is then that of the renamed file.
+    ///
+    /// # Errors
+    /// Returns `Error::Conflict` listing the conflicting keys if both sides changed
+    /// the same key to different values; the file is left untouched in that case.
+    /// Returns an error if the file cannot be read or written.
+    pub fn update_area_merging(
+        &self,
+        path: impl AsRef<Path>,
+        base: &Area,
+        updates: AreaUpdates,
+    ) -> Result<Revision> {
+        let path = self.resolve_area_path(path.as_ref())?;
+        let (area, content) = Self::read_area_file(&path)?;
+
+        let mut ours = base.clone();
+        updates.clone().apply_to(&mut ours);
+
+        let conflicts = conflicting_fields(
+            &base.frontmatter_fields(),
+            &ours.frontmatter_fields(),
+            &area.frontmatter_fields(),
+            &[],
+        );
+        if !conflicts.is_empty() {
+            return Err(Error::Conflict {
+                path,
+                ours: ours.to_string(),
+                theirs: content,
+                fields: conflicts,
+            });
         }
 
-        write_area(&path, &area)
+        self.write_area_updates(&path, area, &content, updates)
+            .map(|(_, revision)| revision)
+    }
+
+    // ==========================================================================
+    // Rename Operations
+    // ==========================================================================
+
+    /// Rename an area file, rewriting every reference to it.
+    ///
+    /// Rewrites the `area` of every task and project that references it, and
+    /// `[[WikiLink]]` mentions of it in markdown bodies. References keep their
+    /// original format. The move and all rewrites happen in one transaction.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the area file
+    /// * `new_path` - New path; relative paths are resolved against the areas directory,
+    ///   and a missing extension becomes `.md`
+    ///
+    /// # Returns
+    /// The area's new path.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the area doesn't exist.
+    /// Returns `Error::Validation` if a file already exists at `new_path`.
+    pub fn rename_area(
+        &self,
+        path: impl AsRef<Path>,
+        new_path: impl AsRef<Path>,
+    ) -> Result<PathBuf> {
+        let mut tx = self.transaction();
+        let new_path = tx.rename_area(path, new_path)?;
+        tx.commit_as("rename_area")?;
+        Ok(new_path)
     }
 
     // ==========================================================================
     // Delete Operations
     // ==========================================================================
 
-    /// Permanently delete an area file.
+    /// Delete an area file.
+    ///
+    /// Moves the file to the trash when [`TaskdnConfig::use_trash`](crate::TaskdnConfig::use_trash)
+    /// is enabled; otherwise it is removed permanently.
     ///
     /// # Arguments
     /// * `path` - Path to the area file
@@ -198,7 +353,117 @@ impl Taskdn {
     /// Returns an error if the file cannot be deleted.
     pub fn delete_area(&self, path: impl AsRef<Path>) -> Result<()> {
         let path = self.resolve_area_path(path.as_ref())?;
-        fs::remove_file(&path).map_err(|_| Error::NotFound { path })
+        self.delete_file("delete_area", &self.config.areas_dir, &path)
+    }
+
+    /// Delete an area, handling the projects and tasks that reference it
+    /// according to `policy`.
+    ///
+    /// The referencing projects are the ones [`Taskdn::get_projects_for_area`]
+    /// returns, plus tasks whose own `area` points at it (tasks that only
+    /// belong to the area through their project are left alone) and the
+    /// direct sub-areas from [`Taskdn::get_subareas`]. A sub-area that is the
+    /// reassignment target becomes top-level instead. They are updated and the
+    /// area deleted in one transaction, so either all of it happens or none of
+    /// it does.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the area file
+    /// * `policy` - What to do with projects, tasks and sub-areas that reference the area
+    ///
+    /// # Returns
+    /// The paths of the projects, tasks and sub-areas that were updated.
+    ///
+    /// # Errors
+    /// Returns `Error::DeleteBlocked` listing the referencing files if `policy`
+    /// is `DeletePolicy::Block` and anything references the area.
+    /// Returns `Error::NotFound` if the area or the reassignment target doesn't exist.
+    /// Returns `Error::Validation` if the reassignment target is the area itself.
+    pub fn delete_area_with_policy(
+        &self,
+        path: impl AsRef<Path>,
+        policy: &DeletePolicy,
+    ) -> Result<Vec<PathBuf>> {
+        let path = self.resolve_area_path(path.as_ref())?;
+        let area = self.get_area(&path)?;
+
+        let projects = self.get_projects_for_area(&path)?;
+        let tasks: Vec<Task> = self
+            .get_tasks_for_area(&path)?
+            .into_iter()
+            .filter(|task| {
+                task.area
+                    .as_ref()
+                    .is_some_and(|area_ref| area_ref.points_to(&path, &area.title))
+            })
+            .collect();
+        let subareas = self.get_subareas(&path)?;
+
+        let reassign_to = match policy {
+            DeletePolicy::Block
+                if !projects.is_empty() || !tasks.is_empty() || !subareas.is_empty() =>
+            {
+                let referenced_by = projects
+                    .into_iter()
+                    .map(|project| project.path)
+                    .chain(tasks.into_iter().map(|task| task.path))
+                    .chain(subareas.into_iter().map(|area| area.path))
+                    .collect();
+                return Err(Error::delete_blocked(&path, referenced_by));
+            }
+            DeletePolicy::Block | DeletePolicy::Unlink => None,
+            DeletePolicy::Reassign(target) => {
+                let target = self.resolve_area_path(target)?;
+                if target == path {
+                    return Err(Error::Validation {
+                        path,
+                        message: "cannot reassign to the area being deleted".to_string(),
+                    });
+                }
+                let title = self.get_area(&target)?.title;
+                Some((target, title))
+            }
+        };
+
+        let retarget = |reference: Option<&FileReference>| {
+            reassign_to
+                .as_ref()
+                .zip(reference)
+                .map(|((target, title), reference)| reference.retargeted(&path, target, title))
+        };
+
+        let mut tx = self.transaction();
+        let mut dependent_paths = Vec::with_capacity(projects.len() + tasks.len() + subareas.len());
+        for project in projects {
+            let updates = match retarget(project.area.as_ref()) {
+                Some(reference) => ProjectUpdates::new().area(reference),
+                None => ProjectUpdates::new().clear_area(),
+            };
+            tx.update_project(&project.path, updates)?;
+            dependent_paths.push(project.path);
+        }
+        for task in tasks {
+            let updates = match retarget(task.area.as_ref()) {
+                Some(reference) => TaskUpdates::new().area(reference),
+                None => TaskUpdates::new().clear_area(),
+            };
+            tx.update_task(&task.path, updates)?;
+            dependent_paths.push(task.path);
+        }
+        for subarea in subareas {
+            let reference = retarget(subarea.parent.as_ref())
+                .filter(|_| reassign_to.as_ref().map(|(target, _)| target) != Some(&subarea.path));
+            let updates = match reference {
+                Some(reference) => AreaUpdates::new().parent(reference),
+                None => AreaUpdates::new().clear_parent(),
+            };
+            tx.update_area(&subarea.path, updates)?;
+            dependent_paths.push(subarea.path);
+        }
+        tx.delete_area(&path)?;
+        tx.commit_as("delete_area")?;
+
+        Ok(dependent_paths)
     }
 
     // ==========================================================================
@@ -216,10 +481,6 @@ impl Taskdn {
         let area_path = self.resolve_area_path(area.as_ref())?;
         let area = self.get_area(&area_path)?;
 
-        let area_filename = area_path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-        let area_stem = area_path.file_stem().and_then(|n| n.to_str()).unwrap_or("");
-
         // Get all projects and filter by area reference
         let all_projects = self.list_projects(&ProjectFilter::new())?;
 
@@ -229,15 +490,7 @@ impl Taskdn {
                 project
                     .area
                     .as_ref()
-                    .is_some_and(|ref area_ref| match area_ref {
-                        crate::FileReference::WikiLink { target, .. } => {
-                            target == area_stem || target == &area.title
-                        }
-                        crate::FileReference::Filename(name) => name == area_filename,
-                        crate::FileReference::RelativePath(rel_path) => {
-                            rel_path.ends_with(area_filename)
-                        }
-                    })
+                    .is_some_and(|area_ref| area_ref.points_to(&area_path, &area.title))
             })
             .collect();
 
@@ -259,25 +512,11 @@ impl Taskdn {
         let area_path = self.resolve_area_path(area.as_ref())?;
         let area = self.get_area(&area_path)?;
 
-        let area_filename = area_path.file_name().and_then(|n| n.to_str()).unwrap_or("");
-
-        let area_stem = area_path.file_stem().and_then(|n| n.to_str()).unwrap_or("");
-
         // Get all tasks
         let all_tasks = self.list_tasks(&TaskFilter::new().include_archive_dir())?;
 
         // Get projects for this area
         let area_projects = self.get_projects_for_area(&area_path)?;
-        let project_stems: Vec<String> = area_projects
-            .iter()
-            .filter_map(|p| {
-                p.path
-                    .file_stem()
-                    .and_then(|s| s.to_str())
-                    .map(String::from)
-            })
-            .collect();
-        let project_titles: Vec<&str> = area_projects.iter().map(|p| p.title.as_str()).collect();
 
         let matching_tasks: Vec<Task> = all_tasks
             .into_iter()
@@ -286,32 +525,14 @@ impl Taskdn {
                 let direct_match = task
                     .area
                     .as_ref()
-                    .is_some_and(|ref area_ref| match area_ref {
-                        crate::FileReference::WikiLink { target, .. } => {
-                            target == area_stem || target == &area.title
-                        }
-                        crate::FileReference::Filename(name) => name == area_filename,
-                        crate::FileReference::RelativePath(rel_path) => {
-                            rel_path.ends_with(area_filename)
-                        }
-                    });
+                    .is_some_and(|area_ref| area_ref.points_to(&area_path, &area.title));
 
                 // Check via project
-                let via_project =
-                    task.project
-                        .as_ref()
-                        .is_some_and(|ref proj_ref| match proj_ref {
-                            crate::FileReference::WikiLink { target, .. } => {
-                                project_stems.contains(&target.to_string())
-                                    || project_titles.contains(&target.as_str())
-                            }
-                            crate::FileReference::Filename(name) => project_stems
-                                .iter()
-                                .any(|stem| name == &format!("{stem}.md")),
-                            crate::FileReference::RelativePath(rel_path) => project_stems
-                                .iter()
-                                .any(|stem| rel_path.ends_with(&format!("{stem}.md"))),
-                        });
+                let via_project = task.project.as_ref().is_some_and(|proj_ref| {
+                    area_projects
+                        .iter()
+                        .any(|project| proj_ref.points_to(&project.path, &project.title))
+                });
 
                 direct_match || via_project
             })
@@ -324,6 +545,64 @@ impl Taskdn {
     // Internal Helpers
     // ==========================================================================
 
+    /// Apply updates to an area read from `content` and write it back.
+    ///
+    /// Patches the file in place when `preserve_formatting` is enabled. Retitles
+    /// are committed as a transaction when `rename_on_retitle` is enabled.
+    /// Returns the area's path after the update and the revision of the
+    /// written file.
+    fn write_area_updates(
+        &self,
+        path: &Path,
+        area: Area,
+        content: &str,
+        updates: AreaUpdates,
+    ) -> Result<(PathBuf, Revision)> {
+        if self.config.rename_on_retitle && updates.title.is_some() {
+            let mut tx = self.transaction();
+            tx.expect_content(path, content);
+            let new_path = tx.update_area(path, updates)?;
+            tx.commit_as("update_area")?;
+            let revision = Revision::for_file(&new_path, &fs::read_to_string(&new_path)?)?;
+            return Ok((new_path, revision));
+        }
+
+        let rendered = self.render_area_updates(area, content, updates);
+        let revision = write_content(path, &rendered)?;
+        self.record("update_area", || {
+            vec![JournalChange::modified(path, content, &rendered)]
+        })?;
+        Ok((path.to_path_buf(), revision))
+    }
+
+    /// Apply updates to a area read from `content` and render the new file content.
+    pub(crate) fn render_area_updates(
+        &self,
+        mut area: Area,
+        content: &str,
+        updates: AreaUpdates,
+    ) -> String {
+        let original = self
+            .config
+            .preserve_formatting
+            .then(|| Original::area(content, &area));
+
+        updates.apply_to(&mut area);
+
+        render_area(&area, original.as_ref())
+    }
+
+    /// Read and parse an area file, returning its raw content alongside it.
+    fn read_area_file(path: &Path) -> Result<(Area, String)> {
+        let content = fs::read_to_string(path).map_err(|_| Error::NotFound {
+            path: path.to_path_buf(),
+        })?;
+        let area = ParsedArea::parse(&content)
+            .map_err(|e| e.with_path(path))?
+            .with_path(path);
+        Ok((area, content))
+    }
+
     /// Resolve an area path - if relative, resolve against `areas_dir`.
     fn resolve_area_path(&self, path: &Path) -> Result<PathBuf> {
         if path.is_absolute() {
@@ -451,6 +730,22 @@ Area body.
             assert_eq!(areas[0].title, "Area 1");
         }
 
+        #[test]
+        fn reports_skipped_files() {
+            let (_temp, sdk) = setup_test_env();
+            create_area_file(
+                &sdk.config.areas_dir,
+                "valid.md",
+                &sample_area_content("Valid", None),
+            );
+            create_area_file(&sdk.config.areas_dir, "invalid.md", "no frontmatter");
+
+            let result = sdk.list_areas_with_skipped(&AreaFilter::new()).unwrap();
+            assert_eq!(result.items.len(), 1);
+            assert_eq!(result.skipped_count(), 1);
+            assert_eq!(result.skipped[0].0, sdk.config.areas_dir.join("invalid.md"));
+        }
+
         #[test]
         fn opt_in_filters_to_typed_areas_only() {
             let (_temp, sdk) = setup_test_env();
@@ -556,6 +851,150 @@ Body.
             let area = sdk.get_area(&path).unwrap();
             assert_eq!(area.status, Some(AreaStatus::Archived));
         }
+
+        #[test]
+        fn update_parent() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_area(NewArea::new("Acme")).unwrap();
+
+            sdk.update_area(&path, AreaUpdates::new().parent("[[Work]]"))
+                .unwrap();
+            assert_eq!(
+                sdk.get_area(&path).unwrap().parent,
+                Some(FileReference::wiki_link("Work"))
+            );
+
+            sdk.update_area(&path, AreaUpdates::new().clear_parent())
+                .unwrap();
+            assert!(sdk.get_area(&path).unwrap().parent.is_none());
+        }
+    }
+
+    mod rename_area {
+        use super::*;
+
+        #[test]
+        fn rewrites_task_and_project_references() {
+            let (_temp, sdk) = setup_test_env();
+            let area = sdk
+                .create_area(NewArea::new("Work").with_filename("work.md"))
+                .unwrap();
+            let project = sdk
+                .create_project(
+                    NewProject::new("Project").in_area(FileReference::filename("work.md")),
+                )
+                .unwrap();
+            let task = sdk
+                .create_task(
+                    NewTask::new("Task")
+                        .in_area(FileReference::wiki_link("work"))
+                        .with_body("Part of [[work]]."),
+                )
+                .unwrap();
+
+            let new_path = sdk.rename_area(&area, "job").unwrap();
+
+            assert_eq!(new_path, sdk.config().areas_dir.join("job.md"));
+            assert!(!area.exists());
+            assert_eq!(
+                sdk.get_project(&project).unwrap().area,
+                Some(FileReference::filename("job.md"))
+            );
+            let task = sdk.get_task(&task).unwrap();
+            assert_eq!(task.area, Some(FileReference::wiki_link("job")));
+            assert_eq!(task.body.trim(), "Part of [[job]].");
+        }
+
+        #[test]
+        fn rewrites_subarea_parents() {
+            let (_temp, sdk) = setup_test_env();
+            let area = sdk
+                .create_area(NewArea::new("Work").with_filename("work.md"))
+                .unwrap();
+            let subarea = sdk
+                .create_area(NewArea::new("Acme").with_parent(FileReference::wiki_link("work")))
+                .unwrap();
+
+            sdk.rename_area(&area, "job").unwrap();
+
+            assert_eq!(
+                sdk.get_area(&subarea).unwrap().parent,
+                Some(FileReference::wiki_link("job"))
+            );
+        }
+
+        #[test]
+        fn rename_is_undoable_as_one_entry() {
+            let (temp, sdk) = setup_test_env();
+            let config = sdk
+                .config()
+                .clone()
+                .with_journal_path(temp.path().join("journal.yaml"));
+            let sdk = Taskdn::new(config).unwrap();
+            let area = sdk
+                .create_area(NewArea::new("Work").with_filename("work.md"))
+                .unwrap();
+            let task = sdk
+                .create_task(NewTask::new("Task").in_area(FileReference::wiki_link("work")))
+                .unwrap();
+
+            let new_path = sdk.rename_area(&area, "job").unwrap();
+            let entry = sdk.undo().unwrap().unwrap();
+
+            assert_eq!(entry.operation, "rename_area");
+            assert!(area.exists());
+            assert!(!new_path.exists());
+            assert_eq!(
+                sdk.get_task(&task).unwrap().area,
+                Some(FileReference::wiki_link("work"))
+            );
+        }
+    }
+
+    mod rename_on_retitle {
+        use super::*;
+
+        #[test]
+        fn retitle_in_transaction_returns_new_path() {
+            let (_temp, sdk) = setup_test_env();
+            let config = sdk.config().clone().with_rename_on_retitle(true);
+            let sdk = Taskdn::new(config).unwrap();
+            let area = sdk.create_area(NewArea::new("Work")).unwrap();
+            let project = sdk
+                .create_project(
+                    NewProject::new("Project").in_area(FileReference::wiki_link("work")),
+                )
+                .unwrap();
+
+            let mut tx = sdk.transaction();
+            let new_path = tx
+                .update_area(&area, AreaUpdates::new().title("Job"))
+                .unwrap();
+            tx.commit().into_result().unwrap();
+
+            assert_eq!(new_path, sdk.config().areas_dir.join("job.md"));
+            assert_eq!(sdk.get_area(&new_path).unwrap().title, "Job");
+            assert_eq!(
+                sdk.get_project(&project).unwrap().area,
+                Some(FileReference::wiki_link("job"))
+            );
+        }
+
+        #[test]
+        fn merging_update_renames() {
+            let (_temp, sdk) = setup_test_env();
+            let config = sdk.config().clone().with_rename_on_retitle(true);
+            let sdk = Taskdn::new(config).unwrap();
+            let area = sdk.create_area(NewArea::new("Work")).unwrap();
+            let base = sdk.get_area(&area).unwrap();
+
+            sdk.update_area_merging(&area, &base, AreaUpdates::new().title("Job"))
+                .unwrap();
+
+            assert!(!area.exists());
+            let renamed = sdk.config().areas_dir.join("job.md");
+            assert_eq!(sdk.get_area(&renamed).unwrap().title, "Job");
+        }
     }
 
     mod delete_area {
@@ -572,6 +1011,126 @@ Body.
         }
     }
 
+    mod delete_area_with_policy {
+        use super::*;
+
+        /// An area referenced by one project and one task, plus a task that
+        /// only belongs to it through the project.
+        fn setup_with_dependents() -> (TempDir, Taskdn, PathBuf, PathBuf, PathBuf, PathBuf) {
+            let (temp, sdk) = setup_test_env();
+            let area = sdk
+                .create_area(NewArea::new("Work").with_filename("work.md"))
+                .unwrap();
+            let project = sdk
+                .create_project(
+                    NewProject::new("Launch")
+                        .with_filename("launch.md")
+                        .in_area(FileReference::wiki_link("Work")),
+                )
+                .unwrap();
+            let direct = sdk
+                .create_task(
+                    NewTask::new("Direct")
+                        .in_area(FileReference::relative_path("../areas/work.md")),
+                )
+                .unwrap();
+            let via_project = sdk
+                .create_task(
+                    NewTask::new("Via project").in_project(FileReference::wiki_link("launch")),
+                )
+                .unwrap();
+            (temp, sdk, area, project, direct, via_project)
+        }
+
+        #[test]
+        fn block_lists_projects_and_direct_tasks() {
+            let (_temp, sdk, area, project, direct, _) = setup_with_dependents();
+
+            let result = sdk.delete_area_with_policy(&area, &DeletePolicy::Block);
+            match result {
+                Err(Error::DeleteBlocked { referenced_by, .. }) => {
+                    assert_eq!(referenced_by, vec![project, direct]);
+                }
+                other => panic!("Expected DeleteBlocked, got {other:?}"),
+            }
+            assert!(area.exists());
+        }
+
+        #[test]
+        fn unlink_clears_direct_references() {
+            let (_temp, sdk, area, project, direct, via_project) = setup_with_dependents();
+            let via_project_before = fs::read_to_string(&via_project).unwrap();
+
+            let updated = sdk
+                .delete_area_with_policy(&area, &DeletePolicy::Unlink)
+                .unwrap();
+
+            assert_eq!(updated, vec![project.clone(), direct.clone()]);
+            assert!(!area.exists());
+            assert!(sdk.get_project(&project).unwrap().area.is_none());
+            assert!(sdk.get_task(&direct).unwrap().area.is_none());
+            assert_eq!(
+                fs::read_to_string(&via_project).unwrap(),
+                via_project_before
+            );
+        }
+
+        #[test]
+        fn reassign_keeps_reference_format() {
+            let (_temp, sdk, area, project, direct, _) = setup_with_dependents();
+            let target = sdk
+                .create_area(NewArea::new("Personal").with_filename("personal.md"))
+                .unwrap();
+
+            sdk.delete_area_with_policy(
+                &area,
+                &DeletePolicy::Reassign(PathBuf::from("personal.md")),
+            )
+            .unwrap();
+
+            assert!(!area.exists());
+            assert_eq!(
+                sdk.get_project(&project).unwrap().area,
+                Some(FileReference::wiki_link("Personal"))
+            );
+            assert_eq!(
+                sdk.get_task(&direct).unwrap().area,
+                Some(FileReference::relative_path("../areas/personal.md"))
+            );
+            assert_eq!(sdk.get_projects_for_area(&target).unwrap().len(), 1);
+        }
+
+        #[test]
+        fn subareas_are_dependents() {
+            let (_temp, sdk) = setup_test_env();
+            let area = sdk
+                .create_area(NewArea::new("Work").with_filename("work.md"))
+                .unwrap();
+            let acme = sdk
+                .create_area(NewArea::new("Acme").with_parent(FileReference::wiki_link("Work")))
+                .unwrap();
+            let beta = sdk
+                .create_area(NewArea::new("Beta").with_parent(FileReference::wiki_link("work")))
+                .unwrap();
+
+            match sdk.delete_area_with_policy(&area, &DeletePolicy::Block) {
+                Err(Error::DeleteBlocked { referenced_by, .. }) => {
+                    assert_eq!(referenced_by, vec![acme.clone(), beta.clone()]);
+                }
+                other => panic!("Expected DeleteBlocked, got {other:?}"),
+            }
+
+            sdk.delete_area_with_policy(&area, &DeletePolicy::Reassign(PathBuf::from("acme.md")))
+                .unwrap();
+
+            assert!(sdk.get_area(&acme).unwrap().parent.is_none());
+            assert_eq!(
+                sdk.get_area(&beta).unwrap().parent,
+                Some(FileReference::wiki_link("acme"))
+            );
+        }
+    }
+
     mod get_projects_for_area {
         use super::*;
 
@@ -676,4 +1235,97 @@ Body.
             assert_eq!(tasks.len(), 2);
         }
     }
+
+    mod concurrency {
+        use super::*;
+
+        fn external_edit(path: &Path, from: &str, to: &str) {
+            let content = fs::read_to_string(path).unwrap();
+            assert!(content.contains(from));
+            fs::write(path, content.replacen(from, to, 1)).unwrap();
+        }
+
+        #[test]
+        fn checked_update_succeeds_when_unchanged() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk
+                .create_area(NewArea::new("Thing").with_description("Original"))
+                .unwrap();
+            let (_, revision) = sdk.get_area_with_revision(&path).unwrap();
+
+            let new_revision = sdk
+                .update_area_checked(
+                    &path,
+                    AreaUpdates::new().status(AreaStatus::Archived),
+                    &revision,
+                )
+                .unwrap();
+
+            let (_, current) = sdk.get_area_with_revision(&path).unwrap();
+            assert_eq!(new_revision, current);
+        }
+
+        #[test]
+        fn checked_update_conflicts_after_external_edit() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk
+                .create_area(NewArea::new("Thing").with_description("Original"))
+                .unwrap();
+            let (_, revision) = sdk.get_area_with_revision(&path).unwrap();
+
+            external_edit(&path, "description: Original", "description: Edited");
+            let on_disk = fs::read_to_string(&path).unwrap();
+
+            let result = sdk.update_area_checked(
+                &path,
+                AreaUpdates::new().status(AreaStatus::Archived),
+                &revision,
+            );
+
+            assert!(
+                matches!(result, Err(Error::Conflict { ref theirs, .. }) if *theirs == on_disk)
+            );
+            assert_eq!(fs::read_to_string(&path).unwrap(), on_disk);
+        }
+
+        #[test]
+        fn merging_update_keeps_external_edit() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk
+                .create_area(NewArea::new("Thing").with_description("Original"))
+                .unwrap();
+            let base = sdk.get_area(&path).unwrap();
+
+            external_edit(&path, "description: Original", "description: Edited");
+
+            sdk.update_area_merging(
+                &path,
+                &base,
+                AreaUpdates::new().status(AreaStatus::Archived),
+            )
+            .unwrap();
+
+            let area = sdk.get_area(&path).unwrap();
+            assert_eq!(area.description.as_deref(), Some("Edited"));
+        }
+
+        #[test]
+        fn merging_update_conflicts_on_same_key() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk
+                .create_area(NewArea::new("Thing").with_description("Original"))
+                .unwrap();
+            let base = sdk.get_area(&path).unwrap();
+
+            external_edit(&path, "description: Original", "description: Edited");
+
+            let result =
+                sdk.update_area_merging(&path, &base, AreaUpdates::new().description("Ours"));
+
+            assert!(matches!(
+                result,
+                Err(Error::Conflict { ref fields, .. }) if fields == &vec!["description".to_string()]
+            ));
+        }
+    }
 }
@@ -1,12 +1,17 @@
 //! Project operations for the Taskdn SDK.
 
-use crate::error::{Error, Result};
+use crate::config::DeletePolicy;
+use crate::error::{Error, ListResult, Result};
 use crate::filter::{ProjectFilter, TaskFilter};
-use crate::types::{NewProject, ParsedProject, Project, ProjectUpdates, Task};
-use crate::utils::generate_filename;
-use crate::writer::write_project;
+use crate::journal::JournalChange;
+use crate::merge::conflicting_fields;
+use crate::project_graph::has_unfinished_blocker;
+use crate::types::{
+    NewProject, ParsedProject, Project, ProjectUpdates, Revision, Task, TaskUpdates,
+};
+use crate::writer::{render_project, write_content, write_project, Original};
 use crate::Taskdn;
-use rayon::prelude::*;
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -25,27 +30,26 @@ impl Taskdn {
     /// Returns `Error::Parse` if the file cannot be parsed.
     pub fn get_project(&self, path: impl AsRef<Path>) -> Result<Project> {
         let path = self.resolve_project_path(path.as_ref())?;
-        let content =
-            fs::read_to_string(&path).map_err(|_| Error::NotFound { path: path.clone() })?;
-
-        ParsedProject::parse(&content)
-            .map(|parsed| parsed.with_path(&path))
-            .map_err(|e| match e {
-                Error::ContentParse { message } => Error::Parse {
-                    path: path.clone(),
-                    message,
-                },
-                Error::ContentMissingField { field } => Error::MissingField {
-                    path: path.clone(),
-                    field,
-                },
-                Error::ContentInvalidField { field, message } => Error::InvalidField {
-                    path: path.clone(),
-                    field,
-                    message,
-                },
-                other => other,
-            })
+        let (project, _) = Self::read_project_file(&path)?;
+        Ok(project)
+    }
+
+    /// Get a single project together with the revision of its file.
+    ///
+    /// Pass the revision to [`Taskdn::update_project_checked`] to make sure the file
+    /// hasn't been changed by someone else before writing it back.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the project file (absolute or relative to `projects_dir`)
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the file doesn't exist.
+    /// Returns `Error::Parse` if the file cannot be parsed.
+    pub fn get_project_with_revision(&self, path: impl AsRef<Path>) -> Result<(Project, Revision)> {
+        let path = self.resolve_project_path(path.as_ref())?;
+        let (project, content) = Self::read_project_file(&path)?;
+        let revision = Revision::for_file(&path, &content)?;
+        Ok((project, revision))
     }
 
     /// List projects matching a filter.
@@ -59,6 +63,55 @@ impl Taskdn {
     /// # Errors
     /// Returns an error if the projects directory cannot be read.
     pub fn list_projects(&self, filter: &ProjectFilter) -> Result<Vec<Project>> {
+        Ok(self.list_projects_with_skipped(filter)?.items)
+    }
+
+    /// List projects matching a filter, reporting files that could not be parsed.
+    ///
+    /// Like `list_projects()`, but invalid files are returned in
+    /// [`ListResult::skipped`] with their errors instead of being dropped.
+    ///
+    /// # Arguments
+    /// * `filter` - Filter criteria for matching projects
+    ///
+    /// # Errors
+    /// Returns an error if the projects directory cannot be read.
+    pub fn list_projects_with_skipped(
+        &self,
+        filter: &ProjectFilter,
+    ) -> Result<ListResult<Project>> {
+        let all_projects = self.load_projects()?;
+
+        // Check for opt-in behavior: if any has taskdn-type: project, filter to those only
+        let has_opt_in = all_projects
+            .items
+            .iter()
+            .any(|x| Self::has_taskdn_type(&x.extra, "project"));
+
+        let visible =
+            all_projects.filter(|x| !has_opt_in || Self::has_taskdn_type(&x.extra, "project"));
+
+        // `blocked_by_unfinished` looks at every visible project, not just matching ones
+        let unfinished: Option<HashSet<PathBuf>> = filter.blocked_by_unfinished.map(|_| {
+            let mut projects: Vec<&Project> = visible.items.iter().collect();
+            projects.sort_by(|a, b| a.path.cmp(&b.path));
+            projects
+                .iter()
+                .filter(|p| has_unfinished_blocker(p, &projects, &self.config.projects_dir))
+                .map(|p| p.path.clone())
+                .collect()
+        });
+
+        Ok(visible.filter(|x| {
+            filter.matches(x)
+                && unfinished.as_ref().map_or(true, |paths| {
+                    Some(paths.contains(&x.path)) == filter.blocked_by_unfinished
+                })
+        }))
+    }
+
+    /// Parse every project file, before the `taskdn-type` opt-in is applied.
+    pub(crate) fn load_projects(&self) -> Result<ListResult<Project>> {
         let entries = fs::read_dir(&self.config.projects_dir)?;
 
         // Collect paths first for parallel processing
@@ -74,35 +127,13 @@ impl Taskdn {
             })
             .collect();
 
-        // Parse all projects in parallel
-        let all_projects: Vec<Project> = paths
-            .par_iter()
-            .filter_map(|path| self.get_project(path).ok())
-            .collect();
-
-        // Check for opt-in behavior: if any has taskdn-type: project, filter to those only
-        let has_opt_in = all_projects
-            .iter()
-            .any(|p| Self::has_taskdn_type(&p.extra, "project"));
-
-        let projects: Vec<Project> = if has_opt_in {
-            all_projects
-                .into_iter()
-                .filter(|p| Self::has_taskdn_type(&p.extra, "project"))
-                .filter(|p| filter.matches(p))
-                .collect()
-        } else {
-            all_projects
-                .into_iter()
-                .filter(|p| filter.matches(p))
-                .collect()
-        };
-
-        Ok(projects)
+        // Parse all projects (in parallel, through the cache)
+        let results = self.parse_files(&paths, std::slice::from_ref(&self.config.projects_dir));
+        Ok(ListResult::from_results(&paths, results))
     }
 
     /// Check if extra fields contain `taskdn-type` with the specified value.
-    fn has_taskdn_type(
+    pub(crate) fn has_taskdn_type(
         extra: &std::collections::HashMap<String, serde_yaml::Value>,
         expected: &str,
     ) -> bool {
@@ -123,23 +154,31 @@ impl Taskdn {
     /// # Errors
     /// Returns an error if the file cannot be created.
     pub fn create_project(&self, project: NewProject) -> Result<PathBuf> {
-        let filename = project
-            .filename
-            .clone()
-            .unwrap_or_else(|| generate_filename(&project.title));
-
-        let path = self.config.projects_dir.join(&filename);
-
-        // Check if file already exists
-        if path.exists() {
-            return Err(Error::Validation {
-                path: path.clone(),
-                message: format!("file already exists: {filename}"),
-            });
-        }
+        let project = self.prepare_new_project(project, Path::exists)?;
+        write_project(&project.path, &project, None)?;
+        self.record("create_project", || {
+            vec![JournalChange::created(&project.path, &project.to_string())]
+        })?;
+        Ok(project.path)
+    }
+
+    /// Build the project that `create_project` would write, checking the target is free.
+    ///
+    /// `exists` reports whether a path is already taken.
+    pub(crate) fn prepare_new_project(
+        &self,
+        project: NewProject,
+        exists: impl Fn(&Path) -> bool,
+    ) -> Result<Project> {
+        let path = self.new_file_path(
+            &self.config.projects_dir,
+            project.filename.as_deref(),
+            &project.title,
+            exists,
+        )?;
 
         let full_project = Project {
-            path: path.clone(),
+            path,
             title: project.title,
             unique_id: None,
             status: project.status,
@@ -148,12 +187,12 @@ impl Taskdn {
             start_date: project.start_date,
             end_date: project.end_date,
             blocked_by: Vec::new(),
+            tags: project.tags,
             body: project.body,
             extra: project.extra,
         };
 
-        write_project(&path, &full_project)?;
-        Ok(path)
+        Ok(full_project)
     }
 
     // ==========================================================================
@@ -166,40 +205,155 @@ impl Taskdn {
     /// * `path` - Path to the project file
     /// * `updates` - Partial updates to apply
     ///
+    /// # Returns
+    /// The project's path. If the title changes and
+    /// [`TaskdnConfig::rename_on_retitle`](crate::TaskdnConfig::rename_on_retitle)
+    /// is enabled, the file is renamed to match and every reference to it rewritten.
+    ///
     /// # Errors
     /// Returns an error if the file cannot be read or written.
-    pub fn update_project(&self, path: impl AsRef<Path>, updates: ProjectUpdates) -> Result<()> {
+    pub fn update_project(
+        &self,
+        path: impl AsRef<Path>,
+        updates: ProjectUpdates,
+    ) -> Result<PathBuf> {
         let path = self.resolve_project_path(path.as_ref())?;
-        let mut project = self.get_project(&path)?;
+        let (project, content) = Self::read_project_file(&path)?;
+        let (path, _) = self.write_project_updates(&path, project, &content, updates)?;
+        Ok(path)
+    }
 
-        // Apply updates
-        if let Some(title) = updates.title {
-            project.title = title;
-        }
-        if let Some(status) = updates.status {
-            project.status = status;
-        }
-        if let Some(description) = updates.description {
-            project.description = description;
-        }
-        if let Some(area) = updates.area {
-            project.area = area;
-        }
-        if let Some(start_date) = updates.start_date {
-            project.start_date = start_date;
+    /// Update a project only if its file hasn't changed since it was read.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the project file
+    /// * `updates` - Partial updates to apply
+    /// * `expected` - The revision returned by [`Taskdn::get_project_with_revision`]
+    ///
+    /// # Returns
+    /// The revision of the file after the update. Title changes rename the file
+    /// as [`Taskdn::update_project`] does when
+    /// [`TaskdnConfig::rename_on_retitle`](crate::TaskdnConfig::rename_on_retitle)
+    /// is enabled; the revision is then that of the renamed file.
+    ///
+    /// # Errors
+    /// Returns `Error::Conflict` if the file no longer matches `expected`; the file
+    /// is left untouched in that case.
+    /// Returns an error if the file cannot be read or written.
+    pub fn update_project_checked(
+        &self,
+        path: impl AsRef<Path>,
+        updates: ProjectUpdates,
+        expected: &Revision,
+    ) -> Result<Revision> {
+        let path = self.resolve_project_path(path.as_ref())?;
+        let (project, content) = Self::read_project_file(&path)?;
+
+        if *expected != Revision::for_file(&path, &content)? {
+            let mut ours = project;
+            updates.apply_to(&mut ours);
+            return Err(Error::Conflict {
+                path,
+                ours: ours.to_string(),
+                theirs: content,
+                fields: Vec::new(),
+            });
         }
-        if let Some(end_date) = updates.end_date {
-            project.end_date = end_date;
+
+        self.write_project_updates(&path, project, &content, updates)
+            .map(|(_, revision)| revision)
+    }
+
+    /// Update a project, merging with any external edits made since `base` was read.
+    ///
+    /// See [`Taskdn::update_task_merging`] for how the merge works.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the project file
+    /// * `base` - The project as it was when the caller read it
+    /// * `updates` - Partial updates to apply
+    ///
+    /// # Returns
+    /// The revision of the file after the update. Title changes rename the file
+    /// as [`Taskdn::update_project`] does when
+    /// [`TaskdnConfig::rename_on_retitle`](crate::TaskdnConfig::rename_on_retitle)
+    /// is enabled; the revision is then that of the renamed file.
+    ///
+    /// # Errors
+    /// Returns `Error::Conflict` listing the conflicting keys if both sides changed
+    /// the same key to different values; the file is left untouched in that case.
+    /// Returns an error if the file cannot be read or written.
+    pub fn update_project_merging(
+        &self,
+        path: impl AsRef<Path>,
+        base: &Project,
+        updates: ProjectUpdates,
+    ) -> Result<Revision> {
+        let path = self.resolve_project_path(path.as_ref())?;
+        let (project, content) = Self::read_project_file(&path)?;
+
+        let mut ours = base.clone();
+        updates.clone().apply_to(&mut ours);
+
+        let conflicts = conflicting_fields(
+            &base.frontmatter_fields(),
+            &ours.frontmatter_fields(),
+            &project.frontmatter_fields(),
+            &[],
+        );
+        if !conflicts.is_empty() {
+            return Err(Error::Conflict {
+                path,
+                ours: ours.to_string(),
+                theirs: content,
+                fields: conflicts,
+            });
         }
 
-        write_project(&path, &project)
+        self.write_project_updates(&path, project, &content, updates)
+            .map(|(_, revision)| revision)
+    }
+
+    // ==========================================================================
+    // Rename Operations
+    // ==========================================================================
+
+    /// Rename a project file, rewriting every reference to it.
+    ///
+    /// Rewrites the `project` of every task and the `blocked-by` of every project that
+    /// references it, and `[[WikiLink]]` mentions of it in markdown bodies. References keep their
+    /// original format. The move and all rewrites happen in one transaction.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the project file
+    /// * `new_path` - New path; relative paths are resolved against the projects directory,
+    ///   and a missing extension becomes `.md`
+    ///
+    /// # Returns
+    /// The project's new path.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the project doesn't exist.
+    /// Returns `Error::Validation` if a file already exists at `new_path`.
+    pub fn rename_project(
+        &self,
+        path: impl AsRef<Path>,
+        new_path: impl AsRef<Path>,
+    ) -> Result<PathBuf> {
+        let mut tx = self.transaction();
+        let new_path = tx.rename_project(path, new_path)?;
+        tx.commit_as("rename_project")?;
+        Ok(new_path)
     }
 
     // ==========================================================================
     // Delete Operations
     // ==========================================================================
 
-    /// Permanently delete a project file.
+    /// Delete a project file.
+    ///
+    /// Moves the file to the trash when [`TaskdnConfig::use_trash`](crate::TaskdnConfig::use_trash)
+    /// is enabled; otherwise it is removed permanently.
     ///
     /// # Arguments
     /// * `path` - Path to the project file
@@ -208,7 +362,103 @@ impl Taskdn {
     /// Returns an error if the file cannot be deleted.
     pub fn delete_project(&self, path: impl AsRef<Path>) -> Result<()> {
         let path = self.resolve_project_path(path.as_ref())?;
-        fs::remove_file(&path).map_err(|_| Error::NotFound { path })
+        self.delete_file("delete_project", &self.config.projects_dir, &path)
+    }
+
+    /// Delete a project, handling the tasks and projects that reference it
+    /// according to `policy`.
+    ///
+    /// The referencing tasks are the ones [`Taskdn::get_tasks_for_project`]
+    /// returns, plus projects whose `blocked-by` points at it. A project that
+    /// is the reassignment target drops the reference instead of blocking
+    /// itself. They are updated and the project deleted in one transaction,
+    /// so either all of it happens or none of it does.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the project file
+    /// * `policy` - What to do with tasks and projects that reference the project
+    ///
+    /// # Returns
+    /// The paths of the tasks and projects that were updated.
+    ///
+    /// # Errors
+    /// Returns `Error::DeleteBlocked` listing the referencing files if `policy`
+    /// is `DeletePolicy::Block` and anything references the project.
+    /// Returns `Error::NotFound` if the project or the reassignment target doesn't exist.
+    /// Returns `Error::Validation` if the reassignment target is the project itself.
+    pub fn delete_project_with_policy(
+        &self,
+        path: impl AsRef<Path>,
+        policy: &DeletePolicy,
+    ) -> Result<Vec<PathBuf>> {
+        let path = self.resolve_project_path(path.as_ref())?;
+        let project = self.get_project(&path)?;
+        let dependents = self.get_tasks_for_project(&path)?;
+        let blocked: Vec<Project> = self
+            .list_projects(&ProjectFilter::new())?
+            .into_iter()
+            .filter(|other| {
+                other.path != path
+                    && other
+                        .blocked_by
+                        .iter()
+                        .any(|r| r.points_to(&path, &project.title))
+            })
+            .collect();
+
+        let reassign_to = match policy {
+            DeletePolicy::Block if !dependents.is_empty() || !blocked.is_empty() => {
+                let referenced_by = dependents
+                    .into_iter()
+                    .map(|task| task.path)
+                    .chain(blocked.into_iter().map(|project| project.path))
+                    .collect();
+                return Err(Error::delete_blocked(&path, referenced_by));
+            }
+            DeletePolicy::Block | DeletePolicy::Unlink => None,
+            DeletePolicy::Reassign(target) => {
+                let target = self.resolve_project_path(target)?;
+                if target == path {
+                    return Err(Error::Validation {
+                        path,
+                        message: "cannot reassign tasks to the project being deleted".to_string(),
+                    });
+                }
+                let title = self.get_project(&target)?.title;
+                Some((target, title))
+            }
+        };
+
+        let mut tx = self.transaction();
+        let mut dependent_paths = Vec::with_capacity(dependents.len() + blocked.len());
+        for task in dependents {
+            let updates = match (&reassign_to, &task.project) {
+                (Some((target, title)), Some(reference)) => {
+                    TaskUpdates::new().project(reference.retargeted(&path, target, title))
+                }
+                _ => TaskUpdates::new().clear_project(),
+            };
+            tx.update_task(&task.path, updates)?;
+            dependent_paths.push(task.path);
+        }
+        for other in blocked {
+            let blockers = other.blocked_by.iter().filter_map(|reference| {
+                if !reference.points_to(&path, &project.title) {
+                    return Some(reference.clone());
+                }
+                reassign_to
+                    .as_ref()
+                    .filter(|(target, _)| *target != other.path)
+                    .map(|(target, title)| reference.retargeted(&path, target, title))
+            });
+            let updates = ProjectUpdates::new().blocked_by(blockers.collect::<Vec<_>>());
+            tx.update_project(&other.path, updates)?;
+            dependent_paths.push(other.path);
+        }
+        tx.delete_project(&path)?;
+        tx.commit_as("delete_project")?;
+
+        Ok(dependent_paths)
     }
 
     // ==========================================================================
@@ -226,36 +476,15 @@ impl Taskdn {
         let project_path = self.resolve_project_path(project.as_ref())?;
         let project = self.get_project(&project_path)?;
 
-        // Create a reference that matches how tasks store project references
-        // We need to match against any reference format that points to this project
-        let project_filename = project_path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("");
-
-        let project_stem = project_path
-            .file_stem()
-            .and_then(|n| n.to_str())
-            .unwrap_or("");
-
-        // Get all tasks and filter by project reference
+        // Get all tasks and keep those whose project reference points here
         let all_tasks = self.list_tasks(&TaskFilter::new().include_archive_dir())?;
 
         let matching_tasks: Vec<Task> = all_tasks
             .into_iter()
             .filter(|task| {
-                task.project.as_ref().is_some_and(|ref proj_ref| {
-                    // Check if the reference matches this project
-                    match proj_ref {
-                        crate::FileReference::WikiLink { target, .. } => {
-                            target == project_stem || target == &project.title
-                        }
-                        crate::FileReference::Filename(name) => name == project_filename,
-                        crate::FileReference::RelativePath(rel_path) => {
-                            rel_path.ends_with(project_filename)
-                        }
-                    }
-                })
+                task.project
+                    .as_ref()
+                    .is_some_and(|proj_ref| proj_ref.points_to(&project_path, &project.title))
             })
             .collect();
 
@@ -266,6 +495,64 @@ impl Taskdn {
     // Internal Helpers
     // ==========================================================================
 
+    /// Apply updates to a project read from `content` and write it back.
+    ///
+    /// Patches the file in place when `preserve_formatting` is enabled. Retitles
+    /// are committed as a transaction when `rename_on_retitle` is enabled.
+    /// Returns the project's path after the update and the revision of the
+    /// written file.
+    fn write_project_updates(
+        &self,
+        path: &Path,
+        project: Project,
+        content: &str,
+        updates: ProjectUpdates,
+    ) -> Result<(PathBuf, Revision)> {
+        if self.config.rename_on_retitle && updates.title.is_some() {
+            let mut tx = self.transaction();
+            tx.expect_content(path, content);
+            let new_path = tx.update_project(path, updates)?;
+            tx.commit_as("update_project")?;
+            let revision = Revision::for_file(&new_path, &fs::read_to_string(&new_path)?)?;
+            return Ok((new_path, revision));
+        }
+
+        let rendered = self.render_project_updates(project, content, updates);
+        let revision = write_content(path, &rendered)?;
+        self.record("update_project", || {
+            vec![JournalChange::modified(path, content, &rendered)]
+        })?;
+        Ok((path.to_path_buf(), revision))
+    }
+
+    /// Apply updates to a project read from `content` and render the new file content.
+    pub(crate) fn render_project_updates(
+        &self,
+        mut project: Project,
+        content: &str,
+        updates: ProjectUpdates,
+    ) -> String {
+        let original = self
+            .config
+            .preserve_formatting
+            .then(|| Original::project(content, &project));
+
+        updates.apply_to(&mut project);
+
+        render_project(&project, original.as_ref())
+    }
+
+    /// Read and parse a project file, returning its raw content alongside it.
+    fn read_project_file(path: &Path) -> Result<(Project, String)> {
+        let content = fs::read_to_string(path).map_err(|_| Error::NotFound {
+            path: path.to_path_buf(),
+        })?;
+        let project = ParsedProject::parse(&content)
+            .map_err(|e| e.with_path(path))?
+            .with_path(path);
+        Ok((project, content))
+    }
+
     /// Resolve a project path - if relative, resolve against `projects_dir`.
     fn resolve_project_path(&self, path: &Path) -> Result<PathBuf> {
         if path.is_absolute() {
@@ -395,6 +682,27 @@ Project body.
             assert_eq!(projects[0].title, "Project 1");
         }
 
+        #[test]
+        fn reports_skipped_files() {
+            let (_temp, sdk) = setup_test_env();
+            create_project_file(
+                &sdk.config.projects_dir,
+                "valid.md",
+                &sample_project_content("Valid", None),
+            );
+            create_project_file(&sdk.config.projects_dir, "invalid.md", "no frontmatter");
+
+            let result = sdk
+                .list_projects_with_skipped(&ProjectFilter::new())
+                .unwrap();
+            assert_eq!(result.items.len(), 1);
+            assert_eq!(result.skipped_count(), 1);
+            assert_eq!(
+                result.skipped[0].0,
+                sdk.config.projects_dir.join("invalid.md")
+            );
+        }
+
         #[test]
         fn opt_in_filters_to_typed_projects_only() {
             let (_temp, sdk) = setup_test_env();
@@ -514,6 +822,183 @@ Body.
         }
     }
 
+    mod rename_project {
+        use super::*;
+
+        #[test]
+        fn rewrites_task_references_in_their_format() {
+            let (_temp, sdk) = setup_test_env();
+            let project = sdk
+                .create_project(NewProject::new("Q1 Planning").with_filename("q1-planning.md"))
+                .unwrap();
+            let by_stem = sdk
+                .create_task(NewTask::new("A").in_project(FileReference::wiki_link("q1-planning")))
+                .unwrap();
+            let by_title = sdk
+                .create_task(NewTask::new("B").in_project(FileReference::wiki_link("Q1 Planning")))
+                .unwrap();
+            let by_filename = sdk
+                .create_task(
+                    NewTask::new("C").in_project(FileReference::filename("q1-planning.md")),
+                )
+                .unwrap();
+            let by_path = sdk
+                .create_task(
+                    NewTask::new("D")
+                        .in_project(FileReference::relative_path("../projects/q1-planning.md")),
+                )
+                .unwrap();
+
+            let new_path = sdk.rename_project(&project, "planning-q1").unwrap();
+
+            assert_eq!(new_path, sdk.config().projects_dir.join("planning-q1.md"));
+            assert!(!project.exists());
+            assert_eq!(sdk.get_project(&new_path).unwrap().title, "Q1 Planning");
+            let project_of = |path: &PathBuf| sdk.get_task(path).unwrap().project.unwrap();
+            assert_eq!(
+                project_of(&by_stem),
+                FileReference::wiki_link("planning-q1")
+            );
+            assert_eq!(
+                project_of(&by_title),
+                FileReference::wiki_link("Q1 Planning")
+            );
+            assert_eq!(
+                project_of(&by_filename),
+                FileReference::filename("planning-q1.md")
+            );
+            assert_eq!(
+                project_of(&by_path),
+                FileReference::relative_path("../projects/planning-q1.md")
+            );
+            assert_eq!(sdk.get_tasks_for_project(&new_path).unwrap().len(), 4);
+        }
+
+        #[test]
+        fn rewrites_blocked_by_and_body_links() {
+            let (_temp, sdk) = setup_test_env();
+            let project = sdk
+                .create_project(NewProject::new("Q1 Planning").with_filename("q1-planning.md"))
+                .unwrap();
+            let blocked = sdk.config().projects_dir.join("launch.md");
+            fs::write(
+                &blocked,
+                "---\ntitle: Launch\nblocked-by:\n  - \"[[q1-planning]]\"\n---\nAfter [[q1-planning#Goals|the plan]].\n",
+            )
+            .unwrap();
+
+            let new_path = sdk.rename_project(&project, "planning-q1.md").unwrap();
+
+            let launch = sdk.get_project(&blocked).unwrap();
+            assert_eq!(
+                launch.blocked_by,
+                vec![FileReference::wiki_link("planning-q1")]
+            );
+            assert_eq!(launch.body.trim(), "After [[planning-q1#Goals|the plan]].");
+            assert!(new_path.exists());
+        }
+
+        #[test]
+        fn leaves_unrelated_files_untouched() {
+            let (_temp, sdk) = setup_test_env();
+            let project = sdk.create_project(NewProject::new("Q1 Planning")).unwrap();
+            let other = sdk
+                .create_task(
+                    NewTask::new("Other").in_project(FileReference::wiki_link("Elsewhere")),
+                )
+                .unwrap();
+            let before = fs::read_to_string(&other).unwrap();
+
+            sdk.rename_project(&project, "renamed").unwrap();
+
+            assert_eq!(fs::read_to_string(&other).unwrap(), before);
+        }
+
+        #[test]
+        fn rename_onto_existing_file_fails() {
+            let (_temp, sdk) = setup_test_env();
+            let project = sdk
+                .create_project(NewProject::new("One").with_filename("one.md"))
+                .unwrap();
+            let task = sdk
+                .create_task(NewTask::new("Task").in_project(FileReference::wiki_link("one")))
+                .unwrap();
+            sdk.create_project(NewProject::new("Two").with_filename("two.md"))
+                .unwrap();
+
+            let result = sdk.rename_project(&project, "two.md");
+
+            assert!(matches!(result, Err(Error::Validation { .. })));
+            assert!(project.exists());
+            assert_eq!(
+                sdk.get_task(&task).unwrap().project,
+                Some(FileReference::wiki_link("one"))
+            );
+        }
+
+        #[test]
+        fn rename_nonexistent_fails() {
+            let (_temp, sdk) = setup_test_env();
+            let result = sdk.rename_project("missing.md", "other.md");
+            assert!(matches!(result, Err(Error::NotFound { .. })));
+        }
+    }
+
+    mod rename_on_retitle {
+        use super::*;
+
+        #[test]
+        fn retitle_rewrites_task_references() {
+            let (_temp, sdk) = setup_test_env();
+            let config = sdk.config().clone().with_rename_on_retitle(true);
+            let sdk = Taskdn::new(config).unwrap();
+            let project = sdk.create_project(NewProject::new("Q1 Planning")).unwrap();
+            let by_title = sdk
+                .create_task(NewTask::new("A").in_project(FileReference::wiki_link("Q1 Planning")))
+                .unwrap();
+            let by_filename = sdk
+                .create_task(
+                    NewTask::new("B").in_project(FileReference::filename("q1-planning.md")),
+                )
+                .unwrap();
+
+            let new_path = sdk
+                .update_project(&project, ProjectUpdates::new().title("Q2 Planning"))
+                .unwrap();
+
+            assert_eq!(new_path, sdk.config().projects_dir.join("q2-planning.md"));
+            assert!(!project.exists());
+            assert_eq!(
+                sdk.get_task(&by_title).unwrap().project,
+                Some(FileReference::wiki_link("Q2 Planning"))
+            );
+            assert_eq!(
+                sdk.get_task(&by_filename).unwrap().project,
+                Some(FileReference::filename("q2-planning.md"))
+            );
+        }
+
+        #[test]
+        fn checked_update_renames() {
+            let (_temp, sdk) = setup_test_env();
+            let config = sdk.config().clone().with_rename_on_retitle(true);
+            let sdk = Taskdn::new(config).unwrap();
+            let project = sdk.create_project(NewProject::new("Q1 Planning")).unwrap();
+            let (_, revision) = sdk.get_project_with_revision(&project).unwrap();
+
+            sdk.update_project_checked(
+                &project,
+                ProjectUpdates::new().title("Q2 Planning"),
+                &revision,
+            )
+            .unwrap();
+
+            assert!(!project.exists());
+            let renamed = sdk.config().projects_dir.join("q2-planning.md");
+            assert_eq!(sdk.get_project(&renamed).unwrap().title, "Q2 Planning");
+        }
+    }
+
     mod delete_project {
         use super::*;
 
@@ -528,6 +1013,189 @@ Body.
         }
     }
 
+    mod delete_project_with_policy {
+        use super::*;
+
+        fn setup_with_tasks() -> (TempDir, Taskdn, PathBuf, Vec<PathBuf>) {
+            let (temp, sdk) = setup_test_env();
+            let project = sdk
+                .create_project(NewProject::new("Q1 Planning").with_filename("q1-planning.md"))
+                .unwrap();
+            let tasks = vec![
+                sdk.create_task(
+                    NewTask::new("By stem").in_project(FileReference::wiki_link("q1-planning")),
+                )
+                .unwrap(),
+                sdk.create_task(
+                    NewTask::new("By filename")
+                        .in_project(FileReference::filename("q1-planning.md")),
+                )
+                .unwrap(),
+            ];
+            sdk.create_task(NewTask::new("Unrelated")).unwrap();
+            (temp, sdk, project, tasks)
+        }
+
+        #[test]
+        fn block_refuses_while_referenced() {
+            let (_temp, sdk, project, tasks) = setup_with_tasks();
+
+            let result = sdk.delete_project_with_policy(&project, &DeletePolicy::Block);
+            match result {
+                Err(Error::DeleteBlocked {
+                    path,
+                    mut referenced_by,
+                    ..
+                }) => {
+                    assert_eq!(path, project);
+                    referenced_by.sort();
+                    let mut expected = tasks.clone();
+                    expected.sort();
+                    assert_eq!(referenced_by, expected);
+                }
+                other => panic!("Expected DeleteBlocked, got {other:?}"),
+            }
+            assert!(project.exists());
+        }
+
+        #[test]
+        fn block_deletes_unreferenced_project() {
+            let (_temp, sdk) = setup_test_env();
+            let project = sdk.create_project(NewProject::new("Lonely")).unwrap();
+
+            let updated = sdk
+                .delete_project_with_policy(&project, &DeletePolicy::Block)
+                .unwrap();
+
+            assert!(updated.is_empty());
+            assert!(!project.exists());
+        }
+
+        #[test]
+        fn unlink_clears_references() {
+            let (_temp, sdk, project, tasks) = setup_with_tasks();
+
+            let updated = sdk
+                .delete_project_with_policy(&project, &DeletePolicy::Unlink)
+                .unwrap();
+
+            assert_eq!(updated.len(), 2);
+            assert!(!project.exists());
+            for path in &tasks {
+                assert!(sdk.get_task(path).unwrap().project.is_none());
+            }
+        }
+
+        #[test]
+        fn reassign_keeps_reference_format() {
+            let (_temp, sdk, project, tasks) = setup_with_tasks();
+            let target = sdk
+                .create_project(NewProject::new("Q2 Planning").with_filename("q2-planning.md"))
+                .unwrap();
+
+            sdk.delete_project_with_policy(&project, &DeletePolicy::Reassign(target.clone()))
+                .unwrap();
+
+            assert!(!project.exists());
+            assert_eq!(
+                sdk.get_task(&tasks[0]).unwrap().project,
+                Some(FileReference::wiki_link("q2-planning"))
+            );
+            assert_eq!(
+                sdk.get_task(&tasks[1]).unwrap().project,
+                Some(FileReference::filename("q2-planning.md"))
+            );
+            assert_eq!(sdk.get_tasks_for_project(&target).unwrap().len(), 2);
+        }
+
+        #[test]
+        fn handles_projects_blocked_by_it() {
+            let (temp, sdk) = setup_test_env();
+            let projects_dir = temp.path().join("projects");
+            let project = sdk
+                .create_project(NewProject::new("Q1 Planning").with_filename("q1-planning.md"))
+                .unwrap();
+            let target = sdk
+                .create_project(NewProject::new("Q2 Planning").with_filename("q2-planning.md"))
+                .unwrap();
+            let launch = projects_dir.join("launch.md");
+            fs::write(
+                &launch,
+                "---\ntitle: Launch\nblocked-by:\n  - \"[[q1-planning]]\"\n  - \"[[Hiring]]\"\n---\n",
+            )
+            .unwrap();
+            let blockers = |sdk: &Taskdn| sdk.get_project(&launch).unwrap().blocked_by;
+
+            match sdk.delete_project_with_policy(&project, &DeletePolicy::Block) {
+                Err(Error::DeleteBlocked { referenced_by, .. }) => {
+                    assert_eq!(referenced_by, std::slice::from_ref(&launch));
+                }
+                other => panic!("Expected DeleteBlocked, got {other:?}"),
+            }
+
+            let updated = sdk
+                .delete_project_with_policy(&project, &DeletePolicy::Reassign(target.clone()))
+                .unwrap();
+            assert_eq!(updated, std::slice::from_ref(&launch));
+            assert_eq!(
+                blockers(&sdk),
+                [
+                    FileReference::wiki_link("q2-planning"),
+                    FileReference::wiki_link("Hiring")
+                ]
+            );
+
+            sdk.delete_project_with_policy(&target, &DeletePolicy::Unlink)
+                .unwrap();
+            assert_eq!(blockers(&sdk), [FileReference::wiki_link("Hiring")]);
+        }
+
+        #[test]
+        fn reassign_drops_the_target_blocking_itself() {
+            let (temp, sdk) = setup_test_env();
+            let project = sdk
+                .create_project(NewProject::new("Q1 Planning").with_filename("q1-planning.md"))
+                .unwrap();
+            let target = temp.path().join("projects").join("launch.md");
+            fs::write(
+                &target,
+                "---\ntitle: Launch\nblocked-by: \"[[q1-planning]]\"\n---\n",
+            )
+            .unwrap();
+
+            sdk.delete_project_with_policy(&project, &DeletePolicy::Reassign(target.clone()))
+                .unwrap();
+
+            assert!(sdk.get_project(&target).unwrap().blocked_by.is_empty());
+        }
+
+        #[test]
+        fn reassign_requires_existing_target() {
+            let (_temp, sdk, project, tasks) = setup_with_tasks();
+            let before = fs::read_to_string(&tasks[0]).unwrap();
+
+            let result = sdk.delete_project_with_policy(
+                &project,
+                &DeletePolicy::Reassign(PathBuf::from("missing.md")),
+            );
+
+            assert!(matches!(result, Err(Error::NotFound { .. })));
+            assert!(project.exists());
+            assert_eq!(fs::read_to_string(&tasks[0]).unwrap(), before);
+        }
+
+        #[test]
+        fn reassign_to_itself_is_rejected() {
+            let (_temp, sdk, project, _) = setup_with_tasks();
+
+            let result =
+                sdk.delete_project_with_policy(&project, &DeletePolicy::Reassign(project.clone()));
+
+            assert!(matches!(result, Err(Error::Validation { .. })));
+            assert!(project.exists());
+        }
+    }
+
     mod get_tasks_for_project {
         use super::*;
 
@@ -578,4 +1246,97 @@ Body.
             assert!(tasks.is_empty());
         }
     }
+
+    mod concurrency {
+        use super::*;
+
+        fn external_edit(path: &Path, from: &str, to: &str) {
+            let content = fs::read_to_string(path).unwrap();
+            assert!(content.contains(from));
+            fs::write(path, content.replacen(from, to, 1)).unwrap();
+        }
+
+        #[test]
+        fn checked_update_succeeds_when_unchanged() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk
+                .create_project(NewProject::new("Thing").with_description("Original"))
+                .unwrap();
+            let (_, revision) = sdk.get_project_with_revision(&path).unwrap();
+
+            let new_revision = sdk
+                .update_project_checked(
+                    &path,
+                    ProjectUpdates::new().status(ProjectStatus::Done),
+                    &revision,
+                )
+                .unwrap();
+
+            let (_, current) = sdk.get_project_with_revision(&path).unwrap();
+            assert_eq!(new_revision, current);
+        }
+
+        #[test]
+        fn checked_update_conflicts_after_external_edit() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk
+                .create_project(NewProject::new("Thing").with_description("Original"))
+                .unwrap();
+            let (_, revision) = sdk.get_project_with_revision(&path).unwrap();
+
+            external_edit(&path, "description: Original", "description: Edited");
+            let on_disk = fs::read_to_string(&path).unwrap();
+
+            let result = sdk.update_project_checked(
+                &path,
+                ProjectUpdates::new().status(ProjectStatus::Done),
+                &revision,
+            );
+
+            assert!(
+                matches!(result, Err(Error::Conflict { ref theirs, .. }) if *theirs == on_disk)
+            );
+            assert_eq!(fs::read_to_string(&path).unwrap(), on_disk);
+        }
+
+        #[test]
+        fn merging_update_keeps_external_edit() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk
+                .create_project(NewProject::new("Thing").with_description("Original"))
+                .unwrap();
+            let base = sdk.get_project(&path).unwrap();
+
+            external_edit(&path, "description: Original", "description: Edited");
+
+            sdk.update_project_merging(
+                &path,
+                &base,
+                ProjectUpdates::new().status(ProjectStatus::Done),
+            )
+            .unwrap();
+
+            let project = sdk.get_project(&path).unwrap();
+            assert_eq!(project.description.as_deref(), Some("Edited"));
+        }
+
+        #[test]
+        fn merging_update_conflicts_on_same_key() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk
+                .create_project(NewProject::new("Thing").with_description("Original"))
+                .unwrap();
+            let base = sdk.get_project(&path).unwrap();
+
+            external_edit(&path, "description: Original", "description: Edited");
+
+            let result =
+                sdk.update_project_merging(&path, &base, ProjectUpdates::new().description("Ours"));
+
+            assert!(matches!(
+                result,
+                Err(Error::Conflict { ref fields, .. }) if fields == &vec!["description".to_string()]
+            ));
+        }
+    }
 }
@@ -1,12 +1,17 @@
 //! Task operations for the Taskdn SDK.
 
-use crate::error::{BatchResult, Error, Result};
-use crate::filter::TaskFilter;
-use crate::types::{DateTimeValue, NewTask, ParsedTask, Task, TaskStatus, TaskUpdates};
-use crate::utils::generate_filename;
-use crate::writer::{write_task, write_task_with_updates};
+use crate::area_hierarchy::AreaScope;
+use crate::error::{BatchResult, Error, ListResult, Result};
+use crate::filter::{AreaFilter, ProjectFilter, TaskFilter};
+use crate::hierarchy::descendant_paths;
+use crate::journal::JournalChange;
+use crate::merge::conflicting_fields;
+use crate::types::{
+    ExpectedRevision, NewTask, ParsedTask, Revision, Task, TaskStatus, TaskUpdates,
+};
+use crate::writer::{render_task_with_updates, write_content, write_task, Original};
 use crate::Taskdn;
-use rayon::prelude::*;
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -25,32 +30,36 @@ impl Taskdn {
     /// Returns `Error::Parse` if the file cannot be parsed.
     pub fn get_task(&self, path: impl AsRef<Path>) -> Result<Task> {
         let path = self.resolve_task_path(path.as_ref())?;
-        let content =
-            fs::read_to_string(&path).map_err(|_| Error::NotFound { path: path.clone() })?;
-
-        ParsedTask::parse(&content)
-            .map(|parsed| parsed.with_path(&path))
-            .map_err(|e| match e {
-                Error::ContentParse { message } => Error::Parse {
-                    path: path.clone(),
-                    message,
-                },
-                Error::ContentMissingField { field } => Error::MissingField {
-                    path: path.clone(),
-                    field,
-                },
-                Error::ContentInvalidField { field, message } => Error::InvalidField {
-                    path: path.clone(),
-                    field,
-                    message,
-                },
-                other => other,
-            })
+        let (task, _) = Self::read_task_file(&path)?;
+        Ok(task)
+    }
+
+    /// Get a single task together with the revision of its file.
+    ///
+    /// Pass the revision to [`Taskdn::update_task_checked`] to make sure the file
+    /// hasn't been changed by someone else before writing it back.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the task file (absolute or relative to `tasks_dir`)
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the file doesn't exist.
+    /// Returns `Error::Parse` if the file cannot be parsed.
+    pub fn get_task_with_revision(&self, path: impl AsRef<Path>) -> Result<(Task, Revision)> {
+        let path = self.resolve_task_path(path.as_ref())?;
+        let (task, content) = Self::read_task_file(&path)?;
+        let revision = Revision::for_file(&path, &content)?;
+        Ok((task, revision))
     }
 
     /// List tasks matching a filter.
     ///
-    /// Invalid files are silently skipped. Use `validate_all_tasks()` for strict validation.
+    /// Invalid files are silently skipped. Use `list_tasks_with_skipped()` to find out
+    /// which files were skipped, or `validate_all_tasks()` for strict validation.
+    ///
+    /// [`TaskFilter::descendants_of`] is resolved against the scanned tasks,
+    /// so subtasks of archived tasks need `include_archive_dir()`.
+    /// [`TaskFilter::within_area`] reads all areas and projects.
     ///
     /// # Arguments
     /// * `filter` - Filter criteria for matching tasks
@@ -59,7 +68,21 @@ impl Taskdn {
     /// Returns an error if the tasks directory cannot be read.
     pub fn list_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>> {
         let tasks = self.scan_tasks(filter)?;
-        Ok(tasks)
+        Ok(tasks.items)
+    }
+
+    /// List tasks matching a filter, reporting files that could not be parsed.
+    ///
+    /// Like `list_tasks()`, but invalid files are returned in
+    /// [`ListResult::skipped`] with their errors instead of being dropped.
+    ///
+    /// # Arguments
+    /// * `filter` - Filter criteria for matching tasks
+    ///
+    /// # Errors
+    /// Returns an error if the tasks directory cannot be read.
+    pub fn list_tasks_with_skipped(&self, filter: &TaskFilter) -> Result<ListResult<Task>> {
+        self.scan_tasks(filter)
     }
 
     /// Count tasks matching a filter (more efficient than list).
@@ -87,25 +110,33 @@ impl Taskdn {
     /// # Errors
     /// Returns an error if the file cannot be created.
     pub fn create_task(&self, task: NewTask) -> Result<PathBuf> {
-        let filename = task
-            .filename
-            .clone()
-            .unwrap_or_else(|| generate_filename(&task.title));
-
-        let path = self.config.tasks_dir.join(&filename);
-
-        // Check if file already exists
-        if path.exists() {
-            return Err(Error::Validation {
-                path: path.clone(),
-                message: format!("file already exists: {filename}"),
-            });
-        }
+        let task = self.prepare_new_task(task, Path::exists)?;
+        write_task(&task.path, &task)?;
+        self.record("create_task", || {
+            vec![JournalChange::created(&task.path, &task.to_string())]
+        })?;
+        Ok(task.path)
+    }
 
-        let now = DateTimeValue::now();
+    /// Build the task that `create_task` would write, checking the target is free.
+    ///
+    /// `exists` reports whether a path is already taken.
+    pub(crate) fn prepare_new_task(
+        &self,
+        task: NewTask,
+        exists: impl Fn(&Path) -> bool,
+    ) -> Result<Task> {
+        let path = self.new_file_path(
+            &self.config.tasks_dir,
+            task.filename.as_deref(),
+            &task.title,
+            exists,
+        )?;
+
+        let now = self.timestamp();
 
         let full_task = Task {
-            path: path.clone(),
+            path,
             title: task.title,
             status: task.status,
             created_at: now.clone(),
@@ -116,13 +147,18 @@ impl Taskdn {
             defer_until: task.defer_until,
             project: task.project,
             area: task.area,
+            parent: task.parent,
+            blocked_by: task.blocked_by,
+            priority: task.priority,
+            recurrence: task.recurrence,
+            series: None,
+            tags: task.tags,
             body: task.body,
             extra: task.extra,
             projects_count: None,
         };
 
-        write_task(&path, &full_task)?;
-        Ok(path)
+        Ok(full_task)
     }
 
     /// Quick capture: create an inbox task with just a title.
@@ -149,47 +185,142 @@ impl Taskdn {
     /// * `path` - Path to the task file
     /// * `updates` - Partial updates to apply
     ///
+    /// # Returns
+    /// The task's path. If the title changes and
+    /// [`TaskdnConfig::rename_on_retitle`](crate::TaskdnConfig::rename_on_retitle)
+    /// is enabled, the file is renamed to match and every reference to it rewritten.
+    ///
     /// # Errors
     /// Returns an error if the file cannot be read or written.
-    pub fn update_task(&self, path: impl AsRef<Path>, updates: TaskUpdates) -> Result<()> {
+    pub fn update_task(&self, path: impl AsRef<Path>, updates: TaskUpdates) -> Result<PathBuf> {
         let path = self.resolve_task_path(path.as_ref())?;
-        let mut task = self.get_task(&path)?;
-        let previous_status = Some(task.status);
+        let (task, content) = Self::read_task_file(&path)?;
+        let (path, _) = self.write_task_updates(&path, task, &content, updates)?;
+        Ok(path)
+    }
 
-        // Apply updates
-        if let Some(title) = updates.title {
-            task.title = title;
-        }
-        if let Some(status) = updates.status {
-            task.status = status;
-        }
-        if let Some(due) = updates.due {
-            task.due = due;
-        }
-        if let Some(scheduled) = updates.scheduled {
-            task.scheduled = scheduled;
-        }
-        if let Some(defer_until) = updates.defer_until {
-            task.defer_until = defer_until;
-        }
-        if let Some(project) = updates.project {
-            task.project = project;
+    /// Update a task only if its file hasn't changed since it was read.
+    ///
+    /// Use this instead of [`Taskdn::update_task`] when the file may be edited
+    /// elsewhere (for example in Obsidian) between reading and writing it.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the task file
+    /// * `updates` - Partial updates to apply
+    /// * `expected` - The [`Revision`] returned by [`Taskdn::get_task_with_revision`],
+    ///   or the `updated-at` value that was last seen
+    ///
+    /// # Returns
+    /// The revision of the file after the update. Title changes rename the file
+    /// as [`Taskdn::update_task`] does when
+    /// [`TaskdnConfig::rename_on_retitle`](crate::TaskdnConfig::rename_on_retitle)
+    /// is enabled; the revision is then that of the renamed file.
+    /// Completing the task unblocks its dependents when
+    /// [`TaskdnConfig::unblock_dependents`](crate::TaskdnConfig::unblock_dependents) is enabled.
+    ///
+    /// # Errors
+    /// Returns `Error::Conflict` if the file no longer matches `expected`; the file
+    /// is left untouched in that case.
+    /// Returns an error if the file cannot be read or written.
+    pub fn update_task_checked(
+        &self,
+        path: impl AsRef<Path>,
+        updates: TaskUpdates,
+        expected: impl Into<ExpectedRevision>,
+    ) -> Result<Revision> {
+        let path = self.resolve_task_path(path.as_ref())?;
+        let (task, content) = Self::read_task_file(&path)?;
+
+        let unchanged = match expected.into() {
+            ExpectedRevision::Revision(revision) => {
+                revision == Revision::for_file(&path, &content)?
+            }
+            ExpectedRevision::UpdatedAt(updated_at) => task.updated_at == updated_at,
+        };
+
+        if !unchanged {
+            let mut ours = task;
+            updates.apply_to(&mut ours);
+            return Err(Error::Conflict {
+                path,
+                ours: ours.to_string(),
+                theirs: content,
+                fields: Vec::new(),
+            });
         }
-        if let Some(area) = updates.area {
-            task.area = area;
+
+        self.write_task_updates(&path, task, &content, updates)
+            .map(|(_, revision)| revision)
+    }
+
+    /// Update a task, merging with any external edits made since `base` was read.
+    ///
+    /// Performs a field-level three-way merge (S3 §4.2): if the file changed on
+    /// disk, the updates are applied on top of the current version as long as the
+    /// external edit touched different frontmatter keys. `updated-at` and
+    /// `completed-at` are managed by the SDK and never conflict.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the task file
+    /// * `base` - The task as it was when the caller read it
+    /// * `updates` - Partial updates to apply
+    ///
+    /// # Returns
+    /// The revision of the file after the update. Title changes rename the file
+    /// as [`Taskdn::update_task`] does when
+    /// [`TaskdnConfig::rename_on_retitle`](crate::TaskdnConfig::rename_on_retitle)
+    /// is enabled; the revision is then that of the renamed file.
+    /// Completing the task unblocks its dependents when
+    /// [`TaskdnConfig::unblock_dependents`](crate::TaskdnConfig::unblock_dependents) is enabled.
+    ///
+    /// # Errors
+    /// Returns `Error::Conflict` listing the conflicting keys if both sides changed
+    /// the same key to different values; the file is left untouched in that case.
+    /// Returns an error if the file cannot be read or written.
+    pub fn update_task_merging(
+        &self,
+        path: impl AsRef<Path>,
+        base: &Task,
+        updates: TaskUpdates,
+    ) -> Result<Revision> {
+        let path = self.resolve_task_path(path.as_ref())?;
+        let (task, content) = Self::read_task_file(&path)?;
+
+        let mut ours = base.clone();
+        updates.clone().apply_to(&mut ours);
+
+        let conflicts = conflicting_fields(
+            &base.frontmatter_fields(),
+            &ours.frontmatter_fields(),
+            &task.frontmatter_fields(),
+            &["updated-at", "completed-at"],
+        );
+        if !conflicts.is_empty() {
+            return Err(Error::Conflict {
+                path,
+                ours: ours.to_string(),
+                theirs: content,
+                fields: conflicts,
+            });
         }
 
-        write_task_with_updates(&path, &mut task, previous_status)
+        self.write_task_updates(&path, task, &content, updates)
+            .map(|(_, revision)| revision)
     }
 
     /// Update all tasks matching a filter.
     ///
+    /// The updates are applied as one [`Transaction`](crate::Transaction):
+    /// either every matching task is updated or none is.
+    ///
     /// # Arguments
     /// * `filter` - Filter criteria for matching tasks
     /// * `updates` - Partial updates to apply to all matching tasks
     ///
     /// # Returns
-    /// A `BatchResult` containing paths of successfully updated tasks and any failures.
+    /// A `BatchResult` containing the paths of the updated tasks. If any task
+    /// cannot be updated, `succeeded` is empty, `failed` holds the errors and
+    /// no file is changed.
     #[must_use]
     pub fn update_tasks_matching(
         &self,
@@ -198,20 +329,30 @@ impl Taskdn {
     ) -> BatchResult<PathBuf> {
         let mut result = BatchResult::new();
 
-        match self.list_tasks(filter) {
-            Ok(tasks) => {
-                for task in tasks {
-                    match self.update_task(&task.path, updates.clone()) {
-                        Ok(()) => result.succeeded.push(task.path),
-                        Err(e) => result.failed.push((task.path, e)),
-                    }
-                }
-            }
+        let tasks = match self.list_tasks(filter) {
+            Ok(tasks) => tasks,
             Err(e) => {
                 result.failed.push((self.config.tasks_dir.clone(), e));
+                return result;
+            }
+        };
+
+        let mut tx = self.transaction();
+        let mut paths = Vec::new();
+        for task in tasks {
+            match tx.update_task(&task.path, updates.clone()) {
+                Ok(path) => paths.push(path),
+                Err(e) => result.failed.push((task.path, e)),
             }
         }
+        if !result.failed.is_empty() {
+            return result;
+        }
 
+        result = tx.commit_batch("update_tasks_matching");
+        if !result.succeeded.is_empty() {
+            result.succeeded = paths;
+        }
         result
     }
 
@@ -221,10 +362,26 @@ impl Taskdn {
 
     /// Mark a task as done (sets `completed_at` automatically).
     ///
+    /// If the task has a `recurrence` rule, its next instance is created in
+    /// the same step, with its dates moved to the next occurrence.
+    ///
+    /// # Returns
+    /// The path of the next instance, if the task recurs.
+    ///
     /// # Errors
     /// Returns an error if the file cannot be read or written.
-    pub fn complete_task(&self, path: impl AsRef<Path>) -> Result<()> {
-        self.update_task(path, TaskUpdates::new().status(TaskStatus::Done))
+    pub fn complete_task(&self, path: impl AsRef<Path>) -> Result<Option<PathBuf>> {
+        let path = self.resolve_task_path(path.as_ref())?;
+        let (task, _) = Self::read_task_file(&path)?;
+        if task.recurrence.is_none() || task.status.is_completed() {
+            self.update_task(path, TaskUpdates::new().status(TaskStatus::Done))?;
+            return Ok(None);
+        }
+
+        let mut tx = self.transaction();
+        let next = tx.complete_task(&path)?;
+        tx.commit_as("complete_task")?;
+        Ok(next)
     }
 
     /// Mark a task as dropped (sets `completed_at` automatically).
@@ -232,7 +389,8 @@ impl Taskdn {
     /// # Errors
     /// Returns an error if the file cannot be read or written.
     pub fn drop_task(&self, path: impl AsRef<Path>) -> Result<()> {
-        self.update_task(path, TaskUpdates::new().status(TaskStatus::Dropped))
+        self.update_task(path, TaskUpdates::new().status(TaskStatus::Dropped))?;
+        Ok(())
     }
 
     /// Start working on a task (status -> `InProgress`).
@@ -240,7 +398,8 @@ impl Taskdn {
     /// # Errors
     /// Returns an error if the file cannot be read or written.
     pub fn start_task(&self, path: impl AsRef<Path>) -> Result<()> {
-        self.update_task(path, TaskUpdates::new().status(TaskStatus::InProgress))
+        self.update_task(path, TaskUpdates::new().status(TaskStatus::InProgress))?;
+        Ok(())
     }
 
     /// Block a task (status -> Blocked).
@@ -248,7 +407,8 @@ impl Taskdn {
     /// # Errors
     /// Returns an error if the file cannot be read or written.
     pub fn block_task(&self, path: impl AsRef<Path>) -> Result<()> {
-        self.update_task(path, TaskUpdates::new().status(TaskStatus::Blocked))
+        self.update_task(path, TaskUpdates::new().status(TaskStatus::Blocked))?;
+        Ok(())
     }
 
     // ==========================================================================
@@ -295,7 +455,7 @@ impl Taskdn {
             });
         }
 
-        fs::rename(&path, &new_path)?;
+        self.rename_file_recorded("archive_task", &path, &new_path)?;
         Ok(new_path)
     }
 
@@ -334,7 +494,38 @@ impl Taskdn {
             });
         }
 
-        fs::rename(&path, &new_path)?;
+        self.rename_file_recorded("unarchive_task", &path, &new_path)?;
+        Ok(new_path)
+    }
+
+    // ==========================================================================
+    // Rename Operations
+    // ==========================================================================
+
+    /// Rename a task file, rewriting every reference to it.
+    ///
+    /// Rewrites `[[WikiLink]]` mentions of it in markdown bodies. References keep their
+    /// original format. The move and all rewrites happen in one transaction.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the task file
+    /// * `new_path` - New path; relative paths are resolved against the tasks directory,
+    ///   and a missing extension becomes `.md`
+    ///
+    /// # Returns
+    /// The task's new path.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the task doesn't exist.
+    /// Returns `Error::Validation` if a file already exists at `new_path`.
+    pub fn rename_task(
+        &self,
+        path: impl AsRef<Path>,
+        new_path: impl AsRef<Path>,
+    ) -> Result<PathBuf> {
+        let mut tx = self.transaction();
+        let new_path = tx.rename_task(path, new_path)?;
+        tx.commit_as("rename_task")?;
         Ok(new_path)
     }
 
@@ -342,7 +533,10 @@ impl Taskdn {
     // Delete Operations
     // ==========================================================================
 
-    /// Permanently delete a task file.
+    /// Delete a task file.
+    ///
+    /// Moves the file to the trash when [`TaskdnConfig::use_trash`](crate::TaskdnConfig::use_trash)
+    /// is enabled; otherwise it is removed permanently.
     ///
     /// # Arguments
     /// * `path` - Path to the task file
@@ -351,13 +545,77 @@ impl Taskdn {
     /// Returns an error if the file cannot be deleted.
     pub fn delete_task(&self, path: impl AsRef<Path>) -> Result<()> {
         let path = self.resolve_task_path(path.as_ref())?;
-        fs::remove_file(&path).map_err(|_| Error::NotFound { path })
+        self.delete_file("delete_task", &self.config.tasks_dir, &path)
     }
 
     // ==========================================================================
     // Internal Helpers
     // ==========================================================================
 
+    /// Apply updates to a task read from `content` and write it back.
+    ///
+    /// Maintains timestamps, and patches the file in place when
+    /// `preserve_formatting` is enabled. Retitles and completions that
+    /// `rename_on_retitle` or `unblock_dependents` apply to are committed as a
+    /// transaction instead. Returns the task's path after the update and the
+    /// revision of the written file.
+    fn write_task_updates(
+        &self,
+        path: &Path,
+        task: Task,
+        content: &str,
+        updates: TaskUpdates,
+    ) -> Result<(PathBuf, Revision)> {
+        let retitles = self.config.rename_on_retitle && updates.title.is_some();
+        let completes = !task.status.is_completed()
+            && updates.status.is_some_and(|status| status.is_completed());
+        if retitles || (self.config.unblock_dependents && completes) {
+            let mut tx = self.transaction();
+            tx.expect_content(path, content);
+            let new_path = tx.update_task(path, updates)?;
+            tx.commit_as("update_task")?;
+            let revision = Revision::for_file(&new_path, &fs::read_to_string(&new_path)?)?;
+            return Ok((new_path, revision));
+        }
+
+        let rendered = self.render_task_updates(task, content, updates);
+        let revision = write_content(path, &rendered)?;
+        self.record("update_task", || {
+            vec![JournalChange::modified(path, content, &rendered)]
+        })?;
+        Ok((path.to_path_buf(), revision))
+    }
+
+    /// Apply updates to a task read from `content` and render the new file content.
+    pub(crate) fn render_task_updates(
+        &self,
+        mut task: Task,
+        content: &str,
+        updates: TaskUpdates,
+    ) -> String {
+        let original = self
+            .config
+            .preserve_formatting
+            .then(|| Original::task(content, &task));
+        let previous_status = Some(task.status);
+
+        updates.apply_to(&mut task);
+
+        let now = self.timestamp();
+        render_task_with_updates(&mut task, previous_status, original.as_ref(), &now)
+    }
+
+    /// Read and parse a task file, returning its raw content alongside it.
+    fn read_task_file(path: &Path) -> Result<(Task, String)> {
+        let content = fs::read_to_string(path).map_err(|_| Error::NotFound {
+            path: path.to_path_buf(),
+        })?;
+        let task = ParsedTask::parse(&content)
+            .map_err(|e| e.with_path(path))?
+            .with_path(path);
+        Ok((task, content))
+    }
+
     /// Resolve a task path - if relative, resolve against `tasks_dir`.
     fn resolve_task_path(&self, path: &Path) -> Result<PathBuf> {
         if path.is_absolute() {
@@ -379,8 +637,9 @@ impl Taskdn {
     }
 
     /// Scan the tasks directory and return tasks matching the filter.
-    fn scan_tasks(&self, filter: &TaskFilter) -> Result<Vec<Task>> {
+    fn scan_tasks(&self, filter: &TaskFilter) -> Result<ListResult<Task>> {
         let mut all_paths = Vec::new();
+        let mut scanned_dirs = vec![self.config.tasks_dir.clone()];
 
         // Collect paths from main tasks directory
         self.collect_md_paths(&self.config.tasks_dir, &mut all_paths)?;
@@ -390,22 +649,42 @@ impl Taskdn {
             let archive_dir = self.config.tasks_dir.join("archive");
             if archive_dir.exists() {
                 self.collect_md_paths(&archive_dir, &mut all_paths)?;
+                scanned_dirs.push(archive_dir);
             }
         }
 
-        // Parse all tasks in parallel and apply filter
-        let tasks: Vec<Task> = all_paths
-            .par_iter()
-            .filter_map(|path| self.get_task(path).ok())
-            .filter(|task| filter.matches(task))
-            .collect();
-
+        // Parse all tasks (in parallel, through the cache) and apply filter
+        let results = self.parse_files(&all_paths, &scanned_dirs);
+        let mut tasks = ListResult::from_results(&all_paths, results);
+        if let Some(root) = &filter.descendants_of {
+            let descendants = descendant_paths(&tasks.items, root);
+            tasks = tasks.filter(|task| descendants.contains(&task.path));
+        }
+        if let Some(root) = &filter.within_area {
+            let areas = self.list_areas(&AreaFilter::new())?;
+            let projects = self.list_projects(&ProjectFilter::new())?;
+            let scope = AreaScope::resolve(&areas, &projects, root);
+            tasks = tasks.filter(|task| scope.contains_task(task));
+        }
+        if let Some(actionable) = filter.actionable {
+            let statuses: HashMap<PathBuf, TaskStatus> = tasks
+                .items
+                .iter()
+                .map(|task| (task.path.clone(), task.status))
+                .collect();
+            tasks = tasks.filter(|task| {
+                task.blockers_completed(|blocker| self.blocker_status(blocker, &statuses))
+                    == actionable
+            });
+        }
+        let mut tasks = tasks.filter(|task| filter.matches(task));
+        filter.sort(&mut tasks.items);
         Ok(tasks)
     }
 
     /// Collect all .md file paths from a directory.
     #[allow(clippy::unused_self)]
-    fn collect_md_paths(&self, dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    pub(crate) fn collect_md_paths(&self, dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
         let entries = fs::read_dir(dir)?;
 
         for entry in entries.flatten() {
@@ -429,7 +708,7 @@ impl Taskdn {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::TaskdnConfig;
+    use crate::{DateTimeValue, FilenameStrategy, TaskdnConfig};
     use std::fs;
     use tempfile::TempDir;
 
@@ -610,6 +889,32 @@ Task body.
             assert_eq!(tasks.len(), 1);
             assert_eq!(tasks[0].title, "Valid");
         }
+
+        #[test]
+        fn reports_skipped_files() {
+            let (_temp, sdk) = setup_test_env();
+            create_task_file(
+                &sdk.config.tasks_dir,
+                "valid.md",
+                &sample_task_content("Valid", "ready"),
+            );
+            create_task_file(
+                &sdk.config.tasks_dir,
+                "invalid.md",
+                "not valid yaml frontmatter",
+            );
+
+            let result = sdk
+                .list_tasks_with_skipped(&TaskFilter::new().with_status(TaskStatus::Done))
+                .unwrap();
+            // Skipped files are reported even though nothing matched the filter
+            assert!(result.items.is_empty());
+            assert_eq!(result.skipped_count(), 1);
+
+            let (path, error) = &result.skipped[0];
+            assert_eq!(path, &sdk.config.tasks_dir.join("invalid.md"));
+            assert!(matches!(error, Error::Parse { path: p, .. } if p == path));
+        }
     }
 
     mod create_task {
@@ -657,6 +962,47 @@ Task body.
             assert!(matches!(result, Err(Error::Validation { .. })));
         }
 
+        #[test]
+        fn create_fails_if_title_slug_taken() {
+            let (_temp, sdk) = setup_test_env();
+            sdk.create_task(NewTask::new("Call dad")).unwrap();
+
+            let result = sdk.create_task(NewTask::new("Call dad"));
+            assert!(matches!(result, Err(Error::Validation { .. })));
+        }
+
+        #[test]
+        fn slug_with_suffix_strategy_avoids_collisions() {
+            let (_temp, sdk) = setup_test_env();
+            let config = sdk
+                .config()
+                .clone()
+                .with_filename_strategy(FilenameStrategy::SlugWithSuffix);
+            let sdk = Taskdn::new(config).unwrap();
+
+            let first = sdk.create_task(NewTask::new("Call dad")).unwrap();
+            let second = sdk.create_task(NewTask::new("Call dad")).unwrap();
+
+            assert_eq!(first.file_name().unwrap(), "call-dad.md");
+            assert_eq!(second.file_name().unwrap(), "call-dad-2.md");
+            assert_eq!(sdk.get_task(&second).unwrap().title, "Call dad");
+        }
+
+        #[test]
+        fn custom_filename_ignores_strategy() {
+            let (_temp, sdk) = setup_test_env();
+            let config = sdk
+                .config()
+                .clone()
+                .with_filename_strategy(FilenameStrategy::UniqueId);
+            let sdk = Taskdn::new(config).unwrap();
+
+            sdk.create_task(NewTask::new("Test").with_filename("test.md"))
+                .unwrap();
+            let result = sdk.create_task(NewTask::new("Test").with_filename("test.md"));
+            assert!(matches!(result, Err(Error::Validation { .. })));
+        }
+
         #[test]
         fn create_inbox_task() {
             let (_temp, sdk) = setup_test_env();
@@ -712,6 +1058,253 @@ Task body.
         }
     }
 
+    mod update_tasks_matching {
+        use super::*;
+
+        #[test]
+        fn updates_every_match() {
+            let (_temp, sdk) = setup_test_env();
+            let dir = &sdk.config.tasks_dir;
+            create_task_file(dir, "a.md", &sample_task_content("A", "ready"));
+            create_task_file(dir, "b.md", &sample_task_content("B", "ready"));
+            create_task_file(dir, "c.md", &sample_task_content("C", "inbox"));
+
+            let filter = TaskFilter::new().with_status(TaskStatus::Ready);
+            let updates = TaskUpdates::new().status(TaskStatus::InProgress);
+            let mut result = sdk.update_tasks_matching(&filter, &updates);
+            result.succeeded.sort();
+
+            assert!(result.is_complete_success());
+            assert_eq!(result.succeeded, [dir.join("a.md"), dir.join("b.md")]);
+            assert_eq!(sdk.get_task("a.md").unwrap().status, TaskStatus::InProgress);
+            assert_eq!(sdk.get_task("c.md").unwrap().status, TaskStatus::Inbox);
+        }
+
+        #[test]
+        fn changes_nothing_if_any_update_fails() {
+            let (_temp, sdk) = setup_test_env();
+            let config = sdk.config().clone().with_rename_on_retitle(true);
+            let sdk = Taskdn::new(config).unwrap();
+            let dir = &sdk.config.tasks_dir;
+            create_task_file(dir, "a.md", &sample_task_content("A", "ready"));
+            create_task_file(dir, "b.md", &sample_task_content("B", "ready"));
+            let before = fs::read_to_string(dir.join("a.md")).unwrap();
+
+            // Both tasks would be renamed to same.md
+            let updates = TaskUpdates::new().title("Same");
+            let result = sdk.update_tasks_matching(&TaskFilter::new(), &updates);
+
+            assert!(result.succeeded.is_empty());
+            assert_eq!(result.failure_count(), 1);
+            assert_eq!(fs::read_to_string(dir.join("a.md")).unwrap(), before);
+            assert!(!dir.join("same.md").exists());
+        }
+    }
+
+    mod concurrency {
+        use super::*;
+
+        /// Simulate another editor changing the file on disk.
+        fn external_edit(path: &Path, from: &str, to: &str) {
+            let content = fs::read_to_string(path).unwrap();
+            assert!(content.contains(from));
+            fs::write(path, content.replacen(from, to, 1)).unwrap();
+        }
+
+        #[test]
+        fn checked_update_succeeds_when_unchanged() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_task(NewTask::new("Task")).unwrap();
+            let (_, revision) = sdk.get_task_with_revision(&path).unwrap();
+
+            let new_revision = sdk
+                .update_task_checked(
+                    &path,
+                    TaskUpdates::new().status(TaskStatus::Ready),
+                    revision,
+                )
+                .unwrap();
+
+            let (task, current) = sdk.get_task_with_revision(&path).unwrap();
+            assert_eq!(task.status, TaskStatus::Ready);
+            assert_eq!(new_revision, current);
+            assert_ne!(new_revision, revision);
+        }
+
+        #[test]
+        fn checked_update_conflicts_after_external_edit() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_task(NewTask::new("Task")).unwrap();
+            let (_, revision) = sdk.get_task_with_revision(&path).unwrap();
+
+            external_edit(&path, "title: Task", "title: Edited in Obsidian");
+            let on_disk = fs::read_to_string(&path).unwrap();
+
+            let result = sdk.update_task_checked(
+                &path,
+                TaskUpdates::new().status(TaskStatus::Done),
+                revision,
+            );
+
+            match result {
+                Err(Error::Conflict { ours, theirs, .. }) => {
+                    assert!(ours.contains("status: done"));
+                    assert!(ours.contains("title: Edited in Obsidian"));
+                    assert_eq!(theirs, on_disk);
+                }
+                other => panic!("Expected Conflict, got {other:?}"),
+            }
+
+            // The external edit must not be lost
+            assert_eq!(fs::read_to_string(&path).unwrap(), on_disk);
+        }
+
+        #[test]
+        fn checked_update_with_updated_at() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_task(NewTask::new("Task")).unwrap();
+            let task = sdk.get_task(&path).unwrap();
+
+            sdk.update_task_checked(
+                &path,
+                TaskUpdates::new().title("Renamed"),
+                task.updated_at.clone(),
+            )
+            .unwrap();
+            assert_eq!(sdk.get_task(&path).unwrap().title, "Renamed");
+
+            let stale: DateTimeValue = "2000-01-01".parse().unwrap();
+            let result = sdk.update_task_checked(&path, TaskUpdates::new().title("Again"), stale);
+            assert!(matches!(result, Err(Error::Conflict { .. })));
+            assert_eq!(sdk.get_task(&path).unwrap().title, "Renamed");
+        }
+
+        #[test]
+        fn merging_update_applies_on_top_of_external_edit() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk
+                .create_task(NewTask::new("Task").with_body("Original body\n"))
+                .unwrap();
+            let base = sdk.get_task(&path).unwrap();
+
+            external_edit(&path, "title: Task", "title: Edited in Obsidian");
+            external_edit(&path, "Original body", "Body edited too");
+
+            sdk.update_task_merging(&path, &base, TaskUpdates::new().status(TaskStatus::Done))
+                .unwrap();
+
+            let task = sdk.get_task(&path).unwrap();
+            assert_eq!(task.title, "Edited in Obsidian");
+            assert_eq!(task.status, TaskStatus::Done);
+            assert!(task.completed_at.is_some());
+            assert_eq!(task.body.trim(), "Body edited too");
+        }
+
+        #[test]
+        fn merging_update_conflicts_on_same_key() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_task(NewTask::new("Task")).unwrap();
+            let base = sdk.get_task(&path).unwrap();
+
+            external_edit(&path, "status: inbox", "status: blocked");
+            let on_disk = fs::read_to_string(&path).unwrap();
+
+            let result =
+                sdk.update_task_merging(&path, &base, TaskUpdates::new().status(TaskStatus::Done));
+
+            match result {
+                Err(Error::Conflict { fields, theirs, .. }) => {
+                    assert_eq!(fields, vec!["status".to_string()]);
+                    assert_eq!(theirs, on_disk);
+                }
+                other => panic!("Expected Conflict, got {other:?}"),
+            }
+            assert_eq!(fs::read_to_string(&path).unwrap(), on_disk);
+        }
+
+        #[test]
+        fn merging_update_without_external_edit() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_task(NewTask::new("Task")).unwrap();
+            let base = sdk.get_task(&path).unwrap();
+
+            sdk.update_task_merging(&path, &base, TaskUpdates::new().title("New"))
+                .unwrap();
+
+            assert_eq!(sdk.get_task(&path).unwrap().title, "New");
+        }
+    }
+
+    mod preserve_formatting {
+        use super::*;
+
+        const ORIGINAL: &str = "---\n# Managed in Obsidian\ntitle: 'My Task'\nstatus: ready   # triage\nzzz-custom: [b, a]\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\n\nBody\n";
+
+        fn setup_preserving() -> (TempDir, Taskdn) {
+            let (temp, sdk) = setup_test_env();
+            let config = sdk.config().clone().with_preserve_formatting(true);
+            (temp, Taskdn::new(config).unwrap())
+        }
+
+        #[test]
+        fn update_only_touches_changed_keys() {
+            let (_temp, sdk) = setup_preserving();
+            create_task_file(&sdk.config.tasks_dir, "task.md", ORIGINAL);
+
+            sdk.update_task("task.md", TaskUpdates::new().status(TaskStatus::InProgress))
+                .unwrap();
+
+            let content = fs::read_to_string(sdk.config.tasks_dir.join("task.md")).unwrap();
+            let changed: Vec<_> = ORIGINAL
+                .lines()
+                .zip(content.lines())
+                .filter(|(a, b)| a != b)
+                .collect();
+
+            // Only the status line and updated-at are rewritten
+            assert_eq!(changed.len(), 2);
+            assert!(content.contains("status: in-progress   # triage\n"));
+            let updated_at = content
+                .lines()
+                .find(|line| line.starts_with("updated-at: "))
+                .unwrap();
+            assert!(!updated_at.contains(['"', '\'']), "{updated_at}");
+            assert!(content.contains("# Managed in Obsidian\n"));
+            assert!(content.contains("title: 'My Task'\n"));
+            assert!(content.contains("zzz-custom: [b, a]\n"));
+            assert!(content.ends_with("---\n\nBody\n"));
+        }
+
+        #[test]
+        fn completion_adds_completed_at_after_updated_at() {
+            let (_temp, sdk) = setup_preserving();
+            create_task_file(&sdk.config.tasks_dir, "task.md", ORIGINAL);
+
+            sdk.complete_task("task.md").unwrap();
+
+            let content = fs::read_to_string(sdk.config.tasks_dir.join("task.md")).unwrap();
+            let lines: Vec<_> = content.lines().collect();
+            let updated = lines
+                .iter()
+                .position(|l| l.starts_with("updated-at:"))
+                .unwrap();
+            assert!(lines[updated + 1].starts_with("completed-at:"));
+            assert!(sdk.get_task("task.md").unwrap().completed_at.is_some());
+        }
+
+        #[test]
+        fn disabled_by_default() {
+            let (_temp, sdk) = setup_test_env();
+            create_task_file(&sdk.config.tasks_dir, "task.md", ORIGINAL);
+
+            sdk.update_task("task.md", TaskUpdates::new().status(TaskStatus::InProgress))
+                .unwrap();
+
+            let content = fs::read_to_string(sdk.config.tasks_dir.join("task.md")).unwrap();
+            assert!(!content.contains("# Managed in Obsidian"));
+        }
+    }
+
     mod status_transitions {
         use super::*;
 
@@ -827,6 +1420,220 @@ Task body.
         }
     }
 
+    mod rename_task {
+        use super::*;
+        use crate::types::NewProject;
+
+        #[test]
+        fn rename_moves_file_and_rewrites_body_links() {
+            let (_temp, sdk) = setup_test_env();
+            let task = sdk
+                .create_task(NewTask::new("Draft").with_filename("draft.md"))
+                .unwrap();
+            let project = sdk
+                .create_project(NewProject::new("Project").with_body("Next: [[draft]]"))
+                .unwrap();
+
+            let new_path = sdk.rename_task(&task, "write-draft").unwrap();
+
+            assert_eq!(new_path, sdk.config().tasks_dir.join("write-draft.md"));
+            assert!(!task.exists());
+            assert_eq!(sdk.get_task(&new_path).unwrap().title, "Draft");
+            assert_eq!(
+                sdk.get_project(&project).unwrap().body.trim(),
+                "Next: [[write-draft]]"
+            );
+        }
+
+        #[test]
+        fn rename_keeps_updated_at_of_rewritten_files() {
+            let (_temp, sdk) = setup_test_env();
+            let task = sdk
+                .create_task(NewTask::new("Draft").with_filename("draft.md"))
+                .unwrap();
+            let other = sdk.config().tasks_dir.join("other.md");
+            create_task_file(
+                &sdk.config().tasks_dir,
+                "other.md",
+                "---\ntitle: Other\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\nSee [[draft]].\n",
+            );
+
+            sdk.rename_task(&task, "final").unwrap();
+
+            assert_eq!(
+                fs::read_to_string(&other).unwrap(),
+                "---\ntitle: Other\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\nSee [[final]].\n"
+            );
+        }
+
+        #[test]
+        fn rename_rewrites_parent_references() {
+            let (_temp, sdk) = setup_test_env();
+            let parent = sdk
+                .create_task(NewTask::new("Epic").with_filename("epic.md"))
+                .unwrap();
+            let child = sdk
+                .create_task(NewTask::new("Step").with_parent("[[epic]]"))
+                .unwrap();
+
+            sdk.rename_task(&parent, "launch").unwrap();
+
+            assert_eq!(
+                sdk.get_task(&child).unwrap().parent,
+                Some(crate::FileReference::wiki_link("launch"))
+            );
+        }
+
+        #[test]
+        fn rename_rewrites_blocked_by_references() {
+            let (_temp, sdk) = setup_test_env();
+            let blocker = sdk
+                .create_task(NewTask::new("Design").with_filename("design.md"))
+                .unwrap();
+            let dependent = sdk
+                .create_task(
+                    NewTask::new("Build")
+                        .with_blocker("[[design]]")
+                        .with_blocker("other.md"),
+                )
+                .unwrap();
+
+            sdk.rename_task(&blocker, "api-design").unwrap();
+
+            assert_eq!(
+                sdk.get_task(&dependent).unwrap().blocked_by,
+                vec![
+                    crate::FileReference::wiki_link("api-design"),
+                    crate::FileReference::Filename("other.md".to_string())
+                ]
+            );
+        }
+
+        #[test]
+        fn rename_to_same_path_is_a_no_op() {
+            let (_temp, sdk) = setup_test_env();
+            let task = sdk.create_task(NewTask::new("Task")).unwrap();
+
+            let new_path = sdk.rename_task(&task, &task).unwrap();
+
+            assert_eq!(new_path, task);
+            assert!(task.exists());
+        }
+    }
+
+    mod rename_on_retitle {
+        use super::*;
+
+        fn setup_renaming() -> (TempDir, Taskdn) {
+            let (temp, sdk) = setup_test_env();
+            let config = sdk.config().clone().with_rename_on_retitle(true);
+            (temp, Taskdn::new(config).unwrap())
+        }
+
+        #[test]
+        fn disabled_by_default() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_task(NewTask::new("Old Title")).unwrap();
+
+            let new_path = sdk
+                .update_task(&path, TaskUpdates::new().title("New Title"))
+                .unwrap();
+
+            assert_eq!(new_path, path);
+            assert_eq!(sdk.get_task(&path).unwrap().title, "New Title");
+        }
+
+        #[test]
+        fn retitle_renames_file_and_rewrites_links() {
+            let (_temp, sdk) = setup_renaming();
+            let path = sdk.create_task(NewTask::new("Old Title")).unwrap();
+            let other = sdk
+                .create_task(
+                    NewTask::new("Other").with_body("See [[old-title]] and [[Old Title]]."),
+                )
+                .unwrap();
+
+            let new_path = sdk
+                .update_task(&path, TaskUpdates::new().title("New Title"))
+                .unwrap();
+
+            assert_eq!(new_path, sdk.config().tasks_dir.join("new-title.md"));
+            assert!(!path.exists());
+            assert_eq!(sdk.get_task(&new_path).unwrap().title, "New Title");
+            assert_eq!(
+                sdk.get_task(&other).unwrap().body.trim(),
+                "See [[new-title]] and [[New Title]]."
+            );
+        }
+
+        #[test]
+        fn retitle_adds_suffix_on_collision() {
+            let (_temp, sdk) = setup_renaming();
+            sdk.create_task(NewTask::new("Call dad")).unwrap();
+            let path = sdk.create_task(NewTask::new("Call mum")).unwrap();
+
+            let new_path = sdk
+                .update_task(&path, TaskUpdates::new().title("Call dad"))
+                .unwrap();
+
+            assert_eq!(new_path, sdk.config().tasks_dir.join("call-dad-2.md"));
+            assert_eq!(sdk.get_task(&new_path).unwrap().title, "Call dad");
+        }
+
+        #[test]
+        fn other_updates_keep_path() {
+            let (_temp, sdk) = setup_renaming();
+            let path = sdk.create_task(NewTask::new("Task")).unwrap();
+
+            let same_title = sdk
+                .update_task(&path, TaskUpdates::new().title("Task"))
+                .unwrap();
+            let status_only = sdk
+                .update_task(&path, TaskUpdates::new().status(TaskStatus::Done))
+                .unwrap();
+
+            assert_eq!(same_title, path);
+            assert_eq!(status_only, path);
+            assert_eq!(sdk.get_task(&path).unwrap().status, TaskStatus::Done);
+        }
+
+        #[test]
+        fn checked_and_merging_updates_rename() {
+            let (_temp, sdk) = setup_renaming();
+            let path = sdk.create_task(NewTask::new("Draft")).unwrap();
+            let (task, revision) = sdk.get_task_with_revision(&path).unwrap();
+
+            let revision = sdk
+                .update_task_checked(&path, TaskUpdates::new().title("Outline"), revision)
+                .unwrap();
+            let outline = sdk.config().tasks_dir.join("outline.md");
+            assert!(!path.exists());
+            assert_eq!(sdk.get_task_with_revision(&outline).unwrap().1, revision);
+
+            let mut base = task;
+            base.path.clone_from(&outline);
+            base.title = "Outline".to_string();
+            sdk.update_task_merging(&outline, &base, TaskUpdates::new().title("Final"))
+                .unwrap();
+            assert!(!outline.exists());
+            assert_eq!(sdk.get_task("final.md").unwrap().title, "Final");
+        }
+
+        #[test]
+        fn archived_task_stays_in_archive() {
+            let (_temp, sdk) = setup_renaming();
+            let path = sdk.create_task(NewTask::new("Old")).unwrap();
+            let archived = sdk.archive_task(&path).unwrap();
+
+            let new_path = sdk
+                .update_task(&archived, TaskUpdates::new().title("New"))
+                .unwrap();
+
+            assert_eq!(new_path, sdk.config().tasks_dir.join("archive/new.md"));
+            assert!(new_path.exists());
+        }
+    }
+
     mod delete_task {
         use super::*;
 
@@ -847,4 +1654,310 @@ Task body.
             assert!(matches!(result, Err(Error::NotFound { .. })));
         }
     }
+
+    mod clock {
+        use super::*;
+        use crate::{FixedClock, OffsetClock, TimestampPolicy};
+        use chrono::{DateTime, Duration, Utc};
+
+        fn instant(s: &str) -> DateTime<Utc> {
+            s.parse().unwrap()
+        }
+
+        #[test]
+        fn timestamps_come_from_the_clock() {
+            let (_temp, sdk) = setup_test_env();
+            let config = sdk
+                .config()
+                .clone()
+                .with_timestamp_policy(TimestampPolicy::Utc);
+            let sdk = Taskdn::new(config)
+                .unwrap()
+                .with_clock(FixedClock::new(instant("2025-01-15T09:00:00Z")));
+
+            let path = sdk.create_task(NewTask::new("Test")).unwrap();
+            let task = sdk.get_task(&path).unwrap();
+            assert_eq!(task.created_at.to_string(), "2025-01-15T09:00:00Z");
+            assert_eq!(task.updated_at.to_string(), "2025-01-15T09:00:00Z");
+
+            let sdk = sdk.with_clock(FixedClock::new(instant("2025-01-16T17:30:00Z")));
+            sdk.complete_task(&path).unwrap();
+            let task = sdk.get_task(&path).unwrap();
+            assert_eq!(task.created_at.to_string(), "2025-01-15T09:00:00Z");
+            assert_eq!(task.updated_at.to_string(), "2025-01-16T17:30:00Z");
+            assert_eq!(
+                task.completed_at.map(|c| c.to_string()),
+                Some("2025-01-16T17:30:00Z".to_string())
+            );
+        }
+
+        #[test]
+        fn filter_presets_use_today_from_the_clock() {
+            let (_temp, sdk) = setup_test_env();
+            let clock = FixedClock::new(instant("2025-01-15T12:00:00Z"));
+            let sdk = sdk.with_clock(clock);
+            let today = sdk.today();
+            sdk.create_task(
+                NewTask::new("Due today")
+                    .with_status(TaskStatus::Ready)
+                    .with_due(DateTimeValue::from_date(today)),
+            )
+            .unwrap();
+
+            let overdue = sdk.list_tasks(&TaskFilter::overdue(sdk.today())).unwrap();
+            assert!(overdue.is_empty());
+
+            let sdk = sdk.with_clock(OffsetClock::wrapping(clock, Duration::days(1)));
+            assert_eq!(sdk.today(), today + Duration::days(1));
+            let overdue = sdk.list_tasks(&TaskFilter::overdue(sdk.today())).unwrap();
+            assert_eq!(overdue.len(), 1);
+        }
+
+        #[test]
+        fn parse_date_is_relative_to_the_clock() {
+            let (_temp, sdk) = setup_test_env();
+            // Noon local time on Thursday 2025-01-16
+            let noon = "2025-01-16T12:00:00"
+                .parse::<chrono::NaiveDateTime>()
+                .unwrap()
+                .and_local_timezone(chrono::Local)
+                .unwrap();
+            let sdk = sdk.with_clock(FixedClock::new(noon.to_utc()));
+
+            let due = sdk.parse_date("next friday").unwrap();
+            assert_eq!(due.to_string(), "2025-01-24");
+            let path = sdk
+                .create_task(NewTask::new("Plan").with_due(due.clone()))
+                .unwrap();
+
+            let scheduled = sdk.parse_date("+3d").unwrap().date();
+            sdk.update_task(&path, TaskUpdates::new().scheduled(scheduled))
+                .unwrap();
+
+            let task = sdk.get_task(&path).unwrap();
+            assert_eq!(task.due, Some(due));
+            assert_eq!(
+                task.scheduled.map(|d| d.to_string()),
+                Some("2025-01-19".to_string())
+            );
+
+            let filter = TaskFilter::new().due_on(sdk.parse_date("next friday").unwrap().date());
+            assert_eq!(sdk.list_tasks(&filter).unwrap().len(), 1);
+
+            assert!(matches!(
+                sdk.parse_date("12/1"),
+                Err(Error::InvalidDate { .. })
+            ));
+        }
+    }
+
+    mod recurring {
+        use super::*;
+        use crate::{Recurrence, TaskFilter};
+        use chrono::NaiveDate;
+
+        fn date(s: &str) -> NaiveDate {
+            s.parse().unwrap()
+        }
+
+        #[test]
+        fn completing_creates_the_next_instance() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk
+                .create_task(
+                    NewTask::new("Water plants")
+                        .with_status(TaskStatus::InProgress)
+                        .with_due(date("2025-01-16"))
+                        .with_scheduled(date("2025-01-15"))
+                        .in_area("[[Home]]")
+                        .with_recurrence(Recurrence::weekly())
+                        .with_body("Both balconies."),
+                )
+                .unwrap();
+
+            let next_path = sdk.complete_task(&path).unwrap().unwrap();
+            assert_ne!(next_path, path);
+
+            let done = sdk.get_task(&path).unwrap();
+            assert_eq!(done.status, TaskStatus::Done);
+            assert!(done.completed_at.is_some());
+            assert_eq!(done.series.as_deref(), Some("water-plants"));
+
+            let next = sdk.get_task(&next_path).unwrap();
+            assert_eq!(next.title, "Water plants");
+            assert_eq!(next.status, TaskStatus::Ready);
+            assert_eq!(next.completed_at, None);
+            assert_eq!(next.due, Some(DateTimeValue::from_date(date("2025-01-23"))));
+            assert_eq!(next.scheduled, Some(date("2025-01-22")));
+            assert_eq!(next.area, done.area);
+            assert_eq!(next.recurrence, Some(Recurrence::weekly()));
+            assert_eq!(next.series, done.series);
+            assert_eq!(next.body.trim(), "Both balconies.");
+        }
+
+        #[test]
+        fn undo_reverts_both_instances() {
+            let (temp, sdk) = setup_test_env();
+            let config = sdk
+                .config()
+                .clone()
+                .with_journal_path(temp.path().join("journal.yaml"));
+            let sdk = Taskdn::new(config).unwrap();
+            let path = sdk
+                .create_task(NewTask::new("Review").with_recurrence(Recurrence::daily()))
+                .unwrap();
+
+            let next_path = sdk.complete_task(&path).unwrap().unwrap();
+            let entry = sdk.undo().unwrap().unwrap();
+            assert_eq!(entry.operation, "complete_task");
+            assert!(!next_path.exists());
+            let task = sdk.get_task(&path).unwrap();
+            assert_eq!(task.status, TaskStatus::Inbox);
+            assert_eq!(task.series, None);
+        }
+
+        #[test]
+        fn series_links_every_instance() {
+            let (_temp, sdk) = setup_test_env();
+            let first = sdk
+                .create_task(
+                    NewTask::new("Standup")
+                        .with_due(date("2025-01-16"))
+                        .with_recurrence(Recurrence::daily()),
+                )
+                .unwrap();
+
+            let second = sdk.complete_task(&first).unwrap().unwrap();
+            let third = sdk.complete_task(&second).unwrap().unwrap();
+
+            let tasks = sdk.list_tasks(&TaskFilter::new()).unwrap();
+            assert_eq!(tasks.len(), 3);
+            assert!(tasks.iter().all(|t| t.series.as_deref() == Some("standup")));
+            assert_eq!(
+                sdk.get_task(&third).unwrap().due,
+                Some(DateTimeValue::from_date(date("2025-01-18")))
+            );
+        }
+
+        #[test]
+        fn series_is_written_when_preserving_formatting() {
+            let (_temp, sdk) = setup_test_env();
+            let config = sdk.config().clone().with_preserve_formatting(true);
+            let sdk = Taskdn::new(config).unwrap();
+            let content = sample_task_content("Chore", "ready")
+                .replace("---\n\nTask", "recurrence: FREQ=DAILY\n---\n\nTask");
+            create_task_file(&sdk.config.tasks_dir, "chore.md", &content);
+
+            let next_path = sdk.complete_task("chore.md").unwrap().unwrap();
+
+            let done = fs::read_to_string(sdk.config.tasks_dir.join("chore.md")).unwrap();
+            assert!(done.contains("\nseries: chore\n"), "{done}");
+            assert!(done.contains("\nstatus: done\n"));
+            let next = sdk.get_task(&next_path).unwrap();
+            assert_eq!(next.series.as_deref(), Some("chore"));
+        }
+
+        #[test]
+        fn completed_and_non_recurring_tasks_create_nothing() {
+            let (_temp, sdk) = setup_test_env();
+            let plain = sdk.create_task(NewTask::new("Once")).unwrap();
+            assert_eq!(sdk.complete_task(&plain).unwrap(), None);
+
+            let recurring = sdk
+                .create_task(NewTask::new("Repeat").with_recurrence(Recurrence::daily()))
+                .unwrap();
+            sdk.complete_task(&recurring).unwrap().unwrap();
+            assert_eq!(sdk.complete_task(&recurring).unwrap(), None);
+
+            assert_eq!(sdk.list_tasks(&TaskFilter::new()).unwrap().len(), 3);
+        }
+
+        #[test]
+        fn recurrence_can_be_updated_and_cleared() {
+            let (_temp, sdk) = setup_test_env();
+            let path = sdk.create_task(NewTask::new("Report")).unwrap();
+            let rule = Recurrence::monthly().every(3).from_completion();
+
+            sdk.update_task(&path, TaskUpdates::new().recurrence(rule.clone()))
+                .unwrap();
+            let content = fs::read_to_string(&path).unwrap();
+            assert!(content.contains("recurrence: FREQ=MONTHLY;INTERVAL=3;FROM=COMPLETION"));
+            assert_eq!(sdk.get_task(&path).unwrap().recurrence, Some(rule));
+
+            sdk.update_task(&path, TaskUpdates::new().clear_recurrence())
+                .unwrap();
+            assert_eq!(sdk.get_task(&path).unwrap().recurrence, None);
+        }
+
+        #[test]
+        fn invalid_recurrence_is_kept_with_a_warning() {
+            let (temp, sdk) = setup_test_env();
+            let content = sample_task_content("Bad", "ready")
+                .replace("---\n\nTask", "recurrence: FREQ=YEARLY\n---\n\nTask");
+            create_task_file(&temp.path().join("tasks"), "bad.md", &content);
+
+            let task = sdk.get_task("bad.md").unwrap();
+            assert_eq!(task.recurrence, None);
+            assert_eq!(sdk.list_tasks(&TaskFilter::new()).unwrap().len(), 1);
+            assert!(sdk.get_task_warnings("bad.md").unwrap().contains(
+                &crate::ValidationWarning::InvalidFieldValue {
+                    field: "recurrence"
+                }
+            ));
+
+            sdk.update_task("bad.md", TaskUpdates::new().clear_recurrence())
+                .unwrap();
+            let content = fs::read_to_string(temp.path().join("tasks/bad.md")).unwrap();
+            assert!(!content.contains("recurrence"));
+        }
+    }
+
+    mod priority {
+        use super::*;
+        use crate::{Priority, TaskSort};
+
+        #[test]
+        fn lists_by_priority() {
+            let (_temp, sdk) = setup_test_env();
+            sdk.create_task(NewTask::new("Low").with_priority(Priority::Low))
+                .unwrap();
+            sdk.create_task(NewTask::new("None")).unwrap();
+            let high = sdk
+                .create_task(NewTask::new("High").with_priority(Priority::High))
+                .unwrap();
+            let medium = sdk
+                .create_task(NewTask::new("Medium").with_priority(1))
+                .unwrap();
+            sdk.update_task(&medium, TaskUpdates::new().priority(Priority::Medium))
+                .unwrap();
+
+            let filter = TaskFilter::new().sorted_by(TaskSort::Priority);
+            let titles: Vec<_> = sdk
+                .list_tasks(&filter)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.title)
+                .collect();
+            assert_eq!(titles, ["High", "Medium", "Low", "None"]);
+
+            let filter = filter.priority_at_least(Priority::Medium);
+            assert_eq!(sdk.list_tasks(&filter).unwrap().len(), 2);
+
+            sdk.update_task(&high, TaskUpdates::new().clear_priority())
+                .unwrap();
+            assert_eq!(sdk.get_task(&high).unwrap().priority, None);
+        }
+
+        #[test]
+        fn unknown_priority_survives_updates() {
+            let (temp, sdk) = setup_test_env();
+            let content = sample_task_content("Odd", "ready")
+                .replace("---\n\nTask", "priority: P0 (urgent)\n---\n\nTask");
+            create_task_file(&temp.path().join("tasks"), "odd.md", &content);
+
+            sdk.start_task("odd.md").unwrap();
+            let written = fs::read_to_string(temp.path().join("tasks/odd.md")).unwrap();
+            assert!(written.contains("priority: P0 (urgent)\n"));
+        }
+    }
 }
@@ -92,6 +92,9 @@ impl Taskdn {
     /// # Arguments
     /// * `path` - Path to the task file
     ///
+    /// With [`TaskdnConfig::subtask_rollup`](crate::TaskdnConfig::subtask_rollup)
+    /// enabled, this also checks the task against its subtasks.
+    ///
     /// # Returns
     /// A vector of validation warnings for the task.
     ///
@@ -99,7 +102,11 @@ impl Taskdn {
     /// Returns an error if the task cannot be read.
     pub fn get_task_warnings(&self, path: impl AsRef<Path>) -> Result<Vec<ValidationWarning>> {
         let task = self.get_task(path)?;
-        Ok(task.validate())
+        let mut warnings = task.validate();
+        if self.config.subtask_rollup {
+            warnings.extend(self.subtask_warnings(&task)?);
+        }
+        Ok(warnings)
     }
 }
 
@@ -5,8 +5,8 @@
 
 use crate::error::Error;
 use crate::types::{
-    AreaStatus, DateTimeValue, FileReference, ParsedArea, ParsedProject, ParsedTask, ProjectStatus,
-    TaskStatus,
+    AreaStatus, DateTimeValue, FileReference, ParsedArea, ParsedProject, ParsedTask, Priority,
+    ProjectStatus, Recurrence, Tag, TaskStatus,
 };
 use chrono::NaiveDate;
 use gray_matter::{engine::YAML, Matter};
@@ -35,6 +35,18 @@ struct RawTaskFrontmatter {
     projects: Option<Vec<String>>,
     #[serde(default)]
     area: Option<String>,
+    #[serde(default)]
+    parent: Option<serde_yaml::Value>,
+    #[serde(default)]
+    blocked_by: Option<serde_yaml::Value>,
+    #[serde(default)]
+    priority: Option<serde_yaml::Value>,
+    #[serde(default)]
+    recurrence: Option<serde_yaml::Value>,
+    #[serde(default)]
+    series: Option<serde_yaml::Value>,
+    #[serde(default)]
+    tags: Option<serde_yaml::Value>,
     #[serde(flatten)]
     extra: HashMap<String, serde_yaml::Value>,
 }
@@ -57,7 +69,9 @@ struct RawProjectFrontmatter {
     #[serde(default)]
     end_date: Option<String>,
     #[serde(default)]
-    blocked_by: Option<Vec<String>>,
+    blocked_by: Option<serde_yaml::Value>,
+    #[serde(default)]
+    tags: Option<serde_yaml::Value>,
     #[serde(flatten)]
     extra: HashMap<String, serde_yaml::Value>,
 }
@@ -73,6 +87,10 @@ struct RawAreaFrontmatter {
     area_type: Option<String>,
     #[serde(default)]
     description: Option<String>,
+    #[serde(default)]
+    parent: Option<serde_yaml::Value>,
+    #[serde(default)]
+    tags: Option<serde_yaml::Value>,
     #[serde(flatten)]
     extra: HashMap<String, serde_yaml::Value>,
 }
@@ -88,6 +106,7 @@ impl ParsedTask {
     /// Returns `Error::ContentParse` if the content cannot be parsed.
     /// Returns `Error::ContentMissingField` if required fields are absent.
     /// Returns `Error::ContentInvalidField` if a field has an invalid value.
+    #[allow(clippy::too_many_lines)]
     pub fn parse(content: &str) -> Result<Self, Error> {
         let matter = Matter::<YAML>::new();
         let parsed =
@@ -177,6 +196,22 @@ impl ParsedTask {
         };
 
         let area = raw.area.as_deref().map(FileReference::parse);
+        let mut extra = raw.extra;
+        let blocked_by = parse_or_keep("blocked-by", raw.blocked_by, &mut extra, parse_references)
+            .unwrap_or_default();
+        let priority = raw
+            .priority
+            .and_then(|value| parse_priority(value, &mut extra));
+        let tags = parse_tags(raw.tags, &mut extra);
+        let parent = parse_or_keep("parent", raw.parent, &mut extra, |value| {
+            value.as_str().map(FileReference::parse)
+        });
+        let recurrence = parse_or_keep("recurrence", raw.recurrence, &mut extra, |value| {
+            value.as_str()?.parse::<Recurrence>().ok()
+        });
+        let series = parse_or_keep("series", raw.series, &mut extra, |value| {
+            value.as_str().map(str::to_string)
+        });
 
         Ok(Self {
             title: raw.title,
@@ -189,13 +224,79 @@ impl ParsedTask {
             defer_until,
             project,
             area,
+            parent,
+            blocked_by,
+            priority,
+            recurrence,
+            series,
+            tags,
             body,
-            extra: raw.extra,
+            extra,
             projects_count,
         })
     }
 }
 
+/// Parse the `priority` field, keeping values that aren't a priority (lists,
+/// maps) in `extra` so they survive a rewrite.
+fn parse_priority(
+    value: serde_yaml::Value,
+    extra: &mut HashMap<String, serde_yaml::Value>,
+) -> Option<Priority> {
+    let priority = Priority::from_yaml(&value);
+    if priority.is_none() && !value.is_null() {
+        extra.insert("priority".to_string(), value);
+    }
+    priority
+}
+
+/// Parse a field with `parse`, keeping values it rejects in `extra` under
+/// `key` so they survive a rewrite (see [`ValidationWarning::InvalidFieldValue`]).
+///
+/// [`ValidationWarning::InvalidFieldValue`]: crate::ValidationWarning::InvalidFieldValue
+fn parse_or_keep<T>(
+    key: &str,
+    value: Option<serde_yaml::Value>,
+    extra: &mut HashMap<String, serde_yaml::Value>,
+    parse: impl FnOnce(&serde_yaml::Value) -> Option<T>,
+) -> Option<T> {
+    let value = value.filter(|value| !value.is_null())?;
+    let parsed = parse(&value);
+    if parsed.is_none() {
+        extra.insert(key.to_string(), value);
+    }
+    parsed
+}
+
+/// Parse a reference list written either as one reference or as a list of
+/// references. Returns `None` for any other shape.
+fn parse_references(value: &serde_yaml::Value) -> Option<Vec<FileReference>> {
+    match value {
+        serde_yaml::Value::String(s) => Some(vec![FileReference::parse(s)]),
+        serde_yaml::Value::Sequence(items) => items
+            .iter()
+            .map(|item| item.as_str().map(FileReference::parse))
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Parse the `tags` field, keeping values that aren't tags (maps) in `extra`
+/// so they survive a rewrite.
+fn parse_tags(
+    value: Option<serde_yaml::Value>,
+    extra: &mut HashMap<String, serde_yaml::Value>,
+) -> Vec<Tag> {
+    match value {
+        Some(value @ serde_yaml::Value::Mapping(_)) => {
+            extra.insert("tags".to_string(), value);
+            Vec::new()
+        }
+        Some(value) => Tag::from_yaml(&value),
+        None => Vec::new(),
+    }
+}
+
 impl ParsedProject {
     /// Parse project content from a string.
     ///
@@ -250,12 +351,10 @@ impl ParsedProject {
                 message: "invalid date format".to_string(),
             })?;
 
-        let blocked_by = raw
-            .blocked_by
-            .unwrap_or_default()
-            .iter()
-            .map(|s| FileReference::parse(s))
-            .collect();
+        let mut extra = raw.extra;
+        let blocked_by = parse_or_keep("blocked-by", raw.blocked_by, &mut extra, parse_references)
+            .unwrap_or_default();
+        let tags = parse_tags(raw.tags, &mut extra);
 
         Ok(Self {
             title: raw.title,
@@ -266,8 +365,9 @@ impl ParsedProject {
             start_date,
             end_date,
             blocked_by,
+            tags,
             body,
-            extra: raw.extra,
+            extra,
         })
     }
 }
@@ -305,13 +405,21 @@ impl ParsedArea {
                 message: e,
             })?;
 
+        let mut extra = raw.extra;
+        let tags = parse_tags(raw.tags, &mut extra);
+        let parent = parse_or_keep("parent", raw.parent, &mut extra, |value| {
+            value.as_str().map(FileReference::parse)
+        });
+
         Ok(Self {
             title: raw.title,
             status,
             area_type: raw.area_type,
             description: raw.description,
+            parent,
+            tags,
             body,
-            extra: raw.extra,
+            extra,
         })
     }
 }
@@ -322,6 +430,7 @@ mod tests {
 
     mod parsed_task {
         use super::*;
+        use crate::ValidationWarning;
 
         #[test]
         fn parse_minimal_task() {
@@ -385,7 +494,143 @@ priority: high
             let task = ParsedTask::parse(content).unwrap();
             assert_eq!(task.title, "Custom Task");
             assert!(task.extra.contains_key("custom-field"));
-            assert!(task.extra.contains_key("priority"));
+            assert_eq!(task.priority, Some(Priority::High));
+            assert!(!task.extra.contains_key("priority"));
+        }
+
+        #[test]
+        fn parse_task_parent() {
+            let content = "---\ntitle: Step\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\nparent: \"[[Epic]]\"\n---\n";
+            let task = ParsedTask::parse(content).unwrap();
+            assert_eq!(task.parent, Some(FileReference::wiki_link("Epic")));
+            assert!(!task.extra.contains_key("parent"));
+
+            let content = "---\ntitle: Step\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\nparent:\n  - \"[[Epic]]\"\n---\n";
+            let task = ParsedTask::parse(content).unwrap();
+            assert_eq!(task.parent, None);
+            assert!(task.extra.contains_key("parent"));
+            assert_eq!(
+                task.validate(),
+                [ValidationWarning::InvalidFieldValue { field: "parent" }]
+            );
+        }
+
+        #[test]
+        fn parse_task_blocked_by() {
+            let content = "---\ntitle: Build\nstatus: blocked\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\nblocked-by:\n  - \"[[Design]]\"\n  - spec.md\n---\n";
+            let task = ParsedTask::parse(content).unwrap();
+            assert_eq!(
+                task.blocked_by,
+                vec![
+                    FileReference::wiki_link("Design"),
+                    FileReference::Filename("spec.md".to_string())
+                ]
+            );
+            assert!(!task.extra.contains_key("blocked-by"));
+        }
+
+        #[test]
+        fn parse_task_blocked_by_scalar() {
+            let content = "---\ntitle: Build\nstatus: blocked\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\nblocked-by: \"[[Design]]\"\n---\n";
+            let task = ParsedTask::parse(content).unwrap();
+            assert_eq!(task.blocked_by, vec![FileReference::wiki_link("Design")]);
+            assert!(task.validate().is_empty());
+        }
+
+        #[test]
+        fn parse_task_blocked_by_leniently() {
+            for value in ["{ task: design }", "[\"[[Design]]\", 3]", "42"] {
+                let content = format!(
+                    "---\ntitle: Build\nstatus: blocked\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\nblocked-by: {value}\n---\n"
+                );
+                let task = ParsedTask::parse(&content).unwrap();
+                assert!(task.blocked_by.is_empty(), "{value}");
+                assert!(task.extra.contains_key("blocked-by"), "{value}");
+                assert_eq!(
+                    task.validate(),
+                    [ValidationWarning::InvalidFieldValue {
+                        field: "blocked-by"
+                    }]
+                );
+            }
+        }
+
+        #[test]
+        fn parse_task_priority_leniently() {
+            let parse = |value: &str| {
+                let content = format!(
+                    "---\ntitle: T\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\npriority: {value}\n---\n"
+                );
+                ParsedTask::parse(&content).unwrap()
+            };
+
+            assert_eq!(parse("Medium").priority, Some(Priority::Medium));
+            assert_eq!(parse("2").priority, Some(Priority::Numeric(2)));
+            assert_eq!(
+                parse("urgent!").priority,
+                Some(Priority::Other("urgent!".into()))
+            );
+            assert_eq!(parse("null").priority, None);
+
+            let list = parse("[a, b]");
+            assert_eq!(list.priority, None);
+            assert!(list.extra.contains_key("priority"));
+        }
+
+        #[test]
+        fn parse_task_recurrence_leniently() {
+            let parse = |fields: &str| {
+                let content = format!(
+                    "---\ntitle: T\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n{fields}\n---\n"
+                );
+                ParsedTask::parse(&content).unwrap()
+            };
+
+            let valid = parse("recurrence: FREQ=DAILY\nseries: water");
+            assert!(valid.recurrence.is_some());
+            assert_eq!(valid.series.as_deref(), Some("water"));
+            assert!(valid.validate().is_empty());
+
+            let invalid = parse("recurrence: every other day\nseries: [a, b]");
+            assert_eq!(invalid.recurrence, None);
+            assert_eq!(invalid.series, None);
+            assert_eq!(
+                invalid.extra.get("recurrence"),
+                Some(&serde_yaml::Value::from("every other day"))
+            );
+            assert!(invalid.extra.contains_key("series"));
+            assert_eq!(
+                invalid.validate(),
+                [
+                    ValidationWarning::InvalidFieldValue {
+                        field: "recurrence"
+                    },
+                    ValidationWarning::InvalidFieldValue { field: "series" },
+                ]
+            );
+        }
+
+        #[test]
+        fn parse_task_tags() {
+            let parse = |value: &str| {
+                let content = format!(
+                    "---\ntitle: T\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\ntags: {value}\n---\nCall #client about it.\n"
+                );
+                ParsedTask::parse(&content).unwrap()
+            };
+            let names = |task: &ParsedTask| -> Vec<String> {
+                task.tags.iter().map(ToString::to_string).collect()
+            };
+
+            let task = parse("[work/acme, '#home']");
+            assert_eq!(names(&task), ["work/acme", "home"]);
+            assert!(!task.extra.contains_key("tags"));
+            assert_eq!(names(&parse("work, errands")), ["work", "errands"]);
+            assert!(parse("null").tags.is_empty());
+
+            let map = parse("{a: b}");
+            assert!(map.tags.is_empty());
+            assert!(map.extra.contains_key("tags"));
         }
 
         #[test]
@@ -508,6 +753,20 @@ updated-at: "2025-01-01 09:00"
             assert!(!task.updated_at.is_date_only());
         }
 
+        #[test]
+        fn parse_task_datetime_with_offset() {
+            let content = r"---
+title: Offset Task
+status: ready
+created-at: 2025-01-01T09:00:00Z
+updated-at: 2025-01-01T09:00:00+01:00
+---
+";
+            let task = ParsedTask::parse(content).unwrap();
+            assert_eq!(task.created_at.to_string(), "2025-01-01T09:00:00Z");
+            assert_eq!(task.updated_at.to_string(), "2025-01-01T09:00:00+01:00");
+        }
+
         #[test]
         fn parse_task_no_frontmatter_fails() {
             let content = "# Just markdown content\n\nNo frontmatter here.";
@@ -637,6 +896,15 @@ custom-field: value
             assert!(project.extra.contains_key("custom-field"));
         }
 
+        #[test]
+        fn parse_project_tags() {
+            let content = "---\ntitle: P\ntags:\n  - work\n  - work/client\n---\n";
+            let project = ParsedProject::parse(content).unwrap();
+            assert_eq!(project.tags.len(), 2);
+            assert_eq!(project.tags[1].as_str(), "work/client");
+            assert!(!project.extra.contains_key("tags"));
+        }
+
         #[test]
         fn parse_project_no_frontmatter_fails() {
             let content = "# Just a header";
@@ -702,6 +970,22 @@ custom-field: value
             assert!(area.extra.contains_key("custom-field"));
         }
 
+        #[test]
+        fn parse_area_tags() {
+            let content = "---\ntitle: Home\ntags: home\n---\n";
+            let area = ParsedArea::parse(content).unwrap();
+            assert_eq!(area.tags.len(), 1);
+            assert_eq!(area.tags[0].as_str(), "home");
+        }
+
+        #[test]
+        fn parse_area_parent() {
+            let content = "---\ntitle: Acme\nparent: \"[[Work]]\"\n---\n";
+            let area = ParsedArea::parse(content).unwrap();
+            assert_eq!(area.parent, Some(FileReference::wiki_link("Work")));
+            assert!(!area.extra.contains_key("parent"));
+        }
+
         #[test]
         fn parse_area_no_frontmatter_fails() {
             let content = "# Just markdown";
@@ -0,0 +1,447 @@
+//! Dependencies between projects.
+//!
+//! A project's `blocked-by` field lists the projects that must be done before
+//! it can proceed. [`Taskdn::project_graph`] resolves every reference with the
+//! rules of [`Taskdn::resolve_project_reference`] into a [`ProjectGraph`],
+//! which orders projects by their dependencies, finds the ones that are
+//! unblocked, and reports references that match no project or form a cycle.
+
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::filter::ProjectFilter;
+use crate::resolve::reference_path;
+use crate::types::{FileReference, Project, ProjectStatus};
+use crate::validation::ValidationWarning;
+use crate::Taskdn;
+
+/// The `blocked-by` links between the projects in a vault.
+///
+/// Paths passed to lookup methods may be absolute or relative to the
+/// projects directory. Results are returned in path order.
+#[derive(Debug, Clone)]
+pub struct ProjectGraph {
+    projects_dir: PathBuf,
+    /// All projects, sorted by path.
+    projects: Vec<Project>,
+    /// Indexes of each project's resolved blockers.
+    blockers: Vec<Vec<usize>>,
+    /// Each project's `blocked-by` entries that match no project.
+    unresolved: Vec<Vec<FileReference>>,
+}
+
+impl ProjectGraph {
+    pub(crate) fn new(mut projects: Vec<Project>, projects_dir: &Path) -> Self {
+        projects.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut blockers = Vec::with_capacity(projects.len());
+        let mut unresolved = Vec::with_capacity(projects.len());
+        for project in &projects {
+            let mut resolved = Vec::new();
+            let mut missing = Vec::new();
+            for reference in &project.blocked_by {
+                match position(&projects, &reference_path(reference, projects_dir)) {
+                    Some(index) if !resolved.contains(&index) => resolved.push(index),
+                    Some(_) => {}
+                    None => missing.push(reference.clone()),
+                }
+            }
+            resolved.sort_unstable();
+            blockers.push(resolved);
+            unresolved.push(missing);
+        }
+
+        Self {
+            projects_dir: projects_dir.to_path_buf(),
+            projects,
+            blockers,
+            unresolved,
+        }
+    }
+
+    /// All projects in the graph.
+    #[must_use]
+    pub fn projects(&self) -> &[Project] {
+        &self.projects
+    }
+
+    /// Get a project by path.
+    #[must_use]
+    pub fn get(&self, path: impl AsRef<Path>) -> Option<&Project> {
+        self.index_of(path.as_ref())
+            .map(|index| &self.projects[index])
+    }
+
+    /// The projects this one is blocked by.
+    ///
+    /// `blocked-by` entries that match no project are left out; see
+    /// [`ProjectGraph::warnings`].
+    #[must_use]
+    pub fn blockers(&self, path: impl AsRef<Path>) -> Vec<&Project> {
+        self.index_of(path.as_ref())
+            .map(|index| {
+                self.blockers[index]
+                    .iter()
+                    .map(|&blocker| &self.projects[blocker])
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// The projects that list this one in their `blocked-by`.
+    #[must_use]
+    pub fn dependents(&self, path: impl AsRef<Path>) -> Vec<&Project> {
+        let Some(index) = self.index_of(path.as_ref()) else {
+            return Vec::new();
+        };
+        self.blockers
+            .iter()
+            .enumerate()
+            .filter(|(_, blockers)| blockers.contains(&index))
+            .map(|(dependent, _)| &self.projects[dependent])
+            .collect()
+    }
+
+    /// Returns true if every project in this one's `blocked-by` is done.
+    ///
+    /// Entries that match no project count as unfinished. Returns `false`
+    /// if the project is not in the graph.
+    #[must_use]
+    pub fn is_unblocked(&self, path: impl AsRef<Path>) -> bool {
+        self.index_of(path.as_ref())
+            .is_some_and(|index| self.blockers_done(index))
+    }
+
+    /// Projects that are not done and whose blockers are all done.
+    #[must_use]
+    pub fn unblocked(&self) -> Vec<&Project> {
+        (0..self.projects.len())
+            .filter(|&index| !is_done(&self.projects[index]) && self.blockers_done(index))
+            .map(|index| &self.projects[index])
+            .collect()
+    }
+
+    /// All projects ordered so that every project comes after its blockers.
+    ///
+    /// Projects with no ordering between them stay in path order.
+    ///
+    /// # Errors
+    /// Returns `Error::DependencyCycle` listing the projects on a cycle if
+    /// there is no such order.
+    pub fn topological_order(&self) -> Result<Vec<&Project>> {
+        let mut waiting: Vec<usize> = self.blockers.iter().map(Vec::len).collect();
+        let mut ready: BTreeSet<usize> = (0..self.projects.len())
+            .filter(|&index| waiting[index] == 0)
+            .collect();
+
+        let mut order = Vec::with_capacity(self.projects.len());
+        while let Some(index) = ready.pop_first() {
+            order.push(&self.projects[index]);
+            for (dependent, blockers) in self.blockers.iter().enumerate() {
+                if blockers.contains(&index) {
+                    waiting[dependent] -= 1;
+                    if waiting[dependent] == 0 {
+                        ready.insert(dependent);
+                    }
+                }
+            }
+        }
+
+        if order.len() == self.projects.len() {
+            Ok(order)
+        } else {
+            Err(Error::DependencyCycle {
+                paths: (0..self.projects.len())
+                    .filter(|&index| self.in_cycle(index))
+                    .map(|index| self.projects[index].path.clone())
+                    .collect(),
+            })
+        }
+    }
+
+    /// Warnings about every project's `blocked-by`, in path order.
+    ///
+    /// Reports [`ValidationWarning::UnresolvedBlocker`] for each entry that
+    /// matches no project, and [`ValidationWarning::BlockerCycle`] for each
+    /// project that is (indirectly) blocked by itself.
+    #[must_use]
+    pub fn warnings(&self) -> Vec<(PathBuf, ValidationWarning)> {
+        (0..self.projects.len())
+            .flat_map(|index| {
+                let path = &self.projects[index].path;
+                self.warnings_at(index)
+                    .into_iter()
+                    .map(move |warning| (path.clone(), warning))
+            })
+            .collect()
+    }
+
+    /// Warnings about one project's `blocked-by`.
+    #[must_use]
+    pub fn warnings_for(&self, path: impl AsRef<Path>) -> Vec<ValidationWarning> {
+        self.index_of(path.as_ref())
+            .map(|index| self.warnings_at(index))
+            .unwrap_or_default()
+    }
+
+    fn warnings_at(&self, index: usize) -> Vec<ValidationWarning> {
+        let mut warnings: Vec<ValidationWarning> = self.unresolved[index]
+            .iter()
+            .map(|reference| ValidationWarning::UnresolvedBlocker {
+                reference: reference.to_string(),
+            })
+            .collect();
+        if self.in_cycle(index) {
+            warnings.push(ValidationWarning::BlockerCycle);
+        }
+        warnings
+    }
+
+    fn index_of(&self, path: &Path) -> Option<usize> {
+        if path.is_absolute() {
+            position(&self.projects, path)
+        } else {
+            position(&self.projects, &self.projects_dir.join(path))
+        }
+    }
+
+    fn blockers_done(&self, index: usize) -> bool {
+        self.unresolved[index].is_empty()
+            && self.blockers[index]
+                .iter()
+                .all(|&blocker| is_done(&self.projects[blocker]))
+    }
+
+    /// Returns true if following blockers from `index` leads back to it.
+    fn in_cycle(&self, index: usize) -> bool {
+        let mut seen = HashSet::new();
+        let mut pending = self.blockers[index].clone();
+        while let Some(next) = pending.pop() {
+            if next == index {
+                return true;
+            }
+            if seen.insert(next) {
+                pending.extend(&self.blockers[next]);
+            }
+        }
+        false
+    }
+}
+
+/// Find the project at `path` in projects sorted by path.
+fn position(projects: &[Project], path: &Path) -> Option<usize> {
+    projects
+        .binary_search_by(|project| project.path.as_path().cmp(path))
+        .ok()
+}
+
+fn is_done(project: &Project) -> bool {
+    project
+        .status
+        .is_some_and(|status| status == ProjectStatus::Done)
+}
+
+/// Returns true if one of `project`'s blockers is not done or matches none
+/// of `projects` (sorted by path).
+pub(crate) fn has_unfinished_blocker(
+    project: &Project,
+    projects: &[&Project],
+    projects_dir: &Path,
+) -> bool {
+    project.blocked_by.iter().any(|reference| {
+        let path = reference_path(reference, projects_dir);
+        projects
+            .binary_search_by(|candidate| candidate.path.as_path().cmp(&path))
+            .map_or(true, |index| !is_done(projects[index]))
+    })
+}
+
+impl Taskdn {
+    /// Build the dependency graph of all projects.
+    ///
+    /// Applies the same `taskdn-type` opt-in rule as [`Taskdn::list_projects`].
+    ///
+    /// # Errors
+    /// Returns an error if the projects directory cannot be read.
+    pub fn project_graph(&self) -> Result<ProjectGraph> {
+        let projects = self.list_projects(&ProjectFilter::new())?;
+        Ok(ProjectGraph::new(projects, &self.config.projects_dir))
+    }
+
+    /// Get validation warnings for a project, including dependency cycles
+    /// through its `blocked-by`.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the project doesn't exist.
+    /// Returns an error if the projects directory cannot be read.
+    pub fn get_project_warnings(&self, path: impl AsRef<Path>) -> Result<Vec<ValidationWarning>> {
+        let project = self.get_project(path)?;
+        let mut warnings = project.validate();
+        warnings.extend(self.project_graph()?.warnings_for(&project.path));
+        Ok(warnings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{setup_test_env, write_file};
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write(temp: &TempDir, name: &str, status: &str, blockers: &[&str]) {
+        let blocked_by = if blockers.is_empty() {
+            String::new()
+        } else {
+            format!("blocked-by: [\"[[{}]]\"]\n", blockers.join("]]\", \"[["))
+        };
+        let content = format!("---\ntitle: {name}\nstatus: {status}\n{blocked_by}---\n");
+        write_file(temp, &format!("projects/{name}.md"), &content);
+    }
+
+    fn titles<'a>(projects: impl IntoIterator<Item = &'a Project>) -> Vec<&'a str> {
+        projects.into_iter().map(|p| p.title.as_str()).collect()
+    }
+
+    /// research -> design -> build, with launch waiting on build and marketing.
+    fn roadmap(temp: &TempDir) {
+        write(temp, "research", "done", &[]);
+        write(temp, "design", "in-progress", &["research"]);
+        write(temp, "build", "planning", &["design"]);
+        write(temp, "marketing", "ready", &[]);
+        write(temp, "launch", "planning", &["build", "marketing"]);
+    }
+
+    #[test]
+    fn blockers_and_dependents() {
+        let (temp, taskdn) = setup_test_env();
+        roadmap(&temp);
+        let graph = taskdn.project_graph().unwrap();
+
+        assert_eq!(titles(graph.blockers("launch.md")), ["build", "marketing"]);
+        assert_eq!(titles(graph.dependents("design.md")), ["build"]);
+        assert!(graph.blockers("research.md").is_empty());
+        assert_eq!(graph.get("launch.md").unwrap().title, "launch");
+    }
+
+    #[test]
+    fn topological_order_puts_blockers_first() {
+        let (temp, taskdn) = setup_test_env();
+        roadmap(&temp);
+        let graph = taskdn.project_graph().unwrap();
+
+        assert_eq!(
+            titles(graph.topological_order().unwrap()),
+            ["marketing", "research", "design", "build", "launch"]
+        );
+    }
+
+    #[test]
+    fn unblocked_projects() {
+        let (temp, taskdn) = setup_test_env();
+        roadmap(&temp);
+        let graph = taskdn.project_graph().unwrap();
+
+        assert!(graph.is_unblocked("design.md"));
+        assert!(!graph.is_unblocked("build.md"));
+        assert_eq!(titles(graph.unblocked()), ["design", "marketing"]);
+    }
+
+    #[test]
+    fn unresolved_blockers_are_reported() {
+        let (temp, taskdn) = setup_test_env();
+        write(&temp, "launch", "planning", &["missing"]);
+        let graph = taskdn.project_graph().unwrap();
+
+        assert!(!graph.is_unblocked("launch.md"));
+        assert_eq!(
+            taskdn.get_project_warnings("launch.md").unwrap(),
+            [ValidationWarning::UnresolvedBlocker {
+                reference: "[[missing]]".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn scalar_and_malformed_blockers() {
+        let (temp, taskdn) = setup_test_env();
+        write(&temp, "design", "done", &[]);
+        let projects = temp.path().join("projects");
+        fs::write(
+            projects.join("build.md"),
+            "---\ntitle: build\nstatus: planning\nblocked-by: \"[[design]]\"\n---\n",
+        )
+        .unwrap();
+        fs::write(
+            projects.join("launch.md"),
+            "---\ntitle: launch\nstatus: planning\nblocked-by:\n  project: build\n---\n",
+        )
+        .unwrap();
+        let graph = taskdn.project_graph().unwrap();
+
+        assert_eq!(titles(graph.blockers("build.md")), ["design"]);
+        assert!(taskdn.get_project_warnings("build.md").unwrap().is_empty());
+
+        assert!(graph.blockers("launch.md").is_empty());
+        let launch = taskdn.get_project("launch.md").unwrap();
+        assert!(launch.extra.contains_key("blocked-by"));
+        assert_eq!(
+            taskdn.get_project_warnings("launch.md").unwrap(),
+            [ValidationWarning::InvalidFieldValue {
+                field: "blocked-by"
+            }]
+        );
+    }
+
+    #[test]
+    fn cycles_are_reported() {
+        let (temp, taskdn) = setup_test_env();
+        write(&temp, "a", "planning", &["b"]);
+        write(&temp, "b", "planning", &["a"]);
+        write(&temp, "c", "planning", &["a"]);
+        write(&temp, "d", "planning", &[]);
+        let graph = taskdn.project_graph().unwrap();
+
+        let warnings = graph.warnings();
+        let cyclic: Vec<&Path> = warnings
+            .iter()
+            .filter(|(_, w)| *w == ValidationWarning::BlockerCycle)
+            .map(|(path, _)| path.as_path())
+            .collect();
+        assert_eq!(cyclic.len(), 2);
+        assert!(graph.warnings_for("c.md").is_empty());
+
+        match graph.topological_order() {
+            Err(Error::DependencyCycle { paths }) => {
+                let names: Vec<_> = paths.iter().map(|p| p.file_name().unwrap()).collect();
+                assert_eq!(names, ["a.md", "b.md"]);
+            }
+            other => panic!("expected a cycle, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn filter_blocked_by_unfinished() {
+        let (temp, taskdn) = setup_test_env();
+        roadmap(&temp);
+
+        let blocked = ProjectFilter::new().blocked_by_unfinished();
+        let projects = taskdn.list_projects(&blocked).unwrap();
+        let mut names = titles(&projects);
+        names.sort_unstable();
+        assert_eq!(names, ["build", "launch"]);
+
+        let free = ProjectFilter::new()
+            .without_unfinished_blockers()
+            .with_status(ProjectStatus::InProgress);
+        assert_eq!(titles(&taskdn.list_projects(&free).unwrap()), ["design"]);
+
+        let index = taskdn.build_index().unwrap();
+        assert_eq!(titles(index.list_projects(&blocked)), ["build", "launch"]);
+        assert_eq!(
+            titles(index.project_graph().topological_order().unwrap()),
+            ["marketing", "research", "design", "build", "launch"]
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Completing recurring tasks.
+//!
+//! A task with a `recurrence` rule repeats: completing it marks it done as
+//! usual and creates the next instance alongside it. The next instance is a
+//! copy of the task with status `ready`, fresh timestamps, its `due`,
+//! `scheduled` and `defer-until` dates moved forward together, and every
+//! checklist item in its body unchecked.
+//!
+//! The dates move by the distance from the task's anchor date (its due date,
+//! else its scheduled date, else its defer-until date) to the next
+//! occurrence. The next occurrence is counted from the anchor date, or from
+//! today if the rule is [`RecurrenceBasis::Completion`].
+//!
+//! Both instances get the same `series` frontmatter field (the first
+//! instance's file stem, unless already set), linking the series together.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveDate};
+
+use crate::error::Result;
+use crate::filename::available_path;
+use crate::types::{
+    Checklist, DateTimeValue, ParsedTask, RecurrenceBasis, Task, TaskStatus, TaskUpdates,
+};
+use crate::Transaction;
+
+impl Task {
+    /// The date the recurrence is measured from: `due`, else `scheduled`,
+    /// else `defer-until`.
+    fn anchor_date(&self) -> Option<NaiveDate> {
+        self.due
+            .as_ref()
+            .map(DateTimeValue::local_date)
+            .or(self.scheduled)
+            .or(self.defer_until)
+    }
+
+    /// The next occurrence of a recurring task, if it were completed `today`.
+    ///
+    /// This is the anchor date (`due`, else `scheduled`, else `defer-until`)
+    /// the next instance would get. Returns `None` if the task doesn't recur.
+    #[must_use]
+    pub fn next_occurrence(&self, today: NaiveDate) -> Option<NaiveDate> {
+        let recurrence = self.recurrence.as_ref()?;
+        let anchor = self.anchor_date().unwrap_or(today);
+        match recurrence.basis {
+            RecurrenceBasis::Due => recurrence.next_after(anchor),
+            RecurrenceBasis::Completion => recurrence.next_after(today),
+        }
+    }
+
+    /// Preview the next `count` occurrences of a recurring task, assuming
+    /// each instance is completed `today` or on its date.
+    ///
+    /// Returns an empty list if the task doesn't recur.
+    #[must_use]
+    pub fn upcoming_occurrences(&self, today: NaiveDate, count: usize) -> Vec<NaiveDate> {
+        let Some(recurrence) = &self.recurrence else {
+            return Vec::new();
+        };
+        let start = match recurrence.basis {
+            RecurrenceBasis::Due => self.anchor_date().unwrap_or(today),
+            RecurrenceBasis::Completion => today,
+        };
+        recurrence.occurrences_after(start).take(count).collect()
+    }
+
+    /// The next instance of this recurring task, completed `today`, with
+    /// `timestamp` as its creation time. The path is left empty.
+    fn next_instance(&self, today: NaiveDate, timestamp: &DateTimeValue) -> Option<Task> {
+        let next = self.next_occurrence(today)?;
+        let delta = next - self.anchor_date().unwrap_or(today);
+
+        Some(Task {
+            path: PathBuf::new(),
+            status: TaskStatus::Ready,
+            created_at: timestamp.clone(),
+            updated_at: timestamp.clone(),
+            completed_at: None,
+            due: match &self.due {
+                Some(due) => Some(shift(due, delta)?),
+                None => None,
+            },
+            scheduled: match self.scheduled {
+                Some(date) => Some(date.checked_add_signed(delta)?),
+                None => None,
+            },
+            defer_until: match self.defer_until {
+                Some(date) => Some(date.checked_add_signed(delta)?),
+                None => None,
+            },
+            body: Checklist::uncheck_all(&self.body),
+            ..self.clone()
+        })
+    }
+}
+
+/// Move a date or datetime by `delta`, keeping its format.
+fn shift(value: &DateTimeValue, delta: Duration) -> Option<DateTimeValue> {
+    Some(match value {
+        DateTimeValue::Date(date) => DateTimeValue::Date(date.checked_add_signed(delta)?),
+        DateTimeValue::DateTime(dt) => DateTimeValue::DateTime(dt.checked_add_signed(delta)?),
+        DateTimeValue::DateTimeWithOffset(dt) => {
+            DateTimeValue::DateTimeWithOffset(dt.checked_add_signed(delta)?)
+        }
+    })
+}
+
+impl Transaction<'_> {
+    /// Stage completing a task, as [`Taskdn::complete_task`](crate::Taskdn::complete_task)
+    /// would apply it.
+    ///
+    /// If the task recurs and wasn't already completed, also stages the
+    /// creation of its next instance (see the [module docs](self)).
+    ///
+    /// # Returns
+    /// The path of the next instance, if one was created.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the task doesn't exist.
+    /// Returns `Error::Parse` if the file cannot be parsed.
+    pub fn complete_task(&mut self, path: impl AsRef<Path>) -> Result<Option<PathBuf>> {
+        let taskdn = self.taskdn();
+        let path = if path.as_ref().is_absolute() {
+            path.as_ref().to_path_buf()
+        } else {
+            taskdn.config.tasks_dir.join(path)
+        };
+        let content = self.read(&path)?;
+        let task = ParsedTask::parse(&content)
+            .map_err(|e| e.with_path(&path))?
+            .with_path(&path);
+
+        let next = if task.status.is_completed() {
+            None
+        } else {
+            task.next_instance(taskdn.today(), &taskdn.timestamp())
+        };
+
+        let mut updates = TaskUpdates::new().status(TaskStatus::Done);
+        let next_path = match next {
+            Some(mut next) => {
+                let series = task.series.clone().unwrap_or_else(|| {
+                    path.file_stem()
+                        .map(|stem| stem.to_string_lossy().into_owned())
+                        .unwrap_or_default()
+                });
+                if task.series.is_none() {
+                    updates = updates.series(series.clone());
+                }
+                next.series = Some(series);
+
+                let strategy = taskdn.config.filename_strategy;
+                let filename = strategy.filename(&next.title, taskdn.now_local());
+                next.path = available_path(&taskdn.config.tasks_dir, &filename, |p| self.exists(p));
+                self.stage_create(&next.path, next.to_string())?;
+                Some(next.path)
+            }
+            None => None,
+        };
+
+        let completes = !task.status.is_completed();
+        let rendered = taskdn.render_task_updates(task, &content, updates);
+        self.stage_write(&path, rendered);
+        if completes && taskdn.config.unblock_dependents {
+            self.stage_unblock_dependents(&path)?;
+        }
+        Ok(next_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Recurrence;
+    use chrono::Weekday;
+    use std::collections::HashMap;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn recurring_task(recurrence: Recurrence) -> Task {
+        Task {
+            path: PathBuf::from("/tasks/review.md"),
+            title: "Review".to_string(),
+            status: TaskStatus::Ready,
+            created_at: "2025-01-01".parse().unwrap(),
+            updated_at: "2025-01-01".parse().unwrap(),
+            completed_at: None,
+            due: Some("2025-01-16T17:00:00".parse().unwrap()),
+            scheduled: Some(date("2025-01-15")),
+            defer_until: None,
+            project: None,
+            area: None,
+            parent: None,
+            blocked_by: Vec::new(),
+            priority: None,
+            recurrence: Some(recurrence),
+            series: None,
+            tags: Vec::new(),
+            body: String::new(),
+            extra: HashMap::new(),
+            projects_count: None,
+        }
+    }
+
+    #[test]
+    fn next_occurrence_counts_from_due_date() {
+        let task = recurring_task(Recurrence::weekly());
+        assert_eq!(
+            task.next_occurrence(date("2025-01-20")),
+            Some(date("2025-01-23"))
+        );
+    }
+
+    #[test]
+    fn next_occurrence_counts_from_completion() {
+        let task = recurring_task(Recurrence::weekly().from_completion());
+        assert_eq!(
+            task.next_occurrence(date("2025-01-20")),
+            Some(date("2025-01-27"))
+        );
+    }
+
+    #[test]
+    fn next_occurrence_falls_back_to_scheduled_then_today() {
+        let mut task = recurring_task(Recurrence::daily());
+        task.due = None;
+        assert_eq!(
+            task.next_occurrence(date("2025-01-20")),
+            Some(date("2025-01-16"))
+        );
+
+        task.scheduled = None;
+        assert_eq!(
+            task.next_occurrence(date("2025-01-20")),
+            Some(date("2025-01-21"))
+        );
+    }
+
+    #[test]
+    fn non_recurring_tasks_have_no_occurrences() {
+        let mut task = recurring_task(Recurrence::daily());
+        task.recurrence = None;
+        assert_eq!(task.next_occurrence(date("2025-01-20")), None);
+        assert!(task.upcoming_occurrences(date("2025-01-20"), 3).is_empty());
+    }
+
+    #[test]
+    fn upcoming_occurrences_preview() {
+        let task = recurring_task(Recurrence::weekly().on([Weekday::Mon, Weekday::Thu]));
+        assert_eq!(
+            task.upcoming_occurrences(date("2025-01-10"), 3),
+            vec![date("2025-01-20"), date("2025-01-23"), date("2025-01-27")]
+        );
+    }
+
+    #[test]
+    fn next_instance_shifts_dates_together() {
+        let task = recurring_task(Recurrence::weekly());
+        let now: DateTimeValue = "2025-01-17T08:00:00".parse().unwrap();
+        let next = task.next_instance(date("2025-01-17"), &now).unwrap();
+
+        assert_eq!(next.status, TaskStatus::Ready);
+        assert_eq!(next.created_at, now);
+        assert_eq!(next.completed_at, None);
+        assert_eq!(next.due, Some("2025-01-23T17:00:00".parse().unwrap()));
+        assert_eq!(next.scheduled, Some(date("2025-01-22")));
+        assert_eq!(next.defer_until, None);
+        assert_eq!(next.recurrence, task.recurrence);
+    }
+
+    #[test]
+    fn next_instance_unchecks_the_checklist() {
+        let mut task = recurring_task(Recurrence::weekly());
+        task.body = "Steps:\n\n- [x] Sweep\n  - [X] Corners\n- [ ] Mop\n".to_string();
+        let now: DateTimeValue = "2025-01-17T08:00:00".parse().unwrap();
+        let next = task.next_instance(date("2025-01-17"), &now).unwrap();
+
+        assert_eq!(
+            next.body,
+            "Steps:\n\n- [ ] Sweep\n  - [ ] Corners\n- [ ] Mop\n"
+        );
+        assert_eq!(task.checklist().checked_count(), 2);
+    }
+}
@@ -0,0 +1,206 @@
+//! Natural-language and relative date input (S2 §6.1).
+//!
+//! [`DateTimeValue::parse_relative`] accepts, case-insensitively:
+//!
+//! - ISO 8601 dates and datetimes, as [`DateTimeValue::from_str`](std::str::FromStr)
+//! - `today`, `tomorrow` and `yesterday`
+//! - day names (`friday`, `fri`): the next occurrence, so `friday` on a Friday
+//!   is a week away
+//! - `next` plus a day name: skips the immediate occurrence, so `next friday`
+//!   on a Thursday is 8 days away
+//! - offsets from today: `+3d`, `+2w`, `+1m` (days, weeks, months)
+//!
+//! Numeric dates other than ISO, such as `12/1`, are rejected as ambiguous.
+
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+
+use crate::error::{Error, Result};
+use crate::types::DateTimeValue;
+use crate::Taskdn;
+
+impl DateTimeValue {
+    /// Parse a date as typed by a person, relative to `today`.
+    ///
+    /// See the [module docs](self) for the accepted forms. Relative forms give
+    /// a date-only value; ISO input keeps its format.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidDate` if the input is ambiguous (`12/1`),
+    /// unrecognised, or out of range.
+    pub fn parse_relative(input: &str, today: NaiveDate) -> Result<Self> {
+        let trimmed = input.trim();
+        if let Ok(value) = trimmed.parse() {
+            return Ok(value);
+        }
+
+        let expr = trimmed.to_lowercase();
+        let invalid = |message: &str| Error::InvalidDate {
+            input: input.to_string(),
+            message: message.to_string(),
+        };
+
+        let date = match expr.as_str() {
+            "today" => Some(today),
+            "tomorrow" => today.checked_add_signed(Duration::days(1)),
+            "yesterday" => today.checked_sub_signed(Duration::days(1)),
+            _ => {
+                if let Some(offset) = expr.strip_prefix('+') {
+                    let unit_start = offset.char_indices().last().map_or(0, |(i, _)| i);
+                    let (amount, unit) = offset.split_at(unit_start);
+                    let amount: u32 = amount
+                        .parse()
+                        .map_err(|_| invalid("expected an offset like +3d, +2w or +1m"))?;
+                    match unit {
+                        "d" => today.checked_add_signed(Duration::days(i64::from(amount))),
+                        "w" => today.checked_add_signed(Duration::weeks(i64::from(amount))),
+                        "m" => today.checked_add_months(Months::new(amount)),
+                        _ => return Err(invalid("expected an offset like +3d, +2w or +1m")),
+                    }
+                } else if let Some((skip, weekday)) = parse_day_name(&expr) {
+                    let ahead = (weekday.num_days_from_monday() + 7
+                        - today.weekday().num_days_from_monday())
+                        % 7;
+                    let ahead = if ahead == 0 { 7 } else { ahead } + if skip { 7 } else { 0 };
+                    today.checked_add_signed(Duration::days(i64::from(ahead)))
+                } else if expr.contains(|c: char| c.is_ascii_digit()) {
+                    return Err(invalid("ambiguous date format, use YYYY-MM-DD"));
+                } else {
+                    return Err(invalid("unrecognised date"));
+                }
+            }
+        };
+
+        date.map(Self::Date)
+            .ok_or_else(|| invalid("date out of range"))
+    }
+}
+
+/// Parse `friday` or `next friday` into whether to skip the immediate
+/// occurrence and the weekday.
+fn parse_day_name(expr: &str) -> Option<(bool, Weekday)> {
+    let (skip, day) = match expr.strip_prefix("next ") {
+        Some(day) => (true, day.trim()),
+        None => (false, expr),
+    };
+    day.parse().ok().map(|weekday| (skip, weekday))
+}
+
+impl Taskdn {
+    /// Parse a date as typed by a person, relative to [`Taskdn::today`].
+    ///
+    /// See [`DateTimeValue::parse_relative`]. The result can be passed to
+    /// [`NewTask::with_due`](crate::NewTask::with_due) and
+    /// [`TaskUpdates::due`](crate::TaskUpdates::due), or its
+    /// [`date`](DateTimeValue::date) to the date-only fields and filters.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidDate` if the input cannot be parsed.
+    pub fn parse_date(&self, input: &str) -> Result<DateTimeValue> {
+        DateTimeValue::parse_relative(input, self.today())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A Thursday.
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, 16).unwrap()
+    }
+
+    fn parse(input: &str) -> NaiveDate {
+        DateTimeValue::parse_relative(input, today())
+            .unwrap()
+            .date()
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 1, day).unwrap()
+    }
+
+    #[test]
+    fn iso_input_is_kept() {
+        let value = DateTimeValue::parse_relative("2025-03-01T09:00:00", today()).unwrap();
+        assert_eq!(value.to_string(), "2025-03-01T09:00:00");
+        assert_eq!(
+            parse("2025-03-01"),
+            NaiveDate::from_ymd_opt(2025, 3, 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn relative_words() {
+        assert_eq!(parse("today"), date(16));
+        assert_eq!(parse("Tomorrow"), date(17));
+        assert_eq!(parse(" yesterday "), date(15));
+        assert!(DateTimeValue::parse_relative("today", today())
+            .unwrap()
+            .is_date_only());
+    }
+
+    #[test]
+    fn day_names_are_the_next_occurrence() {
+        assert_eq!(parse("friday"), date(17));
+        assert_eq!(parse("Fri"), date(17));
+        assert_eq!(parse("monday"), date(20));
+        // Today is Thursday, so "thursday" is a week away
+        assert_eq!(parse("thursday"), date(23));
+    }
+
+    #[test]
+    fn next_skips_the_immediate_occurrence() {
+        assert_eq!(parse("next friday"), date(24));
+        assert_eq!(parse("next thursday"), date(30));
+        assert_eq!(parse("NEXT  monday"), date(27));
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(parse("+3d"), date(19));
+        assert_eq!(parse("+2w"), date(30));
+        assert_eq!(parse("+1m"), NaiveDate::from_ymd_opt(2025, 2, 16).unwrap());
+        assert_eq!(parse("+0d"), date(16));
+    }
+
+    #[test]
+    fn month_offsets_clamp_to_month_end() {
+        let today = NaiveDate::from_ymd_opt(2025, 1, 31).unwrap();
+        let value = DateTimeValue::parse_relative("+1m", today).unwrap();
+        assert_eq!(value.date(), NaiveDate::from_ymd_opt(2025, 2, 28).unwrap());
+    }
+
+    #[test]
+    fn ambiguous_numeric_dates_are_rejected() {
+        for input in ["12/1", "1/12", "01.12.2025", "12-1"] {
+            let result = DateTimeValue::parse_relative(input, today());
+            assert!(
+                matches!(&result, Err(Error::InvalidDate { message, .. }) if message.contains("ambiguous")),
+                "{input}: {result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn unrecognised_input_is_rejected() {
+        for input in [
+            "",
+            "someday",
+            "next",
+            "next week",
+            "+3",
+            "+3y",
+            "+d",
+            "+3é",
+            "-3d",
+        ] {
+            assert!(
+                matches!(
+                    DateTimeValue::parse_relative(input, today()),
+                    Err(Error::InvalidDate { .. })
+                ),
+                "{input}"
+            );
+        }
+    }
+}
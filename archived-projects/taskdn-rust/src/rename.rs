@@ -0,0 +1,342 @@
+//! Renaming entities with vault-wide reference rewriting.
+//!
+//! Moving a file breaks every reference to it. [`Transaction::stage_rename`]
+//! moves the file and rewrites, in every task, project and area:
+//!
+//! - frontmatter references of the matching kind (a task's `project`, `area`,
+//!   `parent` and `blocked-by`, a project's `area` and `blocked-by`, an area's
+//!   `parent`), keeping
+//!   their `WikiLink`, relative-path or filename format
+//! - `[[WikiLink]]` mentions in markdown bodies, keeping any `#heading` and
+//!   `|display` parts
+//!
+//! [`Transaction::stage_retitle`] does the same when a title change should
+//! rename the file, also rewriting references by the old title.
+//!
+//! Rewritten files are patched in place (see `frontmatter::patch`), and their
+//! `updated-at` is left alone: only the spelling of a reference changed.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::filename::available_path;
+use crate::frontmatter;
+use crate::types::{FileReference, ParsedArea, ParsedProject, ParsedTask};
+use crate::writer::{render_area, render_project, render_task, Original};
+use crate::Transaction;
+
+/// The kind of entity being renamed, which decides which references point to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RenameKind {
+    Task,
+    Project,
+    Area,
+}
+
+impl Transaction<'_> {
+    /// Stage moving `from` to `to` and rewriting every reference to it.
+    ///
+    /// `to` is used as given; a missing extension becomes `.md`.
+    ///
+    /// # Returns
+    /// The new path.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if `from` doesn't exist.
+    /// Returns `Error::Parse` if `from` cannot be parsed.
+    /// Returns `Error::Validation` if a file already exists at `to`.
+    pub(crate) fn stage_rename(
+        &mut self,
+        kind: RenameKind,
+        from: &Path,
+        to: &Path,
+    ) -> Result<PathBuf> {
+        let to = if to.extension().is_some() {
+            to.to_path_buf()
+        } else {
+            to.with_extension("md")
+        };
+        let title = self.read_title(kind, from)?;
+        self.stage_relink(kind, from, &to, &title, &title)?;
+        Ok(to)
+    }
+
+    /// Stage renaming `path` to match `new_title`, if
+    /// [`TaskdnConfig::rename_on_retitle`](crate::TaskdnConfig::rename_on_retitle)
+    /// is enabled and the title changes.
+    ///
+    /// The file stays in its directory. References by the old title are
+    /// rewritten to the new one, along with those by file name.
+    ///
+    /// # Returns
+    /// The file's path after the rename, which is `path` if nothing was staged.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if `path` doesn't exist.
+    /// Returns `Error::Parse` if `path` cannot be parsed.
+    pub(crate) fn stage_retitle(
+        &mut self,
+        kind: RenameKind,
+        path: &Path,
+        new_title: &str,
+    ) -> Result<PathBuf> {
+        if !self.taskdn().config.rename_on_retitle {
+            return Ok(path.to_path_buf());
+        }
+        let title = self.read_title(kind, path)?;
+        if title == new_title {
+            return Ok(path.to_path_buf());
+        }
+
+        let strategy = self.taskdn().config.filename_strategy;
+        let Some(filename) = strategy.retitled(path, new_title, self.taskdn().now_local()) else {
+            return Ok(path.to_path_buf());
+        };
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let to = available_path(dir, &filename, |p| p != path && self.exists(p));
+        self.stage_relink(kind, path, &to, &title, new_title)?;
+        Ok(to)
+    }
+
+    /// Read the title of the entity at `path`.
+    fn read_title(&mut self, kind: RenameKind, path: &Path) -> Result<String> {
+        let content = self.read(path)?;
+        match kind {
+            RenameKind::Task => ParsedTask::parse(&content).map(|t| t.title),
+            RenameKind::Project => ParsedProject::parse(&content).map(|p| p.title),
+            RenameKind::Area => ParsedArea::parse(&content).map(|a| a.title),
+        }
+        .map_err(|e| e.with_path(path))
+    }
+
+    /// Stage moving `from` to `to` (if they differ) and rewriting references
+    /// to `from` and `from_title` so they point at `to` and `to_title`.
+    fn stage_relink(
+        &mut self,
+        kind: RenameKind,
+        from: &Path,
+        to: &Path,
+        from_title: &str,
+        to_title: &str,
+    ) -> Result<()> {
+        if to == from && from_title == to_title {
+            return Ok(());
+        }
+        if to != from {
+            self.stage_move(from, to)?;
+        }
+        let link = Link {
+            kind,
+            from,
+            to,
+            from_title,
+            to_title,
+        };
+
+        let taskdn = self.taskdn();
+        let archive_dir = taskdn.config.tasks_dir.join("archive");
+        let dirs = [
+            (RenameKind::Task, &taskdn.config.tasks_dir),
+            (RenameKind::Task, &archive_dir),
+            (RenameKind::Project, &taskdn.config.projects_dir),
+            (RenameKind::Area, &taskdn.config.areas_dir),
+        ];
+
+        for (file_kind, dir) in dirs {
+            let mut paths = Vec::new();
+            if dir.is_dir() {
+                taskdn.collect_md_paths(dir, &mut paths)?;
+            }
+            for path in paths {
+                let path = if path == from { to.to_path_buf() } else { path };
+                self.rewrite_references(file_kind, &path, &link)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stage rewriting the references in one file, if any point at the renamed file.
+    ///
+    /// Files that cannot be parsed are left alone.
+    fn rewrite_references(
+        &mut self,
+        file_kind: RenameKind,
+        path: &Path,
+        link: &Link<'_>,
+    ) -> Result<()> {
+        let content = self.read(path)?;
+
+        let frontmatter = match file_kind {
+            RenameKind::Task => ParsedTask::parse(&content).ok().and_then(|parsed| {
+                let before = parsed.with_path(path);
+                let mut after = before.clone();
+                let changed = match link.kind {
+                    RenameKind::Project => link.rewrite_option(&mut after.project),
+                    RenameKind::Area => link.rewrite_option(&mut after.area),
+                    RenameKind::Task => {
+                        let parent = link.rewrite_option(&mut after.parent);
+                        after
+                            .blocked_by
+                            .iter_mut()
+                            .fold(parent, |changed, r| link.rewrite(r) | changed)
+                    }
+                };
+                changed.then(|| render_task(&after, Some(&Original::task(&content, &before))))
+            }),
+            RenameKind::Project => ParsedProject::parse(&content).ok().and_then(|parsed| {
+                let before = parsed.with_path(path);
+                let mut after = before.clone();
+                let changed = match link.kind {
+                    RenameKind::Project => after
+                        .blocked_by
+                        .iter_mut()
+                        .fold(false, |changed, r| link.rewrite(r) | changed),
+                    RenameKind::Area => link.rewrite_option(&mut after.area),
+                    RenameKind::Task => false,
+                };
+                changed.then(|| render_project(&after, Some(&Original::project(&content, &before))))
+            }),
+            RenameKind::Area => ParsedArea::parse(&content).ok().and_then(|parsed| {
+                let before = parsed.with_path(path);
+                let mut after = before.clone();
+                let changed =
+                    link.kind == RenameKind::Area && link.rewrite_option(&mut after.parent);
+                changed.then(|| render_area(&after, Some(&Original::area(&content, &before))))
+            }),
+        };
+
+        // Body links are rewritten in the raw text so its layout is kept exactly.
+        let rewritten = match link.rewrite_body(frontmatter.as_deref().unwrap_or(&content)) {
+            Some(rewritten) => Some(rewritten),
+            None => frontmatter,
+        };
+        if let Some(rewritten) = rewritten {
+            self.stage_write(path, rewritten);
+        }
+        Ok(())
+    }
+}
+
+/// A rename, as seen by the references that need rewriting.
+struct Link<'a> {
+    kind: RenameKind,
+    from: &'a Path,
+    to: &'a Path,
+    from_title: &'a str,
+    to_title: &'a str,
+}
+
+impl Link<'_> {
+    /// Rewrite `reference` if it points at the renamed file.
+    fn rewrite(&self, reference: &mut FileReference) -> bool {
+        if !reference.points_to(self.from, self.from_title) {
+            return false;
+        }
+        let rewritten = reference.retargeted(self.from, self.to, self.to_title);
+        if rewritten == *reference {
+            return false;
+        }
+        *reference = rewritten;
+        true
+    }
+
+    fn rewrite_option(&self, reference: &mut Option<FileReference>) -> bool {
+        reference.as_mut().is_some_and(|r| self.rewrite(r))
+    }
+
+    /// Rewrite the `[[WikiLink]]` mentions in the body of `content` that point
+    /// at the renamed file.
+    ///
+    /// Returns `None` if nothing changed.
+    fn rewrite_body(&self, content: &str) -> Option<String> {
+        let (head, body) = frontmatter::split_body(content)?;
+        let body = rewrite_wikilinks(body, |target| {
+            let mut reference = FileReference::wiki_link(target);
+            self.rewrite(&mut reference)
+                .then(|| reference.target().to_string())
+        })?;
+        Some(format!("{head}{body}"))
+    }
+}
+
+/// Replace `[[WikiLink]]` targets in `text`.
+///
+/// `replace` gets each link's target (without `#heading` or `|display`) and
+/// returns the new target, or `None` to leave the link alone.
+///
+/// Returns `None` if no link was replaced.
+fn rewrite_wikilinks(
+    text: &str,
+    mut replace: impl FnMut(&str) -> Option<String>,
+) -> Option<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    let mut changed = false;
+
+    while let Some(start) = rest.find("[[") {
+        let inner_start = start + 2;
+        let Some(len) = rest[inner_start..].find("]]") else {
+            break;
+        };
+        let inner = &rest[inner_start..inner_start + len];
+        out.push_str(&rest[..inner_start]);
+
+        let target_len = inner.find(['#', '|']).unwrap_or(inner.len());
+        let (target, suffix) = inner.split_at(target_len);
+        match replace(target.trim()).filter(|_| !target.contains('\n')) {
+            Some(new_target) => {
+                out.push_str(&new_target);
+                changed = true;
+            }
+            None => out.push_str(target),
+        }
+        out.push_str(suffix);
+        out.push_str("]]");
+
+        rest = &rest[inner_start + len + 2..];
+    }
+
+    out.push_str(rest);
+    changed.then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod wikilinks {
+        use super::*;
+
+        fn rename(text: &str) -> Option<String> {
+            rewrite_wikilinks(text, |target| (target == "old").then(|| "new".to_string()))
+        }
+
+        #[test]
+        fn rewrites_matching_links() {
+            assert_eq!(
+                rename("See [[old]] and [[other]].").as_deref(),
+                Some("See [[new]] and [[other]].")
+            );
+        }
+
+        #[test]
+        fn keeps_heading_display_and_embeds() {
+            assert_eq!(
+                rename("[[old#Plan|the plan]] ![[old]]").as_deref(),
+                Some("[[new#Plan|the plan]] ![[new]]")
+            );
+        }
+
+        #[test]
+        fn none_when_nothing_matches() {
+            assert_eq!(rename("[[other]] [[unclosed"), None);
+            assert_eq!(rename("no links"), None);
+        }
+
+        #[test]
+        fn ignores_links_spanning_lines() {
+            assert_eq!(rename("[[old\n]]"), None);
+        }
+    }
+}
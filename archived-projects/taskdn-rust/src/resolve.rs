@@ -1,6 +1,6 @@
 //! File reference resolution for `WikiLink`s and paths.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::{Error, FileReference, Result, Taskdn};
 
@@ -38,33 +38,20 @@ impl Taskdn {
     /// - `RelativePath`: Resolves relative to `tasks_dir`
     /// - `Filename`: Looks for exact filename in `tasks_dir`
     ///
+    /// If no file matches, the same rules are tried in the `archive`
+    /// subdirectory of `tasks_dir`.
+    ///
     /// # Errors
     /// Returns `Error::UnresolvedReference` if the file cannot be found.
     pub fn resolve_task_reference(&self, reference: &FileReference) -> Result<PathBuf> {
         self.resolve_reference(reference, &self.config.tasks_dir)
+            .or_else(|_| self.resolve_reference(reference, &self.config.tasks_dir.join("archive")))
     }
 
     /// Internal resolution logic for any directory.
     #[allow(clippy::unused_self)] // May use self for caching in the future
-    fn resolve_reference(
-        &self,
-        reference: &FileReference,
-        base_dir: &std::path::Path,
-    ) -> Result<PathBuf> {
-        let path = match reference {
-            FileReference::WikiLink { target, .. } => {
-                // WikiLinks resolve to {target}.md in the base directory
-                base_dir.join(format!("{target}.md"))
-            }
-            FileReference::RelativePath(rel_path) => {
-                // Relative paths resolve from the base directory
-                base_dir.join(rel_path)
-            }
-            FileReference::Filename(filename) => {
-                // Filenames are looked up directly in the base directory
-                base_dir.join(filename)
-            }
-        };
+    fn resolve_reference(&self, reference: &FileReference, base_dir: &Path) -> Result<PathBuf> {
+        let path = reference_path(reference, base_dir);
 
         // Canonicalize to resolve any ../ components and verify existence
         if path.exists() {
@@ -77,6 +64,24 @@ impl Taskdn {
     }
 }
 
+/// The path a reference points to in `base_dir`, whether or not it exists.
+pub(crate) fn reference_path(reference: &FileReference, base_dir: &Path) -> PathBuf {
+    match reference {
+        FileReference::WikiLink { target, .. } => {
+            // WikiLinks resolve to {target}.md in the base directory
+            base_dir.join(format!("{target}.md"))
+        }
+        FileReference::RelativePath(rel_path) => {
+            // Relative paths resolve from the base directory
+            base_dir.join(rel_path)
+        }
+        FileReference::Filename(filename) => {
+            // Filenames are looked up directly in the base directory
+            base_dir.join(filename)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -123,6 +128,26 @@ mod tests {
         assert!(matches!(result, Err(Error::UnresolvedReference { .. })));
     }
 
+    #[test]
+    fn resolve_task_falls_back_to_archive() {
+        let (temp, sdk) = setup_test_dirs();
+        fs::create_dir_all(temp.path().join("tasks/archive")).unwrap();
+        let archived = temp.path().join("tasks/archive/old.md");
+        fs::write(&archived, "test").unwrap();
+
+        let resolved = sdk
+            .resolve_task_reference(&FileReference::wiki_link("old"))
+            .unwrap();
+        assert_eq!(resolved, archived);
+
+        let active = temp.path().join("tasks/old.md");
+        fs::write(&active, "test").unwrap();
+        let resolved = sdk
+            .resolve_task_reference(&FileReference::wiki_link("old"))
+            .unwrap();
+        assert_eq!(resolved, active);
+    }
+
     #[test]
     fn resolve_relative_path() {
         let (temp, sdk) = setup_test_dirs();
@@ -0,0 +1,330 @@
+//! Progress rollups for projects and areas.
+//!
+//! Each summary method reads the vault once, however many projects or areas
+//! it summarises, and sorts the tasks into per-entity buckets in one pass.
+//! Tasks are matched the same way as [`Taskdn::get_tasks_for_project`] and
+//! [`Taskdn::get_tasks_for_area`].
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+
+use crate::error::Result;
+use crate::filter::{AreaFilter, ProjectFilter, TaskFilter};
+use crate::types::{Area, FileReference, ProgressSummary, Project, Task};
+use crate::Taskdn;
+
+impl Taskdn {
+    /// Summarise the tasks of one project.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the project file
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the project doesn't exist.
+    /// Returns an error if the tasks directory cannot be read.
+    pub fn project_summary(&self, path: impl AsRef<Path>) -> Result<ProgressSummary> {
+        let project = self.get_project(path)?;
+        let mut summaries = self.summarise_projects(vec![project])?;
+        Ok(summaries.remove(0))
+    }
+
+    /// Summarise the tasks of every project matching a filter.
+    ///
+    /// # Returns
+    /// One summary per project, sorted by path.
+    ///
+    /// # Errors
+    /// Returns an error if the tasks or projects directory cannot be read.
+    pub fn project_summaries(&self, filter: &ProjectFilter) -> Result<Vec<ProgressSummary>> {
+        let projects = self.list_projects(filter)?;
+        self.summarise_projects(projects)
+    }
+
+    /// Summarise the tasks of one area, including tasks in its projects.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the area file
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the area doesn't exist.
+    /// Returns an error if the tasks or projects directory cannot be read.
+    pub fn area_summary(&self, path: impl AsRef<Path>) -> Result<ProgressSummary> {
+        let area = self.get_area(path)?;
+        let mut summaries = self.summarise_areas(vec![area])?;
+        Ok(summaries.remove(0))
+    }
+
+    /// Summarise the tasks of every area matching a filter, including tasks
+    /// in each area's projects.
+    ///
+    /// # Returns
+    /// One summary per area, sorted by path.
+    ///
+    /// # Errors
+    /// Returns an error if the tasks, projects or areas directory cannot be read.
+    pub fn area_summaries(&self, filter: &AreaFilter) -> Result<Vec<ProgressSummary>> {
+        let areas = self.list_areas(filter)?;
+        self.summarise_areas(areas)
+    }
+
+    fn summarise_projects(&self, mut projects: Vec<Project>) -> Result<Vec<ProgressSummary>> {
+        projects.sort_by(|a, b| a.path.cmp(&b.path));
+        let tasks = self.list_tasks(&TaskFilter::new().include_archive_dir())?;
+        let today = self.today();
+
+        let targets = Targets::new(
+            projects
+                .iter()
+                .map(|p| (p.path.as_path(), p.title.as_str())),
+        );
+        let mut buckets: HashMap<PathBuf, Vec<&Task>> = HashMap::new();
+        for task in &tasks {
+            for path in targets.resolve(task.project.as_ref()) {
+                buckets.entry(path).or_default().push(task);
+            }
+        }
+
+        Ok(projects
+            .into_iter()
+            .map(|project| summarise(project.path, project.title, &buckets, today))
+            .collect())
+    }
+
+    fn summarise_areas(&self, mut areas: Vec<Area>) -> Result<Vec<ProgressSummary>> {
+        areas.sort_by(|a, b| a.path.cmp(&b.path));
+        let tasks = self.list_tasks(&TaskFilter::new().include_archive_dir())?;
+        let projects = self.list_projects(&ProjectFilter::new())?;
+        let today = self.today();
+
+        let area_targets = Targets::new(areas.iter().map(|a| (a.path.as_path(), a.title.as_str())));
+        let project_targets = Targets::new(
+            projects
+                .iter()
+                .map(|p| (p.path.as_path(), p.title.as_str())),
+        );
+        let project_areas: HashMap<&Path, Vec<PathBuf>> = projects
+            .iter()
+            .map(|p| (p.path.as_path(), area_targets.resolve(p.area.as_ref())))
+            .collect();
+
+        let mut buckets: HashMap<PathBuf, Vec<&Task>> = HashMap::new();
+        for task in &tasks {
+            let via_projects = project_targets
+                .resolve(task.project.as_ref())
+                .into_iter()
+                .flat_map(|project| project_areas[project.as_path()].iter().cloned());
+            let task_areas: HashSet<PathBuf> = area_targets
+                .resolve(task.area.as_ref())
+                .into_iter()
+                .chain(via_projects)
+                .collect();
+            for path in task_areas {
+                buckets.entry(path).or_default().push(task);
+            }
+        }
+
+        Ok(areas
+            .into_iter()
+            .map(|area| summarise(area.path, area.title, &buckets, today))
+            .collect())
+    }
+}
+
+/// Summarise the tasks bucketed under `path`.
+fn summarise(
+    path: PathBuf,
+    title: String,
+    buckets: &HashMap<PathBuf, Vec<&Task>>,
+    today: NaiveDate,
+) -> ProgressSummary {
+    let tasks = buckets.get(&path).map_or(&[][..], Vec::as_slice);
+    let mut summary = ProgressSummary::new(path, title);
+    for task in tasks {
+        summary.add(task, today);
+    }
+    summary
+}
+
+/// Looks up the entities a reference points to by name, matching the same
+/// way as `FileReference::points_to` without comparing against every entity.
+struct Targets<'a> {
+    /// File stems and titles, for `WikiLink`s.
+    by_link: HashMap<&'a str, Vec<&'a Path>>,
+    /// File names, for `Filename` and `RelativePath` references.
+    by_filename: HashMap<&'a str, Vec<&'a Path>>,
+}
+
+impl<'a> Targets<'a> {
+    fn new(entities: impl IntoIterator<Item = (&'a Path, &'a str)>) -> Self {
+        let mut by_link: HashMap<&str, Vec<&Path>> = HashMap::new();
+        let mut by_filename: HashMap<&str, Vec<&Path>> = HashMap::new();
+        for (path, title) in entities {
+            let stem = path.file_stem().and_then(|n| n.to_str()).unwrap_or("");
+            let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            by_link.entry(stem).or_default().push(path);
+            if title != stem {
+                by_link.entry(title).or_default().push(path);
+            }
+            by_filename.entry(filename).or_default().push(path);
+        }
+        Self {
+            by_link,
+            by_filename,
+        }
+    }
+
+    /// The paths of every entity `reference` points to.
+    fn resolve(&self, reference: Option<&FileReference>) -> Vec<PathBuf> {
+        let matches = match reference {
+            Some(FileReference::WikiLink { target, .. }) => self.by_link.get(target.as_str()),
+            Some(FileReference::Filename(name)) => self.by_filename.get(name.as_str()),
+            Some(FileReference::RelativePath(rel_path)) => Path::new(rel_path)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|name| self.by_filename.get(name)),
+            None => None,
+        };
+        matches
+            .into_iter()
+            .flatten()
+            .map(|path| path.to_path_buf())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{setup_test_env, write_file};
+    use crate::FixedClock;
+    use tempfile::TempDir;
+
+    fn setup() -> (TempDir, Taskdn) {
+        let (temp, taskdn) = setup_test_env();
+        let clock = FixedClock::new("2025-03-10T12:00:00Z".parse().unwrap());
+        (temp, taskdn.with_clock(clock))
+    }
+
+    fn write(temp: &TempDir, path: &str, fields: &str) {
+        let content = format!("---\ntitle: {path}\n{fields}\n---\n");
+        write_file(temp, path, &content);
+    }
+
+    fn task(temp: &TempDir, path: &str, status: &str, updated: &str, extra: &str) {
+        write(
+            temp,
+            path,
+            &format!("status: {status}\ncreated-at: 2025-01-01\nupdated-at: {updated}\n{extra}"),
+        );
+    }
+
+    fn vault(temp: &TempDir) {
+        let launch = "project: \"[[launch]]\"";
+        write(temp, "areas/work.md", "status: active");
+        write(temp, "areas/home.md", "status: active");
+        write(
+            temp,
+            "projects/launch.md",
+            "status: in-progress\narea: \"[[work]]\"",
+        );
+        write(temp, "projects/garden.md", "status: planning");
+        task(temp, "tasks/a.md", "done", "2025-03-09", launch);
+        task(temp, "tasks/archive/b.md", "done", "2025-03-01", launch);
+        task(
+            temp,
+            "tasks/c.md",
+            "ready",
+            "2025-03-01",
+            &format!("{launch}\ndue: 2025-03-05"),
+        );
+        task(
+            temp,
+            "tasks/d.md",
+            "blocked",
+            "2025-03-01",
+            &format!("{launch}\ndue: 2025-03-20"),
+        );
+        task(
+            temp,
+            "tasks/e.md",
+            "blocked",
+            "2025-03-01",
+            "project: \"[[garden]]\"",
+        );
+        task(
+            temp,
+            "tasks/f.md",
+            "in-progress",
+            "2025-03-01",
+            "area: \"[[work]]\"",
+        );
+    }
+
+    #[test]
+    fn summarises_a_project() {
+        let (temp, taskdn) = setup();
+        vault(&temp);
+
+        let summary = taskdn.project_summary("launch.md").unwrap();
+        assert_eq!(summary.title, "projects/launch.md");
+        assert_eq!((summary.total, summary.open, summary.done), (4, 2, 2));
+        assert_eq!(summary.overdue, 1);
+        assert_eq!(summary.next_due, Some("2025-03-20".parse().unwrap()));
+        assert_eq!(summary.last_activity, Some("2025-03-09".parse().unwrap()));
+        assert!(summary.has_next_action);
+        assert_eq!(summary.percent_complete(), Some(50.0));
+    }
+
+    #[test]
+    fn summarises_every_project() {
+        let (temp, taskdn) = setup();
+        vault(&temp);
+
+        let summaries = taskdn.project_summaries(&ProjectFilter::new()).unwrap();
+        let totals: Vec<(&str, usize, bool)> = summaries
+            .iter()
+            .map(|s| (s.title.as_str(), s.total, s.has_next_action))
+            .collect();
+        assert_eq!(
+            totals,
+            [
+                ("projects/garden.md", 1, false),
+                ("projects/launch.md", 4, true)
+            ]
+        );
+    }
+
+    #[test]
+    fn summarises_areas_with_their_projects() {
+        let (temp, taskdn) = setup();
+        vault(&temp);
+
+        let work = taskdn.area_summary("work.md").unwrap();
+        assert_eq!((work.total, work.open, work.done), (5, 3, 2));
+
+        let summaries = taskdn.area_summaries(&AreaFilter::new()).unwrap();
+        let totals: Vec<(&str, usize)> = summaries
+            .iter()
+            .map(|s| (s.title.as_str(), s.total))
+            .collect();
+        assert_eq!(totals, [("areas/home.md", 0), ("areas/work.md", 5)]);
+    }
+
+    #[test]
+    fn counts_a_task_once_per_area() {
+        let (temp, taskdn) = setup();
+        vault(&temp);
+        task(
+            &temp,
+            "tasks/g.md",
+            "ready",
+            "2025-03-01",
+            "project: ./projects/launch.md\narea: work.md",
+        );
+
+        assert_eq!(taskdn.project_summary("launch.md").unwrap().total, 5);
+        assert_eq!(taskdn.area_summary("work.md").unwrap().total, 6);
+    }
+}
@@ -0,0 +1,111 @@
+//! Tag usage across the vault.
+
+use std::collections::HashMap;
+
+use crate::error::Result;
+use crate::filter::{AreaFilter, ProjectFilter, TaskFilter};
+use crate::types::{Tag, TagCount};
+use crate::Taskdn;
+
+impl Taskdn {
+    /// List every tag in use, with how many tasks, projects and areas have it.
+    ///
+    /// Tags come from the `tags` field and from inline `#tags` in bodies.
+    /// Each entity counts once per tag, and a nested tag such as
+    /// `work/client` does not count towards `work`. Tags differing only in
+    /// case are counted together, under the spelling seen first. Tasks in
+    /// the archive subdirectory are not counted.
+    ///
+    /// # Returns
+    /// Tag counts sorted by tag, ignoring case.
+    ///
+    /// # Errors
+    /// Returns an error if a directory cannot be read.
+    pub fn list_tags(&self) -> Result<Vec<TagCount>> {
+        let mut tasks = self.list_tasks(&TaskFilter::new())?;
+        let mut projects = self.list_projects(&ProjectFilter::new())?;
+        let mut areas = self.list_areas(&AreaFilter::new())?;
+        tasks.sort_by(|a, b| a.path.cmp(&b.path));
+        projects.sort_by(|a, b| a.path.cmp(&b.path));
+        areas.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut counts: HashMap<Tag, TagCount> = HashMap::new();
+        let mut count = |tags: Vec<Tag>, bump: fn(&mut TagCount)| {
+            for tag in tags {
+                let entry = counts.entry(tag.clone()).or_insert_with(|| TagCount {
+                    tag,
+                    tasks: 0,
+                    projects: 0,
+                    areas: 0,
+                });
+                bump(entry);
+            }
+        };
+
+        for task in &tasks {
+            count(task.all_tags(), |c| c.tasks += 1);
+        }
+        for project in &projects {
+            count(project.all_tags(), |c| c.projects += 1);
+        }
+        for area in &areas {
+            count(area.all_tags(), |c| c.areas += 1);
+        }
+
+        let mut counts: Vec<TagCount> = counts.into_values().collect();
+        counts.sort_by(|a, b| a.tag.cmp(&b.tag));
+        Ok(counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{setup_test_env, write_file};
+    use tempfile::TempDir;
+
+    fn write(temp: &TempDir, path: &str, tags: &str, body: &str) {
+        let status = if path.starts_with("areas/") {
+            "active"
+        } else {
+            "ready"
+        };
+        let content = format!(
+            "---\ntitle: Test\nstatus: {status}\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\ntags: {tags}\n---\n{body}"
+        );
+        write_file(temp, path, &content);
+    }
+
+    fn count<'a>(counts: &'a [TagCount], tag: &str) -> &'a TagCount {
+        counts.iter().find(|c| c.tag.as_str() == tag).unwrap()
+    }
+
+    #[test]
+    fn counts_tags_per_entity_type() {
+        let (temp, taskdn) = setup_test_env();
+        write(&temp, "tasks/a.md", "[Work, home]", "See #work again.");
+        write(&temp, "tasks/b.md", "[work/client]", "");
+        write(&temp, "tasks/archive/old.md", "[work]", "");
+        write(&temp, "projects/p.md", "work", "#errands");
+        write(&temp, "areas/life.md", "[home]", "");
+
+        let counts = taskdn.list_tags().unwrap();
+        let tags: Vec<&str> = counts.iter().map(|c| c.tag.as_str()).collect();
+        assert_eq!(tags, ["errands", "home", "Work", "work/client"]);
+
+        let work = count(&counts, "Work");
+        assert_eq!((work.tasks, work.projects, work.areas), (1, 1, 0));
+        assert_eq!(work.total(), 2);
+
+        let home = count(&counts, "home");
+        assert_eq!((home.tasks, home.projects, home.areas), (1, 0, 1));
+        assert_eq!(count(&counts, "errands").projects, 1);
+        assert_eq!(count(&counts, "work/client").tasks, 1);
+    }
+
+    #[test]
+    fn empty_vault_has_no_tags() {
+        let (_temp, taskdn) = setup_test_env();
+        assert!(taskdn.list_tags().unwrap().is_empty());
+    }
+}
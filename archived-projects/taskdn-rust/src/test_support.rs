@@ -0,0 +1,30 @@
+//! Fixtures shared by the unit tests.
+
+use crate::{Taskdn, TaskdnConfig};
+use std::fs;
+use tempfile::TempDir;
+
+/// Creates an empty vault (`tasks/archive`, `projects`, `areas`) with the default config.
+pub(crate) fn setup_test_env() -> (TempDir, Taskdn) {
+    setup_test_env_with(|config| config)
+}
+
+/// Like [`setup_test_env`], letting the caller adjust the config first.
+pub(crate) fn setup_test_env_with(
+    configure: impl FnOnce(TaskdnConfig) -> TaskdnConfig,
+) -> (TempDir, Taskdn) {
+    let temp = TempDir::new().unwrap();
+    let tasks_dir = temp.path().join("tasks");
+    let projects_dir = temp.path().join("projects");
+    let areas_dir = temp.path().join("areas");
+    fs::create_dir_all(tasks_dir.join("archive")).unwrap();
+    fs::create_dir_all(&projects_dir).unwrap();
+    fs::create_dir_all(&areas_dir).unwrap();
+    let config = configure(TaskdnConfig::new(tasks_dir, projects_dir, areas_dir));
+    (temp, Taskdn::new(config).unwrap())
+}
+
+/// Writes `content` to `path`, relative to the vault root.
+pub(crate) fn write_file(temp: &TempDir, path: &str, content: &str) {
+    fs::write(temp.path().join(path), content).unwrap();
+}
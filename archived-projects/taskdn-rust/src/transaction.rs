@@ -0,0 +1,1007 @@
+//! Multi-file transactions.
+//!
+//! A [`Transaction`] stages creates, updates, moves and deletes in memory and
+//! applies them together on [`Transaction::commit`]. If any change fails to
+//! apply, every file already touched is restored to its original bytes.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut tx = taskdn.transaction();
+//! tx.update_task("a.md", TaskUpdates::new().status(TaskStatus::Done))?;
+//! tx.archive_task("b.md")?;
+//! let report = tx.commit();
+//! assert!(report.is_complete_success());
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::{BatchResult, Error, Result};
+use crate::journal::JournalChange;
+use crate::rename::RenameKind;
+use crate::types::{
+    AreaUpdates, NewArea, NewProject, NewTask, ParsedArea, ParsedProject, ParsedTask,
+    ProjectUpdates, TaskUpdates,
+};
+use crate::utils::content_hash;
+use crate::writer::atomic_write;
+use crate::Taskdn;
+
+/// A set of file changes that are applied together or not at all.
+///
+/// Created with [`Taskdn::transaction`]. Staging methods validate their input
+/// and compute the new file content immediately, but nothing touches the disk
+/// until [`commit`](Transaction::commit). Later operations see the staged
+/// results of earlier ones, so a task can be created and then updated in the
+/// same transaction.
+///
+/// Dropping a transaction without committing discards all staged changes.
+#[derive(Debug)]
+pub struct Transaction<'a> {
+    taskdn: &'a Taskdn,
+    /// Pending state of each touched path: `Some(content)` to write, `None` to delete.
+    staged: BTreeMap<PathBuf, Option<String>>,
+    /// Hash of each touched path's content when it was first read (`None` if
+    /// it didn't exist). Checked again before committing.
+    base: HashMap<PathBuf, Option<u64>>,
+}
+
+impl Taskdn {
+    /// Start a new transaction.
+    #[must_use]
+    pub fn transaction(&self) -> Transaction<'_> {
+        Transaction {
+            taskdn: self,
+            staged: BTreeMap::new(),
+            base: HashMap::new(),
+        }
+    }
+}
+
+impl Transaction<'_> {
+    // ==========================================================================
+    // Create Operations
+    // ==========================================================================
+
+    /// Stage the creation of a new task.
+    ///
+    /// # Returns
+    /// The path the task will be created at.
+    ///
+    /// # Errors
+    /// Returns `Error::Validation` if the file already exists or is already staged.
+    pub fn create_task(&mut self, task: NewTask) -> Result<PathBuf> {
+        let task = self
+            .taskdn
+            .prepare_new_task(task, |path| self.exists(path))?;
+        self.stage_create(&task.path, task.to_string())?;
+        Ok(task.path)
+    }
+
+    /// Stage the creation of a new project.
+    ///
+    /// # Returns
+    /// The path the project will be created at.
+    ///
+    /// # Errors
+    /// Returns `Error::Validation` if the file already exists or is already staged.
+    pub fn create_project(&mut self, project: NewProject) -> Result<PathBuf> {
+        let project = self
+            .taskdn
+            .prepare_new_project(project, |path| self.exists(path))?;
+        self.stage_create(&project.path, project.to_string())?;
+        Ok(project.path)
+    }
+
+    /// Stage the creation of a new area.
+    ///
+    /// # Returns
+    /// The path the area will be created at.
+    ///
+    /// # Errors
+    /// Returns `Error::Validation` if the file already exists or is already staged.
+    pub fn create_area(&mut self, area: NewArea) -> Result<PathBuf> {
+        let area = self
+            .taskdn
+            .prepare_new_area(area, |path| self.exists(path))?;
+        self.stage_create(&area.path, area.to_string())?;
+        Ok(area.path)
+    }
+
+    // ==========================================================================
+    // Update Operations
+    // ==========================================================================
+
+    /// Stage an update to a task, as [`Taskdn::update_task`] would apply it.
+    ///
+    /// # Returns
+    /// The task's path, which changes if the title does and
+    /// [`TaskdnConfig::rename_on_retitle`](crate::TaskdnConfig::rename_on_retitle) is enabled.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the task doesn't exist.
+    /// Returns `Error::Parse` if the file cannot be parsed.
+    pub fn update_task(&mut self, path: impl AsRef<Path>, updates: TaskUpdates) -> Result<PathBuf> {
+        let mut path = resolve(&self.taskdn.config.tasks_dir, path.as_ref());
+        if let Some(title) = &updates.title {
+            path = self.stage_retitle(RenameKind::Task, &path, title)?;
+        }
+        let content = self.read(&path)?;
+        let task = ParsedTask::parse(&content)
+            .map_err(|e| e.with_path(&path))?
+            .with_path(&path);
+
+        let completes = !task.status.is_completed()
+            && updates.status.is_some_and(|status| status.is_completed());
+        let rendered = self.taskdn.render_task_updates(task, &content, updates);
+        self.staged.insert(path.clone(), Some(rendered));
+        if completes && self.taskdn.config.unblock_dependents {
+            self.stage_unblock_dependents(&path)?;
+        }
+        Ok(path)
+    }
+
+    /// Stage an update to a project, as [`Taskdn::update_project`] would apply it.
+    ///
+    /// # Returns
+    /// The project's path, which changes if the title does and
+    /// [`TaskdnConfig::rename_on_retitle`](crate::TaskdnConfig::rename_on_retitle) is enabled.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the project doesn't exist.
+    /// Returns `Error::Parse` if the file cannot be parsed.
+    pub fn update_project(
+        &mut self,
+        path: impl AsRef<Path>,
+        updates: ProjectUpdates,
+    ) -> Result<PathBuf> {
+        let mut path = resolve(&self.taskdn.config.projects_dir, path.as_ref());
+        if let Some(title) = &updates.title {
+            path = self.stage_retitle(RenameKind::Project, &path, title)?;
+        }
+        let content = self.read(&path)?;
+        let project = ParsedProject::parse(&content)
+            .map_err(|e| e.with_path(&path))?
+            .with_path(&path);
+
+        let rendered = self
+            .taskdn
+            .render_project_updates(project, &content, updates);
+        self.staged.insert(path.clone(), Some(rendered));
+        Ok(path)
+    }
+
+    /// Stage an update to an area, as [`Taskdn::update_area`] would apply it.
+    ///
+    /// # Returns
+    /// The area's path, which changes if the title does and
+    /// [`TaskdnConfig::rename_on_retitle`](crate::TaskdnConfig::rename_on_retitle) is enabled.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the area doesn't exist.
+    /// Returns `Error::Parse` if the file cannot be parsed.
+    pub fn update_area(&mut self, path: impl AsRef<Path>, updates: AreaUpdates) -> Result<PathBuf> {
+        let mut path = resolve(&self.taskdn.config.areas_dir, path.as_ref());
+        if let Some(title) = &updates.title {
+            path = self.stage_retitle(RenameKind::Area, &path, title)?;
+        }
+        let content = self.read(&path)?;
+        let area = ParsedArea::parse(&content)
+            .map_err(|e| e.with_path(&path))?
+            .with_path(&path);
+
+        let rendered = self.taskdn.render_area_updates(area, &content, updates);
+        self.staged.insert(path.clone(), Some(rendered));
+        Ok(path)
+    }
+
+    // ==========================================================================
+    // Archive Operations
+    // ==========================================================================
+
+    /// Stage moving a task to the archive subdirectory.
+    ///
+    /// # Returns
+    /// The path the task will have in the archive directory.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the task doesn't exist.
+    /// Returns `Error::Validation` if the task is already archived, or a file
+    /// with the same name already exists in the archive.
+    pub fn archive_task(&mut self, path: impl AsRef<Path>) -> Result<PathBuf> {
+        let path = resolve(&self.taskdn.config.tasks_dir, path.as_ref());
+        let archive_dir = self.taskdn.config.tasks_dir.join("archive");
+
+        if path.starts_with(&archive_dir) {
+            return Err(Error::Validation {
+                path,
+                message: "task is already archived".to_string(),
+            });
+        }
+
+        let new_path = archive_dir.join(file_name(&path)?);
+        self.stage_move(&path, &new_path)?;
+        Ok(new_path)
+    }
+
+    /// Stage restoring a task from the archive.
+    ///
+    /// # Returns
+    /// The path the task will have in the tasks directory.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the task doesn't exist.
+    /// Returns `Error::Validation` if the task is not archived, or a file with
+    /// the same name already exists in the tasks directory.
+    pub fn unarchive_task(&mut self, path: impl AsRef<Path>) -> Result<PathBuf> {
+        let path = resolve(&self.taskdn.config.tasks_dir, path.as_ref());
+        let archive_dir = self.taskdn.config.tasks_dir.join("archive");
+
+        if !path.starts_with(&archive_dir) {
+            return Err(Error::Validation {
+                path,
+                message: "task is not in archive".to_string(),
+            });
+        }
+
+        let new_path = self.taskdn.config.tasks_dir.join(file_name(&path)?);
+        self.stage_move(&path, &new_path)?;
+        Ok(new_path)
+    }
+
+    // ==========================================================================
+    // Rename Operations
+    // ==========================================================================
+
+    /// Stage renaming a task, as [`Taskdn::rename_task`] would.
+    ///
+    /// # Returns
+    /// The task's new path.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the task doesn't exist.
+    /// Returns `Error::Validation` if a file already exists at the new path.
+    pub fn rename_task(
+        &mut self,
+        path: impl AsRef<Path>,
+        new_path: impl AsRef<Path>,
+    ) -> Result<PathBuf> {
+        let dir = &self.taskdn.config.tasks_dir;
+        let from = resolve(dir, path.as_ref());
+        let to = resolve(dir, new_path.as_ref());
+        self.stage_rename(RenameKind::Task, &from, &to)
+    }
+
+    /// Stage renaming a project, as [`Taskdn::rename_project`] would.
+    ///
+    /// # Returns
+    /// The project's new path.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the project doesn't exist.
+    /// Returns `Error::Validation` if a file already exists at the new path.
+    pub fn rename_project(
+        &mut self,
+        path: impl AsRef<Path>,
+        new_path: impl AsRef<Path>,
+    ) -> Result<PathBuf> {
+        let dir = &self.taskdn.config.projects_dir;
+        let from = resolve(dir, path.as_ref());
+        let to = resolve(dir, new_path.as_ref());
+        self.stage_rename(RenameKind::Project, &from, &to)
+    }
+
+    /// Stage renaming an area, as [`Taskdn::rename_area`] would.
+    ///
+    /// # Returns
+    /// The area's new path.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the area doesn't exist.
+    /// Returns `Error::Validation` if a file already exists at the new path.
+    pub fn rename_area(
+        &mut self,
+        path: impl AsRef<Path>,
+        new_path: impl AsRef<Path>,
+    ) -> Result<PathBuf> {
+        let dir = &self.taskdn.config.areas_dir;
+        let from = resolve(dir, path.as_ref());
+        let to = resolve(dir, new_path.as_ref());
+        self.stage_rename(RenameKind::Area, &from, &to)
+    }
+
+    // ==========================================================================
+    // Delete Operations
+    // ==========================================================================
+
+    /// Stage deleting a task file, as [`Taskdn::delete_task`] would.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the task doesn't exist.
+    pub fn delete_task(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let dir = &self.taskdn.config.tasks_dir;
+        let path = resolve(dir, path.as_ref());
+        self.stage_entity_delete(dir, &path)
+    }
+
+    /// Stage deleting a project file, as [`Taskdn::delete_project`] would.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the project doesn't exist.
+    pub fn delete_project(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let dir = &self.taskdn.config.projects_dir;
+        let path = resolve(dir, path.as_ref());
+        self.stage_entity_delete(dir, &path)
+    }
+
+    /// Stage deleting an area file, as [`Taskdn::delete_area`] would.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the area doesn't exist.
+    pub fn delete_area(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let dir = &self.taskdn.config.areas_dir;
+        let path = resolve(dir, path.as_ref());
+        self.stage_entity_delete(dir, &path)
+    }
+
+    // ==========================================================================
+    // Commit
+    // ==========================================================================
+
+    /// Returns true if no changes have been staged.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.staged.is_empty()
+    }
+
+    /// Returns the number of files the transaction will change.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.staged.len()
+    }
+
+    /// Apply all staged changes.
+    ///
+    /// Files are written first (atomically, see [`Taskdn::create_task`]) and
+    /// removed afterwards, so an interruption never loses the content of a
+    /// moved file. Before anything is written, every touched file is checked
+    /// against the version seen while staging; a file changed in the meantime
+    /// fails the commit with `Error::Conflict`.
+    ///
+    /// # Returns
+    /// A `BatchResult`. On success, `succeeded` lists every changed path. On
+    /// failure, `succeeded` is empty and `failed` holds the error that stopped
+    /// the commit, followed by any files that could not be restored. All other
+    /// files are back to their original bytes, and directories the commit
+    /// created are removed again.
+    ///
+    /// A successful commit is recorded as a single `"transaction"` entry in the
+    /// undo journal, if one is configured. If that fails, the changes stay in
+    /// `succeeded` and the journal error is in `failed` under the journal path.
+    #[must_use]
+    pub fn commit(self) -> BatchResult<PathBuf> {
+        self.apply_staged(Some("transaction"))
+    }
+
+    /// Like [`commit`](Self::commit), recording the journal entry under `operation`.
+    pub(crate) fn commit_batch(self, operation: &str) -> BatchResult<PathBuf> {
+        self.apply_staged(Some(operation))
+    }
+
+    /// Commit, recording the journal entry under `operation`.
+    ///
+    /// Returns the error that stopped the commit, if any.
+    pub(crate) fn commit_as(self, operation: &str) -> Result<Vec<PathBuf>> {
+        let mut result = self.commit_batch(operation);
+        if result.failed.is_empty() {
+            Ok(result.succeeded)
+        } else {
+            Err(result.failed.swap_remove(0).1)
+        }
+    }
+
+    /// Apply all staged changes without recording them in the journal.
+    pub(crate) fn apply(self) -> BatchResult<PathBuf> {
+        self.apply_staged(None)
+    }
+
+    fn apply_staged(self, operation: Option<&str>) -> BatchResult<PathBuf> {
+        let mut result = BatchResult::new();
+
+        // Snapshot every file we're about to touch, and make sure nobody else
+        // changed it since it was staged
+        let mut originals: HashMap<&Path, Option<Vec<u8>>> = HashMap::new();
+        for (path, staged) in &self.staged {
+            let original = match fs::read(path) {
+                Ok(bytes) => Some(bytes),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+                Err(e) => {
+                    result.failed.push((path.clone(), e.into()));
+                    return result;
+                }
+            };
+
+            if let Some(expected) = self.base.get(path) {
+                let actual = original.as_deref().map(content_hash);
+                if actual != *expected {
+                    let error = Error::Conflict {
+                        path: path.clone(),
+                        ours: staged.clone().unwrap_or_default(),
+                        theirs: original
+                            .as_deref()
+                            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+                            .unwrap_or_default(),
+                        fields: Vec::new(),
+                    };
+                    result.failed.push((path.clone(), error));
+                    return result;
+                }
+            }
+
+            originals.insert(path, original);
+        }
+
+        let writes = self
+            .staged
+            .iter()
+            .filter_map(|(path, staged)| staged.as_ref().map(|content| (path, content)));
+        let deletes = self
+            .staged
+            .iter()
+            .filter(|(_, staged)| staged.is_none())
+            .map(|(path, _)| path);
+
+        let mut applied: Vec<&Path> = Vec::new();
+        let mut created_dirs: Vec<PathBuf> = Vec::new();
+        let outcome = writes
+            .map(|(path, content)| (path, write_file(path, content, &mut created_dirs)))
+            .chain(deletes.map(|path| (path, fs::remove_file(path).map_err(Error::from))))
+            .find_map(|(path, outcome)| match outcome {
+                Ok(()) => {
+                    applied.push(path);
+                    None
+                }
+                Err(e) => Some((path.clone(), e)),
+            });
+
+        if let Some(failure) = outcome {
+            result.failed.push(failure);
+            for path in applied.into_iter().rev() {
+                if let Err(e) = restore(path, originals.get(path).and_then(Option::as_deref)) {
+                    result.failed.push((path.to_path_buf(), e));
+                }
+            }
+            for dir in created_dirs.into_iter().rev() {
+                if let Err(e) = fs::remove_dir(&dir) {
+                    result.failed.push((dir, e.into()));
+                }
+            }
+            return result;
+        }
+
+        if let Some(operation) = operation {
+            let recorded = self.taskdn.record(operation, || {
+                self.staged
+                    .iter()
+                    .map(|(path, after)| JournalChange {
+                        path: path.clone(),
+                        before: originals
+                            .get(path.as_path())
+                            .cloned()
+                            .flatten()
+                            .and_then(|bytes| String::from_utf8(bytes).ok()),
+                        after: after.clone(),
+                    })
+                    .collect()
+            });
+            if let Err(e) = recorded {
+                let journal_path = self.taskdn.config.journal_path.clone().unwrap_or_default();
+                result.failed.push((journal_path, e));
+            }
+        }
+
+        result.succeeded = self.staged.into_keys().collect();
+        result
+    }
+
+    // ==========================================================================
+    // Internal Helpers
+    // ==========================================================================
+
+    /// Current content of `path`, taking staged changes into account.
+    pub(crate) fn read(&mut self, path: &Path) -> Result<String> {
+        self.read_optional(path)?.ok_or_else(|| Error::NotFound {
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Like [`read`](Self::read), but `None` if the file doesn't exist.
+    fn read_optional(&mut self, path: &Path) -> Result<Option<String>> {
+        if let Some(staged) = self.staged.get(path) {
+            return Ok(staged.clone());
+        }
+
+        let content = match fs::read_to_string(path) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        };
+        self.base
+            .entry(path.to_path_buf())
+            .or_insert_with(|| content.as_deref().map(|c| content_hash(c.as_bytes())));
+        Ok(content)
+    }
+
+    /// Record that `path` was read as `content` before the transaction began,
+    /// so the commit fails with `Error::Conflict` if it has changed since.
+    pub(crate) fn expect_content(&mut self, path: &Path, content: &str) {
+        self.base
+            .insert(path.to_path_buf(), Some(content_hash(content.as_bytes())));
+    }
+
+    /// Whether `path` exists, taking staged changes into account.
+    pub(crate) fn exists(&self, path: &Path) -> bool {
+        self.staged
+            .get(path)
+            .map_or_else(|| path.exists(), Option::is_some)
+    }
+
+    pub(crate) fn stage_create(&mut self, path: &Path, content: String) -> Result<()> {
+        if self.exists(path) {
+            return Err(Error::Validation {
+                path: path.to_path_buf(),
+                message: "file already exists".to_string(),
+            });
+        }
+
+        self.base.entry(path.to_path_buf()).or_insert(None);
+        self.staged.insert(path.to_path_buf(), Some(content));
+        Ok(())
+    }
+
+    /// Stage new content for `path`, which must have been read first.
+    pub(crate) fn stage_write(&mut self, path: &Path, content: String) {
+        self.staged.insert(path.to_path_buf(), Some(content));
+    }
+
+    pub(crate) fn stage_move(&mut self, from: &Path, to: &Path) -> Result<()> {
+        let content = self.read(from)?;
+        self.stage_create(to, content)?;
+        self.stage_removal(from);
+        Ok(())
+    }
+
+    /// Stage deleting an entity file from `dir`, moving it to the trash if enabled.
+    fn stage_entity_delete(&mut self, dir: &Path, path: &Path) -> Result<()> {
+        if !self.taskdn.config.use_trash {
+            return self.stage_delete(path);
+        }
+
+        self.read(path)?;
+        let (trash_path, info_path, info_content) =
+            self.taskdn.trash_entry(dir, path, |p| self.exists(p))?;
+        self.stage_move(path, &trash_path)?;
+        self.stage_create(&info_path, info_content)
+    }
+
+    pub(crate) fn stage_delete(&mut self, path: &Path) -> Result<()> {
+        self.read(path)?;
+        self.stage_removal(path);
+        Ok(())
+    }
+
+    /// Stage replacing the content of `path` (`None` removes it), provided it
+    /// currently holds `expected` (`None` meaning it doesn't exist).
+    pub(crate) fn stage_restore(
+        &mut self,
+        path: &Path,
+        expected: Option<&str>,
+        content: Option<String>,
+    ) -> Result<()> {
+        let current = self.read_optional(path)?;
+        if current.as_deref() != expected {
+            return Err(Error::Conflict {
+                path: path.to_path_buf(),
+                ours: content.unwrap_or_default(),
+                theirs: current.unwrap_or_default(),
+                fields: Vec::new(),
+            });
+        }
+
+        match content {
+            Some(content) => {
+                self.staged.insert(path.to_path_buf(), Some(content));
+            }
+            None => self.stage_removal(path),
+        }
+        Ok(())
+    }
+
+    /// Mark `path` for removal. Files created earlier in this transaction are
+    /// simply dropped, as there is nothing on disk to remove.
+    fn stage_removal(&mut self, path: &Path) {
+        if matches!(self.base.get(path), Some(None)) {
+            self.staged.remove(path);
+            self.base.remove(path);
+        } else {
+            self.staged.insert(path.to_path_buf(), None);
+        }
+    }
+}
+
+impl<'a> Transaction<'a> {
+    pub(crate) fn taskdn(&self) -> &'a Taskdn {
+        self.taskdn
+    }
+}
+
+/// Resolve a path - if relative, resolve against `dir`.
+fn resolve(dir: &Path, path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        dir.join(path)
+    }
+}
+
+fn file_name(path: &Path) -> Result<&std::ffi::OsStr> {
+    path.file_name().ok_or_else(|| Error::NotFound {
+        path: path.to_path_buf(),
+    })
+}
+
+/// Write a staged file, creating its directory if needed.
+///
+/// Directories that didn't exist are appended to `created`, parents first.
+fn write_file(path: &Path, content: &str, created: &mut Vec<PathBuf>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        let missing: Vec<&Path> = parent
+            .ancestors()
+            .take_while(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+            .collect();
+        for dir in missing.into_iter().rev() {
+            match fs::create_dir(dir) {
+                Ok(()) => created.push(dir.to_path_buf()),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+    atomic_write(path, content)
+}
+
+/// Put a file back the way it was before the commit started.
+fn restore(path: &Path, original: Option<&[u8]>) -> Result<()> {
+    match original {
+        Some(bytes) => atomic_write(path, bytes),
+        None => match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FilenameStrategy, TaskStatus, TaskdnConfig};
+    use tempfile::TempDir;
+
+    fn setup_test_env() -> (TempDir, Taskdn) {
+        let temp = TempDir::new().unwrap();
+        let tasks_dir = temp.path().join("tasks");
+        let projects_dir = temp.path().join("projects");
+        let areas_dir = temp.path().join("areas");
+
+        fs::create_dir_all(&tasks_dir).unwrap();
+        fs::create_dir_all(&projects_dir).unwrap();
+        fs::create_dir_all(&areas_dir).unwrap();
+
+        let config = TaskdnConfig::new(tasks_dir, projects_dir, areas_dir);
+        let sdk = Taskdn::new(config).unwrap();
+        (temp, sdk)
+    }
+
+    fn create_task_file(dir: &Path, filename: &str, title: &str) -> PathBuf {
+        let path = dir.join(filename);
+        let content = format!(
+            "---\ntitle: {title}\nstatus: ready\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\n---\n\nBody\n"
+        );
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    mod staging {
+        use super::*;
+
+        #[test]
+        fn nothing_is_written_before_commit() {
+            let (_temp, sdk) = setup_test_env();
+            let path = create_task_file(&sdk.config.tasks_dir, "a.md", "A");
+            let before = fs::read_to_string(&path).unwrap();
+
+            let mut tx = sdk.transaction();
+            let created = tx.create_task(NewTask::new("New")).unwrap();
+            tx.update_task("a.md", TaskUpdates::new().title("Changed"))
+                .unwrap();
+            assert_eq!(tx.len(), 2);
+            drop(tx);
+
+            assert!(!created.exists());
+            assert_eq!(fs::read_to_string(&path).unwrap(), before);
+        }
+
+        #[test]
+        fn later_operations_see_staged_changes() {
+            let (_temp, sdk) = setup_test_env();
+
+            let mut tx = sdk.transaction();
+            let path = tx.create_task(NewTask::new("New")).unwrap();
+            tx.update_task(&path, TaskUpdates::new().status(TaskStatus::Done))
+                .unwrap();
+            let archived = tx.archive_task(&path).unwrap();
+            assert!(tx.commit().is_complete_success());
+
+            assert!(!path.exists());
+            let task = sdk.get_task(&archived).unwrap();
+            assert_eq!(task.title, "New");
+            assert_eq!(task.status, TaskStatus::Done);
+        }
+
+        #[test]
+        fn rejects_duplicate_creates() {
+            let (_temp, sdk) = setup_test_env();
+
+            let mut tx = sdk.transaction();
+            tx.create_task(NewTask::new("Same")).unwrap();
+            let result = tx.create_task(NewTask::new("Same"));
+            assert!(matches!(result, Err(Error::Validation { .. })));
+        }
+
+        #[test]
+        fn suffixed_creates_see_staged_files() {
+            let (_temp, sdk) = setup_test_env();
+            let config = sdk
+                .config()
+                .clone()
+                .with_filename_strategy(FilenameStrategy::SlugWithSuffix);
+            let sdk = Taskdn::new(config).unwrap();
+
+            let mut tx = sdk.transaction();
+            let first = tx.create_task(NewTask::new("Same")).unwrap();
+            let second = tx.create_task(NewTask::new("Same")).unwrap();
+            assert!(tx.commit().is_complete_success());
+
+            assert_eq!(first.file_name().unwrap(), "same.md");
+            assert_eq!(second.file_name().unwrap(), "same-2.md");
+        }
+
+        #[test]
+        fn rejects_missing_files() {
+            let (_temp, sdk) = setup_test_env();
+
+            let mut tx = sdk.transaction();
+            assert!(matches!(
+                tx.update_task("missing.md", TaskUpdates::new().title("X")),
+                Err(Error::NotFound { .. })
+            ));
+            assert!(matches!(
+                tx.delete_project("missing.md"),
+                Err(Error::NotFound { .. })
+            ));
+            assert!(tx.is_empty());
+        }
+
+        #[test]
+        fn deleted_files_cannot_be_updated() {
+            let (_temp, sdk) = setup_test_env();
+            create_task_file(&sdk.config.tasks_dir, "a.md", "A");
+
+            let mut tx = sdk.transaction();
+            tx.delete_task("a.md").unwrap();
+            assert!(matches!(
+                tx.update_task("a.md", TaskUpdates::new().title("X")),
+                Err(Error::NotFound { .. })
+            ));
+        }
+    }
+
+    mod commit {
+        use super::*;
+
+        #[test]
+        fn applies_all_changes() {
+            let (_temp, sdk) = setup_test_env();
+            let a = create_task_file(&sdk.config.tasks_dir, "a.md", "A");
+            let b = create_task_file(&sdk.config.tasks_dir, "b.md", "B");
+            let c = create_task_file(&sdk.config.tasks_dir, "c.md", "C");
+
+            let mut tx = sdk.transaction();
+            tx.update_task(&a, TaskUpdates::new().status(TaskStatus::Done))
+                .unwrap();
+            let archived = tx.archive_task(&b).unwrap();
+            tx.delete_task(&c).unwrap();
+            let project = tx.create_project(NewProject::new("P")).unwrap();
+            let area = tx.create_area(NewArea::new("Work")).unwrap();
+
+            let result = tx.commit();
+            assert!(result.is_complete_success());
+            assert_eq!(result.success_count(), 6);
+
+            assert_eq!(sdk.get_task(&a).unwrap().status, TaskStatus::Done);
+            assert!(!b.exists());
+            assert_eq!(sdk.get_task(&archived).unwrap().title, "B");
+            assert!(!c.exists());
+            assert_eq!(sdk.get_project(&project).unwrap().title, "P");
+            assert_eq!(sdk.get_area(&area).unwrap().title, "Work");
+        }
+
+        #[test]
+        fn rolls_back_when_a_change_fails() {
+            let (_temp, sdk) = setup_test_env();
+            let a = create_task_file(&sdk.config.tasks_dir, "a.md", "A");
+            let b = create_task_file(&sdk.config.tasks_dir, "b.md", "B");
+            let a_before = fs::read(&a).unwrap();
+
+            let mut tx = sdk.transaction();
+            tx.update_task(&a, TaskUpdates::new().title("Changed"))
+                .unwrap();
+            tx.archive_task(&b).unwrap();
+
+            // Make the archive directory impossible to create
+            fs::write(sdk.config.tasks_dir.join("archive"), "not a directory").unwrap();
+
+            let result = tx.commit();
+            assert!(!result.is_complete_success());
+            assert!(result.succeeded.is_empty());
+            assert_eq!(result.failure_count(), 1);
+            assert_eq!(
+                result.failed[0].0,
+                sdk.config.tasks_dir.join("archive").join("b.md")
+            );
+
+            // a.md was written before the failure and has been restored
+            assert_eq!(fs::read(&a).unwrap(), a_before);
+            assert!(b.exists());
+        }
+
+        #[test]
+        fn rolls_back_created_files() {
+            let (_temp, sdk) = setup_test_env();
+            let a = create_task_file(&sdk.config.tasks_dir, "a.md", "A");
+
+            // The project is written before the task is archived
+            let mut tx = sdk.transaction();
+            let created = tx.create_project(NewProject::new("New")).unwrap();
+            tx.archive_task(&a).unwrap();
+
+            fs::write(sdk.config.tasks_dir.join("archive"), "not a directory").unwrap();
+
+            let result = tx.commit();
+            assert!(!result.is_complete_success());
+            assert_eq!(result.failure_count(), 1);
+            assert!(!created.exists());
+            assert!(a.exists());
+        }
+
+        #[test]
+        fn rolls_back_created_directories() {
+            let (_temp, sdk) = setup_test_env();
+            let a = create_task_file(&sdk.config.tasks_dir, "a.md", "A");
+
+            let mut tx = sdk.transaction();
+            tx.create_project(NewProject::new("New")).unwrap();
+            tx.archive_task(&a).unwrap();
+
+            // The commit has to recreate the projects directory before the
+            // archive fails
+            fs::remove_dir(&sdk.config.projects_dir).unwrap();
+            fs::write(sdk.config.tasks_dir.join("archive"), "not a directory").unwrap();
+
+            let result = tx.commit();
+            assert_eq!(result.failure_count(), 1);
+            assert!(!sdk.config.projects_dir.exists());
+            assert!(a.exists());
+        }
+
+        #[test]
+        fn refuses_to_overwrite_external_edits() {
+            let (_temp, sdk) = setup_test_env();
+            let a = create_task_file(&sdk.config.tasks_dir, "a.md", "A");
+            let b = create_task_file(&sdk.config.tasks_dir, "b.md", "B");
+            let b_before = fs::read(&b).unwrap();
+
+            let mut tx = sdk.transaction();
+            tx.update_task(&a, TaskUpdates::new().title("Ours"))
+                .unwrap();
+            tx.update_task(&b, TaskUpdates::new().title("Ours"))
+                .unwrap();
+
+            let external = fs::read_to_string(&a)
+                .unwrap()
+                .replace("title: A", "title: Theirs");
+            fs::write(&a, &external).unwrap();
+
+            let result = tx.commit();
+            assert_eq!(result.failure_count(), 1);
+            assert!(matches!(&result.failed[0].1, Error::Conflict { path, .. } if path == &a));
+            assert_eq!(fs::read_to_string(&a).unwrap(), external);
+            assert_eq!(fs::read(&b).unwrap(), b_before);
+        }
+
+        #[test]
+        fn refuses_to_replace_files_created_externally() {
+            let (_temp, sdk) = setup_test_env();
+
+            let mut tx = sdk.transaction();
+            let path = tx.create_task(NewTask::new("New")).unwrap();
+            fs::write(&path, "external").unwrap();
+
+            let result = tx.commit();
+            assert!(matches!(&result.failed[0].1, Error::Conflict { .. }));
+            assert_eq!(fs::read_to_string(&path).unwrap(), "external");
+        }
+
+        #[test]
+        fn deletes_move_to_trash_when_enabled() {
+            let (temp, _) = setup_test_env();
+            let config = TaskdnConfig::new(
+                temp.path().join("tasks"),
+                temp.path().join("projects"),
+                temp.path().join("areas"),
+            )
+            .with_trash(true);
+            let sdk = Taskdn::new(config).unwrap();
+            let a = create_task_file(&sdk.config.tasks_dir, "a.md", "A");
+
+            let mut tx = sdk.transaction();
+            tx.delete_task(&a).unwrap();
+            assert!(tx.commit().is_complete_success());
+
+            assert!(!a.exists());
+            let trashed = sdk.list_trash().unwrap();
+            assert_eq!(trashed.len(), 1);
+            assert_eq!(trashed[0].original_path, a);
+        }
+
+        #[test]
+        fn trashed_files_with_the_same_name_get_distinct_paths() {
+            let (temp, _) = setup_test_env();
+            let config = TaskdnConfig::new(
+                temp.path().join("tasks"),
+                temp.path().join("projects"),
+                temp.path().join("areas"),
+            )
+            .with_trash(true);
+            let sdk = Taskdn::new(config).unwrap();
+            let archive = sdk.config.tasks_dir.join("archive");
+            fs::create_dir_all(&archive).unwrap();
+            let current = create_task_file(&sdk.config.tasks_dir, "x.md", "Current");
+            let archived = create_task_file(&archive, "x.md", "Archived");
+
+            let mut tx = sdk.transaction();
+            tx.delete_task(&current).unwrap();
+            tx.delete_task(&archived).unwrap();
+            assert!(tx.commit().is_complete_success());
+
+            let mut trashed = sdk.list_trash().unwrap();
+            trashed.sort_by(|a, b| a.original_path.cmp(&b.original_path));
+            assert_eq!(trashed.len(), 2);
+            assert_eq!(trashed[0].original_path, archived);
+            assert_eq!(trashed[1].original_path, current);
+            assert_ne!(trashed[0].path, trashed[1].path);
+            assert_eq!(sdk.get_task(&trashed[1].path).unwrap().title, "Current");
+        }
+
+        #[test]
+        fn empty_transaction_succeeds() {
+            let (_temp, sdk) = setup_test_env();
+            let result = sdk.transaction().commit();
+            assert!(result.is_complete_success());
+            assert_eq!(result.success_count(), 0);
+        }
+    }
+}
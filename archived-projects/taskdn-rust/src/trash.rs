@@ -0,0 +1,635 @@
+//! Soft delete via a vault trash.
+//!
+//! When [`TaskdnConfig::use_trash`](crate::TaskdnConfig::use_trash) is enabled,
+//! `delete_task`, `delete_project` and `delete_area` move the file into a
+//! `.trash` folder inside its entity directory instead of removing it:
+//!
+//! ```text
+//! tasks/
+//! ├── write-report.md
+//! └── .trash/
+//!     ├── old-idea.md        # the deleted file, unchanged
+//!     └── old-idea.md.yaml   # where it came from and when it was deleted
+//! ```
+//!
+//! The metadata file holds:
+//!
+//! ```yaml
+//! original-path: /vault/tasks/old-idea.md
+//! deleted-at: 2025-01-15T14:30:00
+//! ```
+//!
+//! Trash folders are never scanned by list operations, and
+//! [`Taskdn::process_file_change`] ignores changes inside them.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::journal::JournalChange;
+use crate::writer::atomic_write;
+use crate::Taskdn;
+
+/// Name of the trash folder inside each entity directory.
+pub const TRASH_DIR: &str = ".trash";
+
+/// Extension appended to a trashed file's name for its metadata file.
+const INFO_EXTENSION: &str = "yaml";
+
+/// A file in the trash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrashedFile {
+    /// Where the file currently is, inside a `.trash` folder.
+    pub path: PathBuf,
+    /// Where the file was before it was deleted.
+    pub original_path: PathBuf,
+    /// When the file was deleted (UTC).
+    pub deleted_at: NaiveDateTime,
+}
+
+/// Contents of a trashed file's metadata file.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct TrashInfo {
+    original_path: PathBuf,
+    #[serde(with = "crate::utils::serde_timestamp")]
+    deleted_at: NaiveDateTime,
+}
+
+impl TrashInfo {
+    fn load(path: &Path) -> Result<(Self, String)> {
+        let content = fs::read_to_string(path).map_err(|_| Error::NotFound {
+            path: path.to_path_buf(),
+        })?;
+        let info = serde_yaml::from_str(&content).map_err(|e| Error::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })?;
+        Ok((info, content))
+    }
+
+    fn to_yaml(&self, path: &Path) -> Result<String> {
+        serde_yaml::to_string(self).map_err(|e| Error::Parse {
+            path: path.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+}
+
+impl Taskdn {
+    // ==========================================================================
+    // Trash Operations
+    // ==========================================================================
+
+    /// List every file in the trash, oldest deletion first.
+    ///
+    /// Files without readable metadata are not listed.
+    ///
+    /// # Errors
+    /// Returns an error if a trash folder exists but cannot be read.
+    pub fn list_trash(&self) -> Result<Vec<TrashedFile>> {
+        let mut trashed = Vec::new();
+
+        for trash_dir in self.trash_dirs() {
+            let entries = match fs::read_dir(&trash_dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.extension().map_or(true, |ext| ext != INFO_EXTENSION) {
+                    continue;
+                }
+
+                let file = path.with_extension("");
+                if !file.is_file() {
+                    continue;
+                }
+                if let Ok((info, _)) = TrashInfo::load(&path) {
+                    trashed.push(TrashedFile {
+                        path: file,
+                        original_path: info.original_path,
+                        deleted_at: info.deleted_at,
+                    });
+                }
+            }
+        }
+
+        trashed.sort_by(|a, b| {
+            a.deleted_at
+                .cmp(&b.deleted_at)
+                .then_with(|| a.path.cmp(&b.path))
+        });
+        Ok(trashed)
+    }
+
+    /// Move a file from the trash back to where it was deleted from.
+    ///
+    /// # Arguments
+    /// * `path` - Path to the file in the trash, as returned by [`Taskdn::list_trash`]
+    ///
+    /// # Returns
+    /// The restored file's path.
+    ///
+    /// # Errors
+    /// Returns `Error::NotFound` if the file or its metadata is not in the trash.
+    /// Returns `Error::Validation` if a file already exists at the original path.
+    pub fn restore_from_trash(&self, path: impl AsRef<Path>) -> Result<PathBuf> {
+        let path = path.as_ref();
+        let info_path = info_path(path);
+        let (info, info_content) = TrashInfo::load(&info_path)?;
+
+        if !path.is_file() {
+            return Err(Error::NotFound {
+                path: path.to_path_buf(),
+            });
+        }
+        if info.original_path.exists() {
+            return Err(Error::Validation {
+                path: info.original_path,
+                message: "file already exists".to_string(),
+            });
+        }
+
+        if let Some(parent) = info.original_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let content = self.read_for_journal(path);
+        fs::rename(path, &info.original_path)?;
+        // The file is back; a leftover metadata file is ignored by `list_trash`
+        let _ = fs::remove_file(&info_path);
+
+        if let Some(content) = content {
+            self.record("restore_from_trash", || {
+                vec![
+                    JournalChange::removed(path, &content),
+                    JournalChange::removed(&info_path, &info_content),
+                    JournalChange::created(&info.original_path, &content),
+                ]
+            })?;
+        }
+        Ok(info.original_path)
+    }
+
+    /// Permanently remove files that have been in the trash for at least `older_than`.
+    ///
+    /// Pass `Duration::zero()` to empty the trash. Purging is not recorded in
+    /// the undo journal.
+    ///
+    /// # Returns
+    /// The files that were removed.
+    ///
+    /// # Errors
+    /// Returns an error if the trash cannot be read or a file cannot be removed.
+    pub fn purge_trash(&self, older_than: Duration) -> Result<Vec<TrashedFile>> {
+        let Some(cutoff) = self.now_utc().checked_sub_signed(older_than) else {
+            return Ok(Vec::new());
+        };
+
+        let mut purged = Vec::new();
+        for trashed in self.list_trash()? {
+            if trashed.deleted_at > cutoff {
+                continue;
+            }
+
+            fs::remove_file(&trashed.path)?;
+            fs::remove_file(info_path(&trashed.path))?;
+            purged.push(trashed);
+        }
+        Ok(purged)
+    }
+
+    // ==========================================================================
+    // Internal Helpers
+    // ==========================================================================
+
+    /// Delete an entity file from `dir`, moving it to the trash if enabled.
+    pub(crate) fn delete_file(&self, operation: &str, dir: &Path, path: &Path) -> Result<()> {
+        if self.config.use_trash {
+            self.move_to_trash(operation, dir, path)
+        } else {
+            self.remove_file_recorded(operation, path)
+        }
+    }
+
+    /// Returns true if `path` is inside one of the trash folders.
+    pub(crate) fn is_in_trash(&self, path: &Path) -> bool {
+        self.trash_dirs().iter().any(|trash_dir| {
+            path.starts_with(trash_dir)
+                || trash_dir
+                    .canonicalize()
+                    .is_ok_and(|trash_dir| path.starts_with(trash_dir))
+        })
+    }
+
+    fn trash_dirs(&self) -> [PathBuf; 3] {
+        [
+            self.config.tasks_dir.join(TRASH_DIR),
+            self.config.projects_dir.join(TRASH_DIR),
+            self.config.areas_dir.join(TRASH_DIR),
+        ]
+    }
+
+    fn move_to_trash(&self, operation: &str, dir: &Path, path: &Path) -> Result<()> {
+        if !path.is_file() {
+            return Err(Error::NotFound {
+                path: path.to_path_buf(),
+            });
+        }
+
+        let (trash_path, info_path, info_content) = self.trash_entry(dir, path, Path::exists)?;
+        fs::create_dir_all(dir.join(TRASH_DIR))?;
+
+        let content = self.read_for_journal(path);
+        atomic_write(&info_path, &info_content)?;
+        if let Err(e) = fs::rename(path, &trash_path) {
+            let _ = fs::remove_file(&info_path);
+            return Err(e.into());
+        }
+
+        if let Some(content) = content {
+            self.record(operation, || {
+                vec![
+                    JournalChange::removed(path, &content),
+                    JournalChange::created(&trash_path, &content),
+                    JournalChange::created(&info_path, &info_content),
+                ]
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Where `path` would go in the trash of entity directory `dir`, avoiding
+    /// names for which `exists` returns true.
+    ///
+    /// # Returns
+    /// The trashed file's path, its metadata file's path and the metadata content.
+    pub(crate) fn trash_entry(
+        &self,
+        dir: &Path,
+        path: &Path,
+        exists: impl Fn(&Path) -> bool,
+    ) -> Result<(PathBuf, PathBuf, String)> {
+        let trash_path = unique_trash_path(&dir.join(TRASH_DIR), path, exists)?;
+        let info_path = info_path(&trash_path);
+        let info = TrashInfo {
+            original_path: path.to_path_buf(),
+            deleted_at: self.now_utc(),
+        };
+        let info_content = info.to_yaml(&info_path)?;
+        Ok((trash_path, info_path, info_content))
+    }
+}
+
+/// Path of the metadata file for a trashed file.
+fn info_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(INFO_EXTENSION);
+    path.with_file_name(name)
+}
+
+/// A free name in `trash_dir` for `path`, adding `-2`, `-3`, ... if needed.
+fn unique_trash_path(
+    trash_dir: &Path,
+    path: &Path,
+    exists: impl Fn(&Path) -> bool,
+) -> Result<PathBuf> {
+    let (Some(stem), Some(name)) = (path.file_stem(), path.file_name()) else {
+        return Err(Error::NotFound {
+            path: path.to_path_buf(),
+        });
+    };
+    let extension = path.extension().map(|ext| ext.to_string_lossy());
+
+    let mut candidate = trash_dir.join(name);
+    let mut n = 2;
+    while exists(&candidate) || exists(&info_path(&candidate)) {
+        let mut name = stem.to_os_string();
+        name.push(format!("-{n}"));
+        if let Some(extension) = &extension {
+            name.push(format!(".{extension}"));
+        }
+        candidate = trash_dir.join(name);
+        n += 1;
+    }
+    Ok(candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileChangeKind, NewArea, NewProject, NewTask, TaskdnConfig};
+    use tempfile::TempDir;
+
+    fn setup_test_env() -> (TempDir, Taskdn) {
+        let temp = TempDir::new().unwrap();
+        let tasks_dir = temp.path().join("tasks");
+        let projects_dir = temp.path().join("projects");
+        let areas_dir = temp.path().join("areas");
+
+        fs::create_dir_all(&tasks_dir).unwrap();
+        fs::create_dir_all(&projects_dir).unwrap();
+        fs::create_dir_all(&areas_dir).unwrap();
+
+        let config = TaskdnConfig::new(tasks_dir, projects_dir, areas_dir).with_trash(true);
+        let sdk = Taskdn::new(config).unwrap();
+        (temp, sdk)
+    }
+
+    /// Rewrite a trashed file's deletion time.
+    fn backdate(trashed: &TrashedFile, deleted_at: &str) {
+        let info = TrashInfo {
+            original_path: trashed.original_path.clone(),
+            deleted_at: NaiveDateTime::parse_from_str(deleted_at, "%Y-%m-%dT%H:%M:%S").unwrap(),
+        };
+        let path = info_path(&trashed.path);
+        fs::write(&path, info.to_yaml(&path).unwrap()).unwrap();
+    }
+
+    mod deleting {
+        use super::*;
+
+        #[test]
+        fn moves_files_to_trash() {
+            let (_temp, sdk) = setup_test_env();
+            let task = sdk.create_task(NewTask::new("Old idea")).unwrap();
+            let content = fs::read_to_string(&task).unwrap();
+
+            sdk.delete_task(&task).unwrap();
+
+            assert!(!task.exists());
+            let trash_path = sdk.config.tasks_dir.join(".trash/old-idea.md");
+            assert_eq!(fs::read_to_string(&trash_path).unwrap(), content);
+            assert!(sdk
+                .config
+                .tasks_dir
+                .join(".trash/old-idea.md.yaml")
+                .exists());
+        }
+
+        #[test]
+        fn uses_the_entity_directory_trash() {
+            let (_temp, sdk) = setup_test_env();
+            let project = sdk.create_project(NewProject::new("Launch")).unwrap();
+            let area = sdk.create_area(NewArea::new("Work")).unwrap();
+
+            sdk.delete_project(&project).unwrap();
+            sdk.delete_area(&area).unwrap();
+
+            assert!(sdk.config.projects_dir.join(".trash/launch.md").exists());
+            assert!(sdk.config.areas_dir.join(".trash/work.md").exists());
+        }
+
+        #[test]
+        fn renames_on_collision() {
+            let (_temp, sdk) = setup_test_env();
+
+            let first = sdk.create_task(NewTask::new("Old idea")).unwrap();
+            sdk.delete_task(&first).unwrap();
+            let second = sdk.create_task(NewTask::new("Old idea")).unwrap();
+            sdk.delete_task(&second).unwrap();
+
+            let trash = sdk.config.tasks_dir.join(".trash");
+            assert!(trash.join("old-idea.md").exists());
+            assert!(trash.join("old-idea-2.md").exists());
+            assert!(trash.join("old-idea-2.md.yaml").exists());
+        }
+
+        #[test]
+        fn removes_files_when_disabled() {
+            let (temp, _) = setup_test_env();
+            let config = TaskdnConfig::new(
+                temp.path().join("tasks"),
+                temp.path().join("projects"),
+                temp.path().join("areas"),
+            );
+            let sdk = Taskdn::new(config).unwrap();
+
+            let task = sdk.create_task(NewTask::new("Old idea")).unwrap();
+            sdk.delete_task(&task).unwrap();
+
+            assert!(!task.exists());
+            assert!(!sdk.config.tasks_dir.join(".trash").exists());
+        }
+
+        #[test]
+        fn missing_file_is_not_found() {
+            let (_temp, sdk) = setup_test_env();
+            let result = sdk.delete_task("missing.md");
+            assert!(matches!(result, Err(Error::NotFound { .. })));
+        }
+
+        #[test]
+        fn trashed_files_are_not_listed() {
+            let (_temp, sdk) = setup_test_env();
+            let task = sdk.create_task(NewTask::new("Old idea")).unwrap();
+            sdk.create_task(NewTask::new("Keep")).unwrap();
+            sdk.delete_task(&task).unwrap();
+
+            let tasks = sdk.list_tasks(&crate::TaskFilter::new()).unwrap();
+            assert_eq!(tasks.len(), 1);
+            assert_eq!(tasks[0].title, "Keep");
+        }
+
+        #[test]
+        fn file_changes_in_trash_are_ignored() {
+            let (_temp, sdk) = setup_test_env();
+            let task = sdk.create_task(NewTask::new("Old idea")).unwrap();
+            sdk.delete_task(&task).unwrap();
+
+            let trash_path = sdk.config.tasks_dir.join(".trash/old-idea.md");
+            for kind in [
+                FileChangeKind::Created,
+                FileChangeKind::Modified,
+                FileChangeKind::Deleted,
+            ] {
+                assert!(sdk
+                    .process_file_change(&trash_path, kind)
+                    .unwrap()
+                    .is_none());
+            }
+
+            // The original path is reported as deleted
+            let event = sdk
+                .process_file_change(&task, FileChangeKind::Deleted)
+                .unwrap();
+            assert!(matches!(event, Some(crate::VaultEvent::TaskDeleted { .. })));
+        }
+    }
+
+    mod listing {
+        use super::*;
+
+        #[test]
+        fn lists_trashed_files() {
+            let (_temp, sdk) = setup_test_env();
+            let task = sdk.create_task(NewTask::new("Old idea")).unwrap();
+            let project = sdk.create_project(NewProject::new("Launch")).unwrap();
+            sdk.delete_task(&task).unwrap();
+            sdk.delete_project(&project).unwrap();
+
+            let trashed = sdk.list_trash().unwrap();
+            assert_eq!(trashed.len(), 2);
+            let originals: Vec<_> = trashed.iter().map(|t| &t.original_path).collect();
+            assert!(originals.contains(&&task));
+            assert!(originals.contains(&&project));
+        }
+
+        #[test]
+        fn empty_without_trash() {
+            let (_temp, sdk) = setup_test_env();
+            assert!(sdk.list_trash().unwrap().is_empty());
+        }
+
+        #[test]
+        fn skips_files_without_metadata() {
+            let (_temp, sdk) = setup_test_env();
+            let trash = sdk.config.tasks_dir.join(".trash");
+            fs::create_dir_all(&trash).unwrap();
+            fs::write(trash.join("stray.md"), "---\ntitle: Stray\n---\n").unwrap();
+            fs::write(trash.join("orphan.md.yaml"), "not: metadata").unwrap();
+
+            assert!(sdk.list_trash().unwrap().is_empty());
+        }
+    }
+
+    mod restoring {
+        use super::*;
+
+        #[test]
+        fn restores_to_original_path() {
+            let (_temp, sdk) = setup_test_env();
+            let task = sdk.create_task(NewTask::new("Old idea")).unwrap();
+            let content = fs::read_to_string(&task).unwrap();
+            sdk.delete_task(&task).unwrap();
+
+            let trashed = &sdk.list_trash().unwrap()[0];
+            let restored = sdk.restore_from_trash(&trashed.path).unwrap();
+
+            assert_eq!(restored, task);
+            assert_eq!(fs::read_to_string(&task).unwrap(), content);
+            assert!(sdk.list_trash().unwrap().is_empty());
+            assert!(!info_path(&trashed.path).exists());
+        }
+
+        #[test]
+        fn restores_archived_tasks_into_archive() {
+            let (_temp, sdk) = setup_test_env();
+            let task = sdk.create_task(NewTask::new("Old idea")).unwrap();
+            let archived = sdk.archive_task(&task).unwrap();
+            sdk.delete_task(&archived).unwrap();
+
+            let trashed = &sdk.list_trash().unwrap()[0];
+            assert_eq!(sdk.restore_from_trash(&trashed.path).unwrap(), archived);
+            assert!(archived.exists());
+        }
+
+        #[test]
+        fn refuses_to_overwrite() {
+            let (_temp, sdk) = setup_test_env();
+            let task = sdk.create_task(NewTask::new("Old idea")).unwrap();
+            sdk.delete_task(&task).unwrap();
+            sdk.create_task(NewTask::new("Old idea")).unwrap();
+
+            let trashed = &sdk.list_trash().unwrap()[0];
+            let result = sdk.restore_from_trash(&trashed.path);
+            assert!(matches!(result, Err(Error::Validation { .. })));
+            assert!(trashed.path.exists());
+        }
+
+        #[test]
+        fn missing_entry_is_not_found() {
+            let (_temp, sdk) = setup_test_env();
+            let result = sdk.restore_from_trash(sdk.config.tasks_dir.join(".trash/nope.md"));
+            assert!(matches!(result, Err(Error::NotFound { .. })));
+        }
+    }
+
+    mod purging {
+        use super::*;
+
+        #[test]
+        fn purges_only_old_files() {
+            let (_temp, sdk) = setup_test_env();
+            let old = sdk.create_task(NewTask::new("Old")).unwrap();
+            let recent = sdk.create_task(NewTask::new("Recent")).unwrap();
+            sdk.delete_task(&old).unwrap();
+            sdk.delete_task(&recent).unwrap();
+
+            let trashed = sdk.list_trash().unwrap();
+            let old_entry = trashed.iter().find(|t| t.original_path == old).unwrap();
+            backdate(old_entry, "2020-01-01T00:00:00");
+
+            let purged = sdk.purge_trash(Duration::days(30)).unwrap();
+            assert_eq!(purged.len(), 1);
+            assert_eq!(purged[0].original_path, old);
+            assert!(!old_entry.path.exists());
+            assert!(!info_path(&old_entry.path).exists());
+
+            let remaining = sdk.list_trash().unwrap();
+            assert_eq!(remaining.len(), 1);
+            assert_eq!(remaining[0].original_path, recent);
+        }
+
+        #[test]
+        fn zero_duration_empties_trash() {
+            let (_temp, sdk) = setup_test_env();
+            let task = sdk.create_task(NewTask::new("Old")).unwrap();
+            let area = sdk.create_area(NewArea::new("Work")).unwrap();
+            sdk.delete_task(&task).unwrap();
+            sdk.delete_area(&area).unwrap();
+
+            assert_eq!(sdk.purge_trash(Duration::zero()).unwrap().len(), 2);
+            assert!(sdk.list_trash().unwrap().is_empty());
+        }
+
+        #[test]
+        fn age_is_measured_by_the_clock() {
+            let (_temp, sdk) = setup_test_env();
+            let task = sdk.create_task(NewTask::new("Old")).unwrap();
+            sdk.delete_task(&task).unwrap();
+
+            assert!(sdk.purge_trash(Duration::days(30)).unwrap().is_empty());
+
+            let sdk = sdk.with_clock(crate::OffsetClock::new(Duration::days(31)));
+            assert_eq!(sdk.purge_trash(Duration::days(30)).unwrap().len(), 1);
+        }
+    }
+
+    mod journal {
+        use super::*;
+
+        #[test]
+        fn delete_to_trash_can_be_undone() {
+            let (temp, _) = setup_test_env();
+            let config = TaskdnConfig::new(
+                temp.path().join("tasks"),
+                temp.path().join("projects"),
+                temp.path().join("areas"),
+            )
+            .with_trash(true)
+            .with_journal_path(temp.path().join(".taskdn/journal.yaml"));
+            let sdk = Taskdn::new(config).unwrap();
+
+            let task = sdk.create_task(NewTask::new("Old idea")).unwrap();
+            sdk.delete_task(&task).unwrap();
+
+            sdk.undo().unwrap();
+            assert!(task.exists());
+            assert!(sdk.list_trash().unwrap().is_empty());
+            assert!(!sdk
+                .config
+                .tasks_dir
+                .join(".trash/old-idea.md.yaml")
+                .exists());
+        }
+    }
+}
@@ -1,9 +1,16 @@
 //! Area entity and related types.
 
+use super::tag::{merge_tags, tags_from};
+use super::{FileReference, Tag};
+use crate::validation::{unparsed_fields, ValidationWarning};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+/// Typed fields whose unparseable values are kept in `extra` instead of
+/// failing the whole file.
+const TYPED_FIELDS: &[&str] = &["parent"];
+
 /// Status of an area.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[non_exhaustive]
@@ -67,6 +74,10 @@ pub struct Area {
     pub area_type: Option<String>,
     /// Brief description of the area.
     pub description: Option<String>,
+    /// Reference to the parent area, making this area a sub-area.
+    pub parent: Option<FileReference>,
+    /// Tags from the `tags` field (see [`Area::all_tags`] for inline tags too).
+    pub tags: Vec<Tag>,
 
     /// Markdown body.
     pub body: String,
@@ -95,6 +106,25 @@ impl Area {
     pub fn is_active(&self) -> bool {
         !self.is_archived()
     }
+
+    /// Tags from the `tags` field followed by inline `#tags` in the body,
+    /// without duplicates.
+    #[must_use]
+    pub fn all_tags(&self) -> Vec<Tag> {
+        merge_tags([&self.tags[..], &Tag::inline(&self.body)[..]])
+    }
+
+    /// Validate this area against the specification.
+    ///
+    /// Returns a list of warnings for spec violations. Parent cycles need
+    /// the other areas and are reported by [`Taskdn::get_area_warnings`]
+    /// instead.
+    ///
+    /// [`Taskdn::get_area_warnings`]: crate::Taskdn::get_area_warnings
+    #[must_use]
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        unparsed_fields(&self.extra, TYPED_FIELDS)
+    }
 }
 
 /// Parsed area content without a file path.
@@ -108,6 +138,10 @@ pub struct ParsedArea {
     pub area_type: Option<String>,
     /// Brief description.
     pub description: Option<String>,
+    /// Reference to the parent area.
+    pub parent: Option<FileReference>,
+    /// Tags from the `tags` field.
+    pub tags: Vec<Tag>,
     /// Markdown body.
     pub body: String,
     /// Unknown frontmatter fields.
@@ -124,6 +158,8 @@ impl ParsedArea {
             status: self.status,
             area_type: self.area_type,
             description: self.description,
+            parent: self.parent,
+            tags: self.tags,
             body: self.body,
             extra: self.extra,
         }
@@ -143,6 +179,10 @@ pub struct NewArea {
     pub area_type: Option<String>,
     /// Brief description.
     pub description: Option<String>,
+    /// Reference to the parent area.
+    pub parent: Option<FileReference>,
+    /// Tags.
+    pub tags: Vec<Tag>,
     /// Markdown body.
     pub body: String,
     /// Additional frontmatter fields.
@@ -187,6 +227,20 @@ impl NewArea {
         self
     }
 
+    /// Set the parent area, making this area a sub-area.
+    #[must_use]
+    pub fn with_parent(mut self, parent: impl Into<FileReference>) -> Self {
+        self.parent = Some(parent.into());
+        self
+    }
+
+    /// Set the tags (a leading `#` is stripped).
+    #[must_use]
+    pub fn with_tags<S: AsRef<str>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags = tags_from(tags);
+        self
+    }
+
     /// Set the body content.
     #[must_use]
     pub fn with_body(mut self, body: impl Into<String>) -> Self {
@@ -206,6 +260,10 @@ pub struct AreaUpdates {
     pub area_type: Option<Option<String>>,
     /// New description.
     pub description: Option<Option<String>>,
+    /// New parent area reference.
+    pub parent: Option<Option<FileReference>>,
+    /// New tags (None = don't change, Some(empty) = clear).
+    pub tags: Option<Vec<Tag>>,
 }
 
 impl AreaUpdates {
@@ -264,6 +322,34 @@ impl AreaUpdates {
         self
     }
 
+    /// Set a new parent area reference.
+    #[must_use]
+    pub fn parent(mut self, parent: impl Into<FileReference>) -> Self {
+        self.parent = Some(Some(parent.into()));
+        self
+    }
+
+    /// Clear the parent area reference, making the area top-level.
+    #[must_use]
+    pub fn clear_parent(mut self) -> Self {
+        self.parent = Some(None);
+        self
+    }
+
+    /// Replace the tags (a leading `#` is stripped).
+    #[must_use]
+    pub fn tags<S: AsRef<str>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags = Some(tags_from(tags));
+        self
+    }
+
+    /// Remove all tags.
+    #[must_use]
+    pub fn clear_tags(mut self) -> Self {
+        self.tags = Some(Vec::new());
+        self
+    }
+
     /// Returns true if no updates are specified.
     #[must_use]
     pub fn is_empty(&self) -> bool {
@@ -271,6 +357,31 @@ impl AreaUpdates {
             && self.status.is_none()
             && self.area_type.is_none()
             && self.description.is_none()
+            && self.parent.is_none()
+            && self.tags.is_none()
+    }
+
+    /// Apply these updates to an area in place.
+    pub(crate) fn apply_to(self, area: &mut Area) {
+        if let Some(title) = self.title {
+            area.title = title;
+        }
+        if let Some(status) = self.status {
+            area.status = status;
+        }
+        if let Some(area_type) = self.area_type {
+            area.area_type = area_type;
+        }
+        if let Some(description) = self.description {
+            area.description = description;
+        }
+        if let Some(parent) = self.parent {
+            area.extra.remove("parent");
+            area.parent = parent;
+        }
+        if let Some(tags) = self.tags {
+            area.tags = tags;
+        }
     }
 }
 
@@ -323,6 +434,8 @@ mod tests {
                 status: None,
                 area_type: None,
                 description: None,
+                parent: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
             }
@@ -0,0 +1,531 @@
+//! Markdown checklists in task and project bodies.
+
+/// A `- [ ]` or `- [x]` item in a markdown body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistItem {
+    /// The item text, after the checkbox.
+    pub text: String,
+    /// Whether the box is checked (any mark other than a space).
+    pub checked: bool,
+    /// Nesting depth; top-level items are 0.
+    pub depth: usize,
+    /// Index of the item this one is nested under, if any.
+    pub parent: Option<usize>,
+    /// Line of the item in the body, counting from 0.
+    pub line: usize,
+}
+
+/// The checklist items of a markdown body, in document order.
+///
+/// Items are lines such as `- [ ] Draft outline` or `1. [x] Send invite`,
+/// using `-`, `*`, `+` or a number as the list marker. An item indented
+/// under another is nested in it. Lines inside fenced code blocks are
+/// ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Checklist {
+    items: Vec<ChecklistItem>,
+}
+
+impl Checklist {
+    /// Parse the checklist items of a markdown body.
+    #[must_use]
+    pub fn parse(body: &str) -> Self {
+        let mut items: Vec<ChecklistItem> = Vec::new();
+        // Indent and index of the items that later items may nest under.
+        let mut open: Vec<(usize, usize)> = Vec::new();
+        let mut in_fence = false;
+
+        for (line_no, line) in body.lines().enumerate() {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+                open.clear();
+                continue;
+            }
+            if in_fence {
+                continue;
+            }
+
+            let Some(item) = ItemLine::parse(line) else {
+                // Unindented text ends the list.
+                if !trimmed.is_empty() && trimmed.len() == line.len() {
+                    open.clear();
+                }
+                continue;
+            };
+
+            while open
+                .last()
+                .is_some_and(|(indent, _)| *indent >= item.indent)
+            {
+                open.pop();
+            }
+            let parent = open.last().map(|(_, index)| *index);
+            open.push((item.indent, items.len()));
+            items.push(ChecklistItem {
+                text: item.text.to_string(),
+                checked: item.checked,
+                depth: open.len() - 1,
+                parent,
+                line: line_no,
+            });
+        }
+
+        Self { items }
+    }
+
+    /// Returns all items in document order.
+    #[must_use]
+    pub fn items(&self) -> &[ChecklistItem] {
+        &self.items
+    }
+
+    /// Returns the item at `index`.
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&ChecklistItem> {
+        self.items.get(index)
+    }
+
+    /// Returns the items nested directly under the item at `index`.
+    pub fn children(&self, index: usize) -> impl Iterator<Item = &ChecklistItem> {
+        self.items
+            .iter()
+            .filter(move |item| item.parent == Some(index))
+    }
+
+    /// Returns true if there are no checklist items.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Number of items, nested ones included.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Number of checked items.
+    #[must_use]
+    pub fn checked_count(&self) -> usize {
+        self.items.iter().filter(|item| item.checked).count()
+    }
+
+    /// Number of unchecked items.
+    #[must_use]
+    pub fn open_count(&self) -> usize {
+        self.len() - self.checked_count()
+    }
+
+    /// Fraction of items that are checked, from 0.0 to 1.0.
+    ///
+    /// Returns `None` if there are no items.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn completion(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.checked_count() as f64 / self.len() as f64)
+    }
+
+    /// `body` with every checklist item unchecked and everything else as it was.
+    pub(crate) fn uncheck_all(body: &str) -> String {
+        let mut lines: Vec<String> = body.split_inclusive('\n').map(str::to_string).collect();
+        for item in Self::parse(body).items() {
+            set_checked(&mut lines[item.line], false);
+        }
+        lines.concat()
+    }
+}
+
+/// A change to the checklist in a markdown body.
+///
+/// Items are identified by their index in [`Checklist::items`]. Applying an
+/// edit only touches the lines of the affected items; the rest of the body,
+/// including line endings, is left as it was.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ChecklistEdit {
+    /// Check the item if unchecked, uncheck it otherwise.
+    Toggle(usize),
+    /// Check the item.
+    Check(usize),
+    /// Uncheck the item.
+    Uncheck(usize),
+    /// Add an unchecked item after the last item of the checklist, or under
+    /// `parent` after its last nested item. Without a checklist, the item is
+    /// appended to the end of the body.
+    Add {
+        /// The item text.
+        text: String,
+        /// Index of the item to nest the new item under.
+        parent: Option<usize>,
+    },
+    /// Remove the item along with everything nested under it.
+    Remove(usize),
+}
+
+impl ChecklistEdit {
+    /// Add a top-level item.
+    #[must_use]
+    pub fn add(text: impl Into<String>) -> Self {
+        Self::Add {
+            text: text.into(),
+            parent: None,
+        }
+    }
+
+    /// Add an item nested under the item at `parent`.
+    #[must_use]
+    pub fn add_under(parent: usize, text: impl Into<String>) -> Self {
+        Self::Add {
+            text: text.into(),
+            parent: Some(parent),
+        }
+    }
+
+    /// The index of the item this edit refers to, if any.
+    #[must_use]
+    pub fn index(&self) -> Option<usize> {
+        match self {
+            Self::Toggle(index)
+            | Self::Check(index)
+            | Self::Uncheck(index)
+            | Self::Remove(index) => Some(*index),
+            Self::Add { parent, .. } => *parent,
+        }
+    }
+
+    /// Apply the edit to a markdown body.
+    ///
+    /// Returns `None` if the item the edit refers to doesn't exist.
+    #[must_use]
+    pub fn apply(&self, body: &str) -> Option<String> {
+        let checklist = Checklist::parse(body);
+        let mut lines: Vec<String> = body.split_inclusive('\n').map(str::to_string).collect();
+
+        match self {
+            Self::Toggle(index) => {
+                let item = checklist.get(*index)?;
+                set_checked(&mut lines[item.line], !item.checked);
+            }
+            Self::Check(index) => {
+                let item = checklist.get(*index)?;
+                set_checked(&mut lines[item.line], true);
+            }
+            Self::Uncheck(index) => {
+                let item = checklist.get(*index)?;
+                set_checked(&mut lines[item.line], false);
+            }
+            Self::Remove(index) => {
+                let item = checklist.get(*index)?;
+                let end = block_end(&lines, item.line);
+                lines.drain(item.line..=end);
+            }
+            Self::Add { text, parent } => {
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                // The item to insert after, and the prefix for the new item
+                let anchor = match parent {
+                    Some(parent) => {
+                        let parent_item = checklist.get(*parent)?;
+                        Some(match checklist.children(*parent).last() {
+                            Some(sibling) => (sibling.line, sibling_prefix(&lines[sibling.line])),
+                            None => (parent_item.line, child_prefix(&lines[parent_item.line])),
+                        })
+                    }
+                    None => checklist
+                        .items()
+                        .iter()
+                        .rfind(|item| item.depth == 0)
+                        .map(|last| (last.line, sibling_prefix(&lines[last.line]))),
+                };
+
+                if let Some((after, prefix)) = anchor {
+                    insert_after_block(&mut lines, after, &prefix, &text);
+                } else {
+                    if lines.last().is_some_and(|line| !line.ends_with('\n')) {
+                        lines.push("\n".to_string());
+                    }
+                    lines.push(format!("- [ ] {text}\n"));
+                }
+            }
+        }
+
+        Some(lines.concat())
+    }
+}
+
+/// A checklist item line, split into its parts.
+struct ItemLine<'a> {
+    /// Width of the leading whitespace (a tab counts as 4).
+    indent: usize,
+    /// Leading whitespace and list marker, e.g. `  - ` or `1. `.
+    prefix: &'a str,
+    /// Byte offset of the mark between the brackets.
+    mark: usize,
+    checked: bool,
+    text: &'a str,
+}
+
+impl<'a> ItemLine<'a> {
+    fn parse(line: &'a str) -> Option<Self> {
+        let content = line.trim_start();
+        let leading = &line[..line.len() - content.len()];
+        let indent = leading.chars().map(|c| if c == '\t' { 4 } else { 1 }).sum();
+
+        let digits = content.len()
+            - content
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        let marker_len = if digits > 0 {
+            matches!(content.as_bytes().get(digits), Some(b'.' | b')')).then_some(digits + 1)?
+        } else {
+            matches!(content.as_bytes().first(), Some(b'-' | b'*' | b'+')).then_some(1)?
+        };
+
+        let after_marker = &content[marker_len..];
+        let rest = after_marker.trim_start_matches([' ', '\t']);
+        if rest.len() == after_marker.len() {
+            return None;
+        }
+        let rest = rest.strip_prefix('[')?;
+        let mark = rest.chars().next().filter(|c| *c != ']' && *c != '\t')?;
+        let rest = rest[mark.len_utf8()..].strip_prefix(']')?;
+        let text = rest.trim_end_matches(['\n', '\r']);
+        if !(text.is_empty() || text.starts_with([' ', '\t'])) {
+            return None;
+        }
+
+        let prefix_len = line.len() - rest.len() - mark.len_utf8() - 2;
+        Some(Self {
+            indent,
+            prefix: &line[..prefix_len],
+            mark: prefix_len + 1,
+            checked: mark != ' ',
+            text: text.trim(),
+        })
+    }
+}
+
+/// Set the mark of an item line to `x` or a space.
+fn set_checked(line: &mut String, checked: bool) {
+    if let Some(item) = ItemLine::parse(line) {
+        let mark = item.mark;
+        let end = mark + line[mark..].chars().next().map_or(1, char::len_utf8);
+        if item.checked != checked {
+            line.replace_range(mark..end, if checked { "x" } else { " " });
+        }
+    }
+}
+
+/// The last line of the block starting at `start`: the line itself plus the
+/// non-blank lines after it that are indented further.
+fn block_end(lines: &[String], start: usize) -> usize {
+    let indent = |line: &str| line.len() - line.trim_start().len();
+    let base = indent(&lines[start]);
+    let mut end = start;
+    for (i, line) in lines.iter().enumerate().skip(start + 1) {
+        if line.trim().is_empty() || indent(line) <= base {
+            break;
+        }
+        end = i;
+    }
+    end
+}
+
+/// The prefix for a new item next to the item on `line`, with the number
+/// incremented for ordered lists.
+fn sibling_prefix(line: &str) -> String {
+    let prefix = ItemLine::parse(line).map_or("- ", |item| item.prefix);
+    let content = prefix.trim_start();
+    let leading = &prefix[..prefix.len() - content.len()];
+    let digits = content.trim_start_matches(|c: char| c.is_ascii_digit());
+    match content[..content.len() - digits.len()].parse::<u64>() {
+        Ok(n) => format!("{leading}{}{digits}", n + 1),
+        Err(_) => prefix.to_string(),
+    }
+}
+
+/// The prefix for the first item nested under the item on `line`.
+fn child_prefix(line: &str) -> String {
+    let prefix = ItemLine::parse(line).map_or("- ", |item| item.prefix);
+    let content = prefix.trim_start();
+    let bullet = if content.starts_with(['-', '*', '+']) {
+        content
+    } else {
+        "- "
+    };
+    format!("{}{bullet}", " ".repeat(prefix.len()))
+}
+
+/// Insert a new unchecked item after the block starting at `start`, matching
+/// its line ending.
+fn insert_after_block(lines: &mut Vec<String>, start: usize, prefix: &str, text: &str) {
+    let index = block_end(lines, start);
+    let ending = if lines[index].ends_with("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let last_without_newline = !lines[index].ends_with('\n');
+    if last_without_newline {
+        lines[index].push_str(ending);
+    }
+    let mut item = format!("{prefix}[ ] {text}");
+    if !last_without_newline {
+        item.push_str(ending);
+    }
+    lines.insert(index + 1, item);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = "## Steps\n\n- [ ] Draft outline\n  - [x] Collect notes\n  - [ ] Pick title\n- [X] Book room\n\nSome notes.\n";
+
+    fn texts(checklist: &Checklist) -> Vec<&str> {
+        checklist.items().iter().map(|i| i.text.as_str()).collect()
+    }
+
+    #[test]
+    fn parses_items_with_nesting_and_lines() {
+        let checklist = Checklist::parse(BODY);
+        assert_eq!(
+            texts(&checklist),
+            ["Draft outline", "Collect notes", "Pick title", "Book room"]
+        );
+
+        let items = checklist.items();
+        assert_eq!(
+            items[1],
+            ChecklistItem {
+                text: "Collect notes".to_string(),
+                checked: true,
+                depth: 1,
+                parent: Some(0),
+                line: 3,
+            }
+        );
+        assert_eq!(items[3].depth, 0);
+        assert_eq!(items[3].parent, None);
+        assert!(items[3].checked);
+        assert_eq!(checklist.children(0).count(), 2);
+    }
+
+    #[test]
+    fn counts_and_completion() {
+        let checklist = Checklist::parse(BODY);
+        assert_eq!(checklist.len(), 4);
+        assert_eq!(checklist.checked_count(), 2);
+        assert_eq!(checklist.open_count(), 2);
+        assert_eq!(checklist.completion(), Some(0.5));
+        assert_eq!(Checklist::parse("No list here").completion(), None);
+    }
+
+    #[test]
+    fn ignores_non_items() {
+        let body = "- plain bullet\n-[ ] no space\n- [ ]no space after\n- [link](x)\n\
+                    ```\n- [ ] in code\n```\n1. [ ] Numbered\n* [-] Cancelled\n";
+        let checklist = Checklist::parse(body);
+        assert_eq!(texts(&checklist), ["Numbered", "Cancelled"]);
+        assert!(checklist.items()[1].checked);
+    }
+
+    #[test]
+    fn unindented_text_ends_nesting() {
+        let checklist = Checklist::parse("- [ ] A\nParagraph\n  - [ ] B\n");
+        assert_eq!(checklist.items()[1].parent, None);
+    }
+
+    #[test]
+    fn toggle_check_and_uncheck() {
+        let body = ChecklistEdit::Toggle(0).apply(BODY).unwrap();
+        assert!(body.contains("- [x] Draft outline\n"));
+        let body = ChecklistEdit::Toggle(0).apply(&body).unwrap();
+        assert_eq!(body, BODY);
+
+        let body = ChecklistEdit::Uncheck(3).apply(BODY).unwrap();
+        assert!(body.contains("- [ ] Book room\n"));
+        assert_eq!(ChecklistEdit::Check(1).apply(BODY).unwrap(), BODY);
+    }
+
+    #[test]
+    fn edits_keep_line_endings() {
+        let body = "- [ ] A\r\n- [ ] B";
+        assert_eq!(
+            ChecklistEdit::Check(1).apply(body).unwrap(),
+            "- [ ] A\r\n- [x] B"
+        );
+        assert_eq!(
+            ChecklistEdit::add("C").apply(body).unwrap(),
+            "- [ ] A\r\n- [ ] B\n- [ ] C"
+        );
+        assert_eq!(
+            ChecklistEdit::add("C").apply("- [ ] A\r\n").unwrap(),
+            "- [ ] A\r\n- [ ] C\r\n"
+        );
+    }
+
+    #[test]
+    fn add_after_last_top_level_item() {
+        let body = ChecklistEdit::add("Send invites").apply(BODY).unwrap();
+        assert_eq!(
+            body,
+            BODY.replace("- [X] Book room\n", "- [X] Book room\n- [ ] Send invites\n")
+        );
+    }
+
+    #[test]
+    fn add_under_parent() {
+        let body = ChecklistEdit::add_under(0, "Get review")
+            .apply(BODY)
+            .unwrap();
+        assert!(body.contains("  - [ ] Pick title\n  - [ ] Get review\n- [X] Book room"));
+
+        let body = ChecklistEdit::add_under(3, "Confirm").apply(BODY).unwrap();
+        assert!(body.contains("- [X] Book room\n  - [ ] Confirm\n\nSome notes."));
+    }
+
+    #[test]
+    fn add_continues_numbering() {
+        let body = "1. [ ] One\n2. [ ] Two\n";
+        assert_eq!(
+            ChecklistEdit::add("Three").apply(body).unwrap(),
+            "1. [ ] One\n2. [ ] Two\n3. [ ] Three\n"
+        );
+        assert_eq!(
+            ChecklistEdit::add_under(0, "Sub").apply(body).unwrap(),
+            "1. [ ] One\n   - [ ] Sub\n2. [ ] Two\n"
+        );
+    }
+
+    #[test]
+    fn add_without_checklist_appends() {
+        assert_eq!(
+            ChecklistEdit::add("First").apply("Notes").unwrap(),
+            "Notes\n- [ ] First\n"
+        );
+        assert_eq!(
+            ChecklistEdit::add("First").apply("").unwrap(),
+            "- [ ] First\n"
+        );
+    }
+
+    #[test]
+    fn remove_takes_nested_items() {
+        let body = ChecklistEdit::Remove(0).apply(BODY).unwrap();
+        assert_eq!(body, "## Steps\n\n- [X] Book room\n\nSome notes.\n");
+
+        let body = ChecklistEdit::Remove(2).apply(BODY).unwrap();
+        assert_eq!(
+            texts(&Checklist::parse(&body)),
+            ["Draft outline", "Collect notes", "Book room"]
+        );
+    }
+
+    #[test]
+    fn missing_items_are_rejected() {
+        assert_eq!(ChecklistEdit::Toggle(9).apply(BODY), None);
+        assert_eq!(ChecklistEdit::add_under(9, "x").apply(BODY), None);
+        assert_eq!(ChecklistEdit::Remove(0).apply("no items"), None);
+    }
+}
@@ -1,41 +1,84 @@
 //! Date and datetime value types that preserve original format.
 
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, Timelike, Utc};
 use std::fmt;
 use std::str::FromStr;
 
 /// A date or datetime value, preserving the original format for round-trip serialization.
 ///
 /// The Taskdn spec allows both date (`YYYY-MM-DD`) and datetime (`YYYY-MM-DDTHH:MM:SS`)
-/// formats for certain fields. This enum preserves which format was used so we can
-/// write it back in the same format.
+/// formats for certain fields, optionally followed by a timezone suffix (`Z` or
+/// `+HH:MM`). This enum preserves which format was used so we can write it back
+/// in the same format.
+///
+/// Datetimes without a suffix are in local time, as the spec says. Comparisons
+/// normalise every value to local time first (see [`DateTimeValue::local_datetime`]).
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DateTimeValue {
     /// A date-only value (YYYY-MM-DD).
     Date(NaiveDate),
     /// A datetime value (YYYY-MM-DDTHH:MM:SS).
     DateTime(NaiveDateTime),
+    /// A datetime value with a UTC offset (YYYY-MM-DDTHH:MM:SS+HH:MM).
+    ///
+    /// A zero offset is written as `Z`.
+    DateTimeWithOffset(DateTime<FixedOffset>),
 }
 
 impl DateTimeValue {
-    /// Returns the date portion of this value.
+    /// Returns the date portion of this value, as written.
     #[must_use]
     pub fn date(&self) -> NaiveDate {
         match self {
             Self::Date(d) => *d,
             Self::DateTime(dt) => dt.date(),
+            Self::DateTimeWithOffset(dt) => dt.date_naive(),
         }
     }
 
-    /// Returns the datetime if this is a `DateTime` variant, `None` otherwise.
+    /// Returns the datetime as written (ignoring any offset), or `None` for
+    /// date-only values.
     #[must_use]
     pub fn datetime(&self) -> Option<NaiveDateTime> {
         match self {
             Self::Date(_) => None,
             Self::DateTime(dt) => Some(*dt),
+            Self::DateTimeWithOffset(dt) => Some(dt.naive_local()),
+        }
+    }
+
+    /// Returns the UTC offset if this value has one.
+    #[must_use]
+    pub fn offset(&self) -> Option<FixedOffset> {
+        match self {
+            Self::DateTimeWithOffset(dt) => Some(*dt.offset()),
+            _ => None,
         }
     }
 
+    /// Returns this value as a local datetime.
+    ///
+    /// Date-only values are midnight, datetimes without an offset are taken
+    /// as they are, and datetimes with an offset are converted to the local
+    /// timezone.
+    #[must_use]
+    pub fn local_datetime(&self) -> NaiveDateTime {
+        match self {
+            Self::Date(d) => d.and_hms_opt(0, 0, 0).unwrap_or_default(),
+            Self::DateTime(dt) => *dt,
+            Self::DateTimeWithOffset(dt) => dt.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    /// Returns the local date of this value.
+    ///
+    /// Same as [`date`](Self::date) unless the value has an offset, in which
+    /// case it is the date in the local timezone.
+    #[must_use]
+    pub fn local_date(&self) -> NaiveDate {
+        self.local_datetime().date()
+    }
+
     /// Returns true if this is a date-only value.
     #[must_use]
     pub fn is_date_only(&self) -> bool {
@@ -54,16 +97,60 @@ impl DateTimeValue {
         Self::DateTime(datetime)
     }
 
-    /// Creates a `DateTimeValue` from the current UTC time.
+    /// Creates a new datetime value with a UTC offset.
+    #[must_use]
+    pub fn from_datetime_with_offset(datetime: DateTime<FixedOffset>) -> Self {
+        Self::DateTimeWithOffset(datetime)
+    }
+
+    /// Creates a `DateTimeValue` from the current local time, without an offset.
+    ///
+    /// Use [`TimestampPolicy::now`] for UTC or fixed-offset timestamps.
     #[must_use]
     pub fn now() -> Self {
-        Self::DateTime(chrono::Utc::now().naive_utc())
+        TimestampPolicy::Local.now()
     }
 
-    /// Creates a `DateTimeValue` for today's date (date-only).
+    /// Creates a `DateTimeValue` for today's local date (date-only).
     #[must_use]
     pub fn today() -> Self {
-        Self::Date(chrono::Utc::now().naive_utc().date())
+        Self::Date(Local::now().date_naive())
+    }
+}
+
+/// How the SDK writes the current time into `created-at`, `updated-at` and
+/// `completed-at`.
+///
+/// Set with [`TaskdnConfig::with_timestamp_policy`](crate::TaskdnConfig::with_timestamp_policy).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampPolicy {
+    /// Local time without a suffix (`2025-01-15T14:30:00`), as the spec
+    /// interprets datetimes.
+    #[default]
+    Local,
+    /// UTC with a `Z` suffix (`2025-01-15T13:30:00Z`).
+    Utc,
+    /// Time at a fixed offset, with that offset as suffix
+    /// (`2025-01-15T14:30:00+01:00`).
+    Offset(FixedOffset),
+}
+
+impl TimestampPolicy {
+    /// The current time, truncated to whole seconds, under this policy.
+    #[must_use]
+    pub fn now(self) -> DateTimeValue {
+        let now = Utc::now();
+        self.at(now.with_nanosecond(0).unwrap_or(now))
+    }
+
+    /// The timestamp for `now` under this policy.
+    #[must_use]
+    pub fn at(self, now: DateTime<Utc>) -> DateTimeValue {
+        match self {
+            Self::Local => DateTimeValue::DateTime(now.with_timezone(&Local).naive_local()),
+            Self::Utc => DateTimeValue::DateTimeWithOffset(now.fixed_offset()),
+            Self::Offset(offset) => DateTimeValue::DateTimeWithOffset(now.with_timezone(&offset)),
+        }
     }
 }
 
@@ -72,6 +159,10 @@ impl fmt::Display for DateTimeValue {
         match self {
             Self::Date(d) => write!(f, "{}", d.format("%Y-%m-%d")),
             Self::DateTime(dt) => write!(f, "{}", dt.format("%Y-%m-%dT%H:%M:%S")),
+            Self::DateTimeWithOffset(dt) if dt.offset().local_minus_utc() == 0 => {
+                write!(f, "{}Z", dt.format("%Y-%m-%dT%H:%M:%S"))
+            }
+            Self::DateTimeWithOffset(dt) => write!(f, "{}", dt.format("%Y-%m-%dT%H:%M:%S%:z")),
         }
     }
 }
@@ -80,6 +171,16 @@ impl FromStr for DateTimeValue {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Datetime with a timezone suffix: any of the datetime formats below
+        // followed by `Z` or `+HH:MM`
+        if let Some((datetime, offset)) = split_offset(s) {
+            if let Ok(Self::DateTime(dt)) = datetime.parse() {
+                if let Some(dt) = dt.and_local_timezone(offset).single() {
+                    return Ok(Self::DateTimeWithOffset(dt));
+                }
+            }
+        }
+
         // Try datetime formats (more specific first)
         // ISO format with seconds: YYYY-MM-DDTHH:MM:SS
         if let Ok(dt) = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S") {
@@ -110,6 +211,27 @@ impl FromStr for DateTimeValue {
     }
 }
 
+/// Split a trailing `Z` or `+HH:MM`/`-HH:MM` offset off a datetime string.
+fn split_offset(s: &str) -> Option<(&str, FixedOffset)> {
+    if let Some(datetime) = s.strip_suffix('Z') {
+        return Some((datetime, FixedOffset::east_opt(0)?));
+    }
+
+    let split = s.len().checked_sub(6)?;
+    let (datetime, suffix) = (s.get(..split)?, s.get(split..)?);
+    let sign = match suffix.as_bytes()[0] {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let (hours, minutes) = suffix[1..].split_once(':')?;
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    let seconds = hours.parse::<i32>().ok()? * 3600 + minutes.parse::<i32>().ok()? * 60;
+    Some((datetime, FixedOffset::east_opt(sign * seconds)?))
+}
+
 impl From<NaiveDate> for DateTimeValue {
     fn from(date: NaiveDate) -> Self {
         Self::Date(date)
@@ -122,6 +244,12 @@ impl From<NaiveDateTime> for DateTimeValue {
     }
 }
 
+impl From<DateTime<FixedOffset>> for DateTimeValue {
+    fn from(datetime: DateTime<FixedOffset>) -> Self {
+        Self::DateTimeWithOffset(datetime)
+    }
+}
+
 impl PartialOrd for DateTimeValue {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
         Some(self.cmp(other))
@@ -130,16 +258,8 @@ impl PartialOrd for DateTimeValue {
 
 impl Ord for DateTimeValue {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        // Compare as datetimes, treating date-only as midnight
-        let self_dt = match self {
-            Self::Date(d) => d.and_hms_opt(0, 0, 0).unwrap_or_default(),
-            Self::DateTime(dt) => *dt,
-        };
-        let other_dt = match other {
-            Self::Date(d) => d.and_hms_opt(0, 0, 0).unwrap_or_default(),
-            Self::DateTime(dt) => *dt,
-        };
-        self_dt.cmp(&other_dt)
+        // Compare as local datetimes, treating date-only as midnight
+        self.local_datetime().cmp(&other.local_datetime())
     }
 }
 
@@ -228,6 +348,93 @@ mod tests {
         assert!(date_value < datetime_value); // date is midnight, so earlier than noon
     }
 
+    #[test]
+    fn parse_datetime_with_offset() {
+        let value: DateTimeValue = "2025-01-15T14:30:00+01:00".parse().unwrap();
+        assert!(!value.is_date_only());
+        assert_eq!(value.offset(), FixedOffset::east_opt(3600));
+        assert_eq!(value.date(), NaiveDate::from_ymd_opt(2025, 1, 15).unwrap());
+        let dt = value.datetime().unwrap();
+        assert_eq!(dt.format("%H:%M").to_string(), "14:30");
+
+        let value: DateTimeValue = "2025-01-15 14:30-05:00".parse().unwrap();
+        assert_eq!(value.offset(), FixedOffset::west_opt(5 * 3600));
+    }
+
+    #[test]
+    fn parse_datetime_with_z() {
+        let value: DateTimeValue = "2025-01-15T14:30:00Z".parse().unwrap();
+        assert_eq!(value.offset(), FixedOffset::east_opt(0));
+        assert_eq!(value.to_string(), "2025-01-15T14:30:00Z");
+    }
+
+    #[test]
+    fn display_preserves_offset() {
+        for s in [
+            "2025-01-15T14:30:00Z",
+            "2025-01-15T14:30:00+01:00",
+            "2025-01-15T14:30:00-05:30",
+        ] {
+            let value: DateTimeValue = s.parse().unwrap();
+            assert_eq!(value.to_string(), s);
+        }
+
+        // A zero offset is written as Z
+        let value: DateTimeValue = "2025-01-15T14:30:00+00:00".parse().unwrap();
+        assert_eq!(value.to_string(), "2025-01-15T14:30:00Z");
+    }
+
+    #[test]
+    fn invalid_offsets_return_error() {
+        for s in [
+            "2025-01-15T14:30:00+1:00",
+            "2025-01-15T14:30:00+25:00",
+            "2025-01-15Z",
+        ] {
+            assert!(s.parse::<DateTimeValue>().is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn offset_values_order_by_instant() {
+        let utc: DateTimeValue = "2025-01-15T14:30:00Z".parse().unwrap();
+        let earlier: DateTimeValue = "2025-01-15T15:00:00+01:00".parse().unwrap();
+        let later: DateTimeValue = "2025-01-15T10:00:00-05:00".parse().unwrap();
+        assert!(earlier < utc);
+        assert!(utc < later);
+    }
+
+    #[test]
+    fn local_datetime_normalises_offsets() {
+        let value: DateTimeValue = "2025-01-15T14:30:00Z".parse().unwrap();
+        let expected = "2025-01-15T14:30:00Z"
+            .parse::<DateTime<Utc>>()
+            .unwrap()
+            .with_timezone(&Local)
+            .naive_local();
+        assert_eq!(value.local_datetime(), expected);
+
+        let naive: DateTimeValue = "2025-01-15T14:30:00".parse().unwrap();
+        assert_eq!(naive.local_datetime(), naive.datetime().unwrap());
+    }
+
+    #[test]
+    fn timestamp_policies() {
+        let local = TimestampPolicy::Local.now();
+        assert!(local.offset().is_none());
+        assert!(!local.is_date_only());
+
+        let utc = TimestampPolicy::Utc.now();
+        assert_eq!(utc.offset(), FixedOffset::east_opt(0));
+        assert!(utc.to_string().ends_with('Z'));
+
+        let offset = FixedOffset::east_opt(5 * 3600 + 1800).unwrap();
+        let value = TimestampPolicy::Offset(offset).now();
+        assert_eq!(value.offset(), Some(offset));
+        assert!(value.to_string().ends_with("+05:30"));
+        assert_eq!(value.to_string().parse::<DateTimeValue>().unwrap(), value);
+    }
+
     #[test]
     fn invalid_format_returns_error() {
         let result: Result<DateTimeValue, _> = "not-a-date".parse();
@@ -4,13 +4,26 @@
 //! creation/update types, and supporting types like `DateTimeValue` and `FileReference`.
 
 mod area;
+mod checklist;
 mod datetime;
+mod priority;
 mod project;
+mod recurrence;
 mod reference;
+mod revision;
+mod summary;
+mod tag;
 mod task;
 
 pub use area::{Area, AreaStatus, AreaUpdates, NewArea, ParsedArea};
-pub use datetime::DateTimeValue;
+pub use checklist::{Checklist, ChecklistEdit, ChecklistItem};
+pub use datetime::{DateTimeValue, TimestampPolicy};
+pub use priority::Priority;
 pub use project::{NewProject, ParsedProject, Project, ProjectStatus, ProjectUpdates};
+pub use recurrence::{Frequency, Recurrence, RecurrenceBasis};
 pub use reference::FileReference;
+pub use revision::{ExpectedRevision, Revision};
+pub use summary::ProgressSummary;
+pub(crate) use tag::tags_from;
+pub use tag::{Tag, TagCount};
 pub use task::{NewTask, ParsedTask, Task, TaskStatus, TaskUpdates};
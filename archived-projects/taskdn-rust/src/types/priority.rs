@@ -0,0 +1,205 @@
+//! Task priority.
+
+use std::cmp::Ordering;
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// Priority of a task.
+///
+/// Parsed leniently from the `priority` frontmatter field: `high`, `medium`
+/// and `low` in any case (also `hi`/`h`, `med`/`m`/`normal`, `lo`/`l`), or an
+/// integer. Any other scalar is kept as [`Priority::Other`] and written back
+/// exactly as it was spelled, so `2.5` stays a number and `true` a bool.
+///
+/// Priorities are ranked on one scale where higher is more important: `low`
+/// is 1, `medium` 2, `high` 3, and an integer is its own value. `Other`
+/// values have no rank; they never match priority filters and sort last.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// Low priority (rank 1).
+    Low,
+    /// Medium priority (rank 2).
+    Medium,
+    /// High priority (rank 3).
+    High,
+    /// A numeric priority, ranked by its value.
+    Numeric(i64),
+    /// An unrecognised scalar (a string, float or bool), preserved as written.
+    Other(serde_yaml::Value),
+}
+
+impl Priority {
+    /// Returns the rank of this priority (higher is more important), or
+    /// `None` for an unrecognised value.
+    #[must_use]
+    pub fn rank(&self) -> Option<i64> {
+        match self {
+            Self::Low => Some(1),
+            Self::Medium => Some(2),
+            Self::High => Some(3),
+            Self::Numeric(n) => Some(*n),
+            Self::Other(_) => None,
+        }
+    }
+
+    /// Compare by rank, most important first, with unranked values last.
+    pub(crate) fn cmp_importance(a: Option<&Self>, b: Option<&Self>) -> Ordering {
+        let rank = |p: Option<&Self>| p.and_then(Self::rank);
+        match (rank(a), rank(b)) {
+            (Some(a), Some(b)) => b.cmp(&a),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+
+    /// Parse a priority from a YAML value.
+    ///
+    /// Strings are parsed as by [`FromStr`], integers become
+    /// [`Priority::Numeric`], and any other scalar is kept as it is.
+    pub(crate) fn from_yaml(value: &serde_yaml::Value) -> Option<Self> {
+        match value {
+            serde_yaml::Value::String(s) => s.parse().ok(),
+            serde_yaml::Value::Number(n) => Some(match n.as_i64() {
+                Some(n) => Self::Numeric(n),
+                None => Self::Other(value.clone()),
+            }),
+            serde_yaml::Value::Bool(_) => Some(Self::Other(value.clone())),
+            _ => None,
+        }
+    }
+
+    /// The YAML value [`Priority::from_yaml`] parses back into this priority.
+    pub(crate) fn to_yaml(&self) -> serde_yaml::Value {
+        match self {
+            Self::Numeric(n) => (*n).into(),
+            Self::Other(value) => value.clone(),
+            named => named.to_string().into(),
+        }
+    }
+}
+
+impl FromStr for Priority {
+    type Err = Infallible;
+
+    /// Parse leniently; unrecognised input becomes [`Priority::Other`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        Ok(match trimmed.to_lowercase().as_str() {
+            "high" | "hi" | "h" => Self::High,
+            "medium" | "med" | "m" | "normal" => Self::Medium,
+            "low" | "lo" | "l" => Self::Low,
+            lower => match lower.parse() {
+                Ok(n) => Self::Numeric(n),
+                Err(_) => Self::Other(s.into()),
+            },
+        })
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Low => write!(f, "low"),
+            Self::Medium => write!(f, "medium"),
+            Self::High => write!(f, "high"),
+            Self::Numeric(n) => write!(f, "{n}"),
+            Self::Other(serde_yaml::Value::String(s)) => write!(f, "{s}"),
+            Self::Other(value) => {
+                let yaml = serde_yaml::to_string(value).map_err(|_| fmt::Error)?;
+                write!(f, "{}", yaml.trim_end())
+            }
+        }
+    }
+}
+
+impl From<i64> for Priority {
+    fn from(n: i64) -> Self {
+        Self::Numeric(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Priority {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_named_levels_leniently() {
+        assert_eq!(parse("high"), Priority::High);
+        assert_eq!(parse("HIGH"), Priority::High);
+        assert_eq!(parse("Hi"), Priority::High);
+        assert_eq!(parse(" medium "), Priority::Medium);
+        assert_eq!(parse("normal"), Priority::Medium);
+        assert_eq!(parse("Low"), Priority::Low);
+    }
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(parse("5"), Priority::Numeric(5));
+        assert_eq!(parse("-1"), Priority::Numeric(-1));
+    }
+
+    #[test]
+    fn keeps_unknown_spellings() {
+        assert_eq!(parse("P1 (urgent)"), Priority::Other("P1 (urgent)".into()));
+        assert_eq!(parse("P1 (urgent)").to_string(), "P1 (urgent)");
+        assert_eq!(parse("P1 (urgent)").rank(), None);
+    }
+
+    #[test]
+    fn from_yaml_values() {
+        let yaml = |s: &str| serde_yaml::from_str::<serde_yaml::Value>(s).unwrap();
+        assert_eq!(Priority::from_yaml(&yaml("high")), Some(Priority::High));
+        assert_eq!(Priority::from_yaml(&yaml("2")), Some(Priority::Numeric(2)));
+        assert_eq!(
+            Priority::from_yaml(&yaml("2.5")),
+            Some(Priority::Other(yaml("2.5")))
+        );
+        assert_eq!(
+            Priority::from_yaml(&yaml("true")),
+            Some(Priority::Other(true.into()))
+        );
+        assert_eq!(Priority::from_yaml(&yaml("null")), None);
+        assert_eq!(Priority::from_yaml(&yaml("[a]")), None);
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for p in [
+            Priority::Low,
+            Priority::Medium,
+            Priority::High,
+            Priority::Numeric(7),
+            Priority::Other("someday".into()),
+        ] {
+            assert_eq!(parse(&p.to_string()), p);
+        }
+    }
+
+    #[test]
+    fn importance_order() {
+        let mut priorities = [
+            None,
+            Some(Priority::Other("x".into())),
+            Some(Priority::Low),
+            Some(Priority::Numeric(5)),
+            Some(Priority::High),
+            Some(Priority::Medium),
+        ];
+        priorities.sort_by(|a, b| Priority::cmp_importance(a.as_ref(), b.as_ref()));
+        assert_eq!(
+            priorities[..4],
+            [
+                Some(Priority::Numeric(5)),
+                Some(Priority::High),
+                Some(Priority::Medium),
+                Some(Priority::Low),
+            ]
+        );
+    }
+}
@@ -1,11 +1,17 @@
 //! Project entity and related types.
 
-use super::FileReference;
+use super::tag::{merge_tags, tags_from};
+use super::{Checklist, FileReference, Tag};
+use crate::validation::{unparsed_fields, ValidationWarning};
 use chrono::NaiveDate;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+/// Typed fields whose unparseable values are kept in `extra` instead of
+/// failing the whole file.
+const TYPED_FIELDS: &[&str] = &["blocked-by"];
+
 /// Status of a project.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[non_exhaustive]
@@ -99,6 +105,8 @@ pub struct Project {
     pub end_date: Option<NaiveDate>,
     /// Projects that must complete before this one can proceed.
     pub blocked_by: Vec<FileReference>,
+    /// Tags from the `tags` field (see [`Project::all_tags`] for inline tags too).
+    pub tags: Vec<Tag>,
 
     /// Markdown body.
     pub body: String,
@@ -112,6 +120,30 @@ impl Project {
     pub fn filename(&self) -> &str {
         self.path.file_name().and_then(|n| n.to_str()).unwrap_or("")
     }
+
+    /// Tags from the `tags` field followed by inline `#tags` in the body,
+    /// without duplicates.
+    #[must_use]
+    pub fn all_tags(&self) -> Vec<Tag> {
+        merge_tags([&self.tags[..], &Tag::inline(&self.body)[..]])
+    }
+
+    /// The `- [ ]` checklist items in the body.
+    #[must_use]
+    pub fn checklist(&self) -> Checklist {
+        Checklist::parse(&self.body)
+    }
+
+    /// Validate this project against the specification.
+    ///
+    /// Returns a list of warnings for spec violations. Dependency cycles
+    /// need the other projects and are reported by [`ProjectGraph`] instead.
+    ///
+    /// [`ProjectGraph`]: crate::ProjectGraph
+    #[must_use]
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        unparsed_fields(&self.extra, TYPED_FIELDS)
+    }
 }
 
 /// Parsed project content without a file path.
@@ -133,6 +165,8 @@ pub struct ParsedProject {
     pub end_date: Option<NaiveDate>,
     /// Blocking projects.
     pub blocked_by: Vec<FileReference>,
+    /// Tags from the `tags` field.
+    pub tags: Vec<Tag>,
     /// Markdown body.
     pub body: String,
     /// Unknown frontmatter fields.
@@ -153,6 +187,7 @@ impl ParsedProject {
             start_date: self.start_date,
             end_date: self.end_date,
             blocked_by: self.blocked_by,
+            tags: self.tags,
             body: self.body,
             extra: self.extra,
         }
@@ -176,6 +211,8 @@ pub struct NewProject {
     pub start_date: Option<NaiveDate>,
     /// End date.
     pub end_date: Option<NaiveDate>,
+    /// Tags.
+    pub tags: Vec<Tag>,
     /// Markdown body.
     pub body: String,
     /// Additional frontmatter fields.
@@ -234,6 +271,13 @@ impl NewProject {
         self
     }
 
+    /// Set the tags (a leading `#` is stripped).
+    #[must_use]
+    pub fn with_tags<S: AsRef<str>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags = tags_from(tags);
+        self
+    }
+
     /// Set the body content.
     #[must_use]
     pub fn with_body(mut self, body: impl Into<String>) -> Self {
@@ -257,6 +301,12 @@ pub struct ProjectUpdates {
     pub start_date: Option<Option<NaiveDate>>,
     /// New end date.
     pub end_date: Option<Option<NaiveDate>>,
+    /// New blocking project references (None = don't change, Some(empty) = clear).
+    pub blocked_by: Option<Vec<FileReference>>,
+    /// New tags (None = don't change, Some(empty) = clear).
+    pub tags: Option<Vec<Tag>>,
+    /// New markdown body.
+    pub body: Option<String>,
 }
 
 impl ProjectUpdates {
@@ -343,6 +393,44 @@ impl ProjectUpdates {
         self
     }
 
+    /// Replace the projects this one waits on.
+    #[must_use]
+    pub fn blocked_by<R: Into<FileReference>>(
+        mut self,
+        blockers: impl IntoIterator<Item = R>,
+    ) -> Self {
+        self.blocked_by = Some(blockers.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Clear the projects this one waits on.
+    #[must_use]
+    pub fn clear_blocked_by(mut self) -> Self {
+        self.blocked_by = Some(Vec::new());
+        self
+    }
+
+    /// Replace the tags (a leading `#` is stripped).
+    #[must_use]
+    pub fn tags<S: AsRef<str>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags = Some(tags_from(tags));
+        self
+    }
+
+    /// Remove all tags.
+    #[must_use]
+    pub fn clear_tags(mut self) -> Self {
+        self.tags = Some(Vec::new());
+        self
+    }
+
+    /// Replace the markdown body.
+    #[must_use]
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
     /// Returns true if no updates are specified.
     #[must_use]
     pub fn is_empty(&self) -> bool {
@@ -352,6 +440,41 @@ impl ProjectUpdates {
             && self.area.is_none()
             && self.start_date.is_none()
             && self.end_date.is_none()
+            && self.blocked_by.is_none()
+            && self.tags.is_none()
+            && self.body.is_none()
+    }
+
+    /// Apply these updates to a project in place.
+    pub(crate) fn apply_to(self, project: &mut Project) {
+        if let Some(title) = self.title {
+            project.title = title;
+        }
+        if let Some(status) = self.status {
+            project.status = status;
+        }
+        if let Some(description) = self.description {
+            project.description = description;
+        }
+        if let Some(area) = self.area {
+            project.area = area;
+        }
+        if let Some(start_date) = self.start_date {
+            project.start_date = start_date;
+        }
+        if let Some(end_date) = self.end_date {
+            project.end_date = end_date;
+        }
+        if let Some(blocked_by) = self.blocked_by {
+            project.extra.remove("blocked-by");
+            project.blocked_by = blocked_by;
+        }
+        if let Some(tags) = self.tags {
+            project.tags = tags;
+        }
+        if let Some(body) = self.body {
+            project.body = body;
+        }
     }
 }
 
@@ -422,6 +545,7 @@ mod tests {
                 start_date: None,
                 end_date: None,
                 blocked_by: Vec::new(),
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
             }
@@ -0,0 +1,483 @@
+//! Recurrence rules for repeating tasks.
+
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+use std::fmt;
+use std::str::FromStr;
+
+/// How often a recurring task repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Frequency {
+    /// Every N days.
+    Daily,
+    /// Every N weeks.
+    Weekly,
+    /// Every N months.
+    Monthly,
+}
+
+impl Frequency {
+    /// Returns the RRULE name (`DAILY`, `WEEKLY`, `MONTHLY`).
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Daily => "DAILY",
+            Self::Weekly => "WEEKLY",
+            Self::Monthly => "MONTHLY",
+        }
+    }
+}
+
+/// Which date the next occurrence is counted from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum RecurrenceBasis {
+    /// From the task's due date (or scheduled or defer-until date if it has
+    /// no due date), so the schedule doesn't slip when a task is done late.
+    #[default]
+    Due,
+    /// From the day the task was completed.
+    Completion,
+}
+
+/// A recurrence rule, written in frontmatter as an RRULE-style string.
+///
+/// ```text
+/// recurrence: FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH
+/// recurrence: FREQ=MONTHLY;FROM=COMPLETION
+/// ```
+///
+/// `INTERVAL` defaults to 1. `BYDAY` restricts daily and weekly rules to the
+/// given weekdays. `FROM=COMPLETION` counts from the completion date instead
+/// of the due date (see [`RecurrenceBasis`]).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Recurrence {
+    /// How often the task repeats.
+    pub frequency: Frequency,
+    /// Repeat every `interval` days, weeks or months (at least 1).
+    pub interval: u32,
+    /// Weekdays a daily or weekly rule falls on. Empty means any day.
+    /// Ignored for monthly rules.
+    pub weekdays: Vec<Weekday>,
+    /// Which date the next occurrence is counted from.
+    pub basis: RecurrenceBasis,
+}
+
+impl Recurrence {
+    /// Create a rule repeating every day.
+    #[must_use]
+    pub fn daily() -> Self {
+        Self::new(Frequency::Daily)
+    }
+
+    /// Create a rule repeating every week.
+    #[must_use]
+    pub fn weekly() -> Self {
+        Self::new(Frequency::Weekly)
+    }
+
+    /// Create a rule repeating every month.
+    #[must_use]
+    pub fn monthly() -> Self {
+        Self::new(Frequency::Monthly)
+    }
+
+    fn new(frequency: Frequency) -> Self {
+        Self {
+            frequency,
+            interval: 1,
+            weekdays: Vec::new(),
+            basis: RecurrenceBasis::Due,
+        }
+    }
+
+    /// Repeat every `interval` periods instead of every one.
+    #[must_use]
+    pub fn every(mut self, interval: u32) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Restrict a daily or weekly rule to these weekdays.
+    #[must_use]
+    pub fn on(mut self, weekdays: impl IntoIterator<Item = Weekday>) -> Self {
+        self.weekdays = weekdays.into_iter().collect();
+        self
+    }
+
+    /// Count the next occurrence from the completion date.
+    #[must_use]
+    pub fn from_completion(mut self) -> Self {
+        self.basis = RecurrenceBasis::Completion;
+        self
+    }
+
+    /// The first occurrence after `date`.
+    ///
+    /// Monthly rules keep the day of the month, clamped to the last day of
+    /// shorter months. Returns `None` if no occurrence is representable (for
+    /// example an interval of 0).
+    #[must_use]
+    pub fn next_after(&self, date: NaiveDate) -> Option<NaiveDate> {
+        if self.interval == 0 {
+            return None;
+        }
+        let interval = i64::from(self.interval);
+
+        match self.frequency {
+            Frequency::Daily => {
+                // Seven steps cover every weekday unless the interval is a
+                // multiple of 7, in which case the weekday never changes
+                let mut candidate = date;
+                for _ in 0..7 {
+                    candidate = candidate.checked_add_signed(Duration::days(interval))?;
+                    if self.falls_on(candidate.weekday()) {
+                        return Some(candidate);
+                    }
+                }
+                None
+            }
+            Frequency::Weekly if self.weekdays.is_empty() => {
+                date.checked_add_signed(Duration::weeks(interval))
+            }
+            Frequency::Weekly => {
+                let days = self.sorted_weekdays();
+                let week_start = date.checked_sub_signed(Duration::days(i64::from(
+                    date.weekday().num_days_from_monday(),
+                )))?;
+
+                // A later day in the same week, or the first day `interval` weeks on
+                let later = days
+                    .iter()
+                    .find(|d| **d > date.weekday().num_days_from_monday());
+                match later {
+                    Some(day) => week_start.checked_add_signed(Duration::days(i64::from(*day))),
+                    None => week_start
+                        .checked_add_signed(Duration::weeks(interval))?
+                        .checked_add_signed(Duration::days(i64::from(days[0]))),
+                }
+            }
+            Frequency::Monthly => date.checked_add_months(Months::new(self.interval)),
+        }
+    }
+
+    /// The occurrences following `date`, in order.
+    ///
+    /// Monthly occurrences are counted from `date` itself, so a series that
+    /// starts on the 31st returns to the 31st after a shorter month.
+    pub fn occurrences_after(&self, date: NaiveDate) -> impl Iterator<Item = NaiveDate> + '_ {
+        let mut previous = date;
+        (1..=u32::MAX).map_while(move |n| {
+            let next = match self.frequency {
+                Frequency::Monthly if self.interval > 0 => {
+                    date.checked_add_months(Months::new(self.interval.checked_mul(n)?))
+                }
+                _ => self.next_after(previous),
+            }?;
+            previous = next;
+            Some(next)
+        })
+    }
+
+    fn falls_on(&self, weekday: Weekday) -> bool {
+        self.weekdays.is_empty() || self.weekdays.contains(&weekday)
+    }
+
+    /// Weekdays as days from Monday, sorted and without duplicates.
+    fn sorted_weekdays(&self) -> Vec<u32> {
+        let mut days: Vec<u32> = self
+            .weekdays
+            .iter()
+            .map(Weekday::num_days_from_monday)
+            .collect();
+        days.sort_unstable();
+        days.dedup();
+        days
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={}", self.frequency.as_str())?;
+        if self.interval != 1 {
+            write!(f, ";INTERVAL={}", self.interval)?;
+        }
+        if !self.weekdays.is_empty() {
+            let days: Vec<_> = self.weekdays.iter().map(|d| weekday_code(*d)).collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if self.basis == RecurrenceBasis::Completion {
+            write!(f, ";FROM=COMPLETION")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Recurrence {
+    type Err = String;
+
+    /// Parse an RRULE-style string (case-insensitive, optional `RRULE:` prefix).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let upper = s.trim().to_uppercase();
+        let rule = upper.strip_prefix("RRULE:").unwrap_or(&upper);
+
+        let mut frequency = None;
+        let mut recurrence = Self::daily();
+        for part in rule.split(';').filter(|p| !p.is_empty()) {
+            let (key, value) = part
+                .split_once('=')
+                .ok_or_else(|| format!("invalid recurrence part: {part}"))?;
+            match key {
+                "FREQ" => {
+                    frequency = Some(match value {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        _ => return Err(format!("unsupported recurrence frequency: {value}")),
+                    });
+                }
+                "INTERVAL" => {
+                    recurrence.interval = value
+                        .parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or_else(|| format!("invalid recurrence interval: {value}"))?;
+                }
+                "BYDAY" => {
+                    recurrence.weekdays = value
+                        .split(',')
+                        .map(|code| {
+                            parse_weekday_code(code)
+                                .ok_or_else(|| format!("invalid recurrence weekday: {code}"))
+                        })
+                        .collect::<Result<_, _>>()?;
+                }
+                "FROM" => {
+                    recurrence.basis = match value {
+                        "DUE" => RecurrenceBasis::Due,
+                        "COMPLETION" => RecurrenceBasis::Completion,
+                        _ => return Err(format!("invalid recurrence basis: {value}")),
+                    };
+                }
+                _ => return Err(format!("unsupported recurrence part: {key}")),
+            }
+        }
+
+        recurrence.frequency = frequency.ok_or("recurrence is missing FREQ")?;
+        if recurrence.frequency == Frequency::Monthly && !recurrence.weekdays.is_empty() {
+            return Err("BYDAY is not supported for monthly recurrence".to_string());
+        }
+        Ok(recurrence)
+    }
+}
+
+fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+fn parse_weekday_code(code: &str) -> Option<Weekday> {
+    Some(match code {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    mod parsing {
+        use super::*;
+
+        #[test]
+        fn parses_rrule_parts() {
+            let rule: Recurrence = "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;FROM=COMPLETION"
+                .parse()
+                .unwrap();
+            assert_eq!(
+                rule,
+                Recurrence::weekly()
+                    .every(2)
+                    .on([Weekday::Mon, Weekday::Thu])
+                    .from_completion()
+            );
+        }
+
+        #[test]
+        fn accepts_prefix_and_lowercase() {
+            let rule: Recurrence = "RRULE:freq=daily".parse().unwrap();
+            assert_eq!(rule, Recurrence::daily());
+        }
+
+        #[test]
+        fn display_round_trips() {
+            for s in [
+                "FREQ=DAILY",
+                "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH",
+                "FREQ=MONTHLY;INTERVAL=3;FROM=COMPLETION",
+            ] {
+                let rule: Recurrence = s.parse().unwrap();
+                assert_eq!(rule.to_string(), s);
+            }
+        }
+
+        #[test]
+        fn rejects_invalid_rules() {
+            for s in [
+                "",
+                "INTERVAL=2",
+                "FREQ=YEARLY",
+                "FREQ=DAILY;INTERVAL=0",
+                "FREQ=WEEKLY;BYDAY=XX",
+                "FREQ=MONTHLY;BYDAY=MO",
+                "FREQ=DAILY;COUNT=3",
+                "FREQ=DAILY;FROM=START",
+                "weekly",
+            ] {
+                assert!(s.parse::<Recurrence>().is_err(), "{s}");
+            }
+        }
+    }
+
+    mod next_after {
+        use super::*;
+
+        #[test]
+        fn daily() {
+            assert_eq!(
+                Recurrence::daily().next_after(date("2025-01-31")),
+                Some(date("2025-02-01"))
+            );
+            assert_eq!(
+                Recurrence::daily().every(3).next_after(date("2025-01-31")),
+                Some(date("2025-02-03"))
+            );
+        }
+
+        #[test]
+        fn daily_on_weekdays_skips_weekends() {
+            let rule = Recurrence::daily().on([
+                Weekday::Mon,
+                Weekday::Tue,
+                Weekday::Wed,
+                Weekday::Thu,
+                Weekday::Fri,
+            ]);
+            // Friday -> Monday
+            assert_eq!(
+                rule.next_after(date("2025-01-17")),
+                Some(date("2025-01-20"))
+            );
+        }
+
+        #[test]
+        fn weekly() {
+            assert_eq!(
+                Recurrence::weekly().every(2).next_after(date("2025-01-16")),
+                Some(date("2025-01-30"))
+            );
+        }
+
+        #[test]
+        fn weekly_on_weekdays() {
+            let rule = Recurrence::weekly()
+                .every(2)
+                .on([Weekday::Thu, Weekday::Mon]);
+            // Monday -> Thursday the same week
+            assert_eq!(
+                rule.next_after(date("2025-01-13")),
+                Some(date("2025-01-16"))
+            );
+            // Thursday -> Monday two weeks on
+            assert_eq!(
+                rule.next_after(date("2025-01-16")),
+                Some(date("2025-01-27"))
+            );
+            // Sunday -> Monday two weeks after that week's Monday
+            assert_eq!(
+                rule.next_after(date("2025-01-19")),
+                Some(date("2025-01-27"))
+            );
+        }
+
+        #[test]
+        fn monthly_clamps_to_month_end() {
+            assert_eq!(
+                Recurrence::monthly().next_after(date("2025-01-31")),
+                Some(date("2025-02-28"))
+            );
+            assert_eq!(
+                Recurrence::monthly()
+                    .every(3)
+                    .next_after(date("2025-01-15")),
+                Some(date("2025-04-15"))
+            );
+        }
+
+        #[test]
+        fn monthly_occurrences_keep_the_starting_day() {
+            let upcoming: Vec<_> = Recurrence::monthly()
+                .occurrences_after(date("2025-01-31"))
+                .take(4)
+                .collect();
+            assert_eq!(
+                upcoming,
+                vec![
+                    date("2025-02-28"),
+                    date("2025-03-31"),
+                    date("2025-04-30"),
+                    date("2025-05-31"),
+                ]
+            );
+        }
+
+        #[test]
+        fn zero_interval_never_recurs() {
+            assert_eq!(
+                Recurrence::daily().every(0).next_after(date("2025-01-15")),
+                None
+            );
+            assert_eq!(
+                Recurrence::monthly()
+                    .every(0)
+                    .occurrences_after(date("2025-01-15"))
+                    .next(),
+                None
+            );
+        }
+
+        #[test]
+        fn occurrences_follow_each_other() {
+            let upcoming: Vec<_> = Recurrence::weekly()
+                .on([Weekday::Tue, Weekday::Fri])
+                .occurrences_after(date("2025-01-14"))
+                .take(4)
+                .collect();
+            assert_eq!(
+                upcoming,
+                vec![
+                    date("2025-01-17"),
+                    date("2025-01-21"),
+                    date("2025-01-24"),
+                    date("2025-01-28"),
+                ]
+            );
+        }
+    }
+}
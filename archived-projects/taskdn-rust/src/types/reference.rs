@@ -1,6 +1,7 @@
 //! File reference types for `WikiLink`s and path references.
 
 use std::fmt;
+use std::path::Path;
 
 /// References to other files, as stored in frontmatter.
 ///
@@ -101,6 +102,53 @@ impl FileReference {
         }
     }
 
+    /// Check whether this reference points to the file at `path`.
+    ///
+    /// - `WikiLink`: matches the file stem or the entity's `title`
+    /// - `Filename`: matches the file name
+    /// - `RelativePath`: matches if the path's last component is the file name
+    pub(crate) fn points_to(&self, path: &Path, title: &str) -> bool {
+        let filename = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let stem = path.file_stem().and_then(|n| n.to_str()).unwrap_or("");
+
+        match self {
+            Self::WikiLink { target, .. } => target == stem || target == title,
+            Self::Filename(name) => name == filename,
+            Self::RelativePath(rel_path) => Path::new(rel_path).file_name() == path.file_name(),
+        }
+    }
+
+    /// Rewrite this reference, which points to `from`, to point to `to` instead.
+    ///
+    /// Keeps the reference's format:
+    /// - `WikiLink`: a link by file stem gets the new stem, a link by title gets
+    ///   `to_title`; display text is kept
+    /// - `Filename`: the new file name
+    /// - `RelativePath`: the directory part is kept and the file name replaced
+    pub(crate) fn retargeted(&self, from: &Path, to: &Path, to_title: &str) -> Self {
+        let from_filename = from.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let from_stem = from.file_stem().and_then(|n| n.to_str()).unwrap_or("");
+        let to_filename = to.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        let to_stem = to.file_stem().and_then(|n| n.to_str()).unwrap_or("");
+
+        match self {
+            Self::WikiLink { target, display } => Self::WikiLink {
+                target: if target == from_stem {
+                    to_stem
+                } else {
+                    to_title
+                }
+                .to_string(),
+                display: display.clone(),
+            },
+            Self::Filename(_) => Self::Filename(to_filename.to_string()),
+            Self::RelativePath(rel_path) => {
+                let dir = rel_path.strip_suffix(from_filename).unwrap_or("./");
+                Self::RelativePath(format!("{dir}{to_filename}"))
+            }
+        }
+    }
+
     /// Creates a `WikiLink` reference.
     #[must_use]
     pub fn wiki_link(target: impl Into<String>) -> Self {
@@ -286,4 +334,40 @@ mod tests {
             FileReference::WikiLink { target, display: None } if target == "Page Name"
         ));
     }
+
+    #[test]
+    fn points_to_matches_each_reference_format() {
+        let path = Path::new("/vault/projects/q1-planning.md");
+
+        assert!(FileReference::parse("[[q1-planning]]").points_to(path, "Q1 Planning"));
+        assert!(FileReference::parse("[[Q1 Planning]]").points_to(path, "Q1 Planning"));
+        assert!(FileReference::parse("q1-planning.md").points_to(path, "Q1 Planning"));
+        assert!(FileReference::parse("./projects/q1-planning.md").points_to(path, "Q1 Planning"));
+
+        assert!(!FileReference::parse("[[Other]]").points_to(path, "Q1 Planning"));
+        assert!(!FileReference::parse("other.md").points_to(path, "Q1 Planning"));
+        assert!(
+            !FileReference::parse("./projects/old-q1-planning.md").points_to(path, "Q1 Planning")
+        );
+    }
+
+    #[test]
+    fn retargeted_keeps_reference_format() {
+        let from = Path::new("/vault/projects/q1-planning.md");
+        let to = Path::new("/vault/projects/q2-planning.md");
+        let retarget = |s: &str| {
+            FileReference::parse(s)
+                .retargeted(from, to, "Q2 Planning")
+                .to_string()
+        };
+
+        assert_eq!(retarget("[[q1-planning]]"), "[[q2-planning]]");
+        assert_eq!(retarget("[[Q1 Planning]]"), "[[Q2 Planning]]");
+        assert_eq!(retarget("[[q1-planning|Plan]]"), "[[q2-planning|Plan]]");
+        assert_eq!(retarget("q1-planning.md"), "q2-planning.md");
+        assert_eq!(
+            retarget("../projects/q1-planning.md"),
+            "../projects/q2-planning.md"
+        );
+    }
 }
@@ -0,0 +1,146 @@
+//! File revisions for optimistic concurrency control.
+
+use crate::types::DateTimeValue;
+use crate::utils::content_hash;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// A snapshot of a file's identity at the moment it was read.
+///
+/// Pass a revision back to one of the `update_*_checked` methods to make sure
+/// nobody else (for example Obsidian) has written the file in the meantime.
+///
+/// Two revisions are equal when the file content is identical. The modification
+/// time is recorded for information only, so touching a file without changing it
+/// is not treated as a modification.
+#[derive(Debug, Clone, Copy)]
+pub struct Revision {
+    modified: Option<SystemTime>,
+    len: u64,
+    hash: u64,
+}
+
+impl Revision {
+    /// Build a revision from file content and its modification time.
+    pub(crate) fn from_content(content: &str, modified: Option<SystemTime>) -> Self {
+        Self {
+            modified,
+            len: content.len() as u64,
+            hash: content_hash(content.as_bytes()),
+        }
+    }
+
+    /// Build a revision from content that was just read from `path`.
+    pub(crate) fn for_file(path: &Path, content: &str) -> io::Result<Self> {
+        let modified = fs::metadata(path)?.modified().ok();
+        Ok(Self::from_content(content, modified))
+    }
+
+    /// Returns the file's modification time, if the platform reports one.
+    #[must_use]
+    pub fn modified(&self) -> Option<SystemTime> {
+        self.modified
+    }
+
+    /// Returns the length of the file content in bytes.
+    #[must_use]
+    pub fn content_len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns a stable hash of the file content.
+    #[must_use]
+    pub fn content_hash(&self) -> u64 {
+        self.hash
+    }
+}
+
+impl PartialEq for Revision {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.hash == other.hash
+    }
+}
+
+impl Eq for Revision {}
+
+/// The version of a file a caller expects to be overwriting.
+///
+/// Used by the `update_*_checked` methods. If the file on disk no longer
+/// matches, the update is refused with [`Error::Conflict`](crate::Error::Conflict).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExpectedRevision {
+    /// The file content must be unchanged since this revision was taken.
+    Revision(Revision),
+    /// The task's `updated-at` must still have this value.
+    ///
+    /// Only meaningful for tasks. This is weaker than [`ExpectedRevision::Revision`]
+    /// because editors that don't maintain `updated-at` will go unnoticed.
+    UpdatedAt(DateTimeValue),
+}
+
+impl From<Revision> for ExpectedRevision {
+    fn from(revision: Revision) -> Self {
+        Self::Revision(revision)
+    }
+}
+
+impl From<&Revision> for ExpectedRevision {
+    fn from(revision: &Revision) -> Self {
+        Self::Revision(*revision)
+    }
+}
+
+impl From<DateTimeValue> for ExpectedRevision {
+    fn from(updated_at: DateTimeValue) -> Self {
+        Self::UpdatedAt(updated_at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn equal_content_is_equal_revision() {
+        let a = Revision::from_content("title: A", Some(SystemTime::UNIX_EPOCH));
+        let b = Revision::from_content(
+            "title: A",
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(60)),
+        );
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn different_content_is_different_revision() {
+        let a = Revision::from_content("title: A", None);
+        let b = Revision::from_content("title: B", None);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn accessors() {
+        let revision = Revision::from_content("hello", Some(SystemTime::UNIX_EPOCH));
+        assert_eq!(revision.content_len(), 5);
+        assert_eq!(revision.modified(), Some(SystemTime::UNIX_EPOCH));
+        assert_eq!(revision.content_hash(), content_hash(b"hello"));
+    }
+
+    #[test]
+    fn expected_revision_conversions() {
+        let revision = Revision::from_content("x", None);
+        assert_eq!(
+            ExpectedRevision::from(revision),
+            ExpectedRevision::Revision(revision)
+        );
+
+        let updated_at: DateTimeValue = "2025-01-01".parse().unwrap();
+        assert_eq!(
+            ExpectedRevision::from(updated_at.clone()),
+            ExpectedRevision::UpdatedAt(updated_at)
+        );
+    }
+}
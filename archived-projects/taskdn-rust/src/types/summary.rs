@@ -0,0 +1,178 @@
+//! Progress rollups over a project's or area's tasks.
+
+use super::{DateTimeValue, Task, TaskStatus};
+use chrono::NaiveDate;
+use std::path::PathBuf;
+
+/// Task counts and dates rolled up over the tasks of a project or area.
+///
+/// Returned by [`Taskdn::project_summaries`](crate::Taskdn::project_summaries)
+/// and [`Taskdn::area_summaries`](crate::Taskdn::area_summaries). Archived
+/// tasks are included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgressSummary {
+    /// Path to the project or area file.
+    pub path: PathBuf,
+    /// Title of the project or area.
+    pub title: String,
+    /// Number of tasks.
+    pub total: usize,
+    /// Tasks that are not done or dropped.
+    pub open: usize,
+    /// Tasks with status `done`.
+    pub done: usize,
+    /// Tasks with status `dropped`.
+    pub dropped: usize,
+    /// Open tasks due before today.
+    pub overdue: usize,
+    /// Earliest due date of an open task that is not overdue.
+    pub next_due: Option<DateTimeValue>,
+    /// Latest `updated-at` of any task.
+    pub last_activity: Option<DateTimeValue>,
+    /// Whether an open task can be worked on now: `ready` or `in-progress`
+    /// and not deferred, as with [`TaskFilter::available`](crate::TaskFilter::available).
+    pub has_next_action: bool,
+}
+
+impl ProgressSummary {
+    pub(crate) fn new(path: PathBuf, title: String) -> Self {
+        Self {
+            path,
+            title,
+            total: 0,
+            open: 0,
+            done: 0,
+            dropped: 0,
+            overdue: 0,
+            next_due: None,
+            last_activity: None,
+            has_next_action: false,
+        }
+    }
+
+    /// Count a task, as of `today`.
+    pub(crate) fn add(&mut self, task: &Task, today: NaiveDate) {
+        self.total += 1;
+        self.last_activity = self.last_activity.take().max(Some(task.updated_at.clone()));
+
+        match task.status {
+            TaskStatus::Done => self.done += 1,
+            TaskStatus::Dropped => self.dropped += 1,
+            status => {
+                self.open += 1;
+                match &task.due {
+                    Some(due) if due.local_date() < today => self.overdue += 1,
+                    Some(due) => {
+                        self.next_due = Some(match self.next_due.take() {
+                            Some(next) => next.min(due.clone()),
+                            None => due.clone(),
+                        });
+                    }
+                    None => {}
+                }
+
+                let deferred = task.defer_until.is_some_and(|date| date > today);
+                if matches!(status, TaskStatus::Ready | TaskStatus::InProgress) && !deferred {
+                    self.has_next_action = true;
+                }
+            }
+        }
+    }
+
+    /// Percentage of tasks that are done, from 0.0 to 100.0.
+    ///
+    /// Dropped tasks are left out. Returns `None` if there are no other tasks.
+    #[must_use]
+    pub fn percent_complete(&self) -> Option<f64> {
+        let counted = self.total - self.dropped;
+        #[allow(clippy::cast_precision_loss)]
+        (counted > 0).then(|| self.done as f64 / counted as f64 * 100.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    fn task(status: TaskStatus, due: Option<&str>, updated_at: &str) -> Task {
+        Task {
+            path: PathBuf::from("/tasks/t.md"),
+            title: "T".to_string(),
+            status,
+            created_at: "2025-01-01".parse().unwrap(),
+            updated_at: updated_at.parse().unwrap(),
+            completed_at: None,
+            due: due.map(|d| d.parse().unwrap()),
+            scheduled: None,
+            defer_until: None,
+            project: None,
+            area: None,
+            parent: None,
+            blocked_by: Vec::new(),
+            priority: None,
+            recurrence: None,
+            series: None,
+            tags: Vec::new(),
+            body: String::new(),
+            extra: HashMap::new(),
+            projects_count: None,
+        }
+    }
+
+    #[test]
+    fn counts_and_dates() {
+        let today = date("2025-03-10");
+        let mut summary = ProgressSummary::new(PathBuf::from("/p.md"), "P".to_string());
+        summary.add(&task(TaskStatus::Done, None, "2025-03-09T18:00:00"), today);
+        summary.add(&task(TaskStatus::Dropped, None, "2025-03-01"), today);
+        summary.add(
+            &task(TaskStatus::Ready, Some("2025-03-05"), "2025-02-01"),
+            today,
+        );
+        summary.add(
+            &task(TaskStatus::Blocked, Some("2025-03-20"), "2025-02-01"),
+            today,
+        );
+        summary.add(
+            &task(TaskStatus::Inbox, Some("2025-03-12"), "2025-02-01"),
+            today,
+        );
+
+        assert_eq!(
+            (summary.total, summary.open, summary.done, summary.dropped),
+            (5, 3, 1, 1)
+        );
+        assert_eq!(summary.overdue, 1);
+        assert_eq!(summary.next_due, Some("2025-03-12".parse().unwrap()));
+        assert_eq!(
+            summary.last_activity,
+            Some("2025-03-09T18:00:00".parse().unwrap())
+        );
+        assert!(summary.has_next_action);
+        assert_eq!(summary.percent_complete(), Some(25.0));
+    }
+
+    #[test]
+    fn deferred_and_blocked_tasks_are_not_next_actions() {
+        let today = date("2025-03-10");
+        let mut summary = ProgressSummary::new(PathBuf::from("/p.md"), "P".to_string());
+        let mut deferred = task(TaskStatus::Ready, None, "2025-01-01");
+        deferred.defer_until = Some(date("2025-03-11"));
+        summary.add(&deferred, today);
+        summary.add(&task(TaskStatus::Blocked, None, "2025-01-01"), today);
+
+        assert!(!summary.has_next_action);
+    }
+
+    #[test]
+    fn empty_summary_has_no_percentage() {
+        let summary = ProgressSummary::new(PathBuf::from("/p.md"), "P".to_string());
+        assert_eq!(summary.percent_complete(), None);
+        assert_eq!(summary.last_activity, None);
+    }
+}
@@ -0,0 +1,298 @@
+//! Obsidian-style tags.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// A tag such as `work` or `work/client`.
+///
+/// Tags come from the `tags` frontmatter field of tasks, projects and areas,
+/// and from inline `#tag` mentions in markdown bodies. The leading `#` is not
+/// part of the tag. As in Obsidian, tags compare case-insensitively and `/`
+/// separates nested levels: `work/client` is within `work`.
+#[derive(Debug, Clone)]
+pub struct Tag(String);
+
+impl Tag {
+    /// Create a tag, stripping a leading `#` and surrounding whitespace.
+    ///
+    /// Returns `None` if nothing is left.
+    #[must_use]
+    pub fn new(tag: &str) -> Option<Self> {
+        let tag = tag.trim();
+        let tag = tag.strip_prefix('#').unwrap_or(tag).trim();
+        (!tag.is_empty()).then(|| Self(tag.to_string()))
+    }
+
+    /// Returns the tag as written, without a leading `#`.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns true if this tag is `other` or nested under it.
+    ///
+    /// `work/client` is within `work` and `work/client`, but not `work/c` or
+    /// `client`.
+    #[must_use]
+    pub fn is_within(&self, other: &Tag) -> bool {
+        let (tag, parent) = (self.key(), other.key());
+        tag == parent
+            || tag
+                .strip_prefix(parent.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+
+    /// Returns the parent tags of a nested tag, outermost first.
+    ///
+    /// `work/client/acme` has the ancestors `work` and `work/client`.
+    #[must_use]
+    pub fn ancestors(&self) -> Vec<Tag> {
+        self.0
+            .match_indices('/')
+            .map(|(i, _)| Tag(self.0[..i].to_string()))
+            .collect()
+    }
+
+    /// Parse the `tags` frontmatter value: a list, or a single string of
+    /// tags separated by commas or spaces.
+    pub(crate) fn from_yaml(value: &serde_yaml::Value) -> Vec<Tag> {
+        match value {
+            serde_yaml::Value::Sequence(items) => items
+                .iter()
+                .filter_map(|item| match item {
+                    serde_yaml::Value::String(s) => Self::new(s),
+                    serde_yaml::Value::Number(n) => Self::new(&n.to_string()),
+                    serde_yaml::Value::Bool(b) => Self::new(&b.to_string()),
+                    _ => None,
+                })
+                .collect(),
+            serde_yaml::Value::String(s) => s
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter_map(Self::new)
+                .collect(),
+            serde_yaml::Value::Number(n) => Self::new(&n.to_string()).into_iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Find inline `#tag` mentions in a markdown body.
+    ///
+    /// A tag starts with `#` at the start of a line or after whitespace and
+    /// runs over letters, digits, `_`, `-` and `/`. Like Obsidian, it must
+    /// contain at least one non-digit, so `#123` is not a tag. Code spans and
+    /// fenced code blocks are skipped, as are headings (`# Heading`).
+    pub(crate) fn inline(body: &str) -> Vec<Tag> {
+        let mut tags = Vec::new();
+        let mut in_fence = false;
+
+        for line in body.lines() {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+                continue;
+            }
+            if in_fence {
+                continue;
+            }
+
+            let mut in_code = false;
+            let mut previous = None;
+            let mut chars = line.char_indices().peekable();
+            while let Some((i, c)) = chars.next() {
+                if c == '`' {
+                    in_code = !in_code;
+                } else if c == '#' && !in_code && previous.map_or(true, char::is_whitespace) {
+                    let rest = &line[i + 1..];
+                    let end = rest.find(|c: char| !is_tag_char(c)).unwrap_or(rest.len());
+                    let name = rest[..end].trim_end_matches('/');
+                    if name.chars().any(|c| !c.is_ascii_digit()) {
+                        tags.extend(Self::new(name));
+                    }
+                    while chars.peek().is_some_and(|(j, _)| *j <= i + end) {
+                        chars.next();
+                    }
+                    previous = Some('#');
+                    continue;
+                }
+                previous = Some(c);
+            }
+        }
+        tags
+    }
+
+    /// The case-folded form used for comparison.
+    fn key(&self) -> String {
+        self.0.to_lowercase()
+    }
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '-' | '/')
+}
+
+/// Merge tag lists, dropping duplicates (case-insensitively) and keeping the
+/// first spelling of each.
+pub(crate) fn merge_tags<'a>(lists: impl IntoIterator<Item = &'a [Tag]>) -> Vec<Tag> {
+    let mut merged: Vec<Tag> = Vec::new();
+    for tag in lists.into_iter().flatten() {
+        if !merged.contains(tag) {
+            merged.push(tag.clone());
+        }
+    }
+    merged
+}
+
+impl PartialEq for Tag {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Tag {}
+
+impl Hash for Tag {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl PartialOrd for Tag {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Tag {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl AsRef<str> for Tag {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Parse tags from strings, skipping empty ones.
+pub(crate) fn tags_from<S: AsRef<str>>(tags: impl IntoIterator<Item = S>) -> Vec<Tag> {
+    tags.into_iter()
+        .filter_map(|tag| Tag::new(tag.as_ref()))
+        .collect()
+}
+
+/// A tag and how many tasks, projects and areas use it.
+///
+/// Returned by [`Taskdn::list_tags`](crate::Taskdn::list_tags).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagCount {
+    /// The tag, spelled as first seen.
+    pub tag: Tag,
+    /// Number of tasks with this tag.
+    pub tasks: usize,
+    /// Number of projects with this tag.
+    pub projects: usize,
+    /// Number of areas with this tag.
+    pub areas: usize,
+}
+
+impl TagCount {
+    /// Total number of entities with this tag.
+    #[must_use]
+    pub fn total(&self) -> usize {
+        self.tasks + self.projects + self.areas
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(s: &str) -> Tag {
+        Tag::new(s).unwrap()
+    }
+
+    fn names(tags: &[Tag]) -> Vec<&str> {
+        tags.iter().map(Tag::as_str).collect()
+    }
+
+    #[test]
+    fn new_strips_hash_and_whitespace() {
+        assert_eq!(tag(" #work ").as_str(), "work");
+        assert_eq!(tag("work/client").as_str(), "work/client");
+        assert!(Tag::new("").is_none());
+        assert!(Tag::new(" # ").is_none());
+    }
+
+    #[test]
+    fn compares_case_insensitively() {
+        assert_eq!(tag("Work"), tag("work"));
+        assert_eq!(tag("Work").as_str(), "Work");
+    }
+
+    #[test]
+    fn hierarchical_matching() {
+        assert!(tag("work/client").is_within(&tag("work")));
+        assert!(tag("Work/Client").is_within(&tag("work/client")));
+        assert!(tag("work").is_within(&tag("work")));
+        assert!(!tag("work").is_within(&tag("work/client")));
+        assert!(!tag("workshop").is_within(&tag("work")));
+        assert!(!tag("work/client").is_within(&tag("client")));
+    }
+
+    #[test]
+    fn ancestors() {
+        assert_eq!(
+            names(&tag("work/client/acme").ancestors()),
+            ["work", "work/client"]
+        );
+        assert!(tag("work").ancestors().is_empty());
+    }
+
+    #[test]
+    fn from_yaml_accepts_lists_and_strings() {
+        let yaml = |s: &str| serde_yaml::from_str::<serde_yaml::Value>(s).unwrap();
+        assert_eq!(
+            names(&Tag::from_yaml(&yaml("[work, '#home', 2025]"))),
+            ["work", "home", "2025"]
+        );
+        assert_eq!(
+            names(&Tag::from_yaml(&yaml("work, home/garden  errands"))),
+            ["work", "home/garden", "errands"]
+        );
+        assert!(Tag::from_yaml(&yaml("null")).is_empty());
+    }
+
+    #[test]
+    fn inline_tags() {
+        let body = "Call #client/acme about #Q1-plan.\n#urgent at line start\n";
+        assert_eq!(
+            names(&Tag::inline(body)),
+            ["client/acme", "Q1-plan", "urgent"]
+        );
+    }
+
+    #[test]
+    fn inline_tags_skip_non_tags() {
+        let body = "# Heading\n## Sub\nIssue #123 and a#b and https://x.io/#anchor\n\
+                    `#code` here\n```\n#fenced\n```\n#real";
+        assert_eq!(names(&Tag::inline(body)), ["real"]);
+    }
+
+    #[test]
+    fn merge_drops_duplicates() {
+        let a = [tag("Work"), tag("home")];
+        let b = [tag("work"), tag("errands")];
+        assert_eq!(
+            names(&merge_tags([&a[..], &b[..]])),
+            ["Work", "home", "errands"]
+        );
+    }
+}
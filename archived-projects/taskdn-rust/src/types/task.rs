@@ -1,12 +1,17 @@
 //! Task entity and related types.
 
-use super::{DateTimeValue, FileReference};
-use crate::validation::ValidationWarning;
+use super::tag::{merge_tags, tags_from};
+use super::{Checklist, DateTimeValue, FileReference, Priority, Recurrence, Tag};
+use crate::validation::{unparsed_fields, ValidationWarning};
 use chrono::NaiveDate;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+/// Typed fields whose unparseable values are kept in `extra` instead of
+/// failing the whole file.
+const TYPED_FIELDS: &[&str] = &["parent", "blocked-by", "recurrence", "series"];
+
 /// Status of a task.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 #[non_exhaustive]
@@ -112,6 +117,18 @@ pub struct Task {
     pub project: Option<FileReference>,
     /// Reference to the area this task belongs to.
     pub area: Option<FileReference>,
+    /// Reference to the task this one is a subtask of.
+    pub parent: Option<FileReference>,
+    /// Tasks that must be done or dropped before this one can proceed.
+    pub blocked_by: Vec<FileReference>,
+    /// Tags from the `tags` field (see [`Task::all_tags`] for inline tags too).
+    pub tags: Vec<Tag>,
+    /// How important the task is.
+    pub priority: Option<Priority>,
+    /// How the task repeats, if it is recurring.
+    pub recurrence: Option<Recurrence>,
+    /// Identifier shared by every instance of a recurring task.
+    pub series: Option<String>,
 
     // Preserved content
     /// Markdown body (everything after frontmatter).
@@ -145,6 +162,19 @@ impl Task {
         !self.status.is_completed() && !self.is_archived()
     }
 
+    /// Tags from the `tags` field followed by inline `#tags` in the body,
+    /// without duplicates.
+    #[must_use]
+    pub fn all_tags(&self) -> Vec<Tag> {
+        merge_tags([&self.tags[..], &Tag::inline(&self.body)[..]])
+    }
+
+    /// The `- [ ]` checklist items in the body.
+    #[must_use]
+    pub fn checklist(&self) -> Checklist {
+        Checklist::parse(&self.body)
+    }
+
     /// Validate the task against spec recommendations.
     ///
     /// Returns a list of warnings for spec violations. An empty list means
@@ -153,6 +183,7 @@ impl Task {
     /// Checks performed:
     /// - `projects` array should have exactly one element (warns if >1)
     /// - Completed tasks (done/dropped) should have `completed-at` set
+    /// - Typed fields should have values that parse
     #[must_use]
     pub fn validate(&self) -> Vec<ValidationWarning> {
         let mut warnings = Vec::new();
@@ -169,6 +200,7 @@ impl Task {
             warnings.push(ValidationWarning::MissingCompletedAt);
         }
 
+        warnings.extend(unparsed_fields(&self.extra, TYPED_FIELDS));
         warnings
     }
 }
@@ -198,6 +230,18 @@ pub struct ParsedTask {
     pub project: Option<FileReference>,
     /// Reference to the area this task belongs to.
     pub area: Option<FileReference>,
+    /// Reference to the task this one is a subtask of.
+    pub parent: Option<FileReference>,
+    /// Tasks that must be done or dropped before this one can proceed.
+    pub blocked_by: Vec<FileReference>,
+    /// Tags from the `tags` field.
+    pub tags: Vec<Tag>,
+    /// How important the task is.
+    pub priority: Option<Priority>,
+    /// How the task repeats, if it is recurring.
+    pub recurrence: Option<Recurrence>,
+    /// Identifier shared by every instance of a recurring task.
+    pub series: Option<String>,
     /// Markdown body.
     pub body: String,
     /// Unknown frontmatter fields.
@@ -222,6 +266,12 @@ impl ParsedTask {
             defer_until: self.defer_until,
             project: self.project,
             area: self.area,
+            parent: self.parent,
+            blocked_by: self.blocked_by,
+            priority: self.priority,
+            recurrence: self.recurrence,
+            series: self.series,
+            tags: self.tags,
             body: self.body,
             extra: self.extra,
             projects_count: self.projects_count,
@@ -236,6 +286,7 @@ impl ParsedTask {
     /// Checks performed:
     /// - `projects` array should have exactly one element (warns if >1)
     /// - Completed tasks (done/dropped) should have `completed-at` set
+    /// - Typed fields should have values that parse
     #[must_use]
     pub fn validate(&self) -> Vec<ValidationWarning> {
         let mut warnings = Vec::new();
@@ -252,6 +303,7 @@ impl ParsedTask {
             warnings.push(ValidationWarning::MissingCompletedAt);
         }
 
+        warnings.extend(unparsed_fields(&self.extra, TYPED_FIELDS));
         warnings
     }
 }
@@ -278,6 +330,16 @@ pub struct NewTask {
     pub project: Option<FileReference>,
     /// Reference to the area this task belongs to.
     pub area: Option<FileReference>,
+    /// Reference to the task this one is a subtask of.
+    pub parent: Option<FileReference>,
+    /// Tasks that must be done or dropped before this one can proceed.
+    pub blocked_by: Vec<FileReference>,
+    /// Tags.
+    pub tags: Vec<Tag>,
+    /// How important the task is.
+    pub priority: Option<Priority>,
+    /// How the task repeats, if it is recurring.
+    pub recurrence: Option<Recurrence>,
     /// Markdown body content.
     pub body: String,
     /// Additional frontmatter fields.
@@ -344,6 +406,41 @@ impl NewTask {
         self
     }
 
+    /// Make this a subtask of another task.
+    #[must_use]
+    pub fn with_parent(mut self, parent: impl Into<FileReference>) -> Self {
+        self.parent = Some(parent.into());
+        self
+    }
+
+    /// Add a task this one waits on.
+    #[must_use]
+    pub fn with_blocker(mut self, blocker: impl Into<FileReference>) -> Self {
+        self.blocked_by.push(blocker.into());
+        self
+    }
+
+    /// Set the tags (a leading `#` is stripped).
+    #[must_use]
+    pub fn with_tags<S: AsRef<str>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags = tags_from(tags);
+        self
+    }
+
+    /// Set the priority.
+    #[must_use]
+    pub fn with_priority(mut self, priority: impl Into<Priority>) -> Self {
+        self.priority = Some(priority.into());
+        self
+    }
+
+    /// Make the task recurring.
+    #[must_use]
+    pub fn with_recurrence(mut self, recurrence: Recurrence) -> Self {
+        self.recurrence = Some(recurrence);
+        self
+    }
+
     /// Set the body content.
     #[must_use]
     pub fn with_body(mut self, body: impl Into<String>) -> Self {
@@ -372,6 +469,20 @@ pub struct TaskUpdates {
     pub project: Option<Option<FileReference>>,
     /// New area reference.
     pub area: Option<Option<FileReference>>,
+    /// New parent task reference.
+    pub parent: Option<Option<FileReference>>,
+    /// New blocking tasks (None = don't change, Some(empty) = clear).
+    pub blocked_by: Option<Vec<FileReference>>,
+    /// New tags (None = don't change, Some(empty) = clear).
+    pub tags: Option<Vec<Tag>>,
+    /// New priority.
+    pub priority: Option<Option<Priority>>,
+    /// New recurrence rule.
+    pub recurrence: Option<Option<Recurrence>>,
+    /// New recurring series.
+    pub series: Option<Option<String>>,
+    /// New markdown body.
+    pub body: Option<String>,
 }
 
 impl TaskUpdates {
@@ -465,6 +576,100 @@ impl TaskUpdates {
         self
     }
 
+    /// Set a new parent task reference.
+    #[must_use]
+    pub fn parent(mut self, parent: impl Into<FileReference>) -> Self {
+        self.parent = Some(Some(parent.into()));
+        self
+    }
+
+    /// Clear the parent task reference, making the task top-level.
+    #[must_use]
+    pub fn clear_parent(mut self) -> Self {
+        self.parent = Some(None);
+        self
+    }
+
+    /// Replace the tasks this one waits on.
+    #[must_use]
+    pub fn blocked_by<R: Into<FileReference>>(
+        mut self,
+        blockers: impl IntoIterator<Item = R>,
+    ) -> Self {
+        self.blocked_by = Some(blockers.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Clear the tasks this one waits on.
+    #[must_use]
+    pub fn clear_blocked_by(mut self) -> Self {
+        self.blocked_by = Some(Vec::new());
+        self
+    }
+
+    /// Replace the tags (a leading `#` is stripped).
+    #[must_use]
+    pub fn tags<S: AsRef<str>>(mut self, tags: impl IntoIterator<Item = S>) -> Self {
+        self.tags = Some(tags_from(tags));
+        self
+    }
+
+    /// Remove all tags.
+    #[must_use]
+    pub fn clear_tags(mut self) -> Self {
+        self.tags = Some(Vec::new());
+        self
+    }
+
+    /// Set a new priority.
+    #[must_use]
+    pub fn priority(mut self, priority: impl Into<Priority>) -> Self {
+        self.priority = Some(Some(priority.into()));
+        self
+    }
+
+    /// Clear the priority.
+    #[must_use]
+    pub fn clear_priority(mut self) -> Self {
+        self.priority = Some(None);
+        self
+    }
+
+    /// Set a new recurrence rule.
+    #[must_use]
+    pub fn recurrence(mut self, recurrence: Recurrence) -> Self {
+        self.recurrence = Some(Some(recurrence));
+        self
+    }
+
+    /// Stop the task recurring.
+    #[must_use]
+    pub fn clear_recurrence(mut self) -> Self {
+        self.recurrence = Some(None);
+        self
+    }
+
+    /// Set the recurring series the task belongs to.
+    #[must_use]
+    pub fn series(mut self, series: impl Into<String>) -> Self {
+        self.series = Some(Some(series.into()));
+        self
+    }
+
+    /// Remove the task from its recurring series.
+    #[must_use]
+    pub fn clear_series(mut self) -> Self {
+        self.series = Some(None);
+        self
+    }
+
+    /// Replace the markdown body.
+    #[must_use]
+    pub fn body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
     /// Returns true if no updates are specified.
     #[must_use]
     pub fn is_empty(&self) -> bool {
@@ -475,6 +680,63 @@ impl TaskUpdates {
             && self.defer_until.is_none()
             && self.project.is_none()
             && self.area.is_none()
+            && self.parent.is_none()
+            && self.blocked_by.is_none()
+            && self.tags.is_none()
+            && self.priority.is_none()
+            && self.recurrence.is_none()
+            && self.series.is_none()
+            && self.body.is_none()
+    }
+
+    /// Apply these updates to a task in place.
+    pub(crate) fn apply_to(self, task: &mut Task) {
+        if let Some(title) = self.title {
+            task.title = title;
+        }
+        if let Some(status) = self.status {
+            task.status = status;
+        }
+        if let Some(due) = self.due {
+            task.due = due;
+        }
+        if let Some(scheduled) = self.scheduled {
+            task.scheduled = scheduled;
+        }
+        if let Some(defer_until) = self.defer_until {
+            task.defer_until = defer_until;
+        }
+        if let Some(project) = self.project {
+            task.project = project;
+        }
+        if let Some(area) = self.area {
+            task.area = area;
+        }
+        if let Some(parent) = self.parent {
+            task.extra.remove("parent");
+            task.parent = parent;
+        }
+        if let Some(blocked_by) = self.blocked_by {
+            task.extra.remove("blocked-by");
+            task.blocked_by = blocked_by;
+        }
+        if let Some(tags) = self.tags {
+            task.tags = tags;
+        }
+        if let Some(priority) = self.priority {
+            task.priority = priority;
+        }
+        if let Some(recurrence) = self.recurrence {
+            task.extra.remove("recurrence");
+            task.recurrence = recurrence;
+        }
+        if let Some(series) = self.series {
+            task.extra.remove("series");
+            task.series = series;
+        }
+        if let Some(body) = self.body {
+            task.body = body;
+        }
     }
 }
 
@@ -559,6 +821,12 @@ mod tests {
                 defer_until: None,
                 project: None,
                 area: None,
+                parent: None,
+                blocked_by: Vec::new(),
+                priority: None,
+                recurrence: None,
+                series: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
                 projects_count: None,
@@ -663,6 +931,12 @@ mod tests {
                 defer_until: None,
                 project: None,
                 area: None,
+                parent: None,
+                blocked_by: Vec::new(),
+                priority: None,
+                recurrence: None,
+                series: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
                 projects_count: None,
@@ -747,6 +1021,12 @@ mod tests {
                 defer_until: None,
                 project: None,
                 area: None,
+                parent: None,
+                blocked_by: Vec::new(),
+                priority: None,
+                recurrence: None,
+                series: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
                 projects_count: Some(2),
@@ -1,5 +1,19 @@
 //! Utility functions for the taskdn library.
 
+/// Compute a stable 64-bit hash of file content (FNV-1a).
+///
+/// Unlike `std::hash::DefaultHasher`, the result is identical across processes
+/// and Rust versions, so it can be stored and compared later.
+#[must_use]
+pub(crate) fn content_hash(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
 /// Generate a filename from a title.
 ///
 /// The algorithm:
@@ -69,10 +83,49 @@ pub fn generate_filename(title: &str) -> String {
     format!("{result}.md")
 }
 
+/// Serde format for bookkeeping timestamps (`2025-01-15T14:30:00`).
+///
+/// Use with `#[serde(with = "crate::utils::serde_timestamp")]`.
+pub(crate) mod serde_timestamp {
+    use chrono::NaiveDateTime;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    const FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+    pub(crate) fn serialize<S: Serializer>(
+        value: &NaiveDateTime,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.format(FORMAT).to_string())
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<NaiveDateTime, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        NaiveDateTime::parse_from_str(&s, FORMAT).map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn content_hash_is_stable() {
+        // Known FNV-1a values
+        assert_eq!(content_hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(content_hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn content_hash_differs_for_different_content() {
+        assert_ne!(
+            content_hash(b"status: ready"),
+            content_hash(b"status: done")
+        );
+    }
+
     #[test]
     fn simple_title() {
         assert_eq!(generate_filename("Buy groceries"), "buy-groceries.md");
@@ -3,6 +3,7 @@
 //! The parser validates required fields and formats. This module provides
 //! additional validation for spec recommendations and advisory checks.
 
+use std::collections::HashMap;
 use std::path::Path;
 
 /// A validation warning about spec compliance.
@@ -21,6 +22,37 @@ pub enum ValidationWarning {
 
     /// A completed task (done/dropped) is missing the `completed-at` field.
     MissingCompletedAt,
+
+    /// A completed task still has open subtasks.
+    OpenSubtasks {
+        /// Number of subtasks that are not done or dropped.
+        count: usize,
+    },
+
+    /// An open task whose subtasks are all done or dropped.
+    AllSubtasksCompleted {
+        /// Number of subtasks.
+        count: usize,
+    },
+
+    /// Following the task's or area's `parent` references leads back to it.
+    ParentCycle,
+
+    /// A project's `blocked-by` entry matches no project.
+    UnresolvedBlocker {
+        /// The reference as written.
+        reference: String,
+    },
+
+    /// Following the project's `blocked-by` references leads back to the project.
+    BlockerCycle,
+
+    /// A known field has a value that could not be parsed. The value is kept
+    /// in `extra` as written.
+    InvalidFieldValue {
+        /// The frontmatter key.
+        field: &'static str,
+    },
 }
 
 impl ValidationWarning {
@@ -36,6 +68,20 @@ impl ValidationWarning {
             Self::MissingCompletedAt => {
                 "completed task is missing 'completed-at' field".to_string()
             }
+            Self::OpenSubtasks { count } => {
+                format!("task is completed but {count} subtask(s) are still open")
+            }
+            Self::AllSubtasksCompleted { count } => {
+                format!("all {count} subtask(s) are completed but the task is still open")
+            }
+            Self::ParentCycle => "file is its own ancestor through 'parent'".to_string(),
+            Self::UnresolvedBlocker { reference } => {
+                format!("'blocked-by' entry {reference} matches no project")
+            }
+            Self::BlockerCycle => "project is blocked by itself through 'blocked-by'".to_string(),
+            Self::InvalidFieldValue { field } => {
+                format!("'{field}' could not be parsed and is kept as written")
+            }
         }
     }
 
@@ -46,6 +92,19 @@ impl ValidationWarning {
     }
 }
 
+/// Warnings for the `fields` whose values were kept in `extra` because they
+/// could not be parsed.
+pub(crate) fn unparsed_fields(
+    extra: &HashMap<String, serde_yaml::Value>,
+    fields: &[&'static str],
+) -> Vec<ValidationWarning> {
+    fields
+        .iter()
+        .filter(|field| extra.contains_key(**field))
+        .map(|&field| ValidationWarning::InvalidFieldValue { field })
+        .collect()
+}
+
 impl std::fmt::Display for ValidationWarning {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.message())
@@ -69,6 +128,45 @@ mod tests {
         assert!(warning.message().contains("completed-at"));
     }
 
+    #[test]
+    fn subtask_messages() {
+        assert!(ValidationWarning::OpenSubtasks { count: 2 }
+            .message()
+            .contains("2 subtask(s) are still open"));
+        assert!(ValidationWarning::AllSubtasksCompleted { count: 3 }
+            .message()
+            .contains("all 3 subtask(s)"));
+        assert!(ValidationWarning::ParentCycle.message().contains("parent"));
+    }
+
+    #[test]
+    fn blocker_messages() {
+        let warning = ValidationWarning::UnresolvedBlocker {
+            reference: "[[Missing]]".to_string(),
+        };
+        assert!(warning.message().contains("[[Missing]] matches no project"));
+        assert!(ValidationWarning::BlockerCycle
+            .message()
+            .contains("blocked by itself"));
+    }
+
+    #[test]
+    fn invalid_field_value() {
+        let warning = ValidationWarning::InvalidFieldValue {
+            field: "recurrence",
+        };
+        assert!(warning
+            .message()
+            .contains("'recurrence' could not be parsed"));
+
+        let mut extra = HashMap::new();
+        extra.insert("recurrence".to_string(), serde_yaml::Value::Null);
+        assert_eq!(
+            unparsed_fields(&extra, &["recurrence", "series"]),
+            [warning]
+        );
+    }
+
     #[test]
     fn message_with_path() {
         let warning = ValidationWarning::MissingCompletedAt;
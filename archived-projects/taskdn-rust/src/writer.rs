@@ -4,19 +4,23 @@
 //! unknown frontmatter fields and the markdown body.
 
 use crate::error::Result;
+use crate::frontmatter;
 use crate::types::{
-    Area, DateTimeValue, ParsedArea, ParsedProject, ParsedTask, Project, Task, TaskStatus,
+    Area, DateTimeValue, ParsedArea, ParsedProject, ParsedTask, Priority, Project, Revision, Tag,
+    Task, TaskStatus,
 };
 use std::collections::HashMap;
-use std::fmt::{self, Display, Write as FmtWrite};
-use std::fs;
-use std::path::Path;
+use std::fmt::{self, Display};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write as IoWrite};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Helper to format a YAML string value with proper quoting.
 ///
 /// Quotes the value if it contains special characters that would be
 /// interpreted by YAML parsers.
-fn yaml_string(value: &str) -> String {
+pub(crate) fn yaml_string(value: &str) -> String {
     // Check if the value needs quoting
     let needs_quoting = value.is_empty()
         || value.contains(':')
@@ -60,7 +64,7 @@ fn yaml_string(value: &str) -> String {
 }
 
 /// Helper to serialize a `serde_yaml::Value` to a YAML string.
-fn yaml_value_to_string(value: &serde_yaml::Value, indent: usize) -> String {
+pub(crate) fn yaml_value_to_string(value: &serde_yaml::Value, indent: usize) -> String {
     match value {
         serde_yaml::Value::Null => "null".to_string(),
         serde_yaml::Value::Bool(b) => b.to_string(),
@@ -106,98 +110,188 @@ fn yaml_value_to_string(value: &serde_yaml::Value, indent: usize) -> String {
     }
 }
 
-/// Serialize extra fields to YAML lines.
-fn serialize_extra_fields(extra: &HashMap<String, serde_yaml::Value>) -> String {
-    if extra.is_empty() {
-        return String::new();
-    }
+/// A single frontmatter value, formatted the way the serializer writes it.
+///
+/// Entities are serialized by first building an ordered list of
+/// `(key, FieldValue)` pairs. The same list is used to compare two versions of
+/// an entity key by key (for example when merging concurrent edits).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FieldValue {
+    /// A scalar, already quoted for YAML output if needed.
+    Scalar(String),
+    /// A block sequence of scalars, each already quoted for YAML output.
+    List(Vec<String>),
+    /// An arbitrary value preserved from `extra`.
+    Yaml(serde_yaml::Value),
+}
+
+/// Ordered frontmatter keys and values for an entity.
+pub(crate) type Fields = Vec<(String, FieldValue)>;
+
+fn scalar(key: &str, value: String) -> (String, FieldValue) {
+    (key.to_string(), FieldValue::Scalar(value))
+}
 
-    let mut result = String::new();
+/// Append a `tags` list, if there are any tags.
+fn push_tags(fields: &mut Fields, tags: &[Tag]) {
+    if !tags.is_empty() {
+        fields.push((
+            "tags".to_string(),
+            FieldValue::List(tags.iter().map(|tag| yaml_string(tag.as_str())).collect()),
+        ));
+    }
+}
 
-    // Sort keys for consistent output
-    let mut keys: Vec<_> = extra.keys().collect();
+/// Append `extra` fields, sorted by key for consistent output.
+///
+/// Keys already written from a typed field are skipped, so a value that was
+/// kept in `extra` because it didn't parse never duplicates a key.
+fn push_extra_fields(fields: &mut Fields, extra: &HashMap<String, serde_yaml::Value>) {
+    let mut keys: Vec<_> = extra
+        .keys()
+        .filter(|key| fields.iter().all(|(field, _)| field != *key))
+        .collect();
     keys.sort();
 
     for key in keys {
         if let Some(value) = extra.get(key) {
+            fields.push((key.clone(), FieldValue::Yaml(value.clone())));
+        }
+    }
+}
+
+/// Render a single frontmatter field as YAML lines (each ending in a newline).
+pub(crate) fn render_field(key: &str, value: &FieldValue) -> String {
+    match value {
+        FieldValue::Scalar(value) => format!("{key}: {value}\n"),
+        FieldValue::List(items) => {
+            let mut result = format!("{key}:\n");
+            for item in items {
+                result.push_str("  - ");
+                result.push_str(item);
+                result.push('\n');
+            }
+            result
+        }
+        FieldValue::Yaml(value) => {
             let value_str = yaml_value_to_string(value, 2);
             // Handle multiline values (sequences, mappings)
             if value_str.starts_with('\n') {
-                let _ = writeln!(result, "{key}:{value_str}");
+                format!("{key}:{value_str}\n")
             } else {
-                let _ = writeln!(result, "{key}: {value_str}");
+                format!("{key}: {value_str}\n")
             }
         }
     }
+}
+
+/// Write a full file (frontmatter fields followed by the body).
+fn write_document(f: &mut fmt::Formatter<'_>, fields: &Fields, body: &str) -> fmt::Result {
+    writeln!(f, "---")?;
 
-    result
+    for (key, value) in fields {
+        write!(f, "{}", render_field(key, value))?;
+    }
+
+    writeln!(f, "---")?;
+
+    // Add body if present
+    if !body.is_empty() {
+        write!(f, "{body}")?;
+    }
+
+    Ok(())
 }
 
-impl Display for ParsedTask {
-    /// Serialize to file content (frontmatter + body).
+impl ParsedTask {
+    /// Frontmatter fields in the order they are written.
     ///
-    /// The output is a valid markdown file with YAML frontmatter.
-    /// Field order is consistent and deterministic.
-    /// Unknown fields from `extra` are preserved.
-    /// The `projects_count` field is NOT serialized (validation metadata only).
-    #[allow(clippy::too_many_lines)]
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "---")?;
+    /// Required fields come first in spec order, followed by optional fields
+    /// and then unknown fields from `extra` (sorted).
+    /// The `projects_count` field is NOT included (validation metadata only).
+    pub(crate) fn frontmatter_fields(&self) -> Fields {
+        let mut fields = vec![
+            scalar("title", yaml_string(&self.title)),
+            scalar("status", self.status.to_string()),
+            scalar("created-at", self.created_at.to_string()),
+            scalar("updated-at", self.updated_at.to_string()),
+        ];
 
-        // Required fields in spec order
-        writeln!(f, "title: {}", yaml_string(&self.title))?;
-        writeln!(f, "status: {}", self.status)?;
-        writeln!(f, "created-at: {}", self.created_at)?;
-        writeln!(f, "updated-at: {}", self.updated_at)?;
-
-        // Optional fields in spec order
         if let Some(ref completed_at) = self.completed_at {
-            writeln!(f, "completed-at: {completed_at}")?;
+            fields.push(scalar("completed-at", completed_at.to_string()));
         }
-
         if let Some(ref due) = self.due {
-            writeln!(f, "due: {due}")?;
+            fields.push(scalar("due", due.to_string()));
         }
-
         if let Some(scheduled) = self.scheduled {
-            writeln!(f, "scheduled: {}", scheduled.format("%Y-%m-%d"))?;
+            fields.push(scalar(
+                "scheduled",
+                scheduled.format("%Y-%m-%d").to_string(),
+            ));
         }
-
         if let Some(defer_until) = self.defer_until {
-            writeln!(f, "defer-until: {}", defer_until.format("%Y-%m-%d"))?;
+            fields.push(scalar(
+                "defer-until",
+                defer_until.format("%Y-%m-%d").to_string(),
+            ));
         }
 
         // Project - use "projects" array format per spec
         if let Some(ref project) = self.project {
-            writeln!(f, "projects:")?;
-            writeln!(f, "  - {}", yaml_string(&project.to_string()))?;
+            fields.push((
+                "projects".to_string(),
+                FieldValue::List(vec![yaml_string(&project.to_string())]),
+            ));
         }
 
         if let Some(ref area) = self.area {
-            writeln!(f, "area: {}", yaml_string(&area.to_string()))?;
+            fields.push(scalar("area", yaml_string(&area.to_string())));
         }
 
-        // Extra fields (sorted for consistency)
-        write!(f, "{}", serialize_extra_fields(&self.extra))?;
-
-        writeln!(f, "---")?;
+        if let Some(ref parent) = self.parent {
+            fields.push(scalar("parent", yaml_string(&parent.to_string())));
+        }
+        if !self.blocked_by.is_empty() {
+            fields.push((
+                "blocked-by".to_string(),
+                FieldValue::List(
+                    self.blocked_by
+                        .iter()
+                        .map(|blocked| yaml_string(&blocked.to_string()))
+                        .collect(),
+                ),
+            ));
+        }
 
-        // Add body if present
-        if !self.body.is_empty() {
-            write!(f, "{}", self.body)?;
+        if let Some(ref priority) = self.priority {
+            let value = match priority {
+                Priority::Numeric(n) => n.to_string(),
+                Priority::Other(value) if !value.is_string() => priority.to_string(),
+                other => yaml_string(&other.to_string()),
+            };
+            fields.push(scalar("priority", value));
+        }
+        if let Some(ref recurrence) = self.recurrence {
+            fields.push(scalar("recurrence", recurrence.to_string()));
+        }
+        if let Some(ref series) = self.series {
+            fields.push(scalar("series", yaml_string(series)));
         }
 
-        Ok(())
+        push_tags(&mut fields, &self.tags);
+        push_extra_fields(&mut fields, &self.extra);
+        fields
     }
 }
 
-impl Display for Task {
-    /// Serialize to file content (frontmatter + body).
-    ///
-    /// This delegates to `ParsedTask`'s Display since the path is not
-    /// included in the file content.
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let parsed = ParsedTask {
+impl Task {
+    /// Frontmatter fields in the order they are written.
+    pub(crate) fn frontmatter_fields(&self) -> Fields {
+        self.to_parsed().frontmatter_fields()
+    }
+
+    fn to_parsed(&self) -> ParsedTask {
+        ParsedTask {
             title: self.title.clone(),
             status: self.status,
             created_at: self.created_at.clone(),
@@ -208,71 +302,95 @@ impl Display for Task {
             defer_until: self.defer_until,
             project: self.project.clone(),
             area: self.area.clone(),
+            parent: self.parent.clone(),
+            blocked_by: self.blocked_by.clone(),
+            priority: self.priority.clone(),
+            recurrence: self.recurrence.clone(),
+            series: self.series.clone(),
+            tags: self.tags.clone(),
             body: self.body.clone(),
             extra: self.extra.clone(),
             projects_count: self.projects_count,
-        };
-        write!(f, "{parsed}")
+        }
     }
 }
 
-impl Display for ParsedProject {
+impl Display for ParsedTask {
     /// Serialize to file content (frontmatter + body).
+    ///
+    /// The output is a valid markdown file with YAML frontmatter.
+    /// Field order is consistent and deterministic.
+    /// Unknown fields from `extra` are preserved.
+    /// The `projects_count` field is NOT serialized (validation metadata only).
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "---")?;
+        write_document(f, &self.frontmatter_fields(), &self.body)
+    }
+}
 
+impl Display for Task {
+    /// Serialize to file content (frontmatter + body).
+    ///
+    /// This delegates to `ParsedTask`'s Display since the path is not
+    /// included in the file content.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_parsed())
+    }
+}
+
+impl ParsedProject {
+    /// Frontmatter fields in the order they are written.
+    pub(crate) fn frontmatter_fields(&self) -> Fields {
         // Required field
-        writeln!(f, "title: {}", yaml_string(&self.title))?;
+        let mut fields = vec![scalar("title", yaml_string(&self.title))];
 
         // Optional fields in logical order
         if let Some(ref unique_id) = self.unique_id {
-            writeln!(f, "unique-id: {}", yaml_string(unique_id))?;
+            fields.push(scalar("unique-id", yaml_string(unique_id)));
         }
-
         if let Some(status) = self.status {
-            writeln!(f, "status: {status}")?;
+            fields.push(scalar("status", status.to_string()));
         }
-
         if let Some(ref description) = self.description {
-            writeln!(f, "description: {}", yaml_string(description))?;
+            fields.push(scalar("description", yaml_string(description)));
         }
-
         if let Some(ref area) = self.area {
-            writeln!(f, "area: {}", yaml_string(&area.to_string()))?;
+            fields.push(scalar("area", yaml_string(&area.to_string())));
         }
-
         if let Some(start_date) = self.start_date {
-            writeln!(f, "start-date: {}", start_date.format("%Y-%m-%d"))?;
+            fields.push(scalar(
+                "start-date",
+                start_date.format("%Y-%m-%d").to_string(),
+            ));
         }
-
         if let Some(end_date) = self.end_date {
-            writeln!(f, "end-date: {}", end_date.format("%Y-%m-%d"))?;
+            fields.push(scalar("end-date", end_date.format("%Y-%m-%d").to_string()));
         }
-
         if !self.blocked_by.is_empty() {
-            writeln!(f, "blocked-by:")?;
-            for blocked in &self.blocked_by {
-                writeln!(f, "  - {}", yaml_string(&blocked.to_string()))?;
-            }
-        }
-
-        // Extra fields
-        write!(f, "{}", serialize_extra_fields(&self.extra))?;
-
-        writeln!(f, "---")?;
-
-        if !self.body.is_empty() {
-            write!(f, "{}", self.body)?;
+            fields.push((
+                "blocked-by".to_string(),
+                FieldValue::List(
+                    self.blocked_by
+                        .iter()
+                        .map(|blocked| yaml_string(&blocked.to_string()))
+                        .collect(),
+                ),
+            ));
         }
 
-        Ok(())
+        push_tags(&mut fields, &self.tags);
+        push_extra_fields(&mut fields, &self.extra);
+        fields
     }
 }
 
-impl Display for Project {
-    /// Serialize to file content (frontmatter + body).
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let parsed = ParsedProject {
+impl Project {
+    /// Frontmatter fields in the order they are written.
+    pub(crate) fn frontmatter_fields(&self) -> Fields {
+        self.to_parsed().frontmatter_fields()
+    }
+
+    fn to_parsed(&self) -> ParsedProject {
+        ParsedProject {
             title: self.title.clone(),
             unique_id: self.unique_id.clone(),
             status: self.status,
@@ -281,59 +399,179 @@ impl Display for Project {
             start_date: self.start_date,
             end_date: self.end_date,
             blocked_by: self.blocked_by.clone(),
+            tags: self.tags.clone(),
             body: self.body.clone(),
             extra: self.extra.clone(),
-        };
-        write!(f, "{parsed}")
+        }
     }
 }
 
-impl Display for ParsedArea {
+impl Display for ParsedProject {
+    /// Serialize to file content (frontmatter + body).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_document(f, &self.frontmatter_fields(), &self.body)
+    }
+}
+
+impl Display for Project {
     /// Serialize to file content (frontmatter + body).
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "---")?;
+        write!(f, "{}", self.to_parsed())
+    }
+}
 
+impl ParsedArea {
+    /// Frontmatter fields in the order they are written.
+    pub(crate) fn frontmatter_fields(&self) -> Fields {
         // Required field
-        writeln!(f, "title: {}", yaml_string(&self.title))?;
+        let mut fields = vec![scalar("title", yaml_string(&self.title))];
 
         // Optional fields
         if let Some(status) = self.status {
-            writeln!(f, "status: {status}")?;
+            fields.push(scalar("status", status.to_string()));
         }
-
         if let Some(ref area_type) = self.area_type {
-            writeln!(f, "type: {}", yaml_string(area_type))?;
+            fields.push(scalar("type", yaml_string(area_type)));
         }
-
         if let Some(ref description) = self.description {
-            writeln!(f, "description: {}", yaml_string(description))?;
+            fields.push(scalar("description", yaml_string(description)));
         }
-
-        // Extra fields
-        write!(f, "{}", serialize_extra_fields(&self.extra))?;
-
-        writeln!(f, "---")?;
-
-        if !self.body.is_empty() {
-            write!(f, "{}", self.body)?;
+        if let Some(ref parent) = self.parent {
+            fields.push(scalar("parent", yaml_string(&parent.to_string())));
         }
 
-        Ok(())
+        push_tags(&mut fields, &self.tags);
+        push_extra_fields(&mut fields, &self.extra);
+        fields
     }
 }
 
-impl Display for Area {
-    /// Serialize to file content (frontmatter + body).
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let parsed = ParsedArea {
+impl Area {
+    /// Frontmatter fields in the order they are written.
+    pub(crate) fn frontmatter_fields(&self) -> Fields {
+        self.to_parsed().frontmatter_fields()
+    }
+
+    fn to_parsed(&self) -> ParsedArea {
+        ParsedArea {
             title: self.title.clone(),
             status: self.status,
             area_type: self.area_type.clone(),
             description: self.description.clone(),
+            parent: self.parent.clone(),
+            tags: self.tags.clone(),
             body: self.body.clone(),
             extra: self.extra.clone(),
+        }
+    }
+}
+
+impl Display for ParsedArea {
+    /// Serialize to file content (frontmatter + body).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_document(f, &self.frontmatter_fields(), &self.body)
+    }
+}
+
+impl Display for Area {
+    /// Serialize to file content (frontmatter + body).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_parsed())
+    }
+}
+
+// =============================================================================
+// Atomic writes
+// =============================================================================
+
+/// Counter used to keep temporary filenames unique within a process.
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Build the path of the temporary file used while writing `path`.
+///
+/// The temporary file lives in the same directory as the destination (so the
+/// final rename never crosses a filesystem boundary), is hidden, and does not
+/// end in `.md`, so directory scans and file watchers ignore it.
+fn temp_path_for(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map_or_else(|| "taskdn".into(), |n| n.to_string_lossy().into_owned());
+    let counter = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let temp_name = format!(".{file_name}.{}.{counter}.tmp", std::process::id());
+    path.with_file_name(temp_name)
+}
+
+/// Write `content` to `path` atomically.
+///
+/// The content is written to a temporary file in the same directory, flushed
+/// to disk, and then renamed over the destination. Other readers only ever see
+/// either the previous file or the complete new file, never a partial write.
+///
+/// # Errors
+///
+/// Returns an error if the temporary file cannot be written or renamed. In that
+/// case the temporary file is removed and any existing file at `path` is left
+/// untouched.
+pub(crate) fn atomic_write(path: &Path, content: impl AsRef<[u8]>) -> Result<()> {
+    atomic_write_with(path, |file| file.write_all(content.as_ref()))
+}
+
+/// Atomically write to `path`, using `write` to fill the temporary file.
+fn atomic_write_with<F>(path: &Path, write: F) -> Result<()>
+where
+    F: FnOnce(&mut File) -> io::Result<()>,
+{
+    let temp_path = temp_path_for(path);
+
+    let result = write_temp_and_rename(path, &temp_path, write);
+    if result.is_err() {
+        // Best effort: the original error is more useful than a cleanup failure
+        let _ = fs::remove_file(&temp_path);
+    }
+    result?;
+
+    sync_parent_dir(path);
+    Ok(())
+}
+
+fn write_temp_and_rename<F>(path: &Path, temp_path: &Path, write: F) -> io::Result<()>
+where
+    F: FnOnce(&mut File) -> io::Result<()>,
+{
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(temp_path)?;
+
+    // Keep the permissions of the file being replaced
+    if let Ok(metadata) = fs::metadata(path) {
+        file.set_permissions(metadata.permissions())?;
+    }
+
+    write(&mut file)?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(temp_path, path)
+}
+
+/// Flush the directory entry for `path` so the rename itself is durable.
+///
+/// This is a no-op on platforms where directories cannot be opened as files.
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    {
+        let parent = match path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p,
+            _ => Path::new("."),
         };
-        write!(f, "{parsed}")
+        if let Ok(dir) = File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
     }
 }
 
@@ -341,21 +579,75 @@ impl Display for Area {
 // Internal write functions
 // =============================================================================
 
-/// Write a task to disk.
+/// Write a task to disk as it is.
 ///
 /// This function:
-/// - Automatically updates `updated_at` to the current time
-/// - Automatically sets `completed_at` when status is `Done` or `Dropped`
+/// - Writes `updated_at` and `completed_at` unchanged; callers set them
 /// - Preserves all other fields including `extra` and `body`
+/// - Replaces the file atomically (see [`atomic_write`])
 ///
 /// # Errors
 ///
 /// Returns an error if the file cannot be written.
-#[allow(dead_code)]
 pub(crate) fn write_task(path: &Path, task: &Task) -> Result<()> {
-    let content = task.to_string();
-    fs::write(path, content)?;
-    Ok(())
+    atomic_write(path, task.to_string())
+}
+
+/// Alternative spellings a task file may use for canonical keys.
+const TASK_KEY_ALIASES: &[(&str, &str)] = &[("projects", "project")];
+
+/// The on-disk version of an entity that is about to be rewritten.
+///
+/// When passed to one of the write functions, the existing file is patched in
+/// place so that only changed keys are touched (see [`frontmatter::patch`]).
+pub(crate) struct Original<'a> {
+    content: &'a str,
+    fields: Fields,
+    body: String,
+}
+
+impl<'a> Original<'a> {
+    pub(crate) fn task(content: &'a str, task: &Task) -> Self {
+        Self {
+            content,
+            fields: task.frontmatter_fields(),
+            body: task.body.clone(),
+        }
+    }
+
+    pub(crate) fn project(content: &'a str, project: &Project) -> Self {
+        Self {
+            content,
+            fields: project.frontmatter_fields(),
+            body: project.body.clone(),
+        }
+    }
+
+    pub(crate) fn area(content: &'a str, area: &Area) -> Self {
+        Self {
+            content,
+            fields: area.frontmatter_fields(),
+            body: area.body.clone(),
+        }
+    }
+
+    /// Patch the original content to hold `fields` and `body`.
+    fn patch(&self, fields: &Fields, body: &str, aliases: &[(&str, &str)]) -> Option<String> {
+        frontmatter::patch(
+            self.content,
+            &self.fields,
+            &self.body,
+            fields,
+            body,
+            aliases,
+        )
+    }
+}
+
+/// Write content atomically and return the revision of the new file.
+pub(crate) fn write_content(path: &Path, content: &str) -> Result<Revision> {
+    atomic_write(path, content)?;
+    Ok(Revision::for_file(path, content)?)
 }
 
 /// Write a task with automatic timestamp updates.
@@ -369,6 +661,8 @@ pub(crate) fn write_task(path: &Path, task: &Task) -> Result<()> {
 /// * `path` - Path to write the task file
 /// * `task` - The task to write (will be modified with updated timestamps)
 /// * `previous_status` - The status before any updates (for detecting completion transitions)
+/// * `original` - The file being replaced, to patch it in place instead of regenerating it
+/// * `now` - The current time, as written to `updated-at` and `completed-at`
 ///
 /// # Errors
 ///
@@ -378,43 +672,89 @@ pub(crate) fn write_task_with_updates(
     path: &Path,
     task: &mut Task,
     previous_status: Option<TaskStatus>,
-) -> Result<()> {
+    original: Option<&Original<'_>>,
+    now: &DateTimeValue,
+) -> Result<Revision> {
+    let content = render_task_with_updates(task, previous_status, original, now);
+    write_content(path, &content)
+}
+
+/// Render a task's file content, applying automatic timestamp updates.
+///
+/// See [`write_task_with_updates`].
+pub(crate) fn render_task_with_updates(
+    task: &mut Task,
+    previous_status: Option<TaskStatus>,
+    original: Option<&Original<'_>>,
+    now: &DateTimeValue,
+) -> String {
     // Update updated_at to current time
-    task.updated_at = DateTimeValue::now();
+    task.updated_at = now.clone();
 
     // Set completed_at if transitioning to completed status
     let was_completed = previous_status.is_some_and(|s| s.is_completed());
     let is_completed = task.status.is_completed();
 
     if is_completed && !was_completed {
-        task.completed_at = Some(DateTimeValue::now());
+        task.completed_at = Some(now.clone());
     }
 
-    write_task(path, task)
+    original
+        .and_then(|o| o.patch(&task.frontmatter_fields(), &task.body, TASK_KEY_ALIASES))
+        .unwrap_or_else(|| task.to_string())
+}
+
+/// Render a task's file content as is, patching `original` if given.
+///
+/// Unlike [`render_task_with_updates`], timestamps are left untouched.
+pub(crate) fn render_task(task: &Task, original: Option<&Original<'_>>) -> String {
+    original
+        .and_then(|o| o.patch(&task.frontmatter_fields(), &task.body, TASK_KEY_ALIASES))
+        .unwrap_or_else(|| task.to_string())
 }
 
 /// Write a project to disk.
 ///
+/// If `original` is given, the existing file is patched in place.
+///
 /// # Errors
 ///
 /// Returns an error if the file cannot be written.
-#[allow(dead_code)]
-pub(crate) fn write_project(path: &Path, project: &Project) -> Result<()> {
-    let content = project.to_string();
-    fs::write(path, content)?;
-    Ok(())
+pub(crate) fn write_project(
+    path: &Path,
+    project: &Project,
+    original: Option<&Original<'_>>,
+) -> Result<Revision> {
+    write_content(path, &render_project(project, original))
+}
+
+/// Render a project's file content, patching `original` if given.
+pub(crate) fn render_project(project: &Project, original: Option<&Original<'_>>) -> String {
+    original
+        .and_then(|o| o.patch(&project.frontmatter_fields(), &project.body, &[]))
+        .unwrap_or_else(|| project.to_string())
 }
 
 /// Write an area to disk.
 ///
+/// If `original` is given, the existing file is patched in place.
+///
 /// # Errors
 ///
 /// Returns an error if the file cannot be written.
-#[allow(dead_code)]
-pub(crate) fn write_area(path: &Path, area: &Area) -> Result<()> {
-    let content = area.to_string();
-    fs::write(path, content)?;
-    Ok(())
+pub(crate) fn write_area(
+    path: &Path,
+    area: &Area,
+    original: Option<&Original<'_>>,
+) -> Result<Revision> {
+    write_content(path, &render_area(area, original))
+}
+
+/// Render an area's file content, patching `original` if given.
+pub(crate) fn render_area(area: &Area, original: Option<&Original<'_>>) -> String {
+    original
+        .and_then(|o| o.patch(&area.frontmatter_fields(), &area.body, &[]))
+        .unwrap_or_else(|| area.to_string())
 }
 
 #[cfg(test)]
@@ -469,6 +809,12 @@ mod tests {
                 defer_until: None,
                 project: None,
                 area: None,
+                parent: None,
+                blocked_by: Vec::new(),
+                priority: None,
+                recurrence: None,
+                series: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
                 projects_count: None,
@@ -590,6 +936,12 @@ mod tests {
                 defer_until: None,
                 project: None,
                 area: None,
+                parent: None,
+                blocked_by: Vec::new(),
+                priority: None,
+                recurrence: None,
+                series: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
                 projects_count: None,
@@ -616,6 +968,7 @@ mod tests {
                 start_date: None,
                 end_date: None,
                 blocked_by: Vec::new(),
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
             }
@@ -670,6 +1023,8 @@ mod tests {
                 status: None,
                 area_type: None,
                 description: None,
+                parent: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
             }
@@ -710,6 +1065,17 @@ mod tests {
 
             assert!(output.contains("status: archived\n"));
         }
+
+        #[test]
+        fn area_with_parent() {
+            let mut area = sample_area();
+            area.parent = Some(FileReference::wiki_link("Work"));
+
+            let output = area.to_string();
+
+            assert!(output.contains("parent: \"[[Work]]\"\n"));
+            assert_eq!(ParsedArea::parse(&output).unwrap().parent, area.parent);
+        }
     }
 
     mod round_trip {
@@ -717,13 +1083,13 @@ mod tests {
 
         #[test]
         fn task_round_trip_preserves_values() {
-            let original = r#"---
+            let original = r"---
 title: Test Task
 status: ready
 created-at: 2025-01-01
 updated-at: 2025-01-02
 ---
-"#;
+";
             let parsed = ParsedTask::parse(original).unwrap();
             let serialized = parsed.to_string();
             let reparsed = ParsedTask::parse(&serialized).unwrap();
@@ -736,7 +1102,7 @@ updated-at: 2025-01-02
 
         #[test]
         fn task_round_trip_with_extra_fields() {
-            let original = r#"---
+            let original = r"---
 title: Custom Task
 status: inbox
 created-at: 2025-01-01
@@ -744,13 +1110,95 @@ updated-at: 2025-01-01
 custom-field: custom value
 priority: high
 ---
-"#;
+";
             let parsed = ParsedTask::parse(original).unwrap();
             let serialized = parsed.to_string();
             let reparsed = ParsedTask::parse(&serialized).unwrap();
 
             assert!(reparsed.extra.contains_key("custom-field"));
-            assert!(reparsed.extra.contains_key("priority"));
+            assert_eq!(reparsed.priority, Some(Priority::High));
+        }
+
+        #[test]
+        fn task_round_trip_preserves_priority_spelling() {
+            for (value, expected) in [
+                ("HIGH", "priority: high\n"),
+                ("3", "priority: 3\n"),
+                ("\"5\"", "priority: 5\n"),
+                ("someday maybe", "priority: someday maybe\n"),
+                ("\"P1: now\"", "priority: \"P1: now\"\n"),
+                ("2.5", "priority: 2.5\n"),
+                ("true", "priority: true\n"),
+                ("\"2.5\"", "priority: \"2.5\"\n"),
+            ] {
+                let original = format!(
+                    "---\ntitle: T\nstatus: inbox\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\npriority: {value}\n---\n"
+                );
+                let serialized = ParsedTask::parse(&original).unwrap().to_string();
+                assert!(serialized.contains(expected), "{value}: {serialized}");
+            }
+        }
+
+        #[test]
+        fn task_round_trip_keeps_priority_type() {
+            for (value, expected) in [("2.5", 2.5.into()), ("false", false.into())] {
+                let original = format!(
+                    "---\ntitle: T\nstatus: inbox\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\npriority: {value}\n---\n"
+                );
+                let reparsed =
+                    ParsedTask::parse(&ParsedTask::parse(&original).unwrap().to_string()).unwrap();
+                assert_eq!(
+                    reparsed.priority,
+                    Some(Priority::Other(expected)),
+                    "{value}"
+                );
+            }
+        }
+
+        #[test]
+        fn task_round_trip_preserves_parent() {
+            let original = "---\ntitle: T\nstatus: inbox\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\nparent: \"[[Epic]]\"\n---\n";
+            let parsed = ParsedTask::parse(original).unwrap();
+            let serialized = parsed.to_string();
+            assert!(serialized.contains("parent: \"[[Epic]]\"\n"));
+
+            let reparsed = ParsedTask::parse(&serialized).unwrap();
+            assert_eq!(reparsed.parent, parsed.parent);
+        }
+
+        #[test]
+        fn task_round_trip_keeps_unparsed_recurrence() {
+            let original = "---\ntitle: T\nstatus: inbox\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\nrecurrence: every other day\n---\n";
+            let mut parsed = ParsedTask::parse(original).unwrap();
+            assert!(parsed.to_string().contains("recurrence: every other day\n"));
+
+            parsed.recurrence = Some("FREQ=DAILY".parse().unwrap());
+            let serialized = parsed.to_string();
+            assert_eq!(serialized.matches("recurrence:").count(), 1);
+            assert!(serialized.contains("recurrence: FREQ=DAILY\n"));
+        }
+
+        #[test]
+        fn task_round_trip_preserves_blocked_by() {
+            let original = "---\ntitle: T\nstatus: blocked\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\nblocked-by:\n  - \"[[Design]]\"\n  - ./specs/api.md\n---\n";
+            let parsed = ParsedTask::parse(original).unwrap();
+            let serialized = parsed.to_string();
+            assert!(serialized.contains("blocked-by:\n  - \"[[Design]]\"\n  - ./specs/api.md\n"));
+
+            let reparsed = ParsedTask::parse(&serialized).unwrap();
+            assert_eq!(reparsed.blocked_by, parsed.blocked_by);
+        }
+
+        #[test]
+        fn task_round_trip_preserves_tags() {
+            let original = "---\ntitle: T\nstatus: inbox\ncreated-at: 2025-01-01\nupdated-at: 2025-01-01\ntags: Work, home/garden\n---\nInline #errand stays in the body.\n";
+            let parsed = ParsedTask::parse(original).unwrap();
+            let serialized = parsed.to_string();
+            assert!(serialized.contains("tags:\n  - Work\n  - home/garden\n"));
+
+            let reparsed = ParsedTask::parse(&serialized).unwrap();
+            assert_eq!(reparsed.tags, parsed.tags);
+            assert_eq!(reparsed.body, parsed.body);
         }
 
         #[test]
@@ -787,13 +1235,13 @@ fn main() {
 
         #[test]
         fn task_round_trip_preserves_date_format() {
-            let original = r#"---
+            let original = r"---
 title: DateTime Test
 status: ready
 created-at: 2025-01-01T10:30:00
 updated-at: 2025-01-02
 ---
-"#;
+";
             let parsed = ParsedTask::parse(original).unwrap();
             let serialized = parsed.to_string();
             let reparsed = ParsedTask::parse(&serialized).unwrap();
@@ -810,6 +1258,8 @@ updated-at: 2025-01-02
 title: Test Project
 status: in-progress
 area: "[[Work]]"
+tags:
+  - work/client
 ---
 
 ## Overview
@@ -822,21 +1272,23 @@ Project content.
 
             assert_eq!(parsed.title, reparsed.title);
             assert_eq!(parsed.status, reparsed.status);
+            assert_eq!(parsed.tags, reparsed.tags);
             assert_eq!(parsed.body, reparsed.body);
         }
 
         #[test]
         fn area_round_trip() {
-            let original = r#"---
+            let original = r"---
 title: Work
 status: active
 type: professional
+tags: [work]
 ---
 
 ## Context
 
 Area content.
-"#;
+";
             let parsed = ParsedArea::parse(original).unwrap();
             let serialized = parsed.to_string();
             let reparsed = ParsedArea::parse(&serialized).unwrap();
@@ -844,6 +1296,7 @@ Area content.
             assert_eq!(parsed.title, reparsed.title);
             assert_eq!(parsed.status, reparsed.status);
             assert_eq!(parsed.area_type, reparsed.area_type);
+            assert_eq!(parsed.tags, reparsed.tags);
             assert_eq!(parsed.body, reparsed.body);
         }
     }
@@ -920,13 +1373,13 @@ Horizontal rule above.
 
         #[test]
         fn task_empty_body() {
-            let original = r#"---
+            let original = r"---
 title: Empty Body Task
 status: inbox
 created-at: 2025-01-01
 updated-at: 2025-01-01
 ---
-"#;
+";
             let parsed = ParsedTask::parse(original).unwrap();
             let serialized = parsed.to_string();
 
@@ -942,13 +1395,13 @@ updated-at: 2025-01-01
 
         #[test]
         fn task_all_optional_fields_missing() {
-            let original = r#"---
+            let original = r"---
 title: Minimal Task
 status: ready
 created-at: 2025-01-01
 updated-at: 2025-01-02
 ---
-"#;
+";
             let parsed = ParsedTask::parse(original).unwrap();
 
             // Verify all optional fields are None/empty
@@ -974,10 +1427,10 @@ updated-at: 2025-01-02
 
         #[test]
         fn project_all_optional_fields_missing() {
-            let original = r#"---
+            let original = r"---
 title: Minimal Project
 ---
-"#;
+";
             let parsed = ParsedProject::parse(original).unwrap();
 
             assert!(parsed.unique_id.is_none());
@@ -997,10 +1450,10 @@ title: Minimal Project
 
         #[test]
         fn area_all_optional_fields_missing() {
-            let original = r#"---
+            let original = r"---
 title: Minimal Area
 ---
-"#;
+";
             let parsed = ParsedArea::parse(original).unwrap();
 
             assert!(parsed.status.is_none());
@@ -1027,6 +1480,12 @@ title: Minimal Area
                 defer_until: None,
                 project: None,
                 area: None,
+                parent: None,
+                blocked_by: Vec::new(),
+                priority: None,
+                recurrence: None,
+                series: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
                 projects_count: None,
@@ -1083,6 +1542,12 @@ area: "[[area-slug|Area Display]]"
                 defer_until: None,
                 project: Some(FileReference::relative_path("./projects/my-project.md")),
                 area: Some(FileReference::filename("work.md")),
+                parent: None,
+                blocked_by: Vec::new(),
+                priority: None,
+                recurrence: None,
+                series: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
                 projects_count: None,
@@ -1123,6 +1588,12 @@ area: "[[area-slug|Area Display]]"
                 defer_until: None,
                 project: None,
                 area: None,
+                parent: None,
+                blocked_by: Vec::new(),
+                priority: None,
+                recurrence: None,
+                series: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
                 projects_count: None,
@@ -1152,13 +1623,26 @@ area: "[[area-slug|Area Display]]"
                 defer_until: None,
                 project: None,
                 area: None,
+                parent: None,
+                blocked_by: Vec::new(),
+                priority: None,
+                recurrence: None,
+                series: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
                 projects_count: None,
             };
 
             // Transition from Ready to Done should set completed_at
-            write_task_with_updates(&path, &mut task, Some(TaskStatus::Ready)).unwrap();
+            write_task_with_updates(
+                &path,
+                &mut task,
+                Some(TaskStatus::Ready),
+                None,
+                &DateTimeValue::now(),
+            )
+            .unwrap();
 
             // Check that timestamps were updated
             assert!(!task.updated_at.is_date_only()); // Now should have time
@@ -1184,13 +1668,26 @@ area: "[[area-slug|Area Display]]"
                 defer_until: None,
                 project: None,
                 area: None,
+                parent: None,
+                blocked_by: Vec::new(),
+                priority: None,
+                recurrence: None,
+                series: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
                 projects_count: None,
             };
 
             // Already Done, so completed_at should not change
-            write_task_with_updates(&path, &mut task, Some(TaskStatus::Done)).unwrap();
+            write_task_with_updates(
+                &path,
+                &mut task,
+                Some(TaskStatus::Done),
+                None,
+                &DateTimeValue::now(),
+            )
+            .unwrap();
 
             assert_eq!(task.completed_at, Some(original_completed));
         }
@@ -1210,11 +1707,12 @@ area: "[[area-slug|Area Display]]"
                 start_date: None,
                 end_date: None,
                 blocked_by: Vec::new(),
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
             };
 
-            write_project(&path, &project).unwrap();
+            write_project(&path, &project, None).unwrap();
 
             assert!(path.exists());
             let content = fs::read_to_string(&path).unwrap();
@@ -1232,15 +1730,126 @@ area: "[[area-slug|Area Display]]"
                 status: Some(AreaStatus::Active),
                 area_type: None,
                 description: None,
+                parent: None,
+                tags: Vec::new(),
                 body: String::new(),
                 extra: HashMap::new(),
             };
 
-            write_area(&path, &area).unwrap();
+            write_area(&path, &area, None).unwrap();
 
             assert!(path.exists());
             let content = fs::read_to_string(&path).unwrap();
             assert!(content.contains("title: Test Area\n"));
         }
     }
+    mod atomic_writes {
+        use super::*;
+        use tempfile::tempdir;
+
+        fn leftover_temp_files(dir: &Path) -> Vec<PathBuf> {
+            fs::read_dir(dir)
+                .unwrap()
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.extension().is_some_and(|ext| ext == "tmp"))
+                .collect()
+        }
+
+        #[test]
+        fn creates_new_file() {
+            let dir = tempdir().unwrap();
+            let path = dir.path().join("new.md");
+
+            atomic_write(&path, "hello\n").unwrap();
+
+            assert_eq!(fs::read_to_string(&path).unwrap(), "hello\n");
+            assert!(leftover_temp_files(dir.path()).is_empty());
+        }
+
+        #[test]
+        fn replaces_existing_file() {
+            let dir = tempdir().unwrap();
+            let path = dir.path().join("existing.md");
+            fs::write(&path, "old content that is longer than the new one\n").unwrap();
+
+            atomic_write(&path, "new\n").unwrap();
+
+            assert_eq!(fs::read_to_string(&path).unwrap(), "new\n");
+            assert!(leftover_temp_files(dir.path()).is_empty());
+        }
+
+        #[test]
+        fn interrupted_write_leaves_original_intact() {
+            let dir = tempdir().unwrap();
+            let path = dir.path().join("task.md");
+            fs::write(&path, "original\n").unwrap();
+
+            // Simulate a crash part-way through writing the new content
+            let result = atomic_write_with(&path, |file| {
+                file.write_all(b"---\ntitle: Half wri")?;
+                Err(io::Error::new(io::ErrorKind::Other, "disk full"))
+            });
+
+            assert!(result.is_err());
+            assert_eq!(fs::read_to_string(&path).unwrap(), "original\n");
+            assert!(leftover_temp_files(dir.path()).is_empty());
+        }
+
+        #[test]
+        fn interrupted_write_does_not_create_destination() {
+            let dir = tempdir().unwrap();
+            let path = dir.path().join("never-created.md");
+
+            let result = atomic_write_with(&path, |file| {
+                file.write_all(b"partial")?;
+                Err(io::Error::new(io::ErrorKind::Other, "interrupted"))
+            });
+
+            assert!(result.is_err());
+            assert!(!path.exists());
+            assert!(leftover_temp_files(dir.path()).is_empty());
+        }
+
+        #[test]
+        fn missing_directory_returns_error() {
+            let dir = tempdir().unwrap();
+            let path = dir.path().join("missing").join("task.md");
+
+            let result = atomic_write(&path, "content");
+
+            assert!(matches!(result, Err(crate::error::Error::Io(_))));
+        }
+
+        #[test]
+        fn temp_files_are_hidden_and_not_markdown() {
+            let temp = temp_path_for(Path::new("/vault/tasks/my-task.md"));
+            let name = temp.file_name().unwrap().to_string_lossy().into_owned();
+
+            assert_eq!(temp.parent(), Some(Path::new("/vault/tasks")));
+            assert!(name.starts_with(".my-task.md."));
+            assert_eq!(temp.extension().unwrap(), "tmp");
+        }
+
+        #[test]
+        fn temp_paths_are_unique() {
+            let path = Path::new("/vault/tasks/my-task.md");
+            assert_ne!(temp_path_for(path), temp_path_for(path));
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn preserves_permissions() {
+            use std::os::unix::fs::PermissionsExt;
+
+            let dir = tempdir().unwrap();
+            let path = dir.path().join("task.md");
+            fs::write(&path, "original").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o600)).unwrap();
+
+            atomic_write(&path, "updated").unwrap();
+
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
 }
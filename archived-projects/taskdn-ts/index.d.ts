@@ -203,8 +203,9 @@ export declare class Taskdn {
    * Update all tasks matching a filter.
    *
    * This is a bulk operation that applies the same updates to multiple tasks.
-   * Unlike `updateTask`, this method does not throw on individual failures -
-   * instead it returns a result indicating which tasks succeeded and which failed.
+   * Unlike `updateTask`, this method does not throw on failures - instead it
+   * returns a result listing them. The updates are applied all together: if any
+   * task cannot be updated, none are and `succeeded` is empty.
    *
    * # Arguments
    * * `filter` - Filter criteria for selecting tasks to update
@@ -1357,6 +1357,7 @@ impl Taskdn {
 
         self.inner
             .update_task(&path, core_updates)
+            .map(|_| ())
             .map_err(|e| Error::from_reason(e.to_string()))
     }
 
@@ -1372,6 +1373,7 @@ impl Taskdn {
     pub fn complete_task(&self, path: String) -> Result<()> {
         self.inner
             .complete_task(&path)
+            .map(|_| ())
             .map_err(|e| Error::from_reason(e.to_string()))
     }
 
@@ -1540,8 +1542,9 @@ impl Taskdn {
     /// Update all tasks matching a filter.
     ///
     /// This is a bulk operation that applies the same updates to multiple tasks.
-    /// Unlike `updateTask`, this method does not throw on individual failures -
-    /// instead it returns a result indicating which tasks succeeded and which failed.
+    /// Unlike `updateTask`, this method does not throw on failures - instead it
+    /// returns a result listing them. The updates are applied all together: if any
+    /// task cannot be updated, none are and `succeeded` is empty.
     ///
     /// # Arguments
     /// * `filter` - Filter criteria for selecting tasks to update
@@ -1657,6 +1660,7 @@ impl Taskdn {
 
         self.inner
             .update_project(&path, core_updates)
+            .map(|_| ())
             .map_err(|e| Error::from_reason(e.to_string()))
     }
 
@@ -1775,6 +1779,7 @@ impl Taskdn {
 
         self.inner
             .update_area(&path, core_updates)
+            .map(|_| ())
             .map_err(|e| Error::from_reason(e.to_string()))
     }
 